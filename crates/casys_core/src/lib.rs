@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ops::Bound;
 
 pub type NodeId = u64;
 pub type EdgeId = u64;
@@ -8,7 +9,7 @@ pub type EdgeId = u64;
 // -----------------------
 
 /// A graph node with labels and properties
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Node {
     pub id: NodeId,
     pub labels: Vec<String>,
@@ -16,7 +17,7 @@ pub struct Node {
 }
 
 /// A graph edge connecting two nodes
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Edge {
     pub id: EdgeId,
     pub from_node: NodeId,
@@ -36,12 +37,61 @@ pub trait GraphReadStore {
     fn get_node(&self, id: NodeId) -> Result<Option<Node>, EngineError>;
     fn get_neighbors(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError>;
     fn get_neighbors_incoming(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError>;
+    /// Ids of nodes whose `prop` property falls within `(lo, hi)`, in
+    /// ascending property-value order, using the total ordering `Value`
+    /// defines for indexing purposes (see
+    /// `casys_engine::index::InMemoryGraphStore`'s property index). A
+    /// property nobody has set, or a store with no property index at all,
+    /// both return an empty result rather than an error.
+    fn scan_by_property_range(&self, prop: &str, lo: Bound<Value>, hi: Bound<Value>) -> Result<Vec<NodeId>, EngineError>;
+
+    /// `get_node` for each id, in the same order, `None` where the id
+    /// doesn't exist. The default just calls `get_node` per id, which is
+    /// all an in-memory store like `MockGraphStore` needs; a remote
+    /// backend (S3/KV-backed) should override this with a single
+    /// multi-key round trip instead of one per id.
+    fn get_nodes(&self, ids: &[NodeId]) -> Result<Vec<Option<Node>>, EngineError> {
+        ids.iter().map(|id| self.get_node(*id)).collect()
+    }
+
+    /// `get_neighbors` for every id in `node_ids`, keyed by id, for
+    /// breadth-first expansion without one call per frontier node. The
+    /// default fans out to `get_neighbors`; override it where a single
+    /// batched index lookup is cheaper than `node_ids.len()` of them.
+    fn get_neighbors_batch(&self, node_ids: &[NodeId], edge_type: Option<&str>) -> Result<HashMap<NodeId, Vec<(Edge, Node)>>, EngineError> {
+        node_ids.iter().map(|id| Ok((*id, self.get_neighbors(*id, edge_type)?))).collect()
+    }
+
+    /// A page of `scan_by_label`'s results, in ascending `NodeId` order,
+    /// starting just after `after` (or from the beginning if `None`) and
+    /// holding at most `limit` nodes. Returns the id to pass as `after` for
+    /// the next page, or `None` once the label is exhausted. The default
+    /// sorts `scan_by_label`'s full result and slices it, so it still pays
+    /// for a full scan per page; a backend with an ordered label index
+    /// should override this to page the index itself instead.
+    fn scan_by_label_range(&self, label: &str, after: Option<NodeId>, limit: usize) -> Result<(Vec<Node>, Option<NodeId>), EngineError> {
+        let mut nodes = self.scan_by_label(label)?;
+        nodes.sort_by_key(|n| n.id);
+        let start = match after {
+            Some(cursor) => nodes.partition_point(|n| n.id <= cursor),
+            None => 0,
+        };
+        let page: Vec<Node> = nodes[start..].iter().take(limit).cloned().collect();
+        let next = (start + page.len() < nodes.len()).then(|| page.last().map(|n| n.id)).flatten();
+        Ok((page, next))
+    }
 }
 
 /// Write-capable storage interface (extends read)
 pub trait GraphWriteStore: GraphReadStore {
     fn add_node(&mut self, labels: Vec<String>, properties: HashMap<String, Value>) -> Result<NodeId, EngineError>;
     fn add_edge(&mut self, from: NodeId, to: NodeId, edge_type: String, properties: HashMap<String, Value>) -> Result<EdgeId, EngineError>;
+    /// Tombstones `id` rather than physically removing it - see
+    /// `casys_engine::index::InMemoryGraphStore`'s versioned-delete model for
+    /// why. `Ok(())` even if `id` doesn't exist, matching `add_node`/
+    /// `add_edge` not distinguishing "new" from "already there".
+    fn delete_node(&mut self, id: NodeId) -> Result<(), EngineError>;
+    fn delete_edge(&mut self, id: EdgeId) -> Result<(), EngineError>;
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -57,6 +107,86 @@ pub enum Value {
     NodeId(NodeId),
 }
 
+/// Where a variant sits in [`value_cmp`]'s total order.
+fn value_rank(v: &Value) -> u8 {
+    match v {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Int(_) => 2,
+        Value::Float(_) => 3,
+        Value::String(_) => 4,
+        Value::Bytes(_) => 5,
+        Value::Array(_) => 6,
+        Value::Map(_) => 7,
+        Value::NodeId(_) => 8,
+    }
+}
+
+/// `Ord`-compatible wrapper so arrays/maps of `Value` can be compared
+/// element-wise via `Iterator::cmp` without `Value` itself needing to be
+/// totally ordered (it isn't - `PartialEq` only, since `Value` is also used
+/// where `f64` NaN makes a blanket `Eq`/`Ord` impl the wrong default).
+struct ValueKey<'a>(&'a Value);
+
+impl PartialEq for ValueKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        value_cmp(self.0, other.0) == std::cmp::Ordering::Equal
+    }
+}
+impl Eq for ValueKey<'_> {}
+impl PartialOrd for ValueKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ValueKey<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        value_cmp(self.0, other.0)
+    }
+}
+
+/// Total ordering over `Value`, for property range indexes/queries: values
+/// order first by variant (`Null` < `Bool` < `Int` < `Float` < `String` <
+/// `Bytes` < `Array` < `Map` < `NodeId`), then by payload. `Float` compares
+/// via `f64::total_cmp` so `NaN` sorts consistently instead of needing a
+/// fallback. Arrays and maps compare element-wise, recursing through this
+/// same function.
+pub fn value_cmp(a: &Value, b: &Value) -> std::cmp::Ordering {
+    value_rank(a).cmp(&value_rank(b)).then_with(|| match (a, b) {
+        (Value::Null, Value::Null) => std::cmp::Ordering::Equal,
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Int(x), Value::Int(y)) => x.cmp(y),
+        (Value::Float(x), Value::Float(y)) => x.total_cmp(y),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Bytes(x), Value::Bytes(y)) => x.cmp(y),
+        (Value::NodeId(x), Value::NodeId(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x.iter().map(ValueKey).cmp(y.iter().map(ValueKey)),
+        (Value::Map(x), Value::Map(y)) => {
+            x.iter().map(|(k, v)| (k, ValueKey(v))).cmp(y.iter().map(|(k, v)| (k, ValueKey(v))))
+        }
+        // Unreachable: `value_rank` only ties within a single variant, all
+        // of which are matched above.
+        _ => std::cmp::Ordering::Equal,
+    })
+}
+
+/// Whether `v` falls within `(lo, hi)` under [`value_cmp`]'s ordering -
+/// shared by any `GraphReadStore::scan_by_property_range` implementation
+/// that filters rather than seeks (e.g. a linear-scan mock).
+pub fn value_in_range(v: &Value, lo: &Bound<Value>, hi: &Bound<Value>) -> bool {
+    let above_lo = match lo {
+        Bound::Included(b) => value_cmp(v, b) != std::cmp::Ordering::Less,
+        Bound::Excluded(b) => value_cmp(v, b) == std::cmp::Ordering::Greater,
+        Bound::Unbounded => true,
+    };
+    let below_hi = match hi {
+        Bound::Included(b) => value_cmp(v, b) != std::cmp::Ordering::Greater,
+        Bound::Excluded(b) => value_cmp(v, b) == std::cmp::Ordering::Less,
+        Bound::Unbounded => true,
+    };
+    above_lo && below_hi
+}
+
 // -----------------------
 // Granular Storage Ports (optional for adapters)
 // -----------------------
@@ -78,6 +208,60 @@ pub struct ManifestMeta {
     pub wal_tail: Option<WalTailMeta>,
 }
 
+/// Caller-supplied encryption-at-rest configuration, threaded from
+/// `Engine::open`/`open_with_backend` down to whichever storage backend
+/// constructor supports it (currently `FsBackend::with_encryption`).
+/// Backends that don't support encryption are free to ignore this.
+#[derive(Clone)]
+pub enum EncryptionConfig {
+    /// Segments, chunks, and manifests are stored as plaintext (default).
+    None,
+    /// A raw 32-byte data key, e.g. loaded from a secrets manager.
+    Key([u8; 32]),
+    /// A human passphrase; the data key is derived via Argon2id using a
+    /// per-data-dir random salt recorded in that directory's key header.
+    Passphrase(String),
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        EncryptionConfig::None
+    }
+}
+
+// -----------------------
+// Format/capability negotiation
+// -----------------------
+
+/// On-disk format version, checked on `Engine::open`. `major` gates
+/// compatibility (a data directory written by a newer major version is
+/// refused); `minor` is informational only, since minor bumps are meant to
+/// stay backward-readable.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FormatVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+/// A data directory's negotiated version/feature set, written once at
+/// creation and compared against the opening binary's own `Capabilities`
+/// on every subsequent `open`. `features` is a plain capability set - an
+/// unsupported or disabled feature simply doesn't appear in the list - not
+/// a struct of optional fields, so new features don't require a schema
+/// change here.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Capabilities {
+    pub version: FormatVersion,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn has(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
+
 pub trait StorageCatalog: Send + Sync + 'static {
     fn list_branches(&self, root: &Path, db: &DatabaseName) -> Result<Vec<BranchName>, EngineError>;
     fn create_branch(&self, root: &Path, db: &DatabaseName, from: &BranchName, new_branch: &BranchName, at: Option<Timestamp>) -> Result<(), EngineError>;
@@ -111,6 +295,16 @@ pub trait StorageBackend: Send + Sync + 'static {
     fn snapshot(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Timestamp, EngineError>;
     fn commit_tx(&self, root: &Path, db: &DatabaseName, branch: &BranchName, records: &[Vec<u8>]) -> Result<Timestamp, EngineError>;
     fn list_snapshot_timestamps(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Vec<Timestamp>, EngineError>;
+
+    /// Reclaims any storage this backend can no longer reach from `branches`
+    /// (e.g. content-addressed chunks no longer referenced by any of their
+    /// manifests). `branches` must be every branch of `db` that might still
+    /// reference something, or live data will be collected. Backends with
+    /// nothing to reclaim can leave this as the default no-op; `FsBackend`
+    /// overrides it to sweep its chunk store.
+    fn gc(&self, _root: &Path, _db: &DatabaseName, _branches: &[BranchName]) -> Result<usize, EngineError> {
+        Ok(0)
+    }
 }
 
 // -----------------------
@@ -185,6 +379,100 @@ impl StorageBackend for CompositeBackend {
     }
 }
 
+// -----------------------
+// Read-through cache (wraps any SegmentStore)
+// -----------------------
+
+struct CacheEntry {
+    data: Vec<u8>,
+    node_count: u64,
+    edge_count: u64,
+    last_used: u64,
+}
+
+/// Read-through LRU cache over any `SegmentStore`. Segments are content-
+/// addressed (see `casys_storage_fs::segments::content_id`), so once a
+/// `SegmentId` is cached its bytes can never go stale under a different
+/// branch or snapshot - the only invalidation `write_segment` needs to do
+/// is drop that same id's entry, for the legacy fixed-name ids that do get
+/// overwritten.
+///
+/// Eviction is by total cached bytes rather than entry count, since
+/// segments vary widely in size: `max_bytes` bounds `read_segment`'s worst-
+/// case memory footprint instead of an entry count that says nothing about
+/// it.
+pub struct CachingBackend<B: SegmentStore> {
+    inner: B,
+    max_bytes: u64,
+    cache: std::sync::Mutex<HashMap<SegmentId, CacheEntry>>,
+    clock: std::sync::atomic::AtomicU64,
+    hit_count: std::sync::atomic::AtomicU64,
+    miss_count: std::sync::atomic::AtomicU64,
+}
+
+impl<B: SegmentStore> CachingBackend<B> {
+    pub fn new(inner: B, max_bytes: u64) -> Self {
+        Self {
+            inner,
+            max_bytes,
+            cache: std::sync::Mutex::new(HashMap::new()),
+            clock: std::sync::atomic::AtomicU64::new(0),
+            hit_count: std::sync::atomic::AtomicU64::new(0),
+            miss_count: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    pub fn hit_count(&self) -> u64 {
+        self.hit_count.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn miss_count(&self) -> u64 {
+        self.miss_count.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Evicts least-recently-used entries (by `last_used`) until the cache
+    /// fits under `max_bytes`. Linear in entry count per eviction, which is
+    /// fine here since entry count is itself bounded by `max_bytes` divided
+    /// by typical segment size.
+    fn evict_to_budget(&self, cache: &mut HashMap<SegmentId, CacheEntry>) {
+        let mut total: u64 = cache.values().map(|e| e.data.len() as u64).sum();
+        while total > self.max_bytes {
+            let Some(oldest) = cache.iter().min_by_key(|(_, e)| e.last_used).map(|(id, _)| id.clone()) else { break };
+            if let Some(e) = cache.remove(&oldest) {
+                total -= e.data.len() as u64;
+            }
+        }
+    }
+}
+
+impl<B: SegmentStore> SegmentStore for CachingBackend<B> {
+    fn write_segment(&self, root: &Path, db: &DatabaseName, segment_id: &SegmentId, data: &[u8], node_count: u64, edge_count: u64) -> Result<(), EngineError> {
+        self.inner.write_segment(root, db, segment_id, data, node_count, edge_count)?;
+        self.cache.lock().unwrap().remove(segment_id);
+        Ok(())
+    }
+
+    fn read_segment(&self, root: &Path, db: &DatabaseName, segment_id: &SegmentId) -> Result<(Vec<u8>, u64, u64), EngineError> {
+        {
+            let mut cache = self.cache.lock().unwrap();
+            if let Some(entry) = cache.get_mut(segment_id) {
+                entry.last_used = self.clock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                self.hit_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return Ok((entry.data.clone(), entry.node_count, entry.edge_count));
+            }
+        }
+        self.miss_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let (data, node_count, edge_count) = self.inner.read_segment(root, db, segment_id)?;
+
+        let mut cache = self.cache.lock().unwrap();
+        let last_used = self.clock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        cache.insert(segment_id.clone(), CacheEntry { data: data.clone(), node_count, edge_count, last_used });
+        self.evict_to_budget(&mut cache);
+
+        Ok((data, node_count, edge_count))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CommitId(pub u64);
 
@@ -210,6 +498,24 @@ pub enum EngineError {
     Concurrency(String),
     #[error("not implemented: {0}")]
     NotImplemented(String),
+    #[error("locked: {0}")]
+    Locked(String),
+    #[error("data corruption: {0}")]
+    Corruption(String),
+    #[error("type mismatch: {0}")]
+    TypeMismatch(String),
+    #[error("incompatible format: data directory needs {found:?}, this build supports {supported:?}")]
+    IncompatibleFormat {
+        found: Capabilities,
+        supported: Capabilities,
+    },
+    #[error("{message}")]
+    ParseError {
+        message: String,
+        line: u32,
+        col: u32,
+        span: (usize, usize),
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
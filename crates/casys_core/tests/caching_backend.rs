@@ -0,0 +1,96 @@
+//! Unit tests for CachingBackend, the read-through LRU wrapper over any
+//! SegmentStore.
+
+use casys_core::{CachingBackend, DatabaseName, EngineError, SegmentId, SegmentStore};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+struct CountingStore {
+    segments: Mutex<HashMap<String, Vec<u8>>>,
+    reads: Mutex<u32>,
+}
+
+impl CountingStore {
+    fn new() -> Self {
+        Self { segments: Mutex::new(HashMap::new()), reads: Mutex::new(0) }
+    }
+}
+
+impl SegmentStore for CountingStore {
+    fn write_segment(&self, _root: &Path, _db: &DatabaseName, segment_id: &SegmentId, data: &[u8], _node_count: u64, _edge_count: u64) -> Result<(), EngineError> {
+        self.segments.lock().unwrap().insert(segment_id.0.clone(), data.to_vec());
+        Ok(())
+    }
+
+    fn read_segment(&self, _root: &Path, _db: &DatabaseName, segment_id: &SegmentId) -> Result<(Vec<u8>, u64, u64), EngineError> {
+        *self.reads.lock().unwrap() += 1;
+        self.segments.lock().unwrap().get(&segment_id.0).cloned()
+            .map(|d| (d, 0, 0))
+            .ok_or_else(|| EngineError::NotFound(segment_id.0.clone()))
+    }
+}
+
+fn db() -> DatabaseName { DatabaseName::try_from("testdb").unwrap() }
+
+#[test]
+fn repeated_reads_hit_the_cache_instead_of_the_inner_store() {
+    let inner = CountingStore::new();
+    let root = Path::new("/fake/root");
+    let database = db();
+    inner.write_segment(root, &database, &SegmentId("nodes".into()), b"node bytes", 1, 0).unwrap();
+
+    let cache = CachingBackend::new(inner, 1024 * 1024);
+    let (data1, _, _) = cache.read_segment(root, &database, &SegmentId("nodes".into())).unwrap();
+    let (data2, _, _) = cache.read_segment(root, &database, &SegmentId("nodes".into())).unwrap();
+
+    assert_eq!(data1, b"node bytes");
+    assert_eq!(data2, b"node bytes");
+    assert_eq!(cache.hit_count(), 1);
+    assert_eq!(cache.miss_count(), 1);
+}
+
+#[test]
+fn write_segment_invalidates_the_cached_entry() {
+    let inner = CountingStore::new();
+    let root = Path::new("/fake/root");
+    let database = db();
+    inner.write_segment(root, &database, &SegmentId("edges".into()), b"v1", 0, 1).unwrap();
+
+    let cache = CachingBackend::new(inner, 1024 * 1024);
+    let (v1, _, _) = cache.read_segment(root, &database, &SegmentId("edges".into())).unwrap();
+    assert_eq!(v1, b"v1");
+
+    cache.write_segment(root, &database, &SegmentId("edges".into()), b"v2", 0, 1).unwrap();
+    let (v2, _, _) = cache.read_segment(root, &database, &SegmentId("edges".into())).unwrap();
+    assert_eq!(v2, b"v2");
+    assert_eq!(cache.miss_count(), 2, "invalidated entry must be re-fetched from the inner store");
+}
+
+#[test]
+fn evicts_least_recently_used_entries_once_over_the_byte_budget() {
+    let inner = CountingStore::new();
+    let root = Path::new("/fake/root");
+    let database = db();
+    inner.write_segment(root, &database, &SegmentId("a".into()), &[0u8; 40], 0, 0).unwrap();
+    inner.write_segment(root, &database, &SegmentId("b".into()), &[0u8; 40], 0, 0).unwrap();
+    inner.write_segment(root, &database, &SegmentId("c".into()), &[0u8; 40], 0, 0).unwrap();
+
+    // Budget fits two of the three 40-byte segments.
+    let cache = CachingBackend::new(inner, 80);
+    cache.read_segment(root, &database, &SegmentId("a".into())).unwrap();
+    cache.read_segment(root, &database, &SegmentId("b".into())).unwrap();
+    // Touch "a" again so "b" becomes the least recently used.
+    cache.read_segment(root, &database, &SegmentId("a".into())).unwrap();
+    cache.read_segment(root, &database, &SegmentId("c".into())).unwrap();
+
+    assert_eq!(cache.miss_count(), 3);
+
+    // "a" and "c" should still be cached; "b" was evicted.
+    cache.read_segment(root, &database, &SegmentId("a".into())).unwrap();
+    cache.read_segment(root, &database, &SegmentId("c".into())).unwrap();
+    assert_eq!(cache.miss_count(), 3, "a and c should still be warm");
+
+    cache.read_segment(root, &database, &SegmentId("b".into())).unwrap();
+    assert_eq!(cache.miss_count(), 4, "b should have been evicted");
+}
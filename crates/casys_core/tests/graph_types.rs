@@ -2,6 +2,7 @@
 
 use casys_core::{Node, Edge, GraphReadStore, GraphWriteStore, Value, NodeId, EdgeId, EngineError};
 use std::collections::HashMap;
+use std::ops::Bound;
 
 // =============================================================================
 // Node struct tests
@@ -180,6 +181,14 @@ impl GraphReadStore for MockGraphStore {
         }
         Ok(result)
     }
+
+    fn scan_by_property_range(&self, prop: &str, lo: Bound<Value>, hi: Bound<Value>) -> Result<Vec<NodeId>, EngineError> {
+        let mut ids: Vec<NodeId> = self.nodes.values()
+            .filter_map(|n| n.properties.get(prop).filter(|v| casys_core::value_in_range(v, &lo, &hi)).map(|_| n.id))
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
 }
 
 impl GraphWriteStore for MockGraphStore {
@@ -196,6 +205,16 @@ impl GraphWriteStore for MockGraphStore {
         self.edges.insert(id, Edge { id, from_node: from, to_node: to, edge_type, properties });
         Ok(id)
     }
+
+    fn delete_node(&mut self, id: NodeId) -> Result<(), EngineError> {
+        self.nodes.remove(&id);
+        Ok(())
+    }
+
+    fn delete_edge(&mut self, id: EdgeId) -> Result<(), EngineError> {
+        self.edges.remove(&id);
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -280,6 +299,33 @@ fn test_graph_read_store_get_neighbors_incoming() {
     assert_eq!(no_incoming.len(), 0);
 }
 
+#[test]
+fn test_graph_read_store_scan_by_property_range() {
+    let mut store = MockGraphStore::new();
+    let mut younger = HashMap::new();
+    younger.insert("age".to_string(), Value::Int(17));
+    let a = store.add_node(vec![], younger).unwrap();
+
+    let mut adult = HashMap::new();
+    adult.insert("age".to_string(), Value::Int(18));
+    let b = store.add_node(vec![], adult).unwrap();
+
+    let mut senior = HashMap::new();
+    senior.insert("age".to_string(), Value::Int(65));
+    let c = store.add_node(vec![], senior).unwrap();
+
+    let working_age = store
+        .scan_by_property_range("age", Bound::Included(Value::Int(18)), Bound::Excluded(Value::Int(65)))
+        .unwrap();
+    assert_eq!(working_age, vec![b]);
+
+    let all_known_ages = store.scan_by_property_range("age", Bound::Unbounded, Bound::Unbounded).unwrap();
+    assert_eq!(all_known_ages, vec![a, b, c]);
+
+    let unset_prop = store.scan_by_property_range("height", Bound::Unbounded, Bound::Unbounded).unwrap();
+    assert!(unset_prop.is_empty());
+}
+
 // =============================================================================
 // GraphWriteStore trait tests
 // =============================================================================
@@ -328,3 +374,47 @@ fn test_graph_write_store_increments_ids() {
     assert_eq!(id2, 2);
     assert_eq!(id3, 3);
 }
+
+// =============================================================================
+// GraphReadStore default batch/range methods
+// =============================================================================
+
+#[test]
+fn test_get_nodes_default_fans_out_to_get_node_preserving_order_and_missing_ids() {
+    let mut store = MockGraphStore::new();
+    let a = store.add_node(vec!["A".into()], HashMap::new()).unwrap();
+    let b = store.add_node(vec!["B".into()], HashMap::new()).unwrap();
+
+    let fetched = store.get_nodes(&[a, 999, b]).unwrap();
+    assert_eq!(fetched[0].as_ref().unwrap().id, a);
+    assert!(fetched[1].is_none());
+    assert_eq!(fetched[2].as_ref().unwrap().id, b);
+}
+
+#[test]
+fn test_get_neighbors_batch_default_keys_results_by_node_id() {
+    let mut store = MockGraphStore::new();
+    let a = store.add_node(vec![], HashMap::new()).unwrap();
+    let b = store.add_node(vec![], HashMap::new()).unwrap();
+    let c = store.add_node(vec![], HashMap::new()).unwrap();
+    store.add_edge(a, b, "KNOWS".into(), HashMap::new()).unwrap();
+
+    let batch = store.get_neighbors_batch(&[a, c], None).unwrap();
+    assert_eq!(batch[&a].len(), 1);
+    assert_eq!(batch[&a][0].1.id, b);
+    assert!(batch[&c].is_empty());
+}
+
+#[test]
+fn test_scan_by_label_range_default_pages_and_returns_a_continuation_cursor() {
+    let mut store = MockGraphStore::new();
+    let ids: Vec<_> = (0..3).map(|_| store.add_node(vec!["Person".into()], HashMap::new()).unwrap()).collect();
+
+    let (page1, cursor1) = store.scan_by_label_range("Person", None, 2).unwrap();
+    assert_eq!(page1.iter().map(|n| n.id).collect::<Vec<_>>(), ids[0..2]);
+    assert_eq!(cursor1, Some(ids[1]));
+
+    let (page2, cursor2) = store.scan_by_label_range("Person", cursor1, 2).unwrap();
+    assert_eq!(page2.iter().map(|n| n.id).collect::<Vec<_>>(), ids[2..3]);
+    assert_eq!(cursor2, None);
+}
@@ -0,0 +1,77 @@
+//! Async facade over [`Engine`]'s durability paths, for callers already
+//! running on a tokio runtime (servers/SDKs) that would otherwise have to
+//! wrap every `commit_tx`/`flush_branch`/`load_branch` call in
+//! `spawn_blocking` themselves.
+//!
+//! The low-level primitives these paths are built on now have true async
+//! counterparts that never park the reactor thread:
+//! `casys_storage_fs::util::atomic_write_file_async` (tokio::fs
+//! create/write/rename/fsync, same temp-then-rename-then-fsync-dir
+//! ordering as the sync version) and `casys_storage_fs::lock::acquire_timeout_async`
+//! (polls the non-blocking `flock` attempt on `tokio::time::sleep` instead
+//! of `std::thread::sleep`). `StorageBackend` itself is still a synchronous
+//! trait, though, so `AsyncEngine` dispatches the actual WAL-append /
+//! manifest-publish / segment-encode work - which already holds the
+//! (synchronous) cross-process writer lock internally - onto the blocking
+//! thread pool via `tokio::task::spawn_blocking`. That keeps this a drop-in
+//! replacement for manual `spawn_blocking` wrapping today without
+//! introducing a second, independently-acquired lock that could deadlock
+//! against the one `FsBackend` already takes; a fully async `StorageBackend`
+//! built directly on the primitives above is tracked as future work.
+
+use std::sync::Arc;
+
+use crate::{BranchHandle, DbHandle, Engine, Timestamp};
+use casys_core::EngineError;
+
+/// Async wrapper around an [`Engine`]. Cheap to construct/clone: it just
+/// holds an `Arc<Engine>`.
+#[derive(Clone)]
+pub struct AsyncEngine {
+    inner: Arc<Engine>,
+}
+
+impl AsyncEngine {
+    pub fn new(engine: Arc<Engine>) -> Self {
+        Self { inner: engine }
+    }
+
+    pub fn inner(&self) -> &Engine {
+        &self.inner
+    }
+
+    /// Async mirror of [`Engine::commit_tx`].
+    pub async fn commit_tx(&self, branch: &BranchHandle, records: Vec<Vec<u8>>) -> Result<Timestamp, EngineError> {
+        let engine = self.inner.clone();
+        let branch = BranchHandle { db: branch.db.clone(), name: branch.name.clone() };
+        tokio::task::spawn_blocking(move || engine.commit_tx(&branch, &records))
+            .await
+            .map_err(|e| EngineError::StorageIo(format!("commit_tx task join: {e}")))?
+    }
+
+    /// Async mirror of [`Engine::flush_branch`].
+    pub async fn flush_branch(
+        &self,
+        db: &DbHandle,
+        branch: &BranchHandle,
+        store: Arc<crate::index::InMemoryGraphStore>,
+        compression: Option<crate::index::compression::CompressionOptions>,
+    ) -> Result<(), EngineError> {
+        let engine = self.inner.clone();
+        let db = DbHandle { name: db.name.clone() };
+        let branch = BranchHandle { db: branch.db.clone(), name: branch.name.clone() };
+        tokio::task::spawn_blocking(move || engine.flush_branch(&db, &branch, &store, compression))
+            .await
+            .map_err(|e| EngineError::StorageIo(format!("flush_branch task join: {e}")))?
+    }
+
+    /// Async mirror of [`Engine::load_branch`].
+    pub async fn load_branch(&self, db: &DbHandle, branch: &BranchHandle) -> Result<crate::index::InMemoryGraphStore, EngineError> {
+        let engine = self.inner.clone();
+        let db = DbHandle { name: db.name.clone() };
+        let branch = BranchHandle { db: branch.db.clone(), name: branch.name.clone() };
+        tokio::task::spawn_blocking(move || engine.load_branch(&db, &branch))
+            .await
+            .map_err(|e| EngineError::StorageIo(format!("load_branch task join: {e}")))?
+    }
+}
@@ -0,0 +1,83 @@
+//! Format/capability negotiation for a data directory: a small JSON file
+//! recording the on-disk format version and enabled feature set at
+//! creation, checked on every later `Engine::open`/`open_with_backend` so a
+//! binary that doesn't understand a directory's format fails fast with a
+//! structured `EngineError::IncompatibleFormat` instead of misreading it (or
+//! a generic IO error further down).
+//!
+//! Modeled on a `Capabilities`/`Version` handshake: a fixed version tuple
+//! plus a capability set where an unsupported or disabled feature simply
+//! doesn't appear in the list, so adding a feature never requires bumping
+//! the format version.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use casys_core::{Capabilities, EngineError, FormatVersion};
+
+const CAPABILITIES_FILENAME: &str = "capabilities.json";
+
+/// The format version and feature set this build knows how to read/write.
+pub const CURRENT_VERSION: FormatVersion = FormatVersion { major: 1, minor: 0 };
+pub const SUPPORTED_FEATURES: &[&str] = &["encryption", "binary-manifests"];
+
+fn supported() -> Capabilities {
+    Capabilities {
+        version: CURRENT_VERSION,
+        features: SUPPORTED_FEATURES.iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+fn capabilities_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(CAPABILITIES_FILENAME)
+}
+
+fn atomic_write(path: &Path, bytes: &[u8]) -> Result<(), EngineError> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let tmp_path = parent.join(format!(".{}.tmp-{}", path.file_name().unwrap().to_string_lossy(), ts));
+    {
+        let mut f = fs::File::create(&tmp_path)
+            .map_err(|e| EngineError::StorageIo(format!("create({}): {e}", tmp_path.display())))?;
+        f.write_all(bytes).map_err(|e| EngineError::StorageIo(format!("write({}): {e}", tmp_path.display())))?;
+        f.sync_all().map_err(|e| EngineError::StorageIo(format!("fsync({}): {e}", tmp_path.display())))?;
+    }
+    fs::rename(&tmp_path, path).map_err(|e| EngineError::StorageIo(format!("rename({}): {e}", path.display())))
+}
+
+/// On first open of `data_dir`, writes a `Capabilities` recording
+/// `CURRENT_VERSION` and `enabled_features`. On later opens, reads the
+/// recorded `Capabilities` and validates it against what this build
+/// supports: the major version must match exactly, and every recorded
+/// feature must be one this build knows about. Returns the negotiated
+/// (recorded) `Capabilities` either way, for `Engine::capabilities()`.
+pub fn negotiate(data_dir: &Path, enabled_features: &[&str]) -> Result<Capabilities, EngineError> {
+    let path = capabilities_path(data_dir);
+    if !path.exists() {
+        let found = Capabilities {
+            version: CURRENT_VERSION,
+            features: enabled_features.iter().map(|f| f.to_string()).collect(),
+        };
+        let bytes = serde_json::to_vec_pretty(&found)
+            .map_err(|e| EngineError::StorageIo(format!("serialize capabilities: {e}")))?;
+        atomic_write(&path, &bytes)?;
+        return Ok(found);
+    }
+
+    let bytes = fs::read(&path)
+        .map_err(|e| EngineError::StorageIo(format!("read({}): {e}", path.display())))?;
+    let found: Capabilities = serde_json::from_slice(&bytes)
+        .map_err(|e| EngineError::StorageIo(format!("parse capabilities ({}): {e}", path.display())))?;
+
+    let supported = supported();
+    let major_ok = found.version.major == CURRENT_VERSION.major;
+    let features_ok = found.features.iter().all(|f| supported.has(f));
+    if !major_ok || !features_ok {
+        return Err(EngineError::IncompatibleFormat { found, supported });
+    }
+    Ok(found)
+}
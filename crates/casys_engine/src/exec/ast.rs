@@ -10,13 +10,21 @@ pub struct QueryBatch {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Query {
+    /// `UNWIND expr AS var` - precedes MATCH/CREATE, driving the rest of the
+    /// query once per element of a list-valued expression.
+    pub unwind_clause: Option<UnwindClause>,
     pub match_clause: Option<MatchClause>,    // Optional MATCH
     pub create_clause: Option<CreateClause>,  // Optional CREATE
     pub with_clause: Option<WithClause>,      // Pipeline transformation
     pub where_clause: Option<WhereClause>,
+    pub set_clause: Option<SetClause>,        // SET n.prop = expr, ...
+    pub delete_clause: Option<DeleteClause>,  // DELETE / DETACH DELETE n, ...
     pub return_clause: Option<ReturnClause>,  // Optional for CREATE without RETURN
     pub order_by: Option<OrderByClause>,
     pub limit: Option<u64>,
+    /// `DEFINE NODE`/`DEFINE EDGE` DDL - a standalone statement, mutually
+    /// exclusive with every other clause above.
+    pub define_clause: Option<DefineClause>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -29,6 +37,68 @@ pub struct CreateClause {
     pub patterns: Vec<Pattern>,
 }
 
+/// `UNWIND expr AS var` - `expr` must evaluate to `Value::Array`; each
+/// element becomes `var` in its own child tuple downstream (currently only
+/// a following `CREATE`, mirroring how `MatchCreate` drives one `CREATE` per
+/// matched tuple).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnwindClause {
+    pub expr: Expr,
+    pub variable: String,
+}
+
+/// `DEFINE NODE Label { prop: Type, ... }` or
+/// `DEFINE EDGE Type (FromLabel -> ToLabel) { prop: Type, ... }`, populating
+/// `schema::SchemaRegistry` so later `CREATE`s against that label/edge type
+/// are validated rather than accepting any property bag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefineClause {
+    pub target: DefineTarget,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefineTarget {
+    Node(NodeTypeDef),
+    Edge(EdgeTypeDef),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeTypeDef {
+    pub label: String,
+    pub properties: Vec<PropertyDef>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeTypeDef {
+    pub edge_type: String,
+    pub from_label: String,
+    pub to_label: String,
+    pub properties: Vec<PropertyDef>,
+}
+
+/// One declared property: its name, its `Typing`, and whether it's a "key"
+/// (identity) column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyDef {
+    pub name: String,
+    pub typing: Typing,
+    pub key: bool,
+}
+
+/// The typing a declared node/edge property must satisfy. Mirrors
+/// `CastType` in spirit (a closed set of scalar types) but is attached to
+/// schema declarations rather than comparison coercion, and adds
+/// `Nullable`/`Any` since a DDL field can be optional or untyped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Typing {
+    Int,
+    Float,
+    String,
+    Bool,
+    Nullable(Box<Typing>),
+    Any,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Pattern {
     Node(NodePattern),
@@ -39,7 +109,7 @@ pub enum Pattern {
 pub struct NodePattern {
     pub variable: Option<String>,
     pub labels: Vec<String>,
-    pub properties: HashMap<String, Literal>,
+    pub properties: HashMap<String, Expr>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -47,7 +117,7 @@ pub struct EdgePattern {
     pub variable: Option<String>,
     pub edge_type: Option<String>,
     pub direction: Direction,
-    pub properties: HashMap<String, Literal>,
+    pub properties: HashMap<String, Expr>,
     pub from_node: Box<NodePattern>,
     pub to_node: Box<NodePattern>,
     pub depth: Option<DepthRange>,  // For variable-length paths: *min..max
@@ -59,6 +129,27 @@ pub struct DepthRange {
     pub max: u32,
 }
 
+/// Relationship/node-repeat policy for a variable-length `Expand`. Standard
+/// Cypher MATCH semantics are `Trail` (no relationship repeats within a
+/// path); `Walk` keeps this engine's pre-existing behavior of only
+/// forbidding the trivial from==to loop, and `AcyclicPath` is the stricter
+/// "simple path" mode that also forbids revisiting a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathUniqueness {
+    Walk,
+    Trail,
+    AcyclicPath,
+}
+
+/// The engine-wide default when a query doesn't request a specific mode.
+/// Matches standard Cypher MATCH semantics rather than this engine's
+/// historical `Walk` behavior.
+impl Default for PathUniqueness {
+    fn default() -> Self {
+        PathUniqueness::Trail
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Direction {
     Left,     // <-
@@ -71,6 +162,24 @@ pub struct WhereClause {
     pub expr: Expr,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetClause {
+    pub assignments: Vec<SetItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetItem {
+    pub variable: String,
+    pub property: String,
+    pub value: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteClause {
+    pub variables: Vec<String>,
+    pub detach: bool, // DETACH DELETE also removes incident edges
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct WithClause {
     pub items: Vec<WithItem>,
@@ -95,6 +204,32 @@ pub enum Expr {
     IsNull(Box<Expr>),        // expr IS NULL
     IsNotNull(Box<Expr>),     // expr IS NOT NULL
     Exists(Box<Query>),       // EXISTS { subquery } - returns true if subquery has results
+    List(Vec<Expr>),          // [expr, expr, ...] list literal
+    In(Box<Expr>, Box<Expr>), // expr IN list - true if expr equals a member of list
+    Case(CaseExpr),           // CASE [operand] WHEN ... THEN ... [ELSE ...] END
+    Cast(Box<Expr>, CastType), // Explicit coercion, inserted by `coercion::coerce_binary`
+}
+
+/// Target type for an `Expr::Cast`, covering the scalar `Literal` variants a
+/// comparison can coerce between. No `Null` variant: casting anything to/from
+/// null is handled by the existing `IS [NOT] NULL` checks, not by coercion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastType {
+    Int,
+    Float,
+    String,
+    Bool,
+}
+
+/// `CASE` in both its simple form (`CASE operand WHEN value THEN ...`, operand
+/// compared for equality against each `when`) and searched form (`CASE WHEN
+/// cond THEN ...`, each `when` evaluated as a boolean), distinguished by
+/// whether `operand` is present.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseExpr {
+    pub operand: Option<Box<Expr>>,
+    pub branches: Vec<(Expr, Expr)>, // (when, then)
+    pub else_branch: Option<Box<Expr>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -104,6 +239,18 @@ pub enum AggFunc {
     Avg,
     Min,
     Max,
+    /// `collect(expr)` - accumulates every (non-null-filtered-by-nothing, in
+    /// row order) evaluated value into a `Value::Array`.
+    Collect,
+    /// Population standard deviation, via Welford's online algorithm.
+    Stdev,
+    /// `distinct` mirrors the non-distinct variant it wraps, but folds
+    /// duplicate evaluated values (by JSON-serialized value, the same
+    /// stability trick `PlanNode::Aggregate`'s GROUP BY key uses) into a
+    /// single contribution before handing off to the wrapped function's
+    /// logic - `count(distinct x)` is `Distinct(Count)`, `sum(distinct x)` is
+    /// `Distinct(Sum)`, and so on.
+    Distinct(Box<AggFunc>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -123,11 +270,15 @@ pub enum BinOp {
     Sub,
     Mul,
     Div,
+    Mod,
+    Pow, // right-associative exponentiation
+    Coalesce, // ?? (returns the left operand unless it is Null)
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnOp {
     Not,
+    Neg,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -165,7 +316,12 @@ impl Query {
     /// Extracts all parameter names used in this query
     pub fn extract_parameters(&self) -> HashSet<String> {
         let mut params = HashSet::new();
-        
+
+        // Extract from UNWIND clause
+        if let Some(unwind_clause) = &self.unwind_clause {
+            unwind_clause.expr.collect_parameters(&mut params);
+        }
+
         // Extract from WITH clause
         if let Some(with_clause) = &self.with_clause {
             for item in &with_clause.items {
@@ -178,6 +334,13 @@ impl Query {
             where_clause.expr.collect_parameters(&mut params);
         }
         
+        // Extract from SET clause
+        if let Some(set_clause) = &self.set_clause {
+            for assignment in &set_clause.assignments {
+                assignment.value.collect_parameters(&mut params);
+            }
+        }
+
         // Extract from RETURN clause (if present)
         if let Some(ref return_clause) = self.return_clause {
             for item in &return_clause.items {
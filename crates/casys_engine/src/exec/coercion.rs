@@ -0,0 +1,87 @@
+//! Binary-comparison type coercion, modeled on DataFusion's `coerce_types`:
+//! before a comparison atom reaches the executor, reconcile the static types
+//! of its two operands into one common comparison type, wrapping whichever
+//! operand needs it in an explicit `Expr::Cast` rather than letting a
+//! mismatched pair (an int literal against a float literal) fall through to
+//! `eval_binary_op`'s type-mismatch error.
+//!
+//! Only `Literal`s have a statically known type here - a `Property`/`Ident`
+//! is graph-bound and its runtime type isn't known until evaluation, so it
+//! is never itself cast here. That includes a property inline-compared
+//! against a *quoted* number (`{x: "5"}`): casting the literal to `Int` up
+//! front would be correct when `x` turns out to hold the number 5, but
+//! wrong when `x` holds the one-character string `"5"` - and this module
+//! has no way to tell which until the property's actual value is read. That
+//! decision is left to `eval_binary_op`, which falls back to parsing a
+//! quoted number against an actual `Int`/`Float` value once both sides are
+//! concrete `Value`s.
+use super::ast::{BinOp, CastType, Expr, Literal};
+use crate::types::EngineError;
+
+/// Reconciles `lhs`/`rhs` of a `op` comparison, returning the operands with a
+/// `Cast` inserted wherever needed. Non-comparison operators (arithmetic,
+/// `AND`/`OR`, ...) pass through untouched - coercion only ever changes what
+/// a comparison atom compares.
+pub fn coerce_binary(lhs: Expr, op: &BinOp, rhs: Expr) -> Result<(Expr, Expr), EngineError> {
+    if !is_comparison(op) {
+        return Ok((lhs, rhs));
+    }
+
+    match (static_type(&lhs), static_type(&rhs)) {
+        (Some(l), Some(r)) if l == r => Ok((lhs, rhs)),
+        (Some(l), Some(r)) => {
+            let common = common_type(l, r).ok_or_else(|| {
+                EngineError::InvalidArgument(format!("cannot compare {:?} and {:?}: no common type", l, r))
+            })?;
+            Ok((cast_to(lhs, l, common), cast_to(rhs, r, common)))
+        }
+        // Any pairing against a graph-bound expression (a `Literal` vs a
+        // `Property`/`Ident`, including a quoted number vs an untyped
+        // property), or neither side a `Literal` at all, is left for
+        // `eval_binary_op` to resolve once the property's actual value is known.
+        (Some(_), None) | (None, Some(_)) | (None, None) => Ok((lhs, rhs)),
+    }
+}
+
+fn is_comparison(op: &BinOp) -> bool {
+    matches!(op, BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge)
+}
+
+/// The type a `Literal` expression is known to produce, or `None` for
+/// anything whose type depends on graph data (or on `Null`, which compares
+/// via `IS [NOT] NULL` rather than coercion).
+fn static_type(expr: &Expr) -> Option<CastType> {
+    match expr {
+        Expr::Literal(Literal::Int(_)) => Some(CastType::Int),
+        Expr::Literal(Literal::Float(_)) => Some(CastType::Float),
+        Expr::Literal(Literal::String(_)) => Some(CastType::String),
+        Expr::Literal(Literal::Bool(_)) => Some(CastType::Bool),
+        _ => None,
+    }
+}
+
+/// The type two `Literal`s should both be coerced to for comparison, or
+/// `None` if the pairing is genuinely incomparable (e.g. `Bool` against
+/// `String`). Numeric pairs widen to `Float`; a numeric paired with a
+/// `String` coerces the string to that numeric type.
+fn common_type(l: CastType, r: CastType) -> Option<CastType> {
+    use CastType::*;
+    match (l, r) {
+        (Int, Int) => Some(Int),
+        (Float, Float) => Some(Float),
+        (String, String) => Some(String),
+        (Bool, Bool) => Some(Bool),
+        (Int, Float) | (Float, Int) => Some(Float),
+        (Int, String) | (String, Int) => Some(Int),
+        (Float, String) | (String, Float) => Some(Float),
+        _ => None,
+    }
+}
+
+fn cast_to(expr: Expr, from: CastType, to: CastType) -> Expr {
+    if from == to {
+        expr
+    } else {
+        Expr::Cast(Box::new(expr), to)
+    }
+}
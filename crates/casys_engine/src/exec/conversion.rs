@@ -0,0 +1,124 @@
+//! `toInteger`/`toFloat`/`toString`/`toBoolean`/`timestamp` property-expression
+//! conversions. Modeled as a `Conversion` enum rather than one function per
+//! name so `Executor::eval_expr`'s `FunctionCall` arm resolves the name to a
+//! variant once, then calls `convert` uniformly regardless of which
+//! conversion was requested.
+
+use crate::types::EngineError;
+use casys_core::Value;
+
+/// A single requested scalar conversion. `TimestampFmt`/`TimestampTzFmt` carry
+/// the explicit `chrono`-style format string `timestamp(expr, fmt)` /
+/// `timestamp(expr, fmt, tz)` was called with; plain `Timestamp` falls back to
+/// RFC3339 parsing. `TimestampTzFmt`'s format is expected to include an offset
+/// specifier (e.g. `%z`) so the source string's own timezone - not UTC - is
+/// used to compute the instant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    TimestampFmt(String),
+    TimestampTzFmt(String),
+}
+
+impl Conversion {
+    pub fn convert(&self, v: Value) -> Result<Value, EngineError> {
+        match self {
+            Conversion::Bytes => Ok(Value::String(display(&v))),
+            Conversion::Integer => to_integer(v),
+            Conversion::Float => to_float(v),
+            Conversion::Boolean => to_boolean(v),
+            Conversion::Timestamp => to_timestamp(v, None, false),
+            Conversion::TimestampFmt(fmt) => to_timestamp(v, Some(fmt), false),
+            Conversion::TimestampTzFmt(fmt) => to_timestamp(v, Some(fmt), true),
+        }
+    }
+}
+
+fn display(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn to_integer(v: Value) -> Result<Value, EngineError> {
+    match v {
+        Value::Int(i) => Ok(Value::Int(i)),
+        Value::Float(f) => Ok(Value::Int(f as i64)),
+        Value::Bool(b) => Ok(Value::Int(b as i64)),
+        Value::String(s) => s
+            .trim()
+            .parse::<i64>()
+            .or_else(|_| s.trim().parse::<f64>().map(|f| f as i64))
+            .map(Value::Int)
+            .map_err(|_| EngineError::InvalidArgument(format!("toInteger(): cannot parse {s:?} as an integer"))),
+        other => Err(EngineError::InvalidArgument(format!("toInteger(): cannot convert {other:?}"))),
+    }
+}
+
+fn to_float(v: Value) -> Result<Value, EngineError> {
+    match v {
+        Value::Float(f) => Ok(Value::Float(f)),
+        Value::Int(i) => Ok(Value::Float(i as f64)),
+        Value::String(s) => s
+            .trim()
+            .parse::<f64>()
+            .map(Value::Float)
+            .map_err(|_| EngineError::InvalidArgument(format!("toFloat(): cannot parse {s:?} as a float"))),
+        other => Err(EngineError::InvalidArgument(format!("toFloat(): cannot convert {other:?}"))),
+    }
+}
+
+fn to_boolean(v: Value) -> Result<Value, EngineError> {
+    match v {
+        Value::Bool(b) => Ok(Value::Bool(b)),
+        Value::Int(i) => Ok(Value::Bool(i != 0)),
+        Value::String(s) => match s.trim() {
+            s if s.eq_ignore_ascii_case("true") => Ok(Value::Bool(true)),
+            s if s.eq_ignore_ascii_case("false") => Ok(Value::Bool(false)),
+            other => Err(EngineError::InvalidArgument(format!("toBoolean(): cannot parse {other:?} as a boolean"))),
+        },
+        other => Err(EngineError::InvalidArgument(format!("toBoolean(): cannot convert {other:?}"))),
+    }
+}
+
+/// Parses a timestamp string to epoch-millis. With no `fmt`, tries RFC3339
+/// first (honoring its offset) and falls back to a bare `%Y-%m-%d %H:%M:%S`
+/// (treated as UTC).
+/// With `fmt` and `tz_aware`, the format is expected to contain an offset
+/// specifier and the source string's own offset is honored; without
+/// `tz_aware`, the parsed local time is treated as UTC.
+fn to_timestamp(v: Value, fmt: Option<&str>, tz_aware: bool) -> Result<Value, EngineError> {
+    let Value::String(s) = v else {
+        return Err(EngineError::InvalidArgument("timestamp(): requires a string argument".into()));
+    };
+    let millis = if tz_aware {
+        let fmt = fmt.ok_or_else(|| {
+            EngineError::InvalidArgument("timestamp(): a timezone-aware parse requires a format string".into())
+        })?;
+        chrono::DateTime::parse_from_str(&s, fmt)
+            .map_err(|e| EngineError::InvalidArgument(format!("timestamp(): {e}")))?
+            .timestamp_millis()
+    } else if let Some(fmt) = fmt {
+        chrono::NaiveDateTime::parse_from_str(&s, fmt)
+            .map_err(|e| EngineError::InvalidArgument(format!("timestamp(): {e}")))?
+            .and_utc()
+            .timestamp_millis()
+    } else {
+        chrono::DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.timestamp_millis())
+            .or_else(|_| {
+                chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").map(|dt| dt.and_utc().timestamp_millis())
+            })
+            .map_err(|e| EngineError::InvalidArgument(format!("timestamp(): cannot parse {s:?}: {e}")))?
+    };
+    Ok(Value::Int(millis))
+}
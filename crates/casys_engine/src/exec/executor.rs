@@ -1,15 +1,217 @@
 //! Executor: exécute le plan via itérateurs
 
 use super::planner::{ExecutionPlan, PlanNode};
-use super::ast::{Expr, BinOp, UnOp, Literal, AggFunc, Pattern};
+use super::ast::{Expr, BinOp, UnOp, Literal, AggFunc, Pattern, CastType, PathUniqueness};
+use super::functions::ExternalFunctionInvoker;
 use crate::types::{EngineError, QueryResult, ColumnMeta};
 use crate::index::{GraphReadStore, GraphWriteStore};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 // Re-export Value from casys_core (unified type across crates)
 pub use casys_core::Value;
 
-pub type Tuple = HashMap<String, Value>;
+/// Column layout shared by every `Tuple` produced off the same scan/join, so
+/// adding a column resolves the name to a position once instead of re-hashing
+/// a string key on every `get`/`insert`. Interned via `Arc` in `Tuple` so
+/// tuples that never acquire a new column (the overwhelming majority - most
+/// pipeline stages only read or replace existing columns) share one `Schema`
+/// instead of each carrying its own copy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Schema {
+    columns: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl Schema {
+    fn index_of(&self, key: &str) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+}
+
+/// A single row flowing through the executor: a flat `Vec<Value>` alongside
+/// an interned `Schema` mapping column name to position. Replaces the
+/// earlier `HashMap<String, Value>` representation - scans and joins copy a
+/// schema pointer plus a value vector instead of re-hashing every column on
+/// every clone. New columns (a scan binding, a `var.prop` lookup) extend the
+/// schema copy-on-write via `Arc::make_mut`: in place when this tuple owns
+/// the only reference (the common case, since schemas fan out from a single
+/// scan), cloned first when siblings still share it.
+#[derive(Debug, Clone)]
+pub struct Tuple {
+    schema: Arc<Schema>,
+    values: Vec<Value>,
+    /// Provenance tag under the active `Provenance` semiring - `one()` until
+    /// `execute_create` reads a `_weight` property or a join/`extend` combines
+    /// it (⊗) with another tuple's tag. Both shipped semirings agree on
+    /// `mul`/`one()`, so this stays a plain `f64` rather than threading the
+    /// executor's chosen semiring through every tuple.
+    tag: f64,
+}
+
+impl Default for Tuple {
+    fn default() -> Self {
+        Self { schema: Arc::default(), values: Vec::new(), tag: 1.0 }
+    }
+}
+
+impl Tuple {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn provenance(&self) -> f64 {
+        self.tag
+    }
+
+    pub fn set_provenance(&mut self, tag: f64) {
+        self.tag = tag;
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.schema.index_of(key).and_then(|i| self.values.get(i))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.schema.index_of(key).is_some()
+    }
+
+    /// Sets `key` to `value`, appending a new column if `key` hasn't been
+    /// seen on this tuple's schema yet. Returns the previous value, matching
+    /// `HashMap::insert`'s signature so call sites didn't need to change.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        if let Some(idx) = self.schema.index_of(&key) {
+            Some(std::mem::replace(&mut self.values[idx], value))
+        } else {
+            let schema = Arc::make_mut(&mut self.schema);
+            schema.index.insert(key.clone(), schema.columns.len());
+            schema.columns.push(key);
+            self.values.push(value);
+            None
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.schema.columns.iter()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.schema.columns.iter().zip(self.values.iter())
+    }
+
+    /// Merges `other`'s columns into this tuple, overwriting on conflict -
+    /// the same semantics `CartesianProduct`/`HashJoin` need when combining
+    /// two branches that bound the same variable. Provenance tags combine via
+    /// ⊗ (multiplication) - both shipped semirings agree on `mul`, so this
+    /// doesn't need the executor's chosen `Provenance` instance.
+    pub fn extend(&mut self, other: Tuple) {
+        self.tag *= other.tag;
+        for (key, value) in other {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl IntoIterator for Tuple {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let columns = self.schema.columns.clone();
+        columns.into_iter().zip(self.values).collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl<'t> IntoIterator for &'t Tuple {
+    type Item = (&'t String, &'t Value);
+    type IntoIter = std::iter::Zip<std::slice::Iter<'t, String>, std::slice::Iter<'t, Value>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.schema.columns.iter().zip(self.values.iter())
+    }
+}
+
+/// One sorted run spilled to a temp file by `Executor::sort_with_spill`:
+/// each line is a JSON object `{"key": [...], "tuple": [[col, value], ...],
+/// "tag": <f64>}` so the k-way merge can compare keys without
+/// re-evaluating `ORDER BY` expressions, and rebuild the winning row's
+/// `Tuple` (provenance tag included) only once it's actually popped off the
+/// merge heap. `tuple` is stored as an ordered list of pairs rather than a
+/// JSON object so column order survives the round trip regardless of how
+/// `serde_json` orders object keys. The backing file is removed on drop so
+/// an aborted or erroring query doesn't leak it.
+struct SpillRun {
+    reader: std::io::BufReader<std::fs::File>,
+    path: std::path::PathBuf,
+}
+
+impl SpillRun {
+    /// Writes `rows` (already sorted) to a fresh temp file and opens it for
+    /// reading back.
+    fn create(rows: &[(Vec<Value>, Tuple)]) -> Result<Self, EngineError> {
+        use std::io::Write;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static NEXT_RUN_ID: AtomicU64 = AtomicU64::new(0);
+        let run_id = NEXT_RUN_ID.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "casys-orderby-{}-{run_id:x}.jsonl",
+            std::process::id(),
+        ));
+        let mut file = std::fs::File::create(&path)
+            .map_err(|e| EngineError::StorageIo(format!("create({}): {e}", path.display())))?;
+        for (key, tuple) in rows {
+            let line = serde_json::json!({
+                "key": key.iter().map(|v| v.to_json()).collect::<Vec<_>>(),
+                "tuple": tuple.iter().map(|(k, v)| serde_json::json!([k, v.to_json()])).collect::<Vec<_>>(),
+                "tag": tuple.provenance(),
+            });
+            writeln!(file, "{}", line)
+                .map_err(|e| EngineError::StorageIo(format!("write({}): {e}", path.display())))?;
+        }
+        file.sync_all().map_err(|e| EngineError::StorageIo(format!("fsync({}): {e}", path.display())))?;
+
+        let reader = std::io::BufReader::new(
+            std::fs::File::open(&path).map_err(|e| EngineError::StorageIo(format!("open({}): {e}", path.display())))?,
+        );
+        Ok(SpillRun { reader, path })
+    }
+
+    /// Reads and parses this run's next `(key, tuple)` row, or `None` once
+    /// the run is exhausted.
+    fn next(&mut self) -> Result<Option<(Vec<Value>, Tuple)>, EngineError> {
+        use std::io::BufRead;
+
+        let mut line = String::new();
+        let n = self.reader.read_line(&mut line)
+            .map_err(|e| EngineError::StorageIo(format!("read({}): {e}", self.path.display())))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        let parsed: serde_json::Value = serde_json::from_str(line.trim_end())
+            .map_err(|e| EngineError::StorageIo(format!("parse sort spill run ({}): {e}", self.path.display())))?;
+
+        let key = parsed["key"].as_array().cloned().unwrap_or_default()
+            .iter().map(|v| Value::from_json(v).unwrap_or(Value::Null)).collect();
+
+        let mut tuple = Tuple::new();
+        for pair in parsed["tuple"].as_array().cloned().unwrap_or_default() {
+            if let [col, val] = pair.as_array().map(|a| a.as_slice()).unwrap_or(&[]) {
+                if let Some(name) = col.as_str() {
+                    tuple.insert(name.to_string(), Value::from_json(val).unwrap_or(Value::Null));
+                }
+            }
+        }
+        tuple.set_provenance(parsed["tag"].as_f64().unwrap_or(1.0));
+        Ok(Some((key, tuple)))
+    }
+}
+
+impl Drop for SpillRun {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
 
 #[derive(Default)]
 struct ExecCounters {
@@ -17,6 +219,166 @@ struct ExecCounters {
     expanded: u64,
 }
 
+/// What `dijkstra_path` (backing the `shortestPath(...)` scalar function) is
+/// searching for: either one specific node, or the first node reached
+/// carrying a given label.
+enum PathTarget {
+    Id(u64),
+    Label(String),
+}
+
+/// Incremental per-aggregate accumulator, shared by the in-memory GROUP BY
+/// path and `merge_and_aggregate_runs`'s streaming spill path so the two
+/// can't drift on what `Sum`/`Avg`/`Min`/`Max` treat as a valid numeric
+/// value. `Sum`/`Avg`/`Count`/`Min`/`Max`/`Collect`/`Stdev` are all
+/// associative/commutative (or, for `Collect`, order-dependent - the spill
+/// merge breaks ties by run index, so a group's rows still arrive in input
+/// order), which is what makes
+/// folding them over a merged, group-sorted stream (rather than a fully
+/// materialized per-group `Vec<Tuple>`) produce the same result as the
+/// single-pass version.
+enum Accumulator {
+    Count(i64),
+    Sum(f64),
+    Avg { sum: f64, count: usize },
+    /// Type-preserving, via `casys_core::value_cmp` - an integer `MIN` stays
+    /// an `Int`, a string `MIN` stays a `String`, instead of every result
+    /// being coerced through `f64`. `Null` values are skipped, same as a
+    /// failed/non-numeric eval was skipped before.
+    Min(Option<Value>),
+    Max(Option<Value>),
+    /// `collect(expr)`: every evaluated value, in the order accumulated.
+    Collect(Vec<Value>),
+    /// Population standard deviation via Welford's online algorithm -
+    /// `count`/`mean`/`m2` are the running values the algorithm tracks, and
+    /// `finish` derives the final variance (`m2 / count`) and its sqrt.
+    Stdev { count: usize, mean: f64, m2: f64 },
+    /// Wraps another accumulator, folding in only the first occurrence of
+    /// each evaluated value (by JSON-serialized value, the same stability
+    /// trick `PlanNode::Aggregate`'s GROUP BY key uses, since `Value` isn't
+    /// `Hash`). `count(DISTINCT x)` is `Distinct(Count(_))`, etc.
+    Distinct { seen: HashSet<String>, inner: Box<Accumulator> },
+}
+
+impl Accumulator {
+    fn new(func: &AggFunc) -> Self {
+        match func {
+            AggFunc::Count => Accumulator::Count(0),
+            AggFunc::Sum => Accumulator::Sum(0.0),
+            AggFunc::Avg => Accumulator::Avg { sum: 0.0, count: 0 },
+            AggFunc::Min => Accumulator::Min(None),
+            AggFunc::Max => Accumulator::Max(None),
+            AggFunc::Collect => Accumulator::Collect(Vec::new()),
+            AggFunc::Stdev => Accumulator::Stdev { count: 0, mean: 0.0, m2: 0.0 },
+            AggFunc::Distinct(inner) => {
+                Accumulator::Distinct { seen: HashSet::new(), inner: Box::new(Self::new(inner)) }
+            }
+        }
+    }
+
+    /// Builds the right accumulator kind for `agg_expr`, defaulting to
+    /// `Count` if it isn't actually an `Expr::Aggregate` - which shouldn't
+    /// happen since the planner only ever puts `Expr::Aggregate` values into
+    /// `PlanNode::Aggregate::aggregates`.
+    fn new_for(agg_expr: &Expr) -> Self {
+        match agg_expr {
+            Expr::Aggregate(func, _) => Self::new(func),
+            _ => Accumulator::Count(0),
+        }
+    }
+
+    /// Evaluates `agg_expr`'s argument against `tuple` and folds it in -
+    /// plain `Count` counts the row unconditionally without evaluating the
+    /// argument (mirroring `COUNT(*)` not needing a meaningful expression);
+    /// everything else, including a `Distinct`-wrapped `Count`, evaluates
+    /// the argument first since distinctness is judged on its value.
+    fn accumulate_row(&mut self, executor: &Executor<'_>, agg_expr: &Expr, tuple: &Tuple) {
+        let Expr::Aggregate(_, arg) = agg_expr else { return };
+        if let Accumulator::Count(n) = self {
+            *n += 1;
+            return;
+        }
+        let Ok(value) = executor.eval_expr(arg, tuple, None) else { return };
+        self.accumulate_value(value);
+    }
+
+    /// Folds an already-evaluated value in, silently skipping it where the
+    /// accumulator kind can't make use of it (non-numeric for `Sum`/`Avg`/
+    /// `Stdev`, `Null` for `Min`/`Max`).
+    fn accumulate_value(&mut self, value: Value) {
+        match self {
+            Accumulator::Count(n) => *n += 1,
+            Accumulator::Sum(sum) => {
+                if let Some(v) = Self::as_f64(&value) {
+                    *sum += v;
+                }
+            }
+            Accumulator::Avg { sum, count } => {
+                if let Some(v) = Self::as_f64(&value) {
+                    *sum += v;
+                    *count += 1;
+                }
+            }
+            Accumulator::Min(best) => {
+                if !matches!(value, Value::Null) {
+                    *best = Some(match best.take() {
+                        None => value,
+                        Some(b) => if casys_core::value_cmp(&value, &b) == std::cmp::Ordering::Less { value } else { b },
+                    });
+                }
+            }
+            Accumulator::Max(best) => {
+                if !matches!(value, Value::Null) {
+                    *best = Some(match best.take() {
+                        None => value,
+                        Some(b) => if casys_core::value_cmp(&value, &b) == std::cmp::Ordering::Greater { value } else { b },
+                    });
+                }
+            }
+            Accumulator::Collect(items) => items.push(value),
+            Accumulator::Stdev { count, mean, m2 } => {
+                if let Some(x) = Self::as_f64(&value) {
+                    *count += 1;
+                    let delta = x - *mean;
+                    *mean += delta / *count as f64;
+                    *m2 += delta * (x - *mean);
+                }
+            }
+            Accumulator::Distinct { seen, inner } => {
+                let key = serde_json::to_string(&value.to_json()).unwrap_or_else(|_| "null".to_string());
+                if seen.insert(key) {
+                    inner.accumulate_value(value);
+                }
+            }
+        }
+    }
+
+    fn as_f64(value: &Value) -> Option<f64> {
+        match value {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn finish(self) -> Value {
+        match self {
+            Accumulator::Count(n) => Value::Int(n),
+            Accumulator::Sum(sum) => Value::Float(sum),
+            Accumulator::Avg { sum, count } => {
+                if count == 0 { Value::Null } else { Value::Float(sum / count as f64) }
+            }
+            Accumulator::Min(best) => best.unwrap_or(Value::Null),
+            Accumulator::Max(best) => best.unwrap_or(Value::Null),
+            Accumulator::Collect(items) => Value::Array(items),
+            Accumulator::Stdev { count, m2, .. } => {
+                if count == 0 { Value::Null } else { Value::Float((m2 / count as f64).sqrt()) }
+            }
+            Accumulator::Distinct { inner, .. } => inner.finish(),
+        }
+    }
+}
+
 /// Extension trait for Value to provide JSON conversion methods
 /// These methods are engine-specific and use serde_json which is not in casys_core
 pub trait ValueExt {
@@ -99,16 +461,37 @@ fn base64_encode(data: &[u8]) -> String {
     result
 }
 
+/// Row-count threshold past which `OrderBy` spills sorted runs to temp files
+/// instead of sorting the whole result set in memory. Overridable per
+/// `Executor` via `with_sort_spill_threshold`.
+const DEFAULT_SORT_SPILL_THRESHOLD: usize = 100_000;
+
+/// Row-count threshold past which a `GROUP BY` aggregation spills
+/// group-key-sorted runs to temp files instead of holding every group's rows
+/// resident in a `HashMap`. Overridable per `Executor` via
+/// `with_agg_spill_threshold`.
+const DEFAULT_AGG_SPILL_THRESHOLD: usize = 100_000;
+
 pub struct Executor<'a> {
     read: Option<&'a dyn GraphReadStore>,
     parameters: HashMap<String, Value>,
+    external_functions: HashMap<String, Arc<dyn ExternalFunctionInvoker>>,
+    sort_spill_threshold: usize,
+    agg_spill_threshold: usize,
+    schema: super::schema::SchemaRegistry,
+    semiring: Arc<dyn super::provenance::Provenance>,
 }
 
 impl<'a> Executor<'a> {
     pub fn new(read: &'a dyn GraphReadStore) -> Self {
-        Self { 
+        Self {
             read: Some(read),
             parameters: HashMap::new(),
+            external_functions: HashMap::new(),
+            sort_spill_threshold: DEFAULT_SORT_SPILL_THRESHOLD,
+            agg_spill_threshold: DEFAULT_AGG_SPILL_THRESHOLD,
+            schema: super::schema::SchemaRegistry::new(),
+            semiring: Arc::new(super::provenance::MaxMinProb),
         }
     }
 
@@ -116,15 +499,80 @@ impl<'a> Executor<'a> {
         Self {
             read: None,
             parameters: HashMap::new(),
+            external_functions: HashMap::new(),
+            sort_spill_threshold: DEFAULT_SORT_SPILL_THRESHOLD,
+            agg_spill_threshold: DEFAULT_AGG_SPILL_THRESHOLD,
+            schema: super::schema::SchemaRegistry::new(),
+            semiring: Arc::new(super::provenance::MaxMinProb),
         }
     }
-    
+
     pub fn with_parameters(read: &'a dyn GraphReadStore, parameters: HashMap<String, Value>) -> Self {
-        Self { read: Some(read), parameters }
+        Self {
+            read: Some(read),
+            parameters,
+            external_functions: HashMap::new(),
+            sort_spill_threshold: DEFAULT_SORT_SPILL_THRESHOLD,
+            agg_spill_threshold: DEFAULT_AGG_SPILL_THRESHOLD,
+            schema: super::schema::SchemaRegistry::new(),
+            semiring: Arc::new(super::provenance::MaxMinProb),
+        }
     }
 
     pub fn with_parameters_no_read(parameters: HashMap<String, Value>) -> Self {
-        Self { read: None, parameters }
+        Self {
+            read: None,
+            parameters,
+            external_functions: HashMap::new(),
+            sort_spill_threshold: DEFAULT_SORT_SPILL_THRESHOLD,
+            agg_spill_threshold: DEFAULT_AGG_SPILL_THRESHOLD,
+            schema: super::schema::SchemaRegistry::new(),
+            semiring: Arc::new(super::provenance::MaxMinProb),
+        }
+    }
+
+    /// Attaches host-registered scalar functions (e.g. the napi/pyo3 callback
+    /// bridges), consulted by `FunctionCall` evaluation once the built-in
+    /// names (`ID`, ...) don't match. Chainable so callers can append it to
+    /// whichever constructor above they already use.
+    pub fn with_external_functions(mut self, external_functions: HashMap<String, Arc<dyn ExternalFunctionInvoker>>) -> Self {
+        self.external_functions = external_functions;
+        self
+    }
+
+    /// Attaches the declared node/edge schema `execute_create` validates
+    /// against. Chainable like `with_external_functions`; an `Executor`
+    /// without one (the default) validates nothing, same as before this
+    /// existed.
+    pub fn with_schema(mut self, schema: super::schema::SchemaRegistry) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Overrides the `Provenance` semiring combining tuple tags on `GROUP BY`
+    /// (⊕, when several rows collapse into one group) - joins always use ⊗
+    /// (multiplication), which every shipped semiring agrees on, so only ⊕
+    /// is actually pluggable. Chainable like `with_external_functions`;
+    /// defaults to `MaxMinProb`.
+    pub fn with_semiring(mut self, semiring: Arc<dyn super::provenance::Provenance>) -> Self {
+        self.semiring = semiring;
+        self
+    }
+
+    /// Overrides the row-count threshold past which `ORDER BY` switches from
+    /// an in-memory sort to spilling sorted runs to temp files and merging
+    /// them. Chainable like `with_external_functions`.
+    pub fn with_sort_spill_threshold(mut self, sort_spill_threshold: usize) -> Self {
+        self.sort_spill_threshold = sort_spill_threshold;
+        self
+    }
+
+    /// Overrides the row-count threshold past which `GROUP BY` switches from
+    /// an in-memory `HashMap` of groups to spilling group-key-sorted runs to
+    /// temp files and merging them. Chainable like `with_external_functions`.
+    pub fn with_agg_spill_threshold(mut self, agg_spill_threshold: usize) -> Self {
+        self.agg_spill_threshold = agg_spill_threshold;
+        self
     }
 
     pub fn execute(&self, plan: &ExecutionPlan, write: Option<&mut dyn GraphWriteStore>) -> Result<QueryResult, EngineError> {
@@ -176,17 +624,23 @@ impl<'a> Executor<'a> {
                 rows.push(row);
             }
         } else {
-            // Fallback: derive columns from first tuple (unordered)
+            // Fallback: derive columns from the first tuple's schema. Every
+            // tuple reaching here came off the same plan branch and so
+            // shares that schema, letting the row loop below read `values`
+            // by position instead of re-resolving each column name.
             if let Some(first) = tuples.first() {
                 for key in first.keys() {
                     columns.push(ColumnMeta { name: key.clone(), r#type: "any".to_string() });
                 }
             }
             for tuple in tuples {
-                let mut row = Vec::new();
-                for col in &columns {
-                    let val = tuple.get(&col.name).cloned().unwrap_or(Value::Null);
-                    row.push(val.to_json());
+                let mut row: Vec<serde_json::Value> = tuple.values.iter().map(|v| v.to_json()).collect();
+                // A tuple whose schema diverged from `first` (e.g. an
+                // optional branch that never bound a later column) is
+                // padded/truncated by name so `row.len()` still matches
+                // `columns.len()`.
+                if row.len() != columns.len() {
+                    row = columns.iter().map(|col| tuple.get(&col.name).cloned().unwrap_or(Value::Null).to_json()).collect();
                 }
                 rows.push(row);
             }
@@ -201,19 +655,21 @@ impl<'a> Executor<'a> {
     }
 
     fn execute_node(&self, node: &PlanNode, write: &mut Option<&mut dyn GraphWriteStore>, counters: &mut ExecCounters) -> Result<Vec<Tuple>, EngineError> {
-        self.execute_node_with_context(node, &HashMap::new(), write, counters)
+        self.execute_node_with_context(node, &Tuple::new(), write, counters)
     }
     fn execute_node_with_context(&self, node: &PlanNode, parent_tuple: &Tuple, write: &mut Option<&mut dyn GraphWriteStore>, counters: &mut ExecCounters) -> Result<Vec<Tuple>, EngineError> {
         match node {
             PlanNode::Create { patterns } => {
                 if let Some(w) = write.as_deref_mut() {
-                    self.execute_create(patterns, parent_tuple, Some(w))
+                    self.execute_create(patterns, std::slice::from_ref(parent_tuple), Some(w))
                 } else {
                     Err(EngineError::InvalidArgument("CREATE requires a write-capable store".into()))
                 }
             }
             PlanNode::MatchCreate { match_input, create_patterns } => {
-                // For each matched tuple, execute CREATE with that context
+                // Drive CREATE off the whole matched-tuple stream at once,
+                // so it's the stream (not this call site) that decides how
+                // many output rows come back.
                 let match_tuples = {
                     let t = self.execute_node_with_context(match_input, parent_tuple, write, counters)?;
                     if std::env::var("CASYS_DEBUG_PLAN").ok().as_deref() == Some("1") {
@@ -222,12 +678,15 @@ impl<'a> Executor<'a> {
                     t
                 };
                 if let Some(wi) = write.as_deref_mut() {
-                    let mut all_results = Vec::new();
-                    for tuple in match_tuples {
-                        let created = self.execute_create(create_patterns, &tuple, Some(wi))?;
-                        all_results.extend(created);
-                    }
-                    Ok(all_results)
+                    self.execute_create(create_patterns, &match_tuples, Some(wi))
+                } else {
+                    Err(EngineError::InvalidArgument("CREATE requires a write-capable store".into()))
+                }
+            }
+            PlanNode::UnwindCreate { expr, variable, create_patterns } => {
+                let unwound = self.execute_unwind(expr, variable, parent_tuple)?;
+                if let Some(wi) = write.as_deref_mut() {
+                    self.execute_create(create_patterns, &unwound, Some(wi))
                 } else {
                     Err(EngineError::InvalidArgument("CREATE requires a write-capable store".into()))
                 }
@@ -246,11 +705,80 @@ impl<'a> Executor<'a> {
                         for (k, v) in right_tuple {
                             combined.insert(k.clone(), v.clone());
                         }
+                        // ⊗: this combined row derives from both branches.
+                        combined.set_provenance(combined.provenance() * right_tuple.provenance());
                         result.push(combined);
                     }
                 }
                 Ok(result)
             }
+            PlanNode::HashJoin { left, right, join_keys } => {
+                let left_tuples = self.execute_node_with_context(left, parent_tuple, write, counters)?;
+                let right_tuples = self.execute_node_with_context(right, parent_tuple, write, counters)?;
+
+                if join_keys.is_empty() {
+                    // No equi-join conjunct to hash on: the same merge a
+                    // `CartesianProduct` does.
+                    let mut result = Vec::new();
+                    for left_tuple in &left_tuples {
+                        for right_tuple in &right_tuples {
+                            let mut combined = left_tuple.clone();
+                            combined.extend(right_tuple.clone());
+                            result.push(combined);
+                        }
+                    }
+                    return Ok(result);
+                }
+
+                let join_key_of = |side: &[(&Expr, &Expr)], tuple: &Tuple, pick: impl Fn(&(&Expr, &Expr)) -> &Expr| -> String {
+                    let values: Vec<_> = side
+                        .iter()
+                        .map(|pair| self.eval_expr(pick(pair), tuple, None).unwrap_or(Value::Null).to_json())
+                        .collect();
+                    serde_json::to_string(&values).unwrap_or_else(|_| "null".to_string())
+                };
+                let keys: Vec<(&Expr, &Expr)> = join_keys.iter().map(|(l, r)| (l, r)).collect();
+
+                // Drain the smaller side into the hash table, stream the
+                // larger one as the probe - same cost tradeoff as a textbook
+                // hash join.
+                let (build, probe, build_is_left) = if left_tuples.len() <= right_tuples.len() {
+                    (left_tuples, right_tuples, true)
+                } else {
+                    (right_tuples, left_tuples, false)
+                };
+
+                let mut table: HashMap<String, Vec<Tuple>> = HashMap::new();
+                for tuple in build {
+                    let key = if build_is_left {
+                        join_key_of(&keys, &tuple, |(l, _)| l)
+                    } else {
+                        join_key_of(&keys, &tuple, |(_, r)| r)
+                    };
+                    table.entry(key).or_default().push(tuple);
+                }
+
+                let mut result = Vec::new();
+                for probe_tuple in probe {
+                    let key = if build_is_left {
+                        join_key_of(&keys, &probe_tuple, |(_, r)| r)
+                    } else {
+                        join_key_of(&keys, &probe_tuple, |(l, _)| l)
+                    };
+                    if let Some(matches) = table.get(&key) {
+                        for build_tuple in matches {
+                            // Mirror the existing CartesianProduct merge: the
+                            // side that overwrites on key conflict is whichever
+                            // is logically "right" of the pair.
+                            let mut combined = if build_is_left { build_tuple.clone() } else { probe_tuple.clone() };
+                            let other = if build_is_left { &probe_tuple } else { build_tuple };
+                            combined.extend(other.clone());
+                            result.push(combined);
+                        }
+                    }
+                }
+                Ok(result)
+            }
             PlanNode::LabelScan { variable, label } => {
                 // Check if variable already exists in parent tuple (correlated subquery)
                 if let Some(existing_node_id) = parent_tuple.get(variable) {
@@ -336,6 +864,9 @@ impl<'a> Executor<'a> {
                 }).collect())
             }
             PlanNode::Filter { input, predicate } => {
+                if let Some(tuples) = self.try_discrimination_scan(node, parent_tuple, write, counters)? {
+                    return Ok(tuples);
+                }
                 let tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
                 Ok(tuples.into_iter().filter(|t| {
                     self.eval_expr(predicate, t, None).ok()
@@ -349,7 +880,8 @@ impl<'a> Executor<'a> {
             PlanNode::Project { input, items } => {
                 let tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
                 Ok(tuples.into_iter().map(|t| {
-                    let mut result = HashMap::new();
+                    let mut result = Tuple::new();
+                    result.set_provenance(t.provenance());
                     for item in items {
                         if let Ok(val) = self.eval_expr(&item.expr, &t, None) {
                             let key = item.alias.clone().unwrap_or_else(|| {
@@ -369,17 +901,26 @@ impl<'a> Executor<'a> {
                 let tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
                 
                 if group_by.is_empty() {
-                    // Global aggregation (no GROUP BY)
-                    let mut result = HashMap::new();
+                    // Global aggregation (no GROUP BY). All of `tuples` is
+                    // one group, so its provenance tag combines the same way
+                    // the GROUP BY branch below combines each group's: via
+                    // ⊕ (the semiring's `add`) over every input tuple's tag,
+                    // not the default `Tuple::new` weight of 1.0.
+                    let mut result = Tuple::new();
+                    let tag = tuples.iter().map(Tuple::provenance)
+                        .fold(self.semiring.zero(), |acc, t| self.semiring.add(acc, t));
+                    result.set_provenance(tag);
                     for (alias, agg_expr) in aggregates {
                         let val = self.eval_aggregate(agg_expr, &tuples, None)?;
                         result.insert(alias.clone(), val);
                     }
                     Ok(vec![result])
+                } else if tuples.len() > self.agg_spill_threshold {
+                    self.aggregate_with_spill(tuples, group_by, aggregates)
                 } else {
                     // GROUP BY aggregation
                     let mut groups: HashMap<Vec<String>, Vec<Tuple>> = HashMap::new();
-                    
+
                     // Group tuples by group_by expressions
                     for tuple in tuples {
                         let mut group_key = Vec::new();
@@ -391,66 +932,47 @@ impl<'a> Executor<'a> {
                         }
                         groups.entry(group_key).or_insert_with(Vec::new).push(tuple);
                     }
-                    
+
                     // Compute aggregates for each group
                     let mut results = Vec::new();
                     for (_group_key, group_tuples) in groups {
-                        let mut result = HashMap::new();
-                        
-                        // Add GROUP BY columns (from first tuple of group)
-                        if let Some(first) = group_tuples.first() {
-                            for (idx, expr) in group_by.iter().enumerate() {
-                                let val = self.eval_expr(expr, first, None)?;
-                                let key = match expr {
-                                    Expr::Ident(name) => name.clone(),
-                                    Expr::Property(var, prop) => format!("{}.{}", var, prop),
-                                    _ => format!("group_{}", idx),
-                                };
-                                result.insert(key, val);
+                        let first = group_tuples.first().cloned().unwrap_or_else(Tuple::new);
+                        let mut accumulators: Vec<Accumulator> =
+                            aggregates.iter().map(|(_, agg_expr)| Accumulator::new_for(agg_expr)).collect();
+                        for tuple in &group_tuples {
+                            for (acc, (_, agg_expr)) in accumulators.iter_mut().zip(aggregates) {
+                                acc.accumulate_row(self, agg_expr, tuple);
                             }
                         }
-                        
-                        // Compute aggregates
-                        for (alias, agg_expr) in aggregates {
-                            let val = self.eval_aggregate(agg_expr, &group_tuples, None)?;
-                            result.insert(alias.clone(), val);
-                        }
-                        
-                        results.push(result);
+                        // The group's rows are alternative derivations of the
+                        // same result, so their provenance tags combine via
+                        // ⊕ (the semiring's `add`) rather than simply keeping
+                        // the first tuple's tag.
+                        let tag = group_tuples.iter().map(Tuple::provenance)
+                            .fold(self.semiring.zero(), |acc, t| self.semiring.add(acc, t));
+                        let mut group_result = self.finalize_aggregate_group(group_by, aggregates, &first, accumulators)?;
+                        group_result.set_provenance(tag);
+                        results.push(group_result);
                     }
-                    
+
                     Ok(results)
                 }
             }
             PlanNode::OrderBy { input, items } => {
-                let mut tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
-                tuples.sort_by(|a, b| {
-                    for item in items {
-                        let val_a = self.eval_expr(&item.expr, a, None).ok();
-                        let val_b = self.eval_expr(&item.expr, b, None).ok();
-                        let cmp = match (val_a, val_b) {
-                            (Some(Value::Int(ia)), Some(Value::Int(ib))) => ia.cmp(&ib),
-                            (Some(Value::Float(fa)), Some(Value::Float(fb))) => {
-                                fa.partial_cmp(&fb).unwrap_or(std::cmp::Ordering::Equal)
-                            }
-                            (Some(Value::String(sa)), Some(Value::String(sb))) => sa.cmp(&sb),
-                            _ => std::cmp::Ordering::Equal,
-                        };
-                        if cmp != std::cmp::Ordering::Equal {
-                            return if item.descending { cmp.reverse() } else { cmp };
-                        }
-                    }
-                    std::cmp::Ordering::Equal
-                });
-                Ok(tuples)
+                let tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
+                if tuples.len() > self.sort_spill_threshold {
+                    self.sort_with_spill(tuples, items)
+                } else {
+                    Ok(self.sort_in_memory(tuples, items))
+                }
             }
             PlanNode::Limit { input, count } => {
                 let tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
                 Ok(tuples.into_iter().take(*count as usize).collect())
             }
-            PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth } => {
+            PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional } => {
                 use super::ast::Direction;
-                
+
                 let input_tuples = { self.execute_node_with_context(input, parent_tuple, write, counters)? };
                 let mut result = Vec::new();
                 // Resolve reader once to avoid repeated mutable borrows of `write`
@@ -458,6 +980,7 @@ impl<'a> Executor<'a> {
 
                 for tuple in input_tuples {
                     // Get from_node_id
+                    let before = result.len();
                     if let Some(Value::NodeId(from_id)) = tuple.get(from_var) {
                         // Check if variable-length path
                         if let Some(depth_range) = depth {
@@ -474,6 +997,7 @@ impl<'a> Executor<'a> {
                                 direction.clone(),
                                 depth_range.min,
                                 depth_range.max,
+                                *path_uniqueness,
                             )?;
                             let debug = std::env::var("CASYS_DEBUG_EXPAND").ok().as_deref() == Some("1");
                             if debug {
@@ -564,63 +1088,735 @@ impl<'a> Executor<'a> {
                             }
                         }
                     }
+                    // Left-join semantics: no matching neighbor (or `from_var`
+                    // wasn't even a node) still produces a row, with `to_var`
+                    // (and `edge_var`, if present) bound to null instead of
+                    // being dropped.
+                    if *optional && result.len() == before {
+                        let mut new_tuple = tuple.clone();
+                        new_tuple.insert(to_var.clone(), Value::Null);
+                        if let Some(ref ev) = edge_var {
+                            new_tuple.insert(ev.clone(), Value::Null);
+                            new_tuple.insert(format!("{}.edge_type", ev), Value::Null);
+                        }
+                        result.push(new_tuple);
+                    }
                 }
-                
+
+                Ok(result)
+            }
+            PlanNode::ShortestPath { input, from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var } => {
+                let input_tuples = self.execute_node_with_context(input, parent_tuple, write, counters)?;
+                let mut result = Vec::new();
+                let reader: &dyn GraphReadStore = if let Some(r) = self.read { r } else if let Some(w) = write.as_deref_mut() { w } else { return Ok(result) };
+
+                let edge_types: Vec<&str> = if let Some(et) = edge_type {
+                    et.split('|').map(|s| s.trim()).filter(|s| !s.is_empty()).collect()
+                } else {
+                    Vec::new()
+                };
+
+                for tuple in input_tuples {
+                    let (Some(Value::NodeId(from_id)), Some(Value::NodeId(to_id))) = (tuple.get(from_var), tuple.get(to_var)) else { continue };
+                    if let Some((path, cost)) = self.astar_shortest_path(
+                        reader,
+                        *from_id,
+                        *to_id,
+                        &edge_types,
+                        direction,
+                        weight_prop.as_deref(),
+                        heuristic.as_ref(),
+                        to_var,
+                        counters,
+                    )? {
+                        let mut new_tuple = tuple.clone();
+                        new_tuple.insert(path_var.clone(), Value::Array(path.into_iter().map(Value::NodeId).collect()));
+                        new_tuple.insert(cost_var.clone(), Value::Float(cost));
+                        result.push(new_tuple);
+                    }
+                }
+
+                Ok(result)
+            }
+            PlanNode::GraphAlgo { name, args, yield_cols } => {
+                let reader: &dyn GraphReadStore = if let Some(r) = self.read { r } else if let Some(w) = write.as_deref_mut() { w } else { return Ok(Vec::new()) };
+
+                let edge_type = match args.get("edge_type") {
+                    Some(super::ast::Literal::String(s)) => Some(s.as_str()),
+                    _ => None,
+                };
+                let nodes = reader.scan_all()?;
+                counters.scanned += nodes.len() as u64;
+                let node_ids: Vec<u64> = nodes.iter().map(|n| n.id).collect();
+
+                let scores = match name.as_str() {
+                    "betweenness" => crate::gds::betweenness_centrality(reader, &node_ids, edge_type)?,
+                    "closeness" => crate::gds::closeness_centrality(reader, &node_ids, edge_type)?,
+                    other => return Err(EngineError::InvalidArgument(format!("unknown graph algorithm {:?}", other))),
+                };
+                // One BFS per node, same as the scan it replaces.
+                counters.expanded += node_ids.len() as u64;
+
+                let node_col = yield_cols.first().cloned().unwrap_or_else(|| "node".to_string());
+                let score_col = yield_cols.get(1).cloned().unwrap_or_else(|| "score".to_string());
+                let result = node_ids
+                    .into_iter()
+                    .map(|id| {
+                        let mut tuple = Tuple::new();
+                        tuple.insert(node_col.clone(), Value::NodeId(id));
+                        tuple.insert(score_col.clone(), Value::Float(scores.get(&id).copied().unwrap_or(0.0)));
+                        tuple
+                    })
+                    .collect();
+
                 Ok(result)
             }
+            PlanNode::Fixpoint { seed, recursive, bind_var } => {
+                // Iteration cap: a `recursive` subplan that keeps producing
+                // "new" tuples forever (e.g. one whose dedup key isn't
+                // actually stable) would otherwise hang the query instead of
+                // failing it.
+                const MAX_ROUNDS: usize = 10_000;
+
+                let mut delta = self.execute_node_with_context(seed, parent_tuple, write, counters)?;
+                let mut seen: HashSet<String> = HashSet::new();
+                delta.retain(|tuple| seen.insert(Self::fixpoint_dedup_key(tuple)));
+                let mut accumulated = delta.clone();
+
+                let mut round = 0usize;
+                while !delta.is_empty() {
+                    round += 1;
+                    if round > MAX_ROUNDS {
+                        return Err(EngineError::InvalidArgument(format!(
+                            "Fixpoint over {} exceeded {} rounds without converging",
+                            bind_var, MAX_ROUNDS
+                        )));
+                    }
+                    let mut next_delta = Vec::new();
+                    for frontier_tuple in &delta {
+                        let produced = self.execute_node_with_context(recursive, frontier_tuple, write, counters)?;
+                        for tuple in produced {
+                            if seen.insert(Self::fixpoint_dedup_key(&tuple)) {
+                                next_delta.push(tuple);
+                            }
+                        }
+                    }
+                    accumulated.extend(next_delta.iter().cloned());
+                    delta = next_delta;
+                }
+
+                Ok(accumulated)
+            }
         }
     }
 
-    /// Traverse variable-length paths using BFS with optional union edge types and direction
-    fn traverse_variable_length(
+    /// Canonicalized dedup key for one `Fixpoint` round: every column
+    /// serialized via `to_json`, so two tuples that bind the same values
+    /// under the same column names collapse to one regardless of the order
+    /// scans happened to add those columns in.
+    fn fixpoint_dedup_key(tuple: &Tuple) -> String {
+        let cols: Vec<_> = tuple.iter().map(|(k, v)| (k.clone(), v.to_json())).collect();
+        serde_json::to_string(&cols).unwrap_or_else(|_| "null".to_string())
+    }
+
+    /// If `filter_node` is a (possibly multi-layer, since `push_down_predicates`
+    /// re-inserts a WHERE clause's conjuncts one `Filter` per atom) chain of
+    /// `Filter`s bottoming out at an uncorrelated `LabelScan`/`FullScan`, and
+    /// those layers' conjuncts decompose into at least one `var.prop =
+    /// <literal>` atom, probes the reader's per-property index
+    /// (`scan_by_property_range` pinned to a single value) instead of
+    /// materializing every row and running `eval_expr` over it. Intersects
+    /// candidate ids across every indexable conjunct, applies any remaining
+    /// (non-equality, or not on this variable) conjuncts as a residual
+    /// `eval_expr` check on the smaller candidate set, and returns `None` to
+    /// fall back to the plain scan-then-filter path when no conjunct
+    /// qualifies or the chain doesn't bottom out at a plain scan.
+    fn try_discrimination_scan(
         &self,
-        reader: &dyn GraphReadStore,
-        start_id: u64,
-        edge_types: &[&str],
-        direction: super::ast::Direction,
-        min_depth: u32,
-        max_depth: u32,
-    ) -> Result<Vec<crate::index::Node>, EngineError> {
-        use std::collections::{HashSet, VecDeque};
-        use super::ast::Direction;
-        
+        filter_node: &PlanNode,
+        parent_tuple: &Tuple,
+        write: &mut Option<&mut dyn GraphWriteStore>,
+        counters: &mut ExecCounters,
+    ) -> Result<Option<Vec<Tuple>>, EngineError> {
+        let mut conjuncts = Vec::new();
+        let mut cur = filter_node;
+        let (variable, label) = loop {
+            match cur {
+                PlanNode::Filter { input, predicate } => {
+                    conjuncts.extend(super::plan_optimizer::split_conjuncts(predicate.clone()));
+                    cur = input;
+                }
+                PlanNode::LabelScan { variable, label } => break (variable, Some(label.as_str())),
+                PlanNode::FullScan { variable } => break (variable, None),
+                _ => return Ok(None),
+            }
+        };
+        // A correlated scan (the variable is already bound by an outer row)
+        // isn't what this pre-pass targets - the plain scan path already
+        // resolves it directly without touching the property index at all.
+        if parent_tuple.contains_key(variable) {
+            return Ok(None);
+        }
+        let reader: &dyn GraphReadStore = if let Some(r) = self.read { r } else if let Some(w) = write.as_deref_mut() { w } else { return Ok(None) };
+
+        let mut candidate_ids: Option<HashSet<u64>> = None;
+        let mut residual = Vec::new();
+        for atom in conjuncts {
+            match self.constant_property_eq(&atom, variable) {
+                Some((prop, value)) => {
+                    let mut ids = HashSet::new();
+                    for probe in Self::numeric_probe_values(value) {
+                        ids.extend(reader.scan_by_property_range(
+                            &prop,
+                            std::ops::Bound::Included(probe.clone()),
+                            std::ops::Bound::Included(probe),
+                        )?);
+                    }
+                    candidate_ids = Some(match candidate_ids.take() {
+                        Some(existing) => existing.intersection(&ids).copied().collect(),
+                        None => ids,
+                    });
+                }
+                None => residual.push(atom),
+            }
+        }
+        let Some(candidate_ids) = candidate_ids else {
+            // No `var.prop = <literal>` conjunct to probe an index with.
+            return Ok(None);
+        };
+        counters.scanned += candidate_ids.len() as u64;
+
+        let residual_predicate = super::plan_optimizer::join_conjuncts(residual);
         let mut result = Vec::new();
-        let mut visited = HashSet::new();
-        let mut queue = VecDeque::new();
-        
-        // BFS: (node_id, current_depth)
-        queue.push_back((start_id, 0));
-        visited.insert(start_id);
-        
-        let debug = std::env::var("CASYS_DEBUG_EXPAND").ok().as_deref() == Some("1");
-        while let Some((node_id, depth)) = queue.pop_front() {
-            if debug {
-                println!("BFS pop node {} at depth {}", node_id, depth);
+        for id in candidate_ids {
+            let Some(node) = reader.get_node(id)? else { continue };
+            if let Some(label) = label {
+                if !node.labels.iter().any(|l| l == label) {
+                    continue;
+                }
             }
-            // If we've reached max depth, stop expanding from this node
-            if depth >= max_depth {
-                continue;
+            let mut tuple = parent_tuple.clone();
+            tuple.insert(variable.clone(), Value::NodeId(id));
+            for (k, v) in node.properties {
+                tuple.insert(format!("{}.{}", variable, k), v);
             }
-            
-            // Collect neighbors according to direction
-            let mut neighbors = match direction {
-                Direction::Right => reader.get_neighbors(node_id, None)?,
-                Direction::Left => reader.get_neighbors_incoming(node_id, None)?,
-                Direction::Both => {
-                    let mut out = reader.get_neighbors(node_id, None)?;
-                    let incoming = reader.get_neighbors_incoming(node_id, None)?;
-                    out.extend(incoming);
-                    out
+            if let Some(ref residual_predicate) = residual_predicate {
+                let keep = self.eval_expr(residual_predicate, &tuple, None).ok()
+                    .and_then(|v| match v {
+                        Value::Bool(b) => Some(b),
+                        _ => None,
+                    })
+                    .unwrap_or(false);
+                if !keep {
+                    continue;
                 }
-            };
-            if debug {
+            }
+            result.push(tuple);
+        }
+        Ok(Some(result))
+    }
+
+    /// `(property, literal)` if `atom` is `var.prop = <literal>` (in either
+    /// operand order) with `var` matching `variable`, or `None` for anything
+    /// else - an atom over a different variable, a non-equality comparison,
+    /// or one side that isn't a bare property/literal.
+    fn constant_property_eq(&self, atom: &Expr, variable: &str) -> Option<(String, Value)> {
+        let Expr::BinaryOp(l, BinOp::Eq, r) = atom else { return None };
+        match (l.as_ref(), r.as_ref()) {
+            (Expr::Property(var, prop), Expr::Literal(lit)) if var == variable => {
+                Some((prop.clone(), self.eval_literal(lit).ok()?))
+            }
+            (Expr::Literal(lit), Expr::Property(var, prop)) if var == variable => {
+                Some((prop.clone(), self.eval_literal(lit).ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Every `Value` representation `value` could be stored under and still
+    /// compare equal per `eval_binary_op`'s mixed int/float coercion - an
+    /// integral `Float` also probes the `Int` index bucket and vice versa,
+    /// and a quoted number (`"5"`) also probes the `Int`/`Float` buckets its
+    /// parsed form would land in - so the index probe agrees with row-by-row
+    /// `eval_expr` on nodes whose value is stored as an `Int` or `Float`. A
+    /// numeric `value` does not probe for the value stored as a numeric
+    /// string, which `eval_binary_op` would still compare equal.
+    fn numeric_probe_values(value: Value) -> Vec<Value> {
+        match value {
+            Value::Int(i) => vec![Value::Int(i), Value::Float(i as f64)],
+            Value::Float(f) if f.is_finite() && f.fract() == 0.0 => vec![Value::Int(f as i64), Value::Float(f)],
+            Value::String(ref s) if s.parse::<i64>().is_ok() => {
+                vec![Value::Int(s.parse().unwrap()), Value::Float(s.parse().unwrap()), value.clone()]
+            }
+            Value::String(ref s) if s.parse::<f64>().is_ok() => {
+                vec![Value::Float(s.parse().unwrap()), value.clone()]
+            }
+            other => vec![other],
+        }
+    }
+
+    /// Total order over `Value` for `ORDER BY`: delegates to
+    /// `casys_core::value_cmp`'s type-rank-then-payload ordering (the same
+    /// comparator property range indexes use), except `Null` always sorts
+    /// last regardless of direction - `value_cmp` ranks it first, which
+    /// suits index range scans but not `ORDER BY`'s usual NULLS-LAST
+    /// expectation.
+    fn order_value_cmp(a: &Value, b: &Value) -> std::cmp::Ordering {
+        match (a, b) {
+            (Value::Null, Value::Null) => std::cmp::Ordering::Equal,
+            (Value::Null, _) => std::cmp::Ordering::Greater,
+            (_, Value::Null) => std::cmp::Ordering::Less,
+            _ => casys_core::value_cmp(a, b),
+        }
+    }
+
+    /// Compares two rows' pre-evaluated `ORDER BY` keys key-by-key, applying
+    /// each item's `descending` flag and falling through to the next key on
+    /// a tie.
+    fn compare_order_keys(items: &[super::ast::OrderByItem], a: &[Value], b: &[Value]) -> std::cmp::Ordering {
+        for (item, (va, vb)) in items.iter().zip(a.iter().zip(b.iter())) {
+            let cmp = Self::order_value_cmp(va, vb);
+            if cmp != std::cmp::Ordering::Equal {
+                return if item.descending { cmp.reverse() } else { cmp };
+            }
+        }
+        std::cmp::Ordering::Equal
+    }
+
+    /// Evaluates every `ORDER BY` item's expression against `tuple` once, so
+    /// both the in-memory sort and the spill-to-disk path compare
+    /// pre-computed keys instead of re-running `eval_expr` on every
+    /// comparison. A key expression that fails to evaluate (e.g. a missing
+    /// property) sorts as `Null`.
+    fn order_keys(&self, items: &[super::ast::OrderByItem], tuple: &Tuple) -> Vec<Value> {
+        items.iter().map(|item| self.eval_expr(&item.expr, tuple, None).unwrap_or(Value::Null)).collect()
+    }
+
+    /// Sorts the whole result set in memory - the common path, used while
+    /// `tuples.len()` stays under `sort_spill_threshold`.
+    fn sort_in_memory(&self, tuples: Vec<Tuple>, items: &[super::ast::OrderByItem]) -> Vec<Tuple> {
+        let mut keyed: Vec<(Vec<Value>, Tuple)> =
+            tuples.into_iter().map(|t| (self.order_keys(items, &t), t)).collect();
+        keyed.sort_by(|(ka, _), (kb, _)| Self::compare_order_keys(items, ka, kb));
+        keyed.into_iter().map(|(_, t)| t).collect()
+    }
+
+    /// External sort for result sets over `sort_spill_threshold`: chunks
+    /// `tuples` into in-memory-sortable runs, spills each sorted run to a
+    /// temp file, then k-way merges the runs back into one sorted `Vec`
+    /// without ever materializing the whole input in memory at once.
+    fn sort_with_spill(&self, tuples: Vec<Tuple>, items: &[super::ast::OrderByItem]) -> Result<Vec<Tuple>, EngineError> {
+        let mut runs = Vec::new();
+        let mut remaining = tuples.into_iter();
+        loop {
+            let chunk: Vec<Tuple> = remaining.by_ref().take(self.sort_spill_threshold).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let mut keyed: Vec<(Vec<Value>, Tuple)> =
+                chunk.into_iter().map(|t| (self.order_keys(items, &t), t)).collect();
+            keyed.sort_by(|(ka, _), (kb, _)| Self::compare_order_keys(items, ka, kb));
+            runs.push(SpillRun::create(&keyed)?);
+        }
+        Self::merge_runs(runs, items)
+    }
+
+    /// K-way merges already-sorted `runs` via a min-heap of run cursors,
+    /// mirroring the `Frontier` heap in `astar_shortest_path`: each heap
+    /// entry is the smallest unread row of one run, and popping it advances
+    /// that run's cursor by one line.
+    fn merge_runs(mut runs: Vec<SpillRun>, items: &[super::ast::OrderByItem]) -> Result<Vec<Tuple>, EngineError> {
+        use std::cmp::{Ordering, Reverse};
+        use std::collections::BinaryHeap;
+
+        /// A run's current key, borrowing `items` so `Ord` can apply the
+        /// per-column `descending` flags without threading them through
+        /// every comparison call.
+        struct MergeKey<'k> {
+            items: &'k [super::ast::OrderByItem],
+            values: Vec<Value>,
+        }
+        impl PartialEq for MergeKey<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+        impl Eq for MergeKey<'_> {}
+        impl PartialOrd for MergeKey<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for MergeKey<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                Executor::compare_order_keys(self.items, &self.values, &other.values)
+            }
+        }
+
+        struct HeapEntry<'k> {
+            key: MergeKey<'k>,
+            tuple: Tuple,
+            run: usize,
+        }
+        impl PartialEq for HeapEntry<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key && self.run == other.run
+            }
+        }
+        impl Eq for HeapEntry<'_> {}
+        impl PartialOrd for HeapEntry<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for HeapEntry<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.key.cmp(&other.key).then_with(|| self.run.cmp(&other.run))
+            }
+        }
+
+        let mut heap: BinaryHeap<Reverse<HeapEntry>> = BinaryHeap::new();
+        for (run_idx, run) in runs.iter_mut().enumerate() {
+            if let Some((values, tuple)) = run.next()? {
+                heap.push(Reverse(HeapEntry { key: MergeKey { items, values }, tuple, run: run_idx }));
+            }
+        }
+
+        let mut result = Vec::new();
+        while let Some(Reverse(entry)) = heap.pop() {
+            result.push(entry.tuple);
+            if let Some((values, tuple)) = runs[entry.run].next()? {
+                heap.push(Reverse(HeapEntry { key: MergeKey { items, values }, tuple, run: entry.run }));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Weighted shortest path between `start` and `goal` via A*: a binary
+    /// heap ordered by `f = g + h`, where `g` is the accumulated sum of
+    /// `weight_prop` off each traversed edge (missing/non-numeric defaults
+    /// to `1.0`) and `h` is `heuristic` evaluated against the candidate
+    /// node's properties bound under `to_var` (so it reads the same
+    /// `to_var.prop` keys `eval_expr` already resolves for `Expand`), or
+    /// `0.0` with no `heuristic` - reducing this to plain Dijkstra. Returns
+    /// `None` if `goal` isn't reachable, and
+    /// `EngineError::InvalidArgument` if any traversed edge has a negative
+    /// weight, since A*/Dijkstra aren't correct over those.
+    #[allow(clippy::too_many_arguments)]
+    fn astar_shortest_path(
+        &self,
+        reader: &dyn GraphReadStore,
+        start: u64,
+        goal: u64,
+        edge_types: &[&str],
+        direction: &super::ast::Direction,
+        weight_prop: Option<&str>,
+        heuristic: Option<&Expr>,
+        to_var: &str,
+        counters: &mut ExecCounters,
+    ) -> Result<Option<(Vec<u64>, f64)>, EngineError> {
+        use super::ast::Direction;
+        use std::cmp::Ordering;
+        use std::collections::BinaryHeap;
+
+        struct Frontier {
+            f: f64,
+            g: f64,
+            node: u64,
+        }
+        impl PartialEq for Frontier {
+            fn eq(&self, other: &Self) -> bool {
+                self.f == other.f && self.node == other.node
+            }
+        }
+        impl Eq for Frontier {}
+        impl Ord for Frontier {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other.f.partial_cmp(&self.f).unwrap_or(Ordering::Equal).then_with(|| other.node.cmp(&self.node))
+            }
+        }
+        impl PartialOrd for Frontier {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        let heuristic_of = |id: u64, props: &HashMap<String, Value>| -> Result<f64, EngineError> {
+            let Some(expr) = heuristic else { return Ok(0.0) };
+            let mut h_tuple = Tuple::new();
+            h_tuple.insert(to_var.to_string(), Value::NodeId(id));
+            for (k, v) in props {
+                h_tuple.insert(format!("{}.{}", to_var, k), v.clone());
+            }
+            match self.eval_expr(expr, &h_tuple, None)? {
+                Value::Int(i) => Ok(i as f64),
+                Value::Float(f) => Ok(f),
+                other => Err(EngineError::InvalidArgument(format!(
+                    "shortest-path heuristic must evaluate to a number, got {:?}", other
+                ))),
+            }
+        };
+
+        let mut g_score: HashMap<u64, f64> = HashMap::new();
+        let mut came_from: HashMap<u64, u64> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+
+        g_score.insert(start, 0.0);
+        let h0 = match reader.get_node(start)? {
+            Some(node) => heuristic_of(start, &node.properties)?,
+            None => 0.0,
+        };
+        heap.push(Frontier { f: h0, g: 0.0, node: start });
+
+        while let Some(Frontier { f: _, g, node }) = heap.pop() {
+            if node == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while current != start {
+                    current = came_from[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Ok(Some((path, g)));
+            }
+            if g > *g_score.get(&node).unwrap_or(&f64::INFINITY) {
+                continue; // a cheaper path to `node` was already found and popped
+            }
+
+            let mut neighbors = match direction {
+                Direction::Right => reader.get_neighbors(node, None)?,
+                Direction::Left => reader.get_neighbors_incoming(node, None)?,
+                Direction::Both => {
+                    let mut out = reader.get_neighbors(node, None)?;
+                    out.extend(reader.get_neighbors_incoming(node, None)?);
+                    out
+                }
+            };
+            if !edge_types.is_empty() {
+                neighbors.retain(|(edge, _)| edge_types.contains(&edge.edge_type.as_str()));
+            }
+            counters.expanded += neighbors.len() as u64;
+
+            for (edge, neighbor) in neighbors {
+                let weight = match weight_prop.and_then(|p| edge.properties.get(p)) {
+                    Some(Value::Int(i)) => *i as f64,
+                    Some(Value::Float(f)) => *f,
+                    _ => 1.0,
+                };
+                if weight < 0.0 {
+                    return Err(EngineError::InvalidArgument(format!(
+                        "shortest path does not support negative edge weights (edge {} has weight {weight})",
+                        edge.id
+                    )));
+                }
+                let tentative_g = g + weight;
+                if tentative_g < *g_score.get(&neighbor.id).unwrap_or(&f64::INFINITY) {
+                    g_score.insert(neighbor.id, tentative_g);
+                    came_from.insert(neighbor.id, node);
+                    let h = heuristic_of(neighbor.id, &neighbor.properties)?;
+                    heap.push(Frontier { f: tentative_g + h, g: tentative_g, node: neighbor.id });
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Dijkstra's algorithm over a binary-heap frontier of neighbors, used by
+    /// the `shortestPath(...)` scalar function: unlike `astar_shortest_path`
+    /// (which targets one already-bound node and threads a heuristic through
+    /// the priority), this targets the first node matching `target` - a
+    /// specific id or any node carrying a given label - reached within
+    /// `[min_depth, max_depth]` hops, and returns the full path as an
+    /// alternating `[NodeId, edge id, NodeId, edge id, ..., NodeId]` array
+    /// rather than just the node ids.
+    fn dijkstra_path(
+        &self,
+        reader: &dyn GraphReadStore,
+        start_id: u64,
+        target: &PathTarget,
+        edge_types: &[&str],
+        direction: super::ast::Direction,
+        weight_prop: &str,
+        min_depth: u32,
+        max_depth: u32,
+    ) -> Result<Option<Vec<Value>>, EngineError> {
+        use super::ast::Direction;
+        use std::cmp::Ordering;
+        use std::collections::BinaryHeap;
+
+        struct Frontier {
+            cost: f64,
+            node: u64,
+            hops: u32,
+        }
+        impl PartialEq for Frontier {
+            fn eq(&self, other: &Self) -> bool {
+                self.cost == other.cost && self.node == other.node
+            }
+        }
+        impl Eq for Frontier {}
+        impl Ord for Frontier {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal).then_with(|| other.node.cmp(&self.node))
+            }
+        }
+        impl PartialOrd for Frontier {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        let reaches_target = |node_id: u64| -> Result<bool, EngineError> {
+            match target {
+                PathTarget::Id(id) => Ok(node_id == *id),
+                PathTarget::Label(label) => Ok(reader.get_node(node_id)?
+                    .map(|n| n.labels.iter().any(|l| l == label))
+                    .unwrap_or(false)),
+            }
+        };
+
+        let mut best_cost: HashMap<u64, f64> = HashMap::new();
+        let mut predecessor: HashMap<u64, (u64, u64)> = HashMap::new(); // node -> (edge_id, prev_node)
+        let mut heap = BinaryHeap::new();
+
+        best_cost.insert(start_id, 0.0);
+        heap.push(Frontier { cost: 0.0, node: start_id, hops: 0 });
+
+        while let Some(Frontier { cost, node, hops }) = heap.pop() {
+            if hops >= min_depth && hops <= max_depth && reaches_target(node)? {
+                let mut path = vec![Value::NodeId(node)];
+                let mut current = node;
+                while current != start_id {
+                    let (edge_id, prev) = predecessor[&current];
+                    path.push(Value::Int(edge_id as i64));
+                    path.push(Value::NodeId(prev));
+                    current = prev;
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            if hops >= max_depth {
+                continue;
+            }
+            if cost > *best_cost.get(&node).unwrap_or(&f64::INFINITY) {
+                continue; // a cheaper path to `node` was already found and popped
+            }
+
+            let mut neighbors = match direction {
+                Direction::Right => reader.get_neighbors(node, None)?,
+                Direction::Left => reader.get_neighbors_incoming(node, None)?,
+                Direction::Both => {
+                    let mut out = reader.get_neighbors(node, None)?;
+                    out.extend(reader.get_neighbors_incoming(node, None)?);
+                    out
+                }
+            };
+            if !edge_types.is_empty() {
+                neighbors.retain(|(edge, _)| edge_types.contains(&edge.edge_type.as_str()));
+            }
+
+            for (edge, neighbor) in neighbors {
+                let weight = match edge.properties.get(weight_prop) {
+                    Some(Value::Int(i)) => *i as f64,
+                    Some(Value::Float(f)) => *f,
+                    _ => 1.0,
+                };
+                if weight < 0.0 {
+                    return Err(EngineError::InvalidArgument(format!(
+                        "shortestPath does not support negative edge weights (edge {} has weight {weight})",
+                        edge.id
+                    )));
+                }
+                let tentative_cost = cost + weight;
+                if tentative_cost < *best_cost.get(&neighbor.id).unwrap_or(&f64::INFINITY) {
+                    best_cost.insert(neighbor.id, tentative_cost);
+                    predecessor.insert(neighbor.id, (edge.id, node));
+                    heap.push(Frontier { cost: tentative_cost, node: neighbor.id, hops: hops + 1 });
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Traverse variable-length paths with optional union edge types and
+    /// direction, dispatching to the traversal matching `uniqueness`. `Walk`
+    /// keeps the original global-visited BFS (cheapest, and the only mode
+    /// that doesn't need per-path state); `Trail`/`AcyclicPath` carry the
+    /// visited edges/nodes along each partial path so a node still reachable
+    /// by a non-repeating path isn't pruned just because some other path
+    /// already reached it.
+    fn traverse_variable_length(
+        &self,
+        reader: &dyn GraphReadStore,
+        start_id: u64,
+        edge_types: &[&str],
+        direction: super::ast::Direction,
+        min_depth: u32,
+        max_depth: u32,
+        uniqueness: PathUniqueness,
+    ) -> Result<Vec<crate::index::Node>, EngineError> {
+        match uniqueness {
+            PathUniqueness::Walk => {
+                self.traverse_walk(reader, start_id, edge_types, direction, min_depth, max_depth)
+            }
+            PathUniqueness::Trail | PathUniqueness::AcyclicPath => self.traverse_unique_paths(
+                reader, start_id, edge_types, direction, min_depth, max_depth, uniqueness,
+            ),
+        }
+    }
+
+    /// Traverse variable-length paths using BFS with optional union edge types and direction
+    fn traverse_walk(
+        &self,
+        reader: &dyn GraphReadStore,
+        start_id: u64,
+        edge_types: &[&str],
+        direction: super::ast::Direction,
+        min_depth: u32,
+        max_depth: u32,
+    ) -> Result<Vec<crate::index::Node>, EngineError> {
+        use std::collections::{HashSet, VecDeque};
+        use super::ast::Direction;
+
+        let mut result = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        // BFS: (node_id, current_depth)
+        queue.push_back((start_id, 0));
+        visited.insert(start_id);
+
+        let debug = std::env::var("CASYS_DEBUG_EXPAND").ok().as_deref() == Some("1");
+        while let Some((node_id, depth)) = queue.pop_front() {
+            if debug {
+                println!("BFS pop node {} at depth {}", node_id, depth);
+            }
+            // If we've reached max depth, stop expanding from this node
+            if depth >= max_depth {
+                continue;
+            }
+
+            // Collect neighbors according to direction
+            let mut neighbors = match direction {
+                Direction::Right => reader.get_neighbors(node_id, None)?,
+                Direction::Left => reader.get_neighbors_incoming(node_id, None)?,
+                Direction::Both => {
+                    let mut out = reader.get_neighbors(node_id, None)?;
+                    let incoming = reader.get_neighbors_incoming(node_id, None)?;
+                    out.extend(incoming);
+                    out
+                }
+            };
+            if debug {
                 println!(
                     "Neighbors before type filter for node {}: {}",
                     node_id,
                     neighbors.len()
                 );
             }
-            
+
             // Filter by edge types if provided
             if !edge_types.is_empty() {
                 neighbors.retain(|(edge, _)| edge_types.contains(&edge.edge_type.as_str()));
@@ -633,7 +1829,7 @@ impl<'a> Executor<'a> {
                     );
                 }
             }
-            
+
             for (_edge, to_node) in neighbors {
                 if visited.contains(&to_node.id) {
                     continue;
@@ -661,7 +1857,93 @@ impl<'a> Executor<'a> {
                 }
             }
         }
-        
+
+        Ok(result)
+    }
+
+    /// Traverse variable-length paths under `Trail` (no repeated edge) or
+    /// `AcyclicPath` (no repeated node) semantics. Unlike `traverse_walk`,
+    /// visited state travels with each partial path rather than living in one
+    /// global set, since a node pruned on one path may still be reachable via
+    /// a different, non-repeating one. `result` is still deduplicated by node
+    /// id - callers only need the reachable set, not the paths themselves.
+    fn traverse_unique_paths(
+        &self,
+        reader: &dyn GraphReadStore,
+        start_id: u64,
+        edge_types: &[&str],
+        direction: super::ast::Direction,
+        min_depth: u32,
+        max_depth: u32,
+        uniqueness: PathUniqueness,
+    ) -> Result<Vec<crate::index::Node>, EngineError> {
+        use std::collections::{HashSet, VecDeque};
+        use super::ast::Direction;
+
+        let mut result = Vec::new();
+        let mut emitted: HashSet<u64> = HashSet::new();
+        // Trail tracks visited edge ids; AcyclicPath tracks visited node ids.
+        let seed: HashSet<u64> = match uniqueness {
+            PathUniqueness::AcyclicPath => HashSet::from([start_id]),
+            _ => HashSet::new(),
+        };
+        let mut queue = VecDeque::new();
+        queue.push_back((start_id, 0u32, seed));
+
+        while let Some((node_id, depth, path_visited)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+
+            let mut neighbors = match direction {
+                Direction::Right => reader.get_neighbors(node_id, None)?,
+                Direction::Left => reader.get_neighbors_incoming(node_id, None)?,
+                Direction::Both => {
+                    let mut out = reader.get_neighbors(node_id, None)?;
+                    let incoming = reader.get_neighbors_incoming(node_id, None)?;
+                    out.extend(incoming);
+                    out
+                }
+            };
+            if !edge_types.is_empty() {
+                neighbors.retain(|(edge, _)| edge_types.contains(&edge.edge_type.as_str()));
+            }
+
+            for (edge, to_node) in neighbors {
+                let repeats = match uniqueness {
+                    PathUniqueness::Trail => path_visited.contains(&edge.id),
+                    PathUniqueness::AcyclicPath => path_visited.contains(&to_node.id),
+                    PathUniqueness::Walk => unreachable!("Walk is handled by traverse_walk"),
+                };
+                if repeats {
+                    continue;
+                }
+
+                let next_depth = depth + 1;
+                if next_depth >= min_depth
+                    && next_depth <= max_depth
+                    && to_node.id != start_id
+                    && emitted.insert(to_node.id)
+                {
+                    result.push(to_node.clone());
+                }
+
+                if next_depth < max_depth {
+                    let mut next_visited = path_visited.clone();
+                    match uniqueness {
+                        PathUniqueness::Trail => {
+                            next_visited.insert(edge.id);
+                        }
+                        PathUniqueness::AcyclicPath => {
+                            next_visited.insert(to_node.id);
+                        }
+                        PathUniqueness::Walk => unreachable!("Walk is handled by traverse_walk"),
+                    }
+                    queue.push_back((to_node.id, next_depth, next_visited));
+                }
+            }
+        }
+
         Ok(result)
     }
 
@@ -675,6 +1957,13 @@ impl<'a> Executor<'a> {
                 Literal::Null => Value::Null,
             }),
             Expr::Ident(name) => {
+                // `_weight` is a reserved pseudo-column surfacing the tuple's
+                // provenance tag rather than a bound variable, so a row with
+                // no explicit weight still resolves to `one()` instead of
+                // "variable not found".
+                if name == "_weight" {
+                    return Ok(Value::Float(tuple.provenance()));
+                }
                 tuple.get(name).cloned()
                     .ok_or_else(|| EngineError::InvalidArgument(format!("variable not found: {}", name)))
             }
@@ -695,6 +1984,11 @@ impl<'a> Executor<'a> {
                         Value::Bool(b) => Ok(Value::Bool(!b)),
                         _ => Err(EngineError::InvalidArgument("NOT requires boolean".into())),
                     }
+                    UnOp::Neg => match val {
+                        Value::Int(i) => Ok(Value::Int(-i)),
+                        Value::Float(f) => Ok(Value::Float(-f)),
+                        _ => Err(EngineError::InvalidArgument("unary minus requires a number".into())),
+                    }
                 }
             }
             Expr::Parameter(param_name) => {
@@ -727,7 +2021,129 @@ impl<'a> Executor<'a> {
                             _ => Err(EngineError::InvalidArgument("ID() requires a node argument".into())),
                         }
                     }
-                    _ => Err(EngineError::InvalidArgument(format!("unknown function: {}", name))),
+                    "TOINTEGER" | "TOFLOAT" | "TOSTRING" | "TOBOOLEAN" => {
+                        if args.len() != 1 {
+                            return Err(EngineError::InvalidArgument(format!("{name}() requires exactly 1 argument")));
+                        }
+                        let arg_val = self.eval_expr(&args[0], tuple, None)?;
+                        if arg_val == Value::Null {
+                            return Ok(Value::Null);
+                        }
+                        let conversion = match name.to_uppercase().as_str() {
+                            "TOINTEGER" => super::conversion::Conversion::Integer,
+                            "TOFLOAT" => super::conversion::Conversion::Float,
+                            "TOSTRING" => super::conversion::Conversion::Bytes,
+                            _ => super::conversion::Conversion::Boolean,
+                        };
+                        conversion.convert(arg_val)
+                    }
+                    "TIMESTAMP" => {
+                        // timestamp(value, fmt?, tz?) - `tz` is only a marker that the
+                        // format's own offset (e.g. `%z`) should be honored rather than
+                        // the parsed time treated as UTC.
+                        if args.is_empty() || args.len() > 3 {
+                            return Err(EngineError::InvalidArgument(
+                                "timestamp() requires 1 to 3 arguments: (value, fmt?, tz?)".into(),
+                            ));
+                        }
+                        let arg_val = self.eval_expr(&args[0], tuple, None)?;
+                        if arg_val == Value::Null {
+                            return Ok(Value::Null);
+                        }
+                        let fmt = match args.get(1) {
+                            Some(e) => match self.eval_expr(e, tuple, None)? {
+                                Value::String(s) => Some(s),
+                                other => return Err(EngineError::InvalidArgument(format!(
+                                    "timestamp() format must be a string, got {:?}", other
+                                ))),
+                            },
+                            None => None,
+                        };
+                        let tz_aware = args.get(2).is_some();
+                        let conversion = match fmt {
+                            Some(fmt) if tz_aware => super::conversion::Conversion::TimestampTzFmt(fmt),
+                            Some(fmt) => super::conversion::Conversion::TimestampFmt(fmt),
+                            None => super::conversion::Conversion::Timestamp,
+                        };
+                        conversion.convert(arg_val)
+                    }
+                    "SHORTESTPATH" => {
+                        // shortestPath(from, to, edgeType?, weightProp?, minDepth?, maxDepth?)
+                        // - `to` may be a node (exact target) or a string (first
+                        //   node reached carrying that label).
+                        if args.len() < 2 || args.len() > 6 {
+                            return Err(EngineError::InvalidArgument(
+                                "shortestPath() requires 2 to 6 arguments: (from, to, edgeType?, weightProp?, minDepth?, maxDepth?)".into(),
+                            ));
+                        }
+                        let Value::NodeId(from_id) = self.eval_expr(&args[0], tuple, None)? else {
+                            return Err(EngineError::InvalidArgument("shortestPath() requires a node as the first argument".into()));
+                        };
+                        let target = match self.eval_expr(&args[1], tuple, None)? {
+                            Value::NodeId(id) => PathTarget::Id(id),
+                            Value::String(label) => PathTarget::Label(label),
+                            other => return Err(EngineError::InvalidArgument(format!(
+                                "shortestPath() target must be a node or a label string, got {:?}", other
+                            ))),
+                        };
+                        let edge_type = match args.get(2) {
+                            Some(e) => match self.eval_expr(e, tuple, None)? {
+                                Value::Null => None,
+                                Value::String(s) => Some(s),
+                                other => return Err(EngineError::InvalidArgument(format!(
+                                    "shortestPath() edge type must be a string, got {:?}", other
+                                ))),
+                            },
+                            None => None,
+                        };
+                        let weight_prop = match args.get(3) {
+                            Some(e) => match self.eval_expr(e, tuple, None)? {
+                                Value::String(s) => s,
+                                other => return Err(EngineError::InvalidArgument(format!(
+                                    "shortestPath() weight property must be a string, got {:?}", other
+                                ))),
+                            },
+                            None => "weight".to_string(),
+                        };
+                        let min_depth = match args.get(4) {
+                            Some(e) => match self.eval_expr(e, tuple, None)? {
+                                Value::Int(i) if i >= 0 => i as u32,
+                                other => return Err(EngineError::InvalidArgument(format!(
+                                    "shortestPath() min depth must be a non-negative integer, got {:?}", other
+                                ))),
+                            },
+                            None => 0,
+                        };
+                        let max_depth = match args.get(5) {
+                            Some(e) => match self.eval_expr(e, tuple, None)? {
+                                Value::Int(i) if i >= 0 => i as u32,
+                                other => return Err(EngineError::InvalidArgument(format!(
+                                    "shortestPath() max depth must be a non-negative integer, got {:?}", other
+                                ))),
+                            },
+                            None => u32::MAX,
+                        };
+                        let edge_types: Vec<&str> = edge_type.as_deref().into_iter().collect();
+                        let reader: &dyn GraphReadStore = if let Some(r) = self.read { r } else if let Some(w) = write.as_deref_mut() { w } else {
+                            return Err(EngineError::InvalidArgument("shortestPath() requires a readable graph store".into()));
+                        };
+                        match self.dijkstra_path(
+                            reader, from_id, &target, &edge_types, super::ast::Direction::Right, &weight_prop, min_depth, max_depth,
+                        )? {
+                            Some(path) => Ok(Value::Array(path)),
+                            None => Ok(Value::Null),
+                        }
+                    }
+                    upper => {
+                        if let Some(invoker) = self.external_functions.get(upper) {
+                            let arg_vals = args.iter()
+                                .map(|a| self.eval_expr(a, tuple, None))
+                                .collect::<Result<Vec<_>, _>>()?;
+                            invoker.invoke(upper, arg_vals)
+                        } else {
+                            Err(EngineError::InvalidArgument(format!("unknown function: {}", name)))
+                        }
+                    }
                 }
             }
             Expr::Exists(subquery) => {
@@ -760,6 +2176,7 @@ impl<'a> Executor<'a> {
                                             edge.direction.clone(),
                                             depth.min,
                                             depth.max,
+                                            PathUniqueness::default(),
                                         )?;
                                         let any = reachable.into_iter().any(|n| label_matches(&n));
                                         return Ok(Value::Bool(any));
@@ -788,17 +2205,100 @@ impl<'a> Executor<'a> {
                 let plan = crate::exec::planner::Planner::plan(subquery)
                     .map_err(|e| EngineError::InvalidArgument(format!("EXISTS subquery planning error: {:?}", e)))?;
                 let reader: &dyn GraphReadStore = if let Some(r) = self.read { r } else if let Some(w) = write.as_deref_mut() { w } else { return Ok(Value::Bool(false)); };
-                let sub_executor = Executor { read: Some(reader), parameters: self.parameters.clone() };
+                let sub_executor = Executor {
+                    read: Some(reader),
+                    parameters: self.parameters.clone(),
+                    external_functions: self.external_functions.clone(),
+                    sort_spill_threshold: self.sort_spill_threshold,
+                    agg_spill_threshold: self.agg_spill_threshold,
+                    schema: self.schema.clone(),
+                    semiring: self.semiring.clone(),
+                };
                 let mut none: Option<&mut dyn GraphWriteStore> = None;
                 let mut sub_counters = ExecCounters::default();
                 let sub_tuples = sub_executor.execute_node_with_context(&plan.root, tuple, &mut none, &mut sub_counters)?;
                 Ok(Value::Bool(!sub_tuples.is_empty()))
             }
             Expr::Aggregate(_, _) => Err(EngineError::InvalidArgument("aggregate must be evaluated at Project".into())),
+            Expr::List(items) => {
+                let values = items.iter()
+                    .map(|item| self.eval_expr(item, tuple, None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::Array(values))
+            }
+            Expr::In(left, right) => {
+                let needle = self.eval_expr(left, tuple, None)?;
+                let haystack = self.eval_expr(right, tuple, None)?;
+                match haystack {
+                    Value::Array(items) => Ok(Value::Bool(items.iter().any(|v| *v == needle))),
+                    _ => Err(EngineError::InvalidArgument("IN requires a list on the right-hand side".into())),
+                }
+            }
+            Expr::Case(case) => {
+                let operand = case.operand.as_ref()
+                    .map(|o| self.eval_expr(o, tuple, None))
+                    .transpose()?;
+                for (when, then) in &case.branches {
+                    let matched = match &operand {
+                        Some(operand_val) => *operand_val == self.eval_expr(when, tuple, None)?,
+                        None => matches!(self.eval_expr(when, tuple, None)?, Value::Bool(true)),
+                    };
+                    if matched {
+                        return self.eval_expr(then, tuple, None);
+                    }
+                }
+                match &case.else_branch {
+                    Some(else_expr) => self.eval_expr(else_expr, tuple, None),
+                    None => Ok(Value::Null),
+                }
+            }
+            Expr::Cast(inner, target) => {
+                let value = self.eval_expr(inner, tuple, None)?;
+                Self::cast_value(value, *target)
+            }
         }
     }
 
+    /// Coerces `value` to `target`, inserted by `coercion::coerce_binary` so a
+    /// comparison atom's two sides end up the same type instead of erroring
+    /// out of `eval_binary_op` as a type mismatch. `Null` casts to `Null`
+    /// regardless of target, matching `IS [NOT] NULL`'s treatment of it as a
+    /// distinct third state rather than a typed value.
+    fn cast_value(value: Value, target: CastType) -> Result<Value, EngineError> {
+        match (value, target) {
+            (Value::Null, _) => Ok(Value::Null),
+            (Value::Int(i), CastType::Int) => Ok(Value::Int(i)),
+            (Value::Int(i), CastType::Float) => Ok(Value::Float(i as f64)),
+            (Value::Int(i), CastType::String) => Ok(Value::String(i.to_string())),
+            (Value::Int(i), CastType::Bool) => Ok(Value::Bool(i != 0)),
+            (Value::Float(f), CastType::Float) => Ok(Value::Float(f)),
+            (Value::Float(f), CastType::Int) => Ok(Value::Int(f as i64)),
+            (Value::Float(f), CastType::String) => Ok(Value::String(f.to_string())),
+            (Value::Bool(b), CastType::Bool) => Ok(Value::Bool(b)),
+            (Value::Bool(b), CastType::Int) => Ok(Value::Int(b as i64)),
+            (Value::String(s), CastType::String) => Ok(Value::String(s)),
+            (Value::String(s), CastType::Int) => s.parse::<i64>().map(Value::Int)
+                .map_err(|_| EngineError::InvalidArgument(format!("cannot cast \"{}\" to Int", s))),
+            (Value::String(s), CastType::Float) => s.parse::<f64>().map(Value::Float)
+                .map_err(|_| EngineError::InvalidArgument(format!("cannot cast \"{}\" to Float", s))),
+            (value, target) => Err(EngineError::InvalidArgument(format!("cannot cast {:?} to {:?}", value, target))),
+        }
+    }
+
+    /// Whether `op` is an `Eq`/`Ne`/ordering comparison, as opposed to
+    /// arithmetic or boolean logic - mirrors `coercion::is_comparison`, kept
+    /// separate since it's private to that module.
+    fn is_comparison_op(op: &BinOp) -> bool {
+        matches!(op, BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge)
+    }
+
     fn eval_binary_op(&self, left: &Value, op: &BinOp, right: &Value) -> Result<Value, EngineError> {
+        // Coalesce is defined over any pair of types (that's the point of it),
+        // so it has to run before the type-pair match below rejects mismatched
+        // operands.
+        if *op == BinOp::Coalesce {
+            return Ok(if matches!(left, Value::Null) { right.clone() } else { left.clone() });
+        }
         match (left, right) {
             // Int operations (arithmetic + comparison)
             (Value::Int(l), Value::Int(r)) => match op {
@@ -813,6 +2313,20 @@ impl<'a> Executor<'a> {
                         Ok(Value::Int(l / r))
                     }
                 }
+                BinOp::Mod => {
+                    if *r == 0 {
+                        Err(EngineError::InvalidArgument("modulo by zero".into()))
+                    } else {
+                        Ok(Value::Int(l % r))
+                    }
+                }
+                BinOp::Pow => {
+                    if *r < 0 {
+                        Err(EngineError::InvalidArgument("exponentiation with a negative exponent".into()))
+                    } else {
+                        Ok(Value::Float((*l as f64).powf(*r as f64)))
+                    }
+                }
                 // Comparison
                 BinOp::Eq => Ok(Value::Bool(l == r)),
                 BinOp::Ne => Ok(Value::Bool(l != r)),
@@ -835,6 +2349,20 @@ impl<'a> Executor<'a> {
                         Ok(Value::Float(l / r))
                     }
                 }
+                BinOp::Mod => {
+                    if *r == 0.0 {
+                        Err(EngineError::InvalidArgument("modulo by zero".into()))
+                    } else {
+                        Ok(Value::Float(l % r))
+                    }
+                }
+                BinOp::Pow => {
+                    if *r < 0.0 {
+                        Err(EngineError::InvalidArgument("exponentiation with a negative exponent".into()))
+                    } else {
+                        Ok(Value::Float(l.powf(*r)))
+                    }
+                }
                 // Comparison
                 BinOp::Eq => Ok(Value::Bool(l == r)),
                 BinOp::Ne => Ok(Value::Bool(l != r)),
@@ -859,6 +2387,20 @@ impl<'a> Executor<'a> {
                             Ok(Value::Float(lf / r))
                         }
                     }
+                    BinOp::Mod => {
+                        if *r == 0.0 {
+                            Err(EngineError::InvalidArgument("modulo by zero".into()))
+                        } else {
+                            Ok(Value::Float(lf % r))
+                        }
+                    }
+                    BinOp::Pow => {
+                        if *r < 0.0 {
+                            Err(EngineError::InvalidArgument("exponentiation with a negative exponent".into()))
+                        } else {
+                            Ok(Value::Float(lf.powf(*r)))
+                        }
+                    }
                     // Comparison
                     BinOp::Eq => Ok(Value::Bool(lf == *r)),
                     BinOp::Ne => Ok(Value::Bool(lf != *r)),
@@ -883,6 +2425,20 @@ impl<'a> Executor<'a> {
                             Ok(Value::Float(l / rf))
                         }
                     }
+                    BinOp::Mod => {
+                        if rf == 0.0 {
+                            Err(EngineError::InvalidArgument("modulo by zero".into()))
+                        } else {
+                            Ok(Value::Float(l % rf))
+                        }
+                    }
+                    BinOp::Pow => {
+                        if rf < 0.0 {
+                            Err(EngineError::InvalidArgument("exponentiation with a negative exponent".into()))
+                        } else {
+                            Ok(Value::Float(l.powf(rf)))
+                        }
+                    }
                     // Comparison
                     BinOp::Eq => Ok(Value::Bool(*l == rf)),
                     BinOp::Ne => Ok(Value::Bool(*l != rf)),
@@ -901,12 +2457,33 @@ impl<'a> Executor<'a> {
                 BinOp::Ne => l != r,
                 _ => return Err(EngineError::InvalidArgument("invalid bool op".into())),
             })),
-            // String comparisons
-            (Value::String(l), Value::String(r)) => Ok(Value::Bool(match op {
-                BinOp::Eq => l == r,
-                BinOp::Ne => l != r,
-                _ => return Err(EngineError::InvalidArgument("invalid string op".into())),
-            })),
+            // String comparisons + concatenation
+            (Value::String(l), Value::String(r)) => match op {
+                BinOp::Add => Ok(Value::String(format!("{}{}", l, r))),
+                BinOp::Eq => Ok(Value::Bool(l == r)),
+                BinOp::Ne => Ok(Value::Bool(l != r)),
+                _ => Err(EngineError::InvalidArgument("invalid string op".into())),
+            },
+            // A quoted number (`{x: "5"}`, `WHERE p.x = "5"`) compared against
+            // a property that actually holds a numeric value: parse the
+            // string and compare numerically rather than erroring out, since
+            // `coercion::coerce_binary` can't tell at plan time whether the
+            // property is numeric or genuinely string-typed. If the string
+            // doesn't parse as a number, this is a real type mismatch -
+            // fall through to the error below, which `PlanNode::Filter`
+            // treats as "no match" rather than a numeric-string false match.
+            (Value::String(s), Value::Int(r)) if Self::is_comparison_op(op) && s.parse::<i64>().is_ok() => {
+                self.eval_binary_op(&Value::Int(s.parse().unwrap()), op, &Value::Int(*r))
+            }
+            (Value::Int(l), Value::String(s)) if Self::is_comparison_op(op) && s.parse::<i64>().is_ok() => {
+                self.eval_binary_op(&Value::Int(*l), op, &Value::Int(s.parse().unwrap()))
+            }
+            (Value::String(s), Value::Float(r)) if Self::is_comparison_op(op) && s.parse::<f64>().is_ok() => {
+                self.eval_binary_op(&Value::Float(s.parse().unwrap()), op, &Value::Float(*r))
+            }
+            (Value::Float(l), Value::String(s)) if Self::is_comparison_op(op) && s.parse::<f64>().is_ok() => {
+                self.eval_binary_op(&Value::Float(*l), op, &Value::Float(s.parse().unwrap()))
+            }
             _ => Err(EngineError::InvalidArgument(format!(
                 "type mismatch in binary op: {:?} {:?} {:?}",
                 left, op, right
@@ -916,75 +2493,227 @@ impl<'a> Executor<'a> {
     
     fn eval_aggregate(&self, expr: &Expr, tuples: &[Tuple], _write: Option<&mut dyn GraphWriteStore>) -> Result<Value, EngineError> {
         match expr {
-            Expr::Aggregate(func, arg) => match func {
-                AggFunc::Count => Ok(Value::Int(tuples.len() as i64)),
-                AggFunc::Sum => {
-                    let mut sum = 0.0f64;
-                    for t in tuples {
-                        if let Ok(v) = self.eval_expr(arg, t, None) {
-                            match v {
-                                Value::Int(i) => sum += i as f64,
-                                Value::Float(f) => sum += f,
-                                _ => {}
-                            }
-                        }
-                    }
-                    Ok(Value::Float(sum))
-                }
-                AggFunc::Avg => {
-                    let mut sum = 0.0f64;
-                    let mut cnt = 0usize;
-                    for t in tuples {
-                        if let Ok(v) = self.eval_expr(arg, t, None) {
-                            match v {
-                                Value::Int(i) => { sum += i as f64; cnt += 1; }
-                                Value::Float(f) => { sum += f; cnt += 1; }
-                                _ => {}
-                            }
-                        }
-                    }
-                    if cnt == 0 { Ok(Value::Null) } else { Ok(Value::Float(sum / cnt as f64)) }
-                }
-                AggFunc::Min => {
-                    let mut best: Option<f64> = None;
-                    for t in tuples {
-                        if let Ok(v) = self.eval_expr(arg, t, None) {
-                            let cur = match v { Value::Int(i) => i as f64, Value::Float(f) => f, _ => continue };
-                            best = Some(match best { Some(b) => b.min(cur), None => cur });
-                        }
-                    }
-                    Ok(best.map(Value::Float).unwrap_or(Value::Null))
-                }
-                AggFunc::Max => {
-                    let mut best: Option<f64> = None;
-                    for t in tuples {
-                        if let Ok(v) = self.eval_expr(arg, t, None) {
-                            let cur = match v { Value::Int(i) => i as f64, Value::Float(f) => f, _ => continue };
-                            best = Some(match best { Some(b) => b.max(cur), None => cur });
-                        }
-                    }
-                    Ok(best.map(Value::Float).unwrap_or(Value::Null))
+            Expr::Aggregate(func, _) => {
+                let mut acc = Accumulator::new(func);
+                for t in tuples {
+                    acc.accumulate_row(self, expr, t);
                 }
-            },
+                Ok(acc.finish())
+            }
             _ => Err(EngineError::InvalidArgument("expected aggregate expression".into())),
         }
     }
+
+    /// Rebuilds one GROUP BY result row from its key tuple (for the GROUP BY
+    /// columns) and the accumulators folded over that group's rows (for the
+    /// aggregates) - shared by the in-memory and spill-to-disk GROUP BY
+    /// paths so they can't drift on how a group's output row is shaped.
+    fn finalize_aggregate_group(
+        &self,
+        group_by: &[Expr],
+        aggregates: &[(String, Expr)],
+        first: &Tuple,
+        accumulators: Vec<Accumulator>,
+    ) -> Result<Tuple, EngineError> {
+        let mut result = Tuple::new();
+        for (idx, expr) in group_by.iter().enumerate() {
+            let val = self.eval_expr(expr, first, None)?;
+            let key = match expr {
+                Expr::Ident(name) => name.clone(),
+                Expr::Property(var, prop) => format!("{}.{}", var, prop),
+                _ => format!("group_{}", idx),
+            };
+            result.insert(key, val);
+        }
+        for ((alias, _), acc) in aggregates.iter().zip(accumulators) {
+            result.insert(alias.clone(), acc.finish());
+        }
+        Ok(result)
+    }
+
+    /// GROUP BY execution for input sets over `agg_spill_threshold`: mirrors
+    /// `sort_with_spill`'s external sort - tuples are chunked, each chunk is
+    /// sorted by the GROUP BY key and spilled to a temp file via the same
+    /// `SpillRun` format `ORDER BY` uses, then a k-way merge walks the runs
+    /// in group-key order so each group's rows arrive contiguously. Because
+    /// `Sum`/`Avg`/`Count`/`Min`/`Max` are associative/commutative, an
+    /// `Accumulator` per aggregate can fold over that merged stream and
+    /// finalize as soon as the key changes, so memory stays bounded by
+    /// `agg_spill_threshold` (the runs) plus one open group's accumulators,
+    /// never by the total row count or number of distinct groups.
+    fn aggregate_with_spill(
+        &self,
+        tuples: Vec<Tuple>,
+        group_by: &[Expr],
+        aggregates: &[(String, Expr)],
+    ) -> Result<Vec<Tuple>, EngineError> {
+        let key_items: Vec<super::ast::OrderByItem> = group_by
+            .iter()
+            .map(|expr| super::ast::OrderByItem { expr: expr.clone(), descending: false })
+            .collect();
+
+        let mut runs = Vec::new();
+        let mut remaining = tuples.into_iter();
+        loop {
+            let chunk: Vec<Tuple> = remaining.by_ref().take(self.agg_spill_threshold).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let mut keyed: Vec<(Vec<Value>, Tuple)> =
+                chunk.into_iter().map(|t| (self.order_keys(&key_items, &t), t)).collect();
+            keyed.sort_by(|(ka, _), (kb, _)| Self::compare_order_keys(&key_items, ka, kb));
+            runs.push(SpillRun::create(&keyed)?);
+        }
+
+        self.merge_and_aggregate_runs(runs, &key_items, group_by, aggregates)
+    }
+
+    /// K-way merges `runs` in group-key order (the same heap-of-cursors
+    /// approach as `merge_runs`), but instead of collecting every row, folds
+    /// each group's rows into `Accumulator`s as they arrive and emits one
+    /// result row per group as soon as the key changes - so the merged
+    /// stream itself never materializes beyond the current group.
+    fn merge_and_aggregate_runs(
+        &self,
+        mut runs: Vec<SpillRun>,
+        key_items: &[super::ast::OrderByItem],
+        group_by: &[Expr],
+        aggregates: &[(String, Expr)],
+    ) -> Result<Vec<Tuple>, EngineError> {
+        use std::cmp::{Ordering, Reverse};
+        use std::collections::BinaryHeap;
+
+        struct MergeKey<'k> {
+            items: &'k [super::ast::OrderByItem],
+            values: Vec<Value>,
+        }
+        impl PartialEq for MergeKey<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+        impl Eq for MergeKey<'_> {}
+        impl PartialOrd for MergeKey<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for MergeKey<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                Executor::compare_order_keys(self.items, &self.values, &other.values)
+            }
+        }
+
+        struct HeapEntry<'k> {
+            key: MergeKey<'k>,
+            tuple: Tuple,
+            run: usize,
+        }
+        impl PartialEq for HeapEntry<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key && self.run == other.run
+            }
+        }
+        impl Eq for HeapEntry<'_> {}
+        impl PartialOrd for HeapEntry<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for HeapEntry<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.key.cmp(&other.key).then_with(|| self.run.cmp(&other.run))
+            }
+        }
+
+        let mut heap: BinaryHeap<Reverse<HeapEntry>> = BinaryHeap::new();
+        for (run_idx, run) in runs.iter_mut().enumerate() {
+            if let Some((values, tuple)) = run.next()? {
+                heap.push(Reverse(HeapEntry { key: MergeKey { items: key_items, values }, tuple, run: run_idx }));
+            }
+        }
+
+        let mut results = Vec::new();
+        let mut current_key: Option<Vec<Value>> = None;
+        let mut current_first: Option<Tuple> = None;
+        let mut accumulators: Vec<Accumulator> = Vec::new();
+        // Folds every row's provenance tag via the semiring's `add` (⊕) as
+        // the group's rows stream past, the same "alternative derivations
+        // combine additively" rule the in-memory GROUP BY branch applies to
+        // its fully-materialized `group_tuples` - this is just that fold
+        // done incrementally instead of over a collected `Vec<Tuple>`.
+        let mut current_tag = self.semiring.zero();
+
+        while let Some(Reverse(entry)) = heap.pop() {
+            let is_new_group = match &current_key {
+                Some(k) => Self::compare_order_keys(key_items, k, &entry.key.values) != Ordering::Equal,
+                None => true,
+            };
+            if is_new_group {
+                if let Some(first) = current_first.take() {
+                    let done = std::mem::take(&mut accumulators);
+                    let mut group_result = self.finalize_aggregate_group(group_by, aggregates, &first, done)?;
+                    group_result.set_provenance(current_tag);
+                    results.push(group_result);
+                }
+                current_key = Some(entry.key.values.clone());
+                current_first = Some(entry.tuple.clone());
+                accumulators = aggregates.iter().map(|(_, agg_expr)| Accumulator::new_for(agg_expr)).collect();
+                current_tag = self.semiring.zero();
+            }
+            current_tag = self.semiring.add(current_tag, entry.tuple.provenance());
+            for (acc, (_, agg_expr)) in accumulators.iter_mut().zip(aggregates) {
+                acc.accumulate_row(self, agg_expr, &entry.tuple);
+            }
+
+            if let Some((values, next_tuple)) = runs[entry.run].next()? {
+                heap.push(Reverse(HeapEntry { key: MergeKey { items: key_items, values }, tuple: next_tuple, run: entry.run }));
+            }
+        }
+        if let Some(first) = current_first.take() {
+            let mut group_result = self.finalize_aggregate_group(group_by, aggregates, &first, accumulators)?;
+            group_result.set_provenance(current_tag);
+            results.push(group_result);
+        }
+
+        Ok(results)
+    }
     
-    fn execute_create(&self, patterns: &[Pattern], parent_tuple: &Tuple, write: Option<&mut dyn GraphWriteStore>) -> Result<Vec<Tuple>, EngineError> {
+    /// Runs `patterns` once per tuple in `parent_tuples`, so `MatchCreate`'s
+    /// one CREATE per matched row and `UnwindCreate`'s one CREATE per
+    /// unwound element both emit a `Vec<Tuple>` with one entry per input
+    /// binding. A bare `CREATE` with no upstream operator just passes a
+    /// single-element slice, so it keeps returning its one `result_tuple`.
+    fn execute_create(&self, patterns: &[Pattern], parent_tuples: &[Tuple], write: Option<&mut dyn GraphWriteStore>) -> Result<Vec<Tuple>, EngineError> {
         let write = write.ok_or_else(|| EngineError::InvalidArgument("CREATE requires a write-capable store".into()))?;
+        let mut results = Vec::with_capacity(parent_tuples.len());
+        for parent_tuple in parent_tuples {
+            results.push(self.execute_create_one(patterns, parent_tuple, write)?);
+        }
+        Ok(results)
+    }
+
+    /// Creates `patterns` against a single incoming `parent_tuple`, returning
+    /// the row carrying every variable it bound (including the parent's own).
+    fn execute_create_one(&self, patterns: &[Pattern], parent_tuple: &Tuple, write: &mut dyn GraphWriteStore) -> Result<Tuple, EngineError> {
         let mut created_vars: HashMap<String, u64> = HashMap::new();
         let mut result_tuple = parent_tuple.clone();
-        
+
         for pattern in patterns {
             match pattern {
                 Pattern::Node(node_pattern) => {
-                    // Evaluate properties (may contain expressions from parent tuple)
+                    // Evaluate properties against the tuple accumulated so
+                    // far, so they can read the parent row or a variable
+                    // bound by an earlier pattern in this same CREATE.
                     let mut props = HashMap::new();
-                    for (key, lit) in &node_pattern.properties {
-                        let value = self.eval_literal(lit)?;
+                    for (key, expr) in &node_pattern.properties {
+                        let value = self.eval_expr(expr, &result_tuple, None)?;
                         props.insert(key.clone(), value);
                     }
-                    
+                    let weight = extract_weight(&mut props)?;
+                    result_tuple.set_provenance(result_tuple.provenance() * weight);
+                    let props = self.schema.validate_node(&node_pattern.labels, props)?;
+
                     // Create the node
                     let node_id = write.add_node(node_pattern.labels.clone(), props)?;
                     
@@ -1025,14 +2754,19 @@ impl<'a> Executor<'a> {
                     
                     // Evaluate edge properties
                     let mut props = HashMap::new();
-                    for (key, lit) in &edge_pattern.properties {
-                        let value = self.eval_literal(lit)?;
+                    for (key, expr) in &edge_pattern.properties {
+                        let value = self.eval_expr(expr, &result_tuple, None)?;
                         props.insert(key.clone(), value);
                     }
-                    
+                    let weight = extract_weight(&mut props)?;
+                    result_tuple.set_provenance(result_tuple.provenance() * weight);
+
                     // Create the edge
                     let edge_type = edge_pattern.edge_type.clone()
                         .ok_or_else(|| EngineError::InvalidArgument("edge must have type".into()))?;
+                    let from_labels = write.get_node(from_id)?.map(|n| n.labels).unwrap_or_default();
+                    let to_labels = write.get_node(to_id)?.map(|n| n.labels).unwrap_or_default();
+                    let props = self.schema.validate_edge(&edge_type, &from_labels, &to_labels, props)?;
                     let edge_id = write.add_edge(from_id, to_id, edge_type.clone(), props)?;
                     if std::env::var("CASYS_DEBUG_PLAN").ok().as_deref() == Some("1") {
                         println!("CREATE edge id={} {} -> {} type={} ", edge_id, from_id, to_id, edge_type);
@@ -1046,10 +2780,37 @@ impl<'a> Executor<'a> {
             }
         }
         
-        // Return single tuple with all created variables
-        Ok(vec![result_tuple])
+        // Return the tuple carrying all created variables
+        Ok(result_tuple)
     }
-    
+
+    /// `UNWIND expr AS variable`: evaluates `expr` once against `parent_tuple`
+    /// and expects a `Value::Array`, binding `variable` to each element in
+    /// its own child tuple - one `result_tuple` per element, same shape as
+    /// `execute_create`'s one-row-per-input-binding output. A `Value::Map`
+    /// element also gets its fields flattened as `variable.key`, the same
+    /// convention a node scan uses for its own properties, so `CREATE`
+    /// property expressions can read `row.name` directly.
+    fn execute_unwind(&self, expr: &Expr, variable: &str, parent_tuple: &Tuple) -> Result<Vec<Tuple>, EngineError> {
+        let list = self.eval_expr(expr, parent_tuple, None)?;
+        let Value::Array(items) = list else {
+            return Err(EngineError::InvalidArgument("UNWIND requires a list-valued expression".into()));
+        };
+
+        let mut tuples = Vec::with_capacity(items.len());
+        for item in items {
+            let mut tuple = parent_tuple.clone();
+            if let Value::Map(ref props) = item {
+                for (k, v) in props {
+                    tuple.insert(format!("{}.{}", variable, k), v.clone());
+                }
+            }
+            tuple.insert(variable.to_string(), item);
+            tuples.push(tuple);
+        }
+        Ok(tuples)
+    }
+
     fn eval_literal(&self, lit: &Literal) -> Result<Value, EngineError> {
         Ok(match lit {
             Literal::String(s) => Value::String(s.clone()),
@@ -1060,3 +2821,17 @@ impl<'a> Executor<'a> {
         })
     }
 }
+
+/// Pulls the reserved `_weight` property (if present) out of a `CREATE`
+/// pattern's property bag so it lands on the created element's provenance
+/// tag instead of being stored as an ordinary property. Missing `_weight`
+/// yields `one()` - both shipped semirings agree it's `1.0` - so an
+/// un-weighted `CREATE` leaves the tuple's tag untouched.
+fn extract_weight(props: &mut HashMap<String, Value>) -> Result<f64, EngineError> {
+    match props.remove("_weight") {
+        Some(Value::Float(f)) => Ok(f),
+        Some(Value::Int(i)) => Ok(i as f64),
+        Some(other) => Err(EngineError::InvalidArgument(format!("_weight must be a number, got {:?}", other))),
+        None => Ok(1.0),
+    }
+}
@@ -0,0 +1,114 @@
+//! Pluggable function registry consulted by the parser to validate function
+//! calls (name + arity) before they reach the executor, so a typo like
+//! `COcUNT(...)` is rejected at parse time with a precise location instead of
+//! silently becoming an unknown generic call that only fails at runtime.
+
+use std::collections::HashMap;
+
+use crate::types::EngineError;
+
+/// Dispatches a call to a scalar function whose implementation lives outside
+/// this crate (e.g. a JS/Python callback registered by an embedder), so the
+/// executor can fall through to a host-provided implementation for names the
+/// built-in `FunctionCall` match in `Executor::eval_expr` doesn't recognize.
+/// Implementations are responsible for their own re-entrancy/threading
+/// concerns - the executor just calls `invoke` synchronously and expects a
+/// `casys_core::Value` back.
+pub trait ExternalFunctionInvoker: Send + Sync {
+    fn invoke(&self, name: &str, args: Vec<casys_core::Value>) -> Result<casys_core::Value, EngineError>;
+}
+
+/// What kind of function a name resolves to. The parser builds an
+/// `Expr::Aggregate` for `Aggregate` and an `Expr::FunctionCall` for `Scalar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    Scalar,
+    Aggregate,
+}
+
+/// Expected argument count: an exact count, or a variadic minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, n: usize) -> bool {
+        match self {
+            Arity::Exact(k) => n == *k,
+            Arity::AtLeast(k) => n >= *k,
+        }
+    }
+}
+
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Arity::Exact(k) => write!(f, "exactly {k}"),
+            Arity::AtLeast(k) => write!(f, "at least {k}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionSig {
+    pub kind: FunctionKind,
+    pub arity: Arity,
+}
+
+/// Consulted by `Parser::parse_primary` for every `name(...)` call site.
+/// Implementations are looked up by upper-cased name so registration is
+/// case-insensitive (`COUNT`, `Count`, `count` all resolve the same entry).
+pub trait FunctionRegistry {
+    fn lookup(&self, name_upper: &str) -> Option<FunctionSig>;
+}
+
+/// Built-in scalar/aggregate functions this grammar ships with.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFunctionRegistry;
+
+impl FunctionRegistry for DefaultFunctionRegistry {
+    fn lookup(&self, name_upper: &str) -> Option<FunctionSig> {
+        match name_upper {
+            "COUNT" | "SUM" | "AVG" | "MIN" | "MAX" | "COLLECT" | "STDEV" | "STDDEV" => {
+                Some(FunctionSig { kind: FunctionKind::Aggregate, arity: Arity::Exact(1) })
+            }
+            "ID" | "TOINTEGER" | "TOFLOAT" | "TOSTRING" | "TOBOOLEAN" => {
+                Some(FunctionSig { kind: FunctionKind::Scalar, arity: Arity::Exact(1) })
+            }
+            // shortestPath(from, to, edgeType?, weightProp?, minDepth?, maxDepth?)
+            "SHORTESTPATH" => Some(FunctionSig { kind: FunctionKind::Scalar, arity: Arity::AtLeast(2) }),
+            // timestamp(value, fmt?, tz?)
+            "TIMESTAMP" => Some(FunctionSig { kind: FunctionKind::Scalar, arity: Arity::AtLeast(1) }),
+            _ => None,
+        }
+    }
+}
+
+/// Layers host-registered scalar functions (`coalesce`, `lower`, `labels`,
+/// ...) on top of the built-in `DefaultFunctionRegistry`, so hosts only
+/// declare what they're adding rather than re-declaring every built-in.
+#[derive(Debug, Clone, Default)]
+pub struct CustomFunctionRegistry {
+    custom: HashMap<String, FunctionSig>,
+    fallback: DefaultFunctionRegistry,
+}
+
+impl CustomFunctionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a scalar function under `name`, matched case-insensitively.
+    pub fn register_scalar(&mut self, name: &str, arity: Arity) -> &mut Self {
+        self.custom.insert(name.to_uppercase(), FunctionSig { kind: FunctionKind::Scalar, arity });
+        self
+    }
+}
+
+impl FunctionRegistry for CustomFunctionRegistry {
+    fn lookup(&self, name_upper: &str) -> Option<FunctionSig> {
+        self.custom.get(name_upper).copied().or_else(|| self.fallback.lookup(name_upper))
+    }
+}
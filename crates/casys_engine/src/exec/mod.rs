@@ -0,0 +1,14 @@
+//! GQL execution pipeline: lexer/parser, AST, planner, and tree-walking executor.
+
+pub mod ast;
+pub mod coercion;
+pub mod conversion;
+pub mod executor;
+pub mod functions;
+pub mod optimizer;
+pub mod parser;
+pub mod physical;
+pub mod plan_optimizer;
+pub mod planner;
+pub mod provenance;
+pub mod schema;
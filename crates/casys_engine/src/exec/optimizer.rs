@@ -0,0 +1,202 @@
+//! Constant-folding / expression-simplification pass over the parsed AST.
+//!
+//! Runs after `parse_query` and rewrites the `Expr` tree before it reaches the
+//! engine: binary ops over two literals fold to a single literal, boolean
+//! identities short-circuit (`x AND true` -> `x`, `x OR false` -> `x`, etc.),
+//! arithmetic identities/annihilators fold too (`x + 0` -> `x`, `x * 0` -> `0`,
+//! etc. - see `fold_identity`), and `WHERE` clauses that fold to the constant
+//! `true` are dropped entirely.
+
+use super::ast::{BinOp, CaseExpr, Expr, Literal, Query, UnOp};
+
+/// Rewrites `query` into an equivalent, simplified form. Callers that need the
+/// untouched AST (e.g. for EXPLAIN) can simply skip calling this.
+pub fn optimize_query(mut query: Query) -> Query {
+    if let Some(unwind) = query.unwind_clause.as_mut() {
+        unwind.expr = optimize_expr(std::mem::replace(&mut unwind.expr, Expr::Literal(Literal::Null)));
+    }
+
+    if let Some(with) = query.with_clause.as_mut() {
+        for item in with.items.iter_mut() {
+            item.expr = optimize_expr(std::mem::replace(&mut item.expr, Expr::Literal(Literal::Null)));
+        }
+    }
+
+    if let Some(where_clause) = query.where_clause.take() {
+        let folded = optimize_expr(where_clause.expr);
+        if folded != Expr::Literal(Literal::Bool(true)) {
+            query.where_clause = Some(super::ast::WhereClause { expr: folded });
+        }
+    }
+
+    if let Some(ret) = query.return_clause.as_mut() {
+        for item in ret.items.iter_mut() {
+            item.expr = optimize_expr(std::mem::replace(&mut item.expr, Expr::Literal(Literal::Null)));
+        }
+    }
+
+    if let Some(order_by) = query.order_by.as_mut() {
+        for item in order_by.items.iter_mut() {
+            item.expr = optimize_expr(std::mem::replace(&mut item.expr, Expr::Literal(Literal::Null)));
+        }
+    }
+
+    query
+}
+
+/// Recursively folds constant subexpressions. Never folds across a `Parameter`
+/// or a graph-bound `Ident`/`Property`, since their values are unknown here.
+fn optimize_expr(expr: Expr) -> Expr {
+    match expr {
+        Expr::BinaryOp(left, op, right) => {
+            let left = optimize_expr(*left);
+            let right = optimize_expr(*right);
+
+            if let Some(lit) = fold_identity(&left, &op, &right) {
+                return lit;
+            }
+            if let (Expr::Literal(l), Expr::Literal(r)) = (&left, &right) {
+                if let Some(folded) = fold_binary(l, &op, r) {
+                    return Expr::Literal(folded);
+                }
+            }
+            Expr::BinaryOp(Box::new(left), op, Box::new(right))
+        }
+        Expr::UnaryOp(op, operand) => {
+            let operand = optimize_expr(*operand);
+            match (&op, &operand) {
+                (UnOp::Not, Expr::Literal(Literal::Bool(b))) => Expr::Literal(Literal::Bool(!b)),
+                (UnOp::Not, Expr::UnaryOp(UnOp::Not, inner)) => *inner.clone(),
+                (UnOp::Neg, Expr::Literal(Literal::Int(i))) => Expr::Literal(Literal::Int(-i)),
+                (UnOp::Neg, Expr::Literal(Literal::Float(f))) => Expr::Literal(Literal::Float(-f)),
+                _ => Expr::UnaryOp(op, Box::new(operand)),
+            }
+        }
+        Expr::IsNull(inner) => {
+            let inner = optimize_expr(*inner);
+            match &inner {
+                Expr::Literal(Literal::Null) => Expr::Literal(Literal::Bool(true)),
+                Expr::Literal(_) => Expr::Literal(Literal::Bool(false)),
+                _ => Expr::IsNull(Box::new(inner)),
+            }
+        }
+        Expr::IsNotNull(inner) => {
+            let inner = optimize_expr(*inner);
+            match &inner {
+                Expr::Literal(Literal::Null) => Expr::Literal(Literal::Bool(false)),
+                Expr::Literal(_) => Expr::Literal(Literal::Bool(true)),
+                _ => Expr::IsNotNull(Box::new(inner)),
+            }
+        }
+        Expr::Aggregate(func, arg) => Expr::Aggregate(func, Box::new(optimize_expr(*arg))),
+        Expr::FunctionCall(name, args) => {
+            Expr::FunctionCall(name, args.into_iter().map(optimize_expr).collect())
+        }
+        Expr::Exists(subquery) => Expr::Exists(Box::new(optimize_query(*subquery))),
+        Expr::List(items) => Expr::List(items.into_iter().map(optimize_expr).collect()),
+        Expr::In(left, right) => {
+            let left = optimize_expr(*left);
+            let right = optimize_expr(*right);
+            if let (Expr::Literal(l), Expr::List(items)) = (&left, &right) {
+                if items.iter().all(|i| matches!(i, Expr::Literal(_))) {
+                    let found = items.iter().any(|i| matches!(i, Expr::Literal(r) if r == l));
+                    return Expr::Literal(Literal::Bool(found));
+                }
+            }
+            Expr::In(Box::new(left), Box::new(right))
+        }
+        Expr::Case(case) => Expr::Case(CaseExpr {
+            operand: case.operand.map(|o| Box::new(optimize_expr(*o))),
+            branches: case.branches.into_iter().map(|(w, t)| (optimize_expr(w), optimize_expr(t))).collect(),
+            else_branch: case.else_branch.map(|e| Box::new(optimize_expr(*e))),
+        }),
+        // Literal, Ident, Property, Parameter carry no children to fold.
+        other => other,
+    }
+}
+
+/// True if `expr` is the literal `0` (as either `Int` or `Float`).
+fn is_zero_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::Literal(Literal::Int(0)))
+        || matches!(expr, Expr::Literal(Literal::Float(f)) if *f == 0.0)
+}
+
+/// True if `expr` is the literal `1` (as either `Int` or `Float`).
+fn is_one_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::Literal(Literal::Int(1)))
+        || matches!(expr, Expr::Literal(Literal::Float(f)) if *f == 1.0)
+}
+
+/// Short-circuits boolean and arithmetic identities without requiring both
+/// sides to be literal (e.g. `x AND false` is `false`, and `x * 0` is `0`,
+/// regardless of what `x` is).
+fn fold_identity(left: &Expr, op: &BinOp, right: &Expr) -> Option<Expr> {
+    match op {
+        BinOp::And => match (left, right) {
+            (Expr::Literal(Literal::Bool(true)), _) => Some(right.clone()),
+            (Expr::Literal(Literal::Bool(false)), _) => Some(Expr::Literal(Literal::Bool(false))),
+            (_, Expr::Literal(Literal::Bool(true))) => Some(left.clone()),
+            (_, Expr::Literal(Literal::Bool(false))) => Some(Expr::Literal(Literal::Bool(false))),
+            _ => None,
+        },
+        BinOp::Or => match (left, right) {
+            (Expr::Literal(Literal::Bool(true)), _) => Some(Expr::Literal(Literal::Bool(true))),
+            (Expr::Literal(Literal::Bool(false)), _) => Some(right.clone()),
+            (_, Expr::Literal(Literal::Bool(true))) => Some(Expr::Literal(Literal::Bool(true))),
+            (_, Expr::Literal(Literal::Bool(false))) => Some(left.clone()),
+            _ => None,
+        },
+        // `e - 0` simplifies but `0 - e` doesn't (that's negation, not identity).
+        BinOp::Add | BinOp::Sub if is_zero_literal(right) => Some(left.clone()),
+        BinOp::Add if is_zero_literal(left) => Some(right.clone()),
+        BinOp::Mul if is_zero_literal(left) || is_zero_literal(right) => Some(Expr::Literal(Literal::Int(0))),
+        BinOp::Mul if is_one_literal(right) => Some(left.clone()),
+        BinOp::Mul if is_one_literal(left) => Some(right.clone()),
+        _ => None,
+    }
+}
+
+/// Folds a binary op over two literals, mirroring `Executor::eval_binary_op`'s
+/// coercion rules. Returns `None` (leaving the op unfolded) for division by
+/// zero or an operator/type pairing that isn't foldable at parse time.
+fn fold_binary(l: &Literal, op: &BinOp, r: &Literal) -> Option<Literal> {
+    use Literal::*;
+    match (l, op, r) {
+        (Int(a), BinOp::Add, Int(b)) => Some(Int(a + b)),
+        (Int(a), BinOp::Sub, Int(b)) => Some(Int(a - b)),
+        (Int(a), BinOp::Mul, Int(b)) => Some(Int(a * b)),
+        (Int(a), BinOp::Div, Int(b)) if *b != 0 => Some(Int(a / b)),
+        (Int(a), BinOp::Mod, Int(b)) if *b != 0 => Some(Int(a % b)),
+        (Int(a), BinOp::Pow, Int(b)) if *b >= 0 => Some(Float((*a as f64).powf(*b as f64))),
+        (Int(a), BinOp::Eq, Int(b)) => Some(Bool(a == b)),
+        (Int(a), BinOp::Ne, Int(b)) => Some(Bool(a != b)),
+        (Int(a), BinOp::Lt, Int(b)) => Some(Bool(a < b)),
+        (Int(a), BinOp::Le, Int(b)) => Some(Bool(a <= b)),
+        (Int(a), BinOp::Gt, Int(b)) => Some(Bool(a > b)),
+        (Int(a), BinOp::Ge, Int(b)) => Some(Bool(a >= b)),
+
+        (Float(a), BinOp::Add, Float(b)) => Some(Float(a + b)),
+        (Float(a), BinOp::Sub, Float(b)) => Some(Float(a - b)),
+        (Float(a), BinOp::Mul, Float(b)) => Some(Float(a * b)),
+        (Float(a), BinOp::Div, Float(b)) if *b != 0.0 => Some(Float(a / b)),
+        (Float(a), BinOp::Mod, Float(b)) if *b != 0.0 => Some(Float(a % b)),
+        (Float(a), BinOp::Pow, Float(b)) if *b >= 0.0 => Some(Float(a.powf(*b))),
+        (Float(a), BinOp::Eq, Float(b)) => Some(Bool(a == b)),
+        (Float(a), BinOp::Ne, Float(b)) => Some(Bool(a != b)),
+        (Float(a), BinOp::Lt, Float(b)) => Some(Bool(a < b)),
+        (Float(a), BinOp::Le, Float(b)) => Some(Bool(a <= b)),
+        (Float(a), BinOp::Gt, Float(b)) => Some(Bool(a > b)),
+        (Float(a), BinOp::Ge, Float(b)) => Some(Bool(a >= b)),
+
+        (Bool(a), BinOp::And, Bool(b)) => Some(Bool(*a && *b)),
+        (Bool(a), BinOp::Or, Bool(b)) => Some(Bool(*a || *b)),
+        (Bool(a), BinOp::Eq, Bool(b)) => Some(Bool(a == b)),
+        (Bool(a), BinOp::Ne, Bool(b)) => Some(Bool(a != b)),
+
+        (String(a), BinOp::Add, String(b)) => Some(String(format!("{}{}", a, b))),
+        (String(a), BinOp::Eq, String(b)) => Some(Bool(a == b)),
+        (String(a), BinOp::Ne, String(b)) => Some(Bool(a != b)),
+
+        _ => None,
+    }
+}
@@ -2,6 +2,7 @@
 
 use super::ast::*;
 use super::ast::AggFunc;
+use super::functions::{DefaultFunctionRegistry, FunctionKind, FunctionRegistry};
 use crate::types::EngineError;
 use std::collections::HashMap;
 
@@ -9,6 +10,7 @@ use std::collections::HashMap;
 enum Token {
     // Keywords
     Match,
+    Unwind,       // UNWIND (list-to-rows expansion)
     Create,       // CREATE (for data modification)
     Set,          // SET (for updates)
     Delete,       // DELETE (for deletions)
@@ -29,6 +31,13 @@ enum Token {
     False,
     Is,           // IS (for IS NULL)
     Exists,       // EXISTS (for subqueries)
+    In,           // IN (for list membership)
+    Case,         // CASE
+    When,         // WHEN
+    Then,         // THEN
+    Else,         // ELSE
+    End,          // END
+    Distinct,     // DISTINCT (inside an aggregate call, e.g. COUNT(DISTINCT x))
 
     // Symbols
     LeftParen,
@@ -58,6 +67,9 @@ enum Token {
     Minus,        // -
     // Star already exists for *
     Slash,        // /
+    Percent,      // %
+    Caret,        // ^ (exponentiation)
+    QuestionQuestion, // ?? (null-coalescing)
 
     // Literals
     Ident(String),
@@ -68,9 +80,47 @@ enum Token {
     Eof,
 }
 
+/// A 1-based line/column position within the source query text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Position {
+    line: u32,
+    col: u32,
+}
+
+/// Renders the source line containing `pos` plus a `^` marker under its column,
+/// e.g. for diagnostics like "expected X, got Y".
+fn render_snippet(input: &[char], pos: Position) -> (String, String) {
+    let mut line_start = 0usize;
+    let mut current_line = 1u32;
+    let mut idx = 0usize;
+    while idx < input.len() && current_line < pos.line {
+        if input[idx] == '\n' {
+            current_line += 1;
+            line_start = idx + 1;
+        }
+        idx += 1;
+    }
+    let mut line_end = line_start;
+    while line_end < input.len() && input[line_end] != '\n' {
+        line_end += 1;
+    }
+    let line_text: String = input[line_start..line_end].iter().collect();
+    let caret = format!("{}^", " ".repeat(pos.col.saturating_sub(1) as usize));
+    (line_text, caret)
+}
+
+fn make_parse_error(input: &[char], pos: Position, span: (usize, usize), message: impl Into<String>) -> EngineError {
+    let message = message.into();
+    let (line_text, caret) = render_snippet(input, pos);
+    let rendered = format!("{message} (line {}, col {})\n{line_text}\n{caret}", pos.line, pos.col);
+    EngineError::ParseError { message: rendered, line: pos.line, col: pos.col, span }
+}
+
 struct Lexer {
     input: Vec<char>,
     pos: usize,
+    line: u32,
+    col: u32,
 }
 
 impl Lexer {
@@ -78,9 +128,19 @@ impl Lexer {
         Self {
             input: input.chars().collect(),
             pos: 0,
+            line: 1,
+            col: 1,
         }
     }
 
+    fn position(&self) -> Position {
+        Position { line: self.line, col: self.col }
+    }
+
+    fn error(&self, start: Position, span: (usize, usize), message: impl Into<String>) -> EngineError {
+        make_parse_error(&self.input, start, span, message)
+    }
+
     fn peek(&self) -> Option<char> {
         self.input.get(self.pos).copied()
     }
@@ -88,6 +148,12 @@ impl Lexer {
     fn advance(&mut self) -> Option<char> {
         let ch = self.peek()?;
         self.pos += 1;
+        if ch == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
         Some(ch)
     }
 
@@ -114,25 +180,105 @@ impl Lexer {
         s
     }
 
-    fn read_string(&mut self) -> Result<String, EngineError> {
+    /// Reads a `'`- or `"`-delimited string literal, interpreting backslash escapes
+    /// (`\n`, `\t`, `\r`, `\\`, `\'`, `\"`, `\u{XXXX}`).
+    fn read_string(&mut self, quote: char) -> Result<String, EngineError> {
+        let start_pos = self.position();
+        let start_idx = self.pos;
         self.advance(); // consume opening quote
         let mut s = String::new();
-        while let Some(ch) = self.advance() {
-            if ch == '\'' {
-                return Ok(s);
+        loop {
+            match self.advance() {
+                Some(ch) if ch == quote => return Ok(s),
+                Some('\\') => {
+                    let esc_pos = self.position();
+                    match self.advance() {
+                        Some('n') => s.push('\n'),
+                        Some('t') => s.push('\t'),
+                        Some('r') => s.push('\r'),
+                        Some('\\') => s.push('\\'),
+                        Some('\'') => s.push('\''),
+                        Some('"') => s.push('"'),
+                        Some('u') => {
+                            if self.peek() != Some('{') {
+                                return Err(self.error(esc_pos, (start_idx, self.pos), "malformed escape sequence: expected '{' after \\u"));
+                            }
+                            self.advance(); // consume '{'
+                            let mut hex = String::new();
+                            while let Some(c) = self.peek() {
+                                if c == '}' { break; }
+                                hex.push(c);
+                                self.advance();
+                            }
+                            if self.peek() != Some('}') {
+                                return Err(self.error(esc_pos, (start_idx, self.pos), "malformed escape sequence: unterminated \\u{...}"));
+                            }
+                            self.advance(); // consume '}'
+                            let code = u32::from_str_radix(&hex, 16).map_err(|_| {
+                                self.error(esc_pos, (start_idx, self.pos), format!("malformed escape sequence: invalid hex digits '{hex}'"))
+                            })?;
+                            let ch = char::from_u32(code).ok_or_else(|| {
+                                self.error(esc_pos, (start_idx, self.pos), format!("malformed escape sequence: invalid unicode code point U+{code:X}"))
+                            })?;
+                            s.push(ch);
+                        }
+                        Some(other) => return Err(self.error(esc_pos, (start_idx, self.pos), format!("malformed escape sequence: \\{other}"))),
+                        None => return Err(self.error(esc_pos, (start_idx, self.pos), "malformed escape sequence: unterminated at end of input")),
+                    }
+                }
+                Some(ch) => s.push(ch),
+                None => return Err(self.error(start_pos, (start_idx, self.pos), "unterminated string")),
             }
-            s.push(ch);
         }
-        Err(EngineError::InvalidArgument("unterminated string".into()))
     }
 
+    /// Reads a `0x`/`0o`/`0b`-prefixed integer literal, stripping `_` digit separators,
+    /// and validating that every digit fits the declared base.
+    fn read_radix_int(&mut self, start_pos: Position, start_idx: usize, radix: u32, name: &str) -> Result<Token, EngineError> {
+        self.advance(); // consume '0'
+        self.advance(); // consume radix marker (x/o/b)
+        let mut digits = String::new();
+        while let Some(ch) = self.peek() {
+            if ch.is_ascii_alphanumeric() {
+                digits.push(ch);
+                self.advance();
+            } else if ch == '_' {
+                self.advance(); // digit separator, stripped before parsing
+            } else {
+                break;
+            }
+        }
+        if digits.is_empty() {
+            return Err(self.error(start_pos, (start_idx, self.pos), format!("invalid {name} literal: no digits after prefix")));
+        }
+        i64::from_str_radix(&digits, radix)
+            .map(Token::Int)
+            .map_err(|_| self.error(start_pos, (start_idx, self.pos), format!("invalid {name} literal: '{digits}' has a digit out of range for base {radix}")))
+    }
+
+    /// Reads a base-10 number: an int or float, allowing `_` digit separators, a `..`-safe
+    /// decimal point, and an `e`/`E` exponent that forces the result to `Token::Float`.
     fn read_number(&mut self) -> Result<Token, EngineError> {
+        let start_pos = self.position();
+        let start_idx = self.pos;
+
+        if self.peek() == Some('0') {
+            match self.input.get(self.pos + 1).copied() {
+                Some('x') | Some('X') => return self.read_radix_int(start_pos, start_idx, 16, "hex"),
+                Some('o') | Some('O') => return self.read_radix_int(start_pos, start_idx, 8, "octal"),
+                Some('b') | Some('B') => return self.read_radix_int(start_pos, start_idx, 2, "binary"),
+                _ => {}
+            }
+        }
+
         let mut num = String::new();
         let mut is_float = false;
         while let Some(ch) = self.peek() {
             if ch.is_ascii_digit() {
                 num.push(ch);
                 self.advance();
+            } else if ch == '_' {
+                self.advance(); // digit separator, stripped before parsing
             } else if ch == '.' {
                 // Lookahead: if next char is a digit, it's a float decimal; if next is '.', it's a range '..'
                 let next = self.input.get(self.pos + 1).copied();
@@ -149,6 +295,32 @@ impl Lexer {
                 } else {
                     break;
                 }
+            } else if ch == 'e' || ch == 'E' {
+                // Scientific notation: e.g. 1e10, 6.02e23, 1.5E-9. Only consume if a valid
+                // exponent (optional sign, then a digit) actually follows.
+                let has_sign = matches!(self.input.get(self.pos + 1), Some('+') | Some('-'));
+                let digits_at = if has_sign { self.pos + 2 } else { self.pos + 1 };
+                let looks_like_exponent = self.input.get(digits_at).is_some_and(|c| c.is_ascii_digit());
+                if !looks_like_exponent {
+                    break;
+                }
+                is_float = true;
+                num.push(ch);
+                self.advance(); // consume 'e'/'E'
+                if has_sign {
+                    num.push(self.peek().expect("sign presence checked above"));
+                    self.advance();
+                }
+                while let Some(ec) = self.peek() {
+                    if ec.is_ascii_digit() {
+                        num.push(ec);
+                        self.advance();
+                    } else if ec == '_' {
+                        self.advance();
+                    } else {
+                        break;
+                    }
+                }
             } else {
                 break;
             }
@@ -156,16 +328,23 @@ impl Lexer {
         if is_float {
             num.parse::<f64>()
                 .map(Token::Float)
-                .map_err(|_| EngineError::InvalidArgument("invalid float".into()))
+                .map_err(|_| self.error(start_pos, (start_idx, self.pos), format!("invalid float literal '{num}'")))
         } else {
             num.parse::<i64>()
                 .map(Token::Int)
-                .map_err(|_| EngineError::InvalidArgument("invalid int".into()))
+                .map_err(|_| self.error(start_pos, (start_idx, self.pos), format!("invalid int literal '{num}'")))
         }
     }
 
-    fn next_token(&mut self) -> Result<Token, EngineError> {
+    fn next_token(&mut self) -> Result<(Token, Position, (usize, usize)), EngineError> {
         self.skip_whitespace();
+        let start_pos = self.position();
+        let start_idx = self.pos;
+        let tok = self.next_token_inner(start_pos, start_idx)?;
+        Ok((tok, start_pos, (start_idx, self.pos)))
+    }
+
+    fn next_token_inner(&mut self, start_pos: Position, start_idx: usize) -> Result<Token, EngineError> {
         match self.peek() {
             None => Ok(Token::Eof),
             Some('(') => { self.advance(); Ok(Token::LeftParen) }
@@ -189,8 +368,19 @@ impl Lexer {
             Some('$') => { self.advance(); Ok(Token::Dollar) }
             Some('+') => { self.advance(); Ok(Token::Plus) }
             Some('/') => { self.advance(); Ok(Token::Slash) }
+            Some('%') => { self.advance(); Ok(Token::Percent) }
+            Some('^') => { self.advance(); Ok(Token::Caret) }
+            Some('?') => {
+                self.advance();
+                if self.peek() == Some('?') {
+                    self.advance();
+                    Ok(Token::QuestionQuestion)
+                } else {
+                    Err(self.error(start_pos, (start_idx, self.pos), "expected '?' to start '??'".to_string()))
+                }
+            }
             Some('|') => { self.advance(); Ok(Token::Pipe) }
-            Some('\'') => self.read_string().map(Token::String),
+            Some(q @ ('\'' | '"')) => self.read_string(q).map(Token::String),
             Some('<') => {
                 self.advance();
                 if self.peek() == Some('-') {
@@ -230,7 +420,7 @@ impl Lexer {
                     self.advance();
                     Ok(Token::Ne)
                 } else {
-                    Err(EngineError::InvalidArgument("unexpected !".into()))
+                    Err(self.error(start_pos, (start_idx, self.pos), "unexpected !"))
                 }
             }
             Some(ch) if ch.is_ascii_digit() => self.read_number(),
@@ -239,6 +429,7 @@ impl Lexer {
                 let upper = ident.to_uppercase();
                 Ok(match upper.as_str() {
                     "MATCH" => Token::Match,
+                    "UNWIND" => Token::Unwind,
                     "CREATE" => Token::Create,
                     "SET" => Token::Set,
                     "DELETE" => Token::Delete,
@@ -259,39 +450,102 @@ impl Lexer {
                     "FALSE" => Token::False,
                     "IS" => Token::Is,
                     "EXISTS" => Token::Exists,
-                    "COUNT" | "SUM" | "AVG" | "MIN" | "MAX" => Token::Ident(ident), // Aggregate functions
+                    "IN" => Token::In,
+                    "CASE" => Token::Case,
+                    "WHEN" => Token::When,
+                    "THEN" => Token::Then,
+                    "ELSE" => Token::Else,
+                    "END" => Token::End,
+                    "DISTINCT" => Token::Distinct,
+                    "COUNT" | "SUM" | "AVG" | "MIN" | "MAX" | "COLLECT" | "STDEV" | "STDDEV" => Token::Ident(ident), // Aggregate functions
                     _ => Token::Ident(ident),
                 })
             }
-            Some(ch) => Err(EngineError::InvalidArgument(format!("unexpected char: {ch}"))),
+            Some(ch) => Err(self.error(start_pos, (start_idx, self.pos + 1), format!("unexpected char: {ch}"))),
         }
     }
 }
 
 pub struct Parser {
+    input: Vec<char>,
     tokens: Vec<Token>,
+    positions: Vec<Position>,
+    spans: Vec<(usize, usize)>,
     pos: usize,
+    /// Aliases declared so far via `WITH expr AS alias` or `RETURN expr AS alias`,
+    /// consulted by `parse_order_by` so a bare `Ident` can resolve against a
+    /// projection alias instead of only a graph-bound variable.
+    declared_aliases: std::collections::HashSet<String>,
+    /// Variable names bound by a `MATCH`/`CREATE` node or edge pattern parsed
+    /// so far, e.g. the `n` in `(n:Person)`.
+    bound_vars: std::collections::HashSet<String>,
+    /// Consulted for every `name(...)` call site to reject unknown functions
+    /// or wrong arity at parse time instead of at runtime.
+    functions: Box<dyn FunctionRegistry>,
 }
 
 impl Parser {
     pub fn new(input: &str) -> Result<Self, EngineError> {
+        Self::with_function_registry(input, Box::new(DefaultFunctionRegistry))
+    }
+
+    /// Like `new`, but validates function calls against `functions` instead
+    /// of the built-in registry alone, so hosts can add custom scalar
+    /// functions without touching the core grammar.
+    pub fn with_function_registry(input: &str, functions: Box<dyn FunctionRegistry>) -> Result<Self, EngineError> {
+        let chars: Vec<char> = input.chars().collect();
         let mut lexer = Lexer::new(input);
         let mut tokens = Vec::new();
+        let mut positions = Vec::new();
+        let mut spans = Vec::new();
         loop {
-            let tok = lexer.next_token()?;
-            if tok == Token::Eof {
-                tokens.push(tok);
+            let (tok, tok_pos, tok_span) = lexer.next_token()?;
+            let is_eof = tok == Token::Eof;
+            tokens.push(tok);
+            positions.push(tok_pos);
+            spans.push(tok_span);
+            if is_eof {
                 break;
             }
-            tokens.push(tok);
         }
-        Ok(Self { tokens, pos: 0 })
+        Ok(Self {
+            input: chars,
+            tokens,
+            positions,
+            spans,
+            pos: 0,
+            declared_aliases: std::collections::HashSet::new(),
+            bound_vars: std::collections::HashSet::new(),
+            functions,
+        })
     }
 
     fn peek(&self) -> &Token {
         self.tokens.get(self.pos).unwrap_or(&Token::Eof)
     }
 
+    /// Looks `offset` tokens past the current one without consuming anything,
+    /// used to disambiguate `NOT IN` from a standalone prefix `NOT`.
+    fn peek_at(&self, offset: usize) -> &Token {
+        self.tokens.get(self.pos + offset).unwrap_or(&Token::Eof)
+    }
+
+    fn current_position(&self) -> Position {
+        self.positions.get(self.pos).copied()
+            .or_else(|| self.positions.last().copied())
+            .unwrap_or(Position { line: 1, col: 1 })
+    }
+
+    fn current_span(&self) -> (usize, usize) {
+        self.spans.get(self.pos).copied()
+            .or_else(|| self.spans.last().copied())
+            .unwrap_or((0, 0))
+    }
+
+    fn error(&self, message: impl Into<String>) -> EngineError {
+        make_parse_error(&self.input, self.current_position(), self.current_span(), message)
+    }
+
     fn advance(&mut self) -> Token {
         let tok = self.peek().clone();
         if tok != Token::Eof {
@@ -301,15 +555,35 @@ impl Parser {
     }
 
     fn expect(&mut self, expected: Token) -> Result<(), EngineError> {
+        let err = self.error(format!("expected {:?}, got {:?}", expected, self.peek()));
         let tok = self.advance();
         if tok == expected {
             Ok(())
         } else {
-            Err(EngineError::InvalidArgument(format!("expected {:?}, got {:?}", expected, tok)))
+            Err(err)
         }
     }
 
     pub fn parse_query(&mut self) -> Result<Query, EngineError> {
+        // DEFINE NODE/DEFINE EDGE is a standalone DDL statement - mutually
+        // exclusive with every other clause, so it short-circuits here
+        // instead of flowing through the MATCH/CREATE pipeline below.
+        if matches!(self.peek(), Token::Ident(name) if name.eq_ignore_ascii_case("DEFINE")) {
+            let define_clause = Some(self.parse_define()?);
+            return Ok(Query {
+                unwind_clause: None, match_clause: None, create_clause: None, with_clause: None, where_clause: None,
+                set_clause: None, delete_clause: None, return_clause: None, order_by: None, limit: None,
+                define_clause,
+            });
+        }
+
+        // Parse optional UNWIND clause (precedes MATCH/CREATE)
+        let unwind_clause = if *self.peek() == Token::Unwind {
+            Some(self.parse_unwind()?)
+        } else {
+            None
+        };
+
         // Parse optional MATCH clause
         let match_clause = if *self.peek() == Token::Match {
             Some(self.parse_match()?)
@@ -326,7 +600,7 @@ impl Parser {
         
         // At least one of MATCH or CREATE must be present
         if match_clause.is_none() && create_clause.is_none() {
-            return Err(EngineError::InvalidArgument(format!("expected MATCH or CREATE, got {:?}", self.peek())));
+            return Err(self.error(format!("expected MATCH or CREATE, got {:?}", self.peek())));
         }
         
         // WITH clause (optional pipeline transformation)
@@ -342,7 +616,23 @@ impl Parser {
         } else {
             None
         };
-        
+
+        // SET clause (optional mutation)
+        let set_clause = if *self.peek() == Token::Set {
+            Some(self.parse_set()?)
+        } else {
+            None
+        };
+
+        // DELETE clause (optional mutation), possibly preceded by the DETACH modifier
+        let starts_delete = *self.peek() == Token::Delete
+            || matches!(self.peek(), Token::Ident(name) if name.eq_ignore_ascii_case("DETACH"));
+        let delete_clause = if starts_delete {
+            Some(self.parse_delete()?)
+        } else {
+            None
+        };
+
         // RETURN clause (optional for CREATE)
         let return_clause = if *self.peek() == Token::Return {
             Some(self.parse_return()?)
@@ -360,12 +650,105 @@ impl Parser {
             if let Token::Int(n) = self.advance() {
                 Some(n as u64)
             } else {
-                return Err(EngineError::InvalidArgument("expected int after LIMIT".into()));
+                return Err(self.error("expected int after LIMIT"));
             }
         } else {
             None
         };
-        Ok(Query { match_clause, create_clause, with_clause, where_clause, return_clause, order_by, limit })
+        Ok(Query { unwind_clause, match_clause, create_clause, with_clause, where_clause, set_clause, delete_clause, return_clause, order_by, limit, define_clause: None })
+    }
+
+    /// `UNWIND expr AS var` - the alias is mandatory, same as `WITH`.
+    fn parse_unwind(&mut self) -> Result<UnwindClause, EngineError> {
+        self.expect(Token::Unwind)?;
+        let expr = self.parse_expr()?;
+        self.expect(Token::As)?;
+        let variable = if let Token::Ident(name) = self.advance() {
+            name
+        } else {
+            return Err(self.error("expected variable after AS"));
+        };
+        self.bound_vars.insert(variable.clone());
+        Ok(UnwindClause { expr, variable })
+    }
+
+    /// `DEFINE NODE Label { prop: Type, ... }` or
+    /// `DEFINE EDGE Type (FromLabel -> ToLabel) { prop: Type, ... }`.
+    /// `NODE`/`EDGE`/`KEY`/`NULLABLE` are checked by name rather than given
+    /// dedicated tokens, same as `DETACH DELETE` above, so they stay usable
+    /// as ordinary identifiers everywhere outside this one statement.
+    fn parse_define(&mut self) -> Result<DefineClause, EngineError> {
+        self.advance(); // consume DEFINE
+        let target = match self.advance() {
+            Token::Ident(kw) if kw.eq_ignore_ascii_case("NODE") => {
+                let label = self.expect_ident("label")?;
+                let properties = self.parse_typed_properties()?;
+                DefineTarget::Node(NodeTypeDef { label, properties })
+            }
+            Token::Ident(kw) if kw.eq_ignore_ascii_case("EDGE") => {
+                let edge_type = self.expect_ident("edge type")?;
+                self.expect(Token::LeftParen)?;
+                let from_label = self.expect_ident("source label")?;
+                self.expect(Token::Arrow)?;
+                let to_label = self.expect_ident("target label")?;
+                self.expect(Token::RightParen)?;
+                let properties = self.parse_typed_properties()?;
+                DefineTarget::Edge(EdgeTypeDef { edge_type, from_label, to_label, properties })
+            }
+            other => return Err(self.error(format!("expected NODE or EDGE after DEFINE, got {:?}", other))),
+        };
+        Ok(DefineClause { target })
+    }
+
+    fn expect_ident(&mut self, what: &str) -> Result<String, EngineError> {
+        match self.advance() {
+            Token::Ident(name) => Ok(name),
+            other => Err(self.error(format!("expected {what}, got {:?}", other))),
+        }
+    }
+
+    /// `{ name: Type [KEY], ... }` - the `{...}` property-type list shared by
+    /// `DEFINE NODE`/`DEFINE EDGE`.
+    fn parse_typed_properties(&mut self) -> Result<Vec<PropertyDef>, EngineError> {
+        self.expect(Token::LeftBrace)?;
+        let mut properties = Vec::new();
+        loop {
+            if *self.peek() == Token::RightBrace {
+                break;
+            }
+            let name = self.expect_ident("property name")?;
+            self.expect(Token::Colon)?;
+            let typing = self.parse_typing()?;
+            let key = matches!(self.peek(), Token::Ident(kw) if kw.eq_ignore_ascii_case("KEY"));
+            if key {
+                self.advance();
+            }
+            properties.push(PropertyDef { name, typing, key });
+            if *self.peek() == Token::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect(Token::RightBrace)?;
+        Ok(properties)
+    }
+
+    /// `Int`/`Float`/`String`/`Bool`/`Any`, or `NULLABLE <Type>`.
+    fn parse_typing(&mut self) -> Result<Typing, EngineError> {
+        if matches!(self.peek(), Token::Ident(kw) if kw.eq_ignore_ascii_case("NULLABLE")) {
+            self.advance();
+            return Ok(Typing::Nullable(Box::new(self.parse_typing()?)));
+        }
+        let name = self.expect_ident("a type name")?;
+        match name.to_uppercase().as_str() {
+            "INT" | "INTEGER" => Ok(Typing::Int),
+            "FLOAT" => Ok(Typing::Float),
+            "STRING" => Ok(Typing::String),
+            "BOOL" | "BOOLEAN" => Ok(Typing::Bool),
+            "ANY" => Ok(Typing::Any),
+            other => Err(self.error(format!("unknown type {other}"))),
+        }
     }
 
     fn parse_match(&mut self) -> Result<MatchClause, EngineError> {
@@ -431,6 +814,9 @@ impl Parser {
             let var = if let Token::Ident(name) = self.peek() {
                 let v = Some(name.clone());
                 self.advance();
+                if let Some(name) = &v {
+                    self.bound_vars.insert(name.clone());
+                }
                 v
             } else {
                 None
@@ -451,13 +837,13 @@ impl Parser {
                             types.push(t.clone());
                             self.advance(); // consume type ident
                         } else {
-                            return Err(EngineError::InvalidArgument("expected type after |".into()));
+                            return Err(self.error("expected type after |"));
                         }
                     }
                     
                     Some(types.join("|"))
                 } else {
-                    return Err(EngineError::InvalidArgument("expected type after :".into()));
+                    return Err(self.error("expected type after :"));
                 }
             } else {
                 None
@@ -481,9 +867,7 @@ impl Parser {
             let depth_outside = self.parse_depth_range()?;
             let final_depth = match (depth, depth_outside) {
                 (Some(_), Some(_)) => {
-                    return Err(EngineError::InvalidArgument(
-                        "depth specified twice (inside and outside bracket)".into(),
-                    ));
+                    return Err(self.error("depth specified twice (inside and outside bracket)"));
                 }
                 (Some(d), None) => Some(d),
                 (None, Some(d)) => Some(d),
@@ -561,7 +945,7 @@ impl Parser {
                     self.advance();
                     Ok(Some(DepthRange { min: 0, max: max_val }))
                 } else {
-                    return Err(EngineError::InvalidArgument("expected number after ..".into()));
+                    return Err(self.error("expected number after .."));
                 }
             }
             _ => {
@@ -576,6 +960,9 @@ impl Parser {
         let variable = if let Token::Ident(name) = self.peek() {
             let v = Some(name.clone());
             self.advance();
+            if let Some(name) = &v {
+                self.bound_vars.insert(name.clone());
+            }
             v
         } else {
             None
@@ -597,7 +984,10 @@ impl Parser {
         Ok(NodePattern { variable, labels, properties })
     }
 
-    fn parse_properties(&mut self) -> Result<HashMap<String, Literal>, EngineError> {
+    /// Property values are full expressions, not just literals, so a CREATE
+    /// pattern can read the enclosing tuple (`{score: n.score * 2 + 1}`)
+    /// instead of only ever writing constants.
+    fn parse_properties(&mut self) -> Result<HashMap<String, Expr>, EngineError> {
         let mut props = HashMap::new();
         loop {
             if *self.peek() == Token::RightBrace {
@@ -606,10 +996,10 @@ impl Parser {
             let key = if let Token::Ident(k) = self.advance() {
                 k
             } else {
-                return Err(EngineError::InvalidArgument("expected property key".into()));
+                return Err(self.error("expected property key"));
             };
             self.expect(Token::Colon)?;
-            let val = self.parse_literal()?;
+            let val = self.parse_expr()?;
             props.insert(key, val);
             if *self.peek() == Token::Comma {
                 self.advance();
@@ -621,6 +1011,7 @@ impl Parser {
     }
 
     fn parse_literal(&mut self) -> Result<Literal, EngineError> {
+        let err = self.error(format!("expected literal, got {:?}", self.peek()));
         match self.advance() {
             Token::String(s) => Ok(Literal::String(s)),
             Token::Int(i) => Ok(Literal::Int(i)),
@@ -628,7 +1019,7 @@ impl Parser {
             Token::True => Ok(Literal::Bool(true)),
             Token::False => Ok(Literal::Bool(false)),
             Token::Null => Ok(Literal::Null),
-            tok => Err(EngineError::InvalidArgument(format!("expected literal, got {:?}", tok))),
+            _ => Err(err),
         }
     }
 
@@ -638,6 +1029,65 @@ impl Parser {
         Ok(WhereClause { expr })
     }
 
+    /// Parses `SET n.prop = expr, m.prop2 = expr2, ...`, reusing the precedence-climbing
+    /// expression parser for each right-hand side.
+    fn parse_set(&mut self) -> Result<SetClause, EngineError> {
+        self.expect(Token::Set)?;
+        let mut assignments = Vec::new();
+        loop {
+            let variable = if let Token::Ident(name) = self.advance() {
+                name
+            } else {
+                return Err(self.error("expected variable before '.' in SET assignment"));
+            };
+            self.expect(Token::Dot)?;
+            let property = if let Token::Ident(name) = self.advance() {
+                name
+            } else {
+                return Err(self.error("expected property name after '.' in SET assignment"));
+            };
+            self.expect(Token::Eq)?;
+            let value = self.parse_expr()?;
+            assignments.push(SetItem { variable, property, value });
+            if *self.peek() == Token::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        Ok(SetClause { assignments })
+    }
+
+    /// Parses `[DETACH] DELETE n, m, ...`.
+    fn parse_delete(&mut self) -> Result<DeleteClause, EngineError> {
+        let detach = if let Token::Ident(name) = self.peek() {
+            if name.eq_ignore_ascii_case("DETACH") {
+                self.advance();
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+        self.expect(Token::Delete)?;
+        let mut variables = Vec::new();
+        loop {
+            let variable = if let Token::Ident(name) = self.advance() {
+                name
+            } else {
+                return Err(self.error("expected variable name in DELETE"));
+            };
+            variables.push(variable);
+            if *self.peek() == Token::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        Ok(DeleteClause { variables, detach })
+    }
+
     fn parse_with(&mut self) -> Result<WithClause, EngineError> {
         self.expect(Token::With)?;
         let mut items = Vec::new();
@@ -650,9 +1100,10 @@ impl Parser {
             let alias = if let Token::Ident(name) = self.advance() {
                 name
             } else {
-                return Err(EngineError::InvalidArgument("expected alias after AS".into()));
+                return Err(self.error("expected alias after AS"));
             };
-            
+            self.declared_aliases.insert(alias.clone());
+
             items.push(WithItem { expr, alias });
             
             if *self.peek() == Token::Comma {
@@ -666,91 +1117,100 @@ impl Parser {
     }
 
     fn parse_expr(&mut self) -> Result<Expr, EngineError> {
-        self.parse_or_expr()
+        self.parse_expr_bp(0)
     }
 
-    fn parse_or_expr(&mut self) -> Result<Expr, EngineError> {
-        let mut left = self.parse_and_expr()?;
-        while *self.peek() == Token::Or {
-            self.advance();
-            let right = self.parse_and_expr()?;
-            left = Expr::BinaryOp(Box::new(left), BinOp::Or, Box::new(right));
+    /// Binding power (left, right) of each infix operator this grammar supports.
+    /// Lower binds looser; `right_bp = left_bp + 1` makes an operator left-associative,
+    /// while `right_bp = left_bp - 1` (as for `^`) makes it right-associative, since the
+    /// recursive call in `parse_expr_bp` then accepts another `^` at the same precedence.
+    fn infix_binding_power(tok: &Token) -> Option<(u8, u8)> {
+        match tok {
+            Token::QuestionQuestion => Some((0, 1)),
+            Token::Or => Some((1, 2)),
+            Token::And => Some((3, 4)),
+            Token::Eq | Token::Ne | Token::Lt | Token::Le | Token::Gt | Token::Ge => Some((5, 6)),
+            Token::Plus | Token::Minus => Some((7, 8)),
+            Token::Star | Token::Slash | Token::Percent => Some((9, 10)),
+            Token::Caret => Some((12, 11)),
+            _ => None,
         }
-        Ok(left)
     }
 
-    fn parse_and_expr(&mut self) -> Result<Expr, EngineError> {
-        let mut left = self.parse_comparison()?;
-        while *self.peek() == Token::And {
-            self.advance();
-            let right = self.parse_comparison()?;
-            left = Expr::BinaryOp(Box::new(left), BinOp::And, Box::new(right));
+    fn infix_to_binop(tok: &Token) -> BinOp {
+        match tok {
+            Token::QuestionQuestion => BinOp::Coalesce,
+            Token::Or => BinOp::Or,
+            Token::And => BinOp::And,
+            Token::Eq => BinOp::Eq,
+            Token::Ne => BinOp::Ne,
+            Token::Lt => BinOp::Lt,
+            Token::Le => BinOp::Le,
+            Token::Gt => BinOp::Gt,
+            Token::Ge => BinOp::Ge,
+            Token::Plus => BinOp::Add,
+            Token::Minus => BinOp::Sub,
+            Token::Star => BinOp::Mul,
+            Token::Slash => BinOp::Div,
+            Token::Percent => BinOp::Mod,
+            Token::Caret => BinOp::Pow,
+            _ => unreachable!("infix_to_binop called on a non-operator token"),
         }
-        Ok(left)
     }
 
-    fn parse_comparison(&mut self) -> Result<Expr, EngineError> {
-        let left = self.parse_additive()?;
-        
-        // Check for IS NULL / IS NOT NULL
+    /// Precedence-climbing expression parser: parses a primary term, then folds
+    /// in operators whose left binding power is at least `min_bp`, recursing with
+    /// the operator's right binding power so tighter operators bind first.
+    fn parse_expr_bp(&mut self, min_bp: u8) -> Result<Expr, EngineError> {
+        let mut left = self.parse_primary()?;
+
+        // IS [NOT] NULL is a postfix special case applied directly to the primary.
         if *self.peek() == Token::Is {
             self.advance(); // consume IS
-            
-            // Check for NOT
             let is_not = if *self.peek() == Token::Not {
                 self.advance(); // consume NOT
                 true
             } else {
                 false
             };
-            
-            // Expect NULL
             self.expect(Token::Null)?;
-            
-            return Ok(if is_not {
+            left = if is_not {
                 Expr::IsNotNull(Box::new(left))
             } else {
                 Expr::IsNull(Box::new(left))
-            });
+            };
         }
-        
-        // Regular comparison operators
-        let op = match self.peek() {
-            Token::Eq => { self.advance(); BinOp::Eq }
-            Token::Ne => { self.advance(); BinOp::Ne }
-            Token::Lt => { self.advance(); BinOp::Lt }
-            Token::Le => { self.advance(); BinOp::Le }
-            Token::Gt => { self.advance(); BinOp::Gt }
-            Token::Ge => { self.advance(); BinOp::Ge }
-            _ => return Ok(left),
-        };
-        let right = self.parse_additive()?;
-        Ok(Expr::BinaryOp(Box::new(left), op, Box::new(right)))
-    }
 
-    fn parse_additive(&mut self) -> Result<Expr, EngineError> {
-        let mut left = self.parse_multiplicative()?;
-        loop {
-            let op = match self.peek() {
-                Token::Plus => { self.advance(); BinOp::Add }
-                Token::Minus => { self.advance(); BinOp::Sub }
-                _ => break,
+        // [NOT] IN is another postfix special case; the right-hand side is
+        // typically a list literal but any expression is accepted.
+        if *self.peek() == Token::In || (*self.peek() == Token::Not && *self.peek_at(1) == Token::In) {
+            let negate = if *self.peek() == Token::Not {
+                self.advance(); // consume NOT
+                true
+            } else {
+                false
+            };
+            self.advance(); // consume IN
+            let list = self.parse_primary()?;
+            let in_expr = Expr::In(Box::new(left), Box::new(list));
+            left = if negate {
+                Expr::UnaryOp(UnOp::Not, Box::new(in_expr))
+            } else {
+                in_expr
             };
-            let right = self.parse_multiplicative()?;
-            left = Expr::BinaryOp(Box::new(left), op, Box::new(right));
         }
-        Ok(left)
-    }
 
-    fn parse_multiplicative(&mut self) -> Result<Expr, EngineError> {
-        let mut left = self.parse_primary()?;
         loop {
-            let op = match self.peek() {
-                Token::Star => { self.advance(); BinOp::Mul }
-                Token::Slash => { self.advance(); BinOp::Div }
-                _ => break,
+            let (left_bp, right_bp) = match Self::infix_binding_power(self.peek()) {
+                Some(bp) => bp,
+                None => break,
             };
-            let right = self.parse_primary()?;
+            if left_bp < min_bp {
+                break;
+            }
+            let op_tok = self.advance();
+            let op = Self::infix_to_binop(&op_tok);
+            let right = self.parse_expr_bp(right_bp)?;
             left = Expr::BinaryOp(Box::new(left), op, Box::new(right));
         }
         Ok(left)
@@ -763,7 +1223,7 @@ impl Parser {
                 if let Token::Ident(param_name) = self.advance() {
                     Ok(Expr::Parameter(param_name))
                 } else {
-                    Err(EngineError::InvalidArgument("expected parameter name after $".into()))
+                    Err(self.error("expected parameter name after $"))
                 }
             }
             Token::Ident(name) => {
@@ -772,51 +1232,66 @@ impl Parser {
                 // Check for function call
                 if *self.peek() == Token::LeftParen {
                     let upper = name.to_uppercase();
-                    
-                    // Try aggregate functions first
-                    let agg_func = match upper.as_str() {
-                        "COUNT" => Some(AggFunc::Count),
-                        "SUM" => Some(AggFunc::Sum),
-                        "AVG" => Some(AggFunc::Avg),
-                        "MIN" => Some(AggFunc::Min),
-                        "MAX" => Some(AggFunc::Max),
-                        _ => None,
-                    };
-                    
-                    if let Some(func) = agg_func {
-                        // Aggregate function
-                        self.advance(); // consume (
-                        let arg = self.parse_expr()?;
-                        self.expect(Token::RightParen)?;
-                        return Ok(Expr::Aggregate(func, Box::new(arg)));
+                    self.advance(); // consume (
+
+                    // An aggregate call may lead its argument list with
+                    // DISTINCT (`count(DISTINCT x)`); checked for any
+                    // function here and rejected below once we know whether
+                    // `name` actually resolved to an aggregate.
+                    let distinct = if *self.peek() == Token::Distinct {
+                        self.advance();
+                        true
                     } else {
-                        // Generic function call (ID, etc.)
-                        self.advance(); // consume (
-                        let mut args = Vec::new();
-                        
-                        // Parse arguments (comma-separated)
-                        if *self.peek() != Token::RightParen {
-                            loop {
-                                args.push(self.parse_expr()?);
-                                if *self.peek() == Token::Comma {
-                                    self.advance();
-                                } else {
-                                    break;
-                                }
+                        false
+                    };
+
+                    // Parse arguments (comma-separated) before validating
+                    // arity, so an arity error still reports past the call.
+                    let mut args = Vec::new();
+                    if *self.peek() != Token::RightParen {
+                        loop {
+                            args.push(self.parse_expr()?);
+                            if *self.peek() == Token::Comma {
+                                self.advance();
+                            } else {
+                                break;
                             }
                         }
-                        
-                        self.expect(Token::RightParen)?;
-                        return Ok(Expr::FunctionCall(name, args));
                     }
+                    self.expect(Token::RightParen)?;
+
+                    let sig = self.functions.lookup(&upper)
+                        .ok_or_else(|| self.error(format!("unknown function: {name}")))?;
+                    if !sig.arity.accepts(args.len()) {
+                        return Err(self.error(format!(
+                            "{name} expects {} argument(s), got {}",
+                            sig.arity, args.len()
+                        )));
+                    }
+                    if distinct && sig.kind != FunctionKind::Aggregate {
+                        return Err(self.error(format!("DISTINCT is only valid inside an aggregate call, not {name}")));
+                    }
+                    let wrap = |func: AggFunc| if distinct { AggFunc::Distinct(Box::new(func)) } else { func };
+
+                    return match (sig.kind, upper.as_str()) {
+                        (FunctionKind::Aggregate, "COUNT") => Ok(Expr::Aggregate(wrap(AggFunc::Count), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, "SUM") => Ok(Expr::Aggregate(wrap(AggFunc::Sum), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, "AVG") => Ok(Expr::Aggregate(wrap(AggFunc::Avg), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, "MIN") => Ok(Expr::Aggregate(wrap(AggFunc::Min), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, "MAX") => Ok(Expr::Aggregate(wrap(AggFunc::Max), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, "COLLECT") => Ok(Expr::Aggregate(wrap(AggFunc::Collect), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, "STDEV") | (FunctionKind::Aggregate, "STDDEV") => Ok(Expr::Aggregate(wrap(AggFunc::Stdev), Box::new(args.into_iter().next().unwrap()))),
+                        (FunctionKind::Aggregate, other) => unreachable!("registry declared unknown aggregate {other}"),
+                        (FunctionKind::Scalar, _) => Ok(Expr::FunctionCall(name, args)),
+                    };
                 }
-                
+
                 if *self.peek() == Token::Dot {
                     self.advance();
                     if let Token::Ident(prop) = self.advance() {
                         Ok(Expr::Property(name, prop))
                     } else {
-                        Err(EngineError::InvalidArgument("expected property name".into()))
+                        Err(self.error("expected property name"))
                     }
                 } else {
                     Ok(Expr::Ident(name))
@@ -829,6 +1304,10 @@ impl Parser {
                 self.advance();
                 Ok(Expr::UnaryOp(UnOp::Not, Box::new(self.parse_primary()?)))
             }
+            Token::Minus => {
+                self.advance();
+                Ok(Expr::UnaryOp(UnOp::Neg, Box::new(self.parse_primary()?)))
+            }
             Token::Exists => {
                 self.advance(); // consume EXISTS
                 self.expect(Token::LeftBrace)?; // expect {
@@ -845,16 +1324,70 @@ impl Parser {
                 self.expect(Token::RightParen)?;
                 Ok(expr)
             }
-            tok => Err(EngineError::InvalidArgument(format!("unexpected token in expr: {:?}", tok))),
+            Token::LeftBracket => {
+                self.advance(); // consume [
+                let mut items = Vec::new();
+                if *self.peek() != Token::RightBracket {
+                    loop {
+                        items.push(self.parse_expr()?);
+                        if *self.peek() == Token::Comma {
+                            self.advance();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RightBracket)?;
+                Ok(Expr::List(items))
+            }
+            Token::Case => self.parse_case(),
+            tok => Err(self.error(format!("unexpected token in expr: {:?}", tok))),
         }
     }
 
+    /// Parses both simple (`CASE operand WHEN value THEN ... END`) and searched
+    /// (`CASE WHEN cond THEN ... END`) forms: an operand is present unless the
+    /// token right after `CASE` is `WHEN`.
+    fn parse_case(&mut self) -> Result<Expr, EngineError> {
+        self.expect(Token::Case)?;
+        let operand = if *self.peek() == Token::When {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+
+        let mut branches = Vec::new();
+        while *self.peek() == Token::When {
+            self.advance(); // consume WHEN
+            let when = self.parse_expr()?;
+            self.expect(Token::Then)?;
+            let then = self.parse_expr()?;
+            branches.push((when, then));
+        }
+        if branches.is_empty() {
+            return Err(self.error("CASE requires at least one WHEN branch"));
+        }
+
+        let else_branch = if *self.peek() == Token::Else {
+            self.advance(); // consume ELSE
+            Some(Box::new(self.parse_expr()?))
+        } else {
+            None
+        };
+
+        self.expect(Token::End)?;
+        Ok(Expr::Case(CaseExpr { operand, branches, else_branch }))
+    }
+
     fn parse_order_by(&mut self) -> Result<OrderByClause, EngineError> {
         self.expect(Token::Order)?;
         self.expect(Token::By)?;
         let mut items = Vec::new();
         loop {
             let expr = self.parse_expr()?;
+            if let Expr::Ident(name) = &expr {
+                self.check_known_ident(name)?;
+            }
             let descending = match self.peek() {
                 Token::Desc => { self.advance(); true }
                 Token::Asc => { self.advance(); false }
@@ -870,12 +1403,38 @@ impl Parser {
         Ok(OrderByClause { items })
     }
 
+    /// Rejects a bare `Ident` in ORDER BY that is neither a pattern-bound variable
+    /// nor a `WITH`/`RETURN` alias declared earlier in the same query.
+    fn check_known_ident(&self, name: &str) -> Result<(), EngineError> {
+        if self.bound_vars.contains(name) || self.declared_aliases.contains(name) {
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "ORDER BY references unknown identifier '{name}': expected a MATCH/CREATE variable or a declared alias"
+            )))
+        }
+    }
+
+    /// Parses `RETURN expr [AS alias], ...`. Unlike `WITH`, the alias is optional;
+    /// declared aliases are recorded so `parse_order_by` can resolve a bare `Ident`
+    /// against them instead of assuming it names a graph binding.
     fn parse_return(&mut self) -> Result<ReturnClause, EngineError> {
         self.expect(Token::Return)?;
         let mut items = Vec::new();
         loop {
             let expr = self.parse_expr()?;
-            let alias = None; // Simplified: no AS alias support yet
+            let alias = if *self.peek() == Token::As {
+                self.advance(); // consume AS
+                let name = if let Token::Ident(name) = self.advance() {
+                    name
+                } else {
+                    return Err(self.error("expected alias after AS"));
+                };
+                self.declared_aliases.insert(name.clone());
+                Some(name)
+            } else {
+                None
+            };
             items.push(ReturnItem { expr, alias });
             if *self.peek() == Token::Comma {
                 self.advance();
@@ -887,7 +1446,19 @@ impl Parser {
     }
 }
 
-pub fn parse(input: &str) -> Result<Query, EngineError> {
+/// Parses `input` into a `Query`, running the constant-folding optimizer pass
+/// over it unless `optimize` is false (e.g. callers comparing pre/post
+/// optimization output, such as `EXPLAIN`).
+pub fn parse_with_options(input: &str, optimize: bool) -> Result<Query, EngineError> {
     let mut parser = Parser::new(input)?;
-    parser.parse_query()
+    let query = parser.parse_query()?;
+    if optimize {
+        Ok(super::optimizer::optimize_query(query))
+    } else {
+        Ok(query)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Query, EngineError> {
+    parse_with_options(input, true)
 }
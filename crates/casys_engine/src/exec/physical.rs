@@ -0,0 +1,383 @@
+//! Physical execution plan, lowered from the logical `PlanNode` tree by
+//! `to_physical`. `PlanNode` stays the shape the planner builds from a
+//! parsed query; `PhysicalPlan` is the set of concrete operators an executor
+//! would actually run, chosen using `Statistics` - index seek vs scan,
+//! `ExpandInto` vs `ExpandAll` depending on which side is already bound,
+//! `HashJoin` vs `NestedLoopJoin` depending on whether the enclosing filter
+//! has an equi-join conjunct to key on. Lowering is a pure tree rewrite; it
+//! doesn't touch the store or execute anything.
+
+use super::ast::{BinOp, DepthRange, Direction, Expr, Literal, OrderByItem, PathUniqueness, Pattern, ReturnItem};
+use super::plan_optimizer::{bound_vars, join_conjuncts, split_conjuncts};
+use super::planner::PlanNode;
+use crate::types::EngineError;
+
+/// Indexes available to the lowering pass. Empty by default, so `to_physical`
+/// only ever picks a `NodeByPropertyIndexSeek` when a caller has registered
+/// one with `with_property_index`; it is not filled in from
+/// `InMemoryGraphStore`'s `property_index`.
+#[derive(Debug, Clone, Default)]
+pub struct Statistics {
+    property_indexes: std::collections::HashSet<(String, String)>,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers that `label.property` has an index, making an equality
+    /// filter over it eligible for `NodeByPropertyIndexSeek`.
+    pub fn with_property_index(mut self, label: impl Into<String>, property: impl Into<String>) -> Self {
+        self.property_indexes.insert((label.into(), property.into()));
+        self
+    }
+
+    pub fn has_property_index(&self, label: &str, property: &str) -> bool {
+        self.property_indexes.contains(&(label.to_string(), property.to_string()))
+    }
+}
+
+/// Physical operators chosen from a `PlanNode` by `to_physical`. Most
+/// variants mirror their `PlanNode` counterpart one-to-one; `NodeByLabelScan`
+/// may instead lower to `NodeByPropertyIndexSeek` when an equality filter
+/// sits directly above it and `Statistics` has a matching index, and
+/// `CartesianProduct` lowers to `HashJoin` or `NestedLoopJoin` depending on
+/// whether the enclosing filter has an equi-join conjunct to key on.
+#[derive(Debug, Clone)]
+pub enum PhysicalPlan {
+    NodeByLabelScan {
+        variable: String,
+        label: String,
+    },
+    AllNodesScan {
+        variable: String,
+    },
+    /// Replaces both a `NodeByLabelScan` and the equality filter that fed it:
+    /// seeks `label` nodes where `property == value` via an index instead of
+    /// scanning and filtering.
+    NodeByPropertyIndexSeek {
+        variable: String,
+        label: String,
+        property: String,
+        value: Literal,
+    },
+    CreateExec {
+        patterns: Vec<Pattern>,
+    },
+    MatchCreateExec {
+        match_input: Box<PhysicalPlan>,
+        create_patterns: Vec<Pattern>,
+    },
+    /// Mirrors `PlanNode::UnwindCreate` one-to-one; no indexing applies to a
+    /// literal/parameter list, so there's nothing to choose between here.
+    UnwindCreateExec {
+        expr: Expr,
+        variable: String,
+        create_patterns: Vec<Pattern>,
+    },
+    FilterExec {
+        input: Box<PhysicalPlan>,
+        predicate: Expr,
+    },
+    ProjectExec {
+        input: Box<PhysicalPlan>,
+        items: Vec<ReturnItem>,
+    },
+    OrderByExec {
+        input: Box<PhysicalPlan>,
+        items: Vec<OrderByItem>,
+    },
+    AggregateExec {
+        input: Box<PhysicalPlan>,
+        group_by: Vec<Expr>,
+        aggregates: Vec<(String, Expr)>,
+    },
+    LimitExec {
+        input: Box<PhysicalPlan>,
+        count: u64,
+    },
+    /// Traverses from `from_var` to a fresh `to_var`, materializing every
+    /// matching neighbor.
+    ExpandAll {
+        input: Box<PhysicalPlan>,
+        from_var: String,
+        edge_var: Option<String>,
+        to_var: String,
+        edge_type: Option<String>,
+        direction: Direction,
+        depth: Option<DepthRange>,
+        path_uniqueness: PathUniqueness,
+        optional: bool,
+    },
+    /// Traverses from `from_var` and checks the result against the
+    /// already-bound `to_var`, instead of materializing every neighbor.
+    ExpandInto {
+        input: Box<PhysicalPlan>,
+        from_var: String,
+        edge_var: Option<String>,
+        to_var: String,
+        edge_type: Option<String>,
+        direction: Direction,
+        depth: Option<DepthRange>,
+        path_uniqueness: PathUniqueness,
+        optional: bool,
+    },
+    /// Pairs every `left` row with every `right` row, checking `predicate`
+    /// (if any) on each pair. The fallback when there's no equi-join
+    /// conjunct to build a hash table from.
+    NestedLoopJoin {
+        left: Box<PhysicalPlan>,
+        right: Box<PhysicalPlan>,
+        predicate: Option<Expr>,
+    },
+    /// Joins `left`/`right` by hashing on `join_keys`, the equality conjuncts
+    /// pulled out of the enclosing filter (or carried over one-to-one from a
+    /// logical `PlanNode::HashJoin`); `residual` is whatever else the filter
+    /// required, applied after the join.
+    HashJoin {
+        left: Box<PhysicalPlan>,
+        right: Box<PhysicalPlan>,
+        join_keys: Vec<(Expr, Expr)>,
+        residual: Option<Expr>,
+    },
+    /// Mirrors `PlanNode::ShortestPath` one-to-one; there's no alternative
+    /// physical strategy for it yet (e.g. a bidirectional search), so
+    /// lowering is a plain passthrough.
+    ShortestPathExec {
+        input: Box<PhysicalPlan>,
+        from_var: String,
+        to_var: String,
+        edge_type: Option<String>,
+        direction: Direction,
+        weight_prop: Option<String>,
+        heuristic: Option<Expr>,
+        path_var: String,
+        cost_var: String,
+    },
+    /// Mirrors `PlanNode::GraphAlgo` one-to-one; there's no indexing to
+    /// choose between for a whole-graph algorithm.
+    GraphAlgoExec {
+        name: String,
+        args: std::collections::HashMap<String, Literal>,
+        yield_cols: Vec<String>,
+    },
+    /// Mirrors `PlanNode::Fixpoint` one-to-one; semi-naive iteration is the
+    /// only evaluation strategy implemented, so there's nothing to choose
+    /// between here either.
+    FixpointExec {
+        seed: Box<PhysicalPlan>,
+        recursive: Box<PhysicalPlan>,
+        bind_var: String,
+    },
+}
+
+/// Lowers a logical `PlanNode` into a `PhysicalPlan`, picking concrete
+/// operators using `stats`.
+pub fn to_physical(plan: &PlanNode, stats: &Statistics) -> Result<PhysicalPlan, EngineError> {
+    match plan {
+        PlanNode::LabelScan { variable, label } => {
+            Ok(PhysicalPlan::NodeByLabelScan { variable: variable.clone(), label: label.clone() })
+        }
+        PlanNode::FullScan { variable } => Ok(PhysicalPlan::AllNodesScan { variable: variable.clone() }),
+        PlanNode::Create { patterns } => Ok(PhysicalPlan::CreateExec { patterns: patterns.clone() }),
+        PlanNode::MatchCreate { match_input, create_patterns } => Ok(PhysicalPlan::MatchCreateExec {
+            match_input: Box::new(to_physical(match_input, stats)?),
+            create_patterns: create_patterns.clone(),
+        }),
+        PlanNode::UnwindCreate { expr, variable, create_patterns } => Ok(PhysicalPlan::UnwindCreateExec {
+            expr: expr.clone(),
+            variable: variable.clone(),
+            create_patterns: create_patterns.clone(),
+        }),
+        PlanNode::Filter { input, predicate } => lower_filter(input, predicate, stats),
+        PlanNode::Project { input, items } => {
+            Ok(PhysicalPlan::ProjectExec { input: Box::new(to_physical(input, stats)?), items: items.clone() })
+        }
+        PlanNode::OrderBy { input, items } => {
+            Ok(PhysicalPlan::OrderByExec { input: Box::new(to_physical(input, stats)?), items: items.clone() })
+        }
+        PlanNode::Aggregate { input, group_by, aggregates } => Ok(PhysicalPlan::AggregateExec {
+            input: Box::new(to_physical(input, stats)?),
+            group_by: group_by.clone(),
+            aggregates: aggregates.clone(),
+        }),
+        PlanNode::Limit { input, count } => {
+            Ok(PhysicalPlan::LimitExec { input: Box::new(to_physical(input, stats)?), count: *count })
+        }
+        PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional } => {
+            let physical_input = to_physical(input, stats)?;
+            // Both endpoints already bound (e.g. `MATCH (a)-->(b), (b)-->(a)`):
+            // check the traversal lands on `to_var` instead of enumerating
+            // every neighbor of `from_var`.
+            if bound_vars(input).contains(to_var) {
+                Ok(PhysicalPlan::ExpandInto {
+                    input: Box::new(physical_input),
+                    from_var: from_var.clone(),
+                    edge_var: edge_var.clone(),
+                    to_var: to_var.clone(),
+                    edge_type: edge_type.clone(),
+                    direction: direction.clone(),
+                    depth: depth.clone(),
+                    path_uniqueness: *path_uniqueness,
+                    optional: *optional,
+                })
+            } else {
+                Ok(PhysicalPlan::ExpandAll {
+                    input: Box::new(physical_input),
+                    from_var: from_var.clone(),
+                    edge_var: edge_var.clone(),
+                    to_var: to_var.clone(),
+                    edge_type: edge_type.clone(),
+                    direction: direction.clone(),
+                    depth: depth.clone(),
+                    path_uniqueness: *path_uniqueness,
+                    optional: *optional,
+                })
+            }
+        }
+        PlanNode::CartesianProduct { left, right } => Ok(PhysicalPlan::NestedLoopJoin {
+            left: Box::new(to_physical(left, stats)?),
+            right: Box::new(to_physical(right, stats)?),
+            predicate: None,
+        }),
+        PlanNode::ShortestPath { input, from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var } => {
+            Ok(PhysicalPlan::ShortestPathExec {
+                input: Box::new(to_physical(input, stats)?),
+                from_var: from_var.clone(),
+                to_var: to_var.clone(),
+                edge_type: edge_type.clone(),
+                direction: direction.clone(),
+                weight_prop: weight_prop.clone(),
+                heuristic: heuristic.clone(),
+                path_var: path_var.clone(),
+                cost_var: cost_var.clone(),
+            })
+        }
+        PlanNode::GraphAlgo { name, args, yield_cols } => Ok(PhysicalPlan::GraphAlgoExec {
+            name: name.clone(),
+            args: args.clone(),
+            yield_cols: yield_cols.clone(),
+        }),
+        PlanNode::HashJoin { left, right, join_keys } => Ok(PhysicalPlan::HashJoin {
+            left: Box::new(to_physical(left, stats)?),
+            right: Box::new(to_physical(right, stats)?),
+            join_keys: join_keys.clone(),
+            residual: None,
+        }),
+        PlanNode::Fixpoint { seed, recursive, bind_var } => Ok(PhysicalPlan::FixpointExec {
+            seed: Box::new(to_physical(seed, stats)?),
+            recursive: Box::new(to_physical(recursive, stats)?),
+            bind_var: bind_var.clone(),
+        }),
+    }
+}
+
+/// Lowers a `Filter` node, which is where both special cases live: an
+/// equality filter directly over a `LabelScan` can become an index seek, and
+/// a filter directly over a `CartesianProduct` can become a hash join.
+/// Falls back to a plain `FilterExec` over the lowered input otherwise.
+fn lower_filter(input: &PlanNode, predicate: &Expr, stats: &Statistics) -> Result<PhysicalPlan, EngineError> {
+    if let PlanNode::CartesianProduct { left, right } = input {
+        if let Some((left_key, right_key, residual)) = equi_join_split(predicate, left, right) {
+            return Ok(PhysicalPlan::HashJoin {
+                left: Box::new(to_physical(left, stats)?),
+                right: Box::new(to_physical(right, stats)?),
+                join_keys: vec![(left_key, right_key)],
+                residual,
+            });
+        }
+        return Ok(PhysicalPlan::NestedLoopJoin {
+            left: Box::new(to_physical(left, stats)?),
+            right: Box::new(to_physical(right, stats)?),
+            predicate: Some(predicate.clone()),
+        });
+    }
+
+    if let PlanNode::LabelScan { variable, label } = input {
+        if let Some((property, value, residual)) = property_index_split(predicate, variable, label, stats) {
+            let seek = PhysicalPlan::NodeByPropertyIndexSeek {
+                variable: variable.clone(),
+                label: label.clone(),
+                property,
+                value,
+            };
+            return Ok(match residual {
+                Some(residual) => PhysicalPlan::FilterExec { input: Box::new(seek), predicate: residual },
+                None => seek,
+            });
+        }
+    }
+
+    Ok(PhysicalPlan::FilterExec { input: Box::new(to_physical(input, stats)?), predicate: predicate.clone() })
+}
+
+/// Looks for one `AND`-conjunct of the form `left_var_expr == right_var_expr`
+/// where one side is bound entirely by `left` and the other entirely by
+/// `right`, and splits it out as the join key. The remaining conjuncts (if
+/// any) are rejoined into `residual`, applied after the join.
+fn equi_join_split(predicate: &Expr, left: &PlanNode, right: &PlanNode) -> Option<(Expr, Expr, Option<Expr>)> {
+    let left_vars = bound_vars(left);
+    let right_vars = bound_vars(right);
+    let conjuncts = split_conjuncts(predicate.clone());
+
+    let equi_idx = conjuncts.iter().enumerate().find_map(|(i, atom)| {
+        if let Expr::BinaryOp(l, BinOp::Eq, r) = atom {
+            let (lv, rv) = (single_var(l)?, single_var(r)?);
+            if left_vars.contains(&lv) && right_vars.contains(&rv) {
+                return Some((i, (**l).clone(), (**r).clone()));
+            }
+            if right_vars.contains(&lv) && left_vars.contains(&rv) {
+                return Some((i, (**r).clone(), (**l).clone()));
+            }
+        }
+        None
+    })?;
+
+    let (idx, left_key, right_key) = equi_idx;
+    let residual = conjuncts.into_iter().enumerate().filter(|(j, _)| *j != idx).map(|(_, atom)| atom).collect();
+    Some((left_key, right_key, join_conjuncts(residual)))
+}
+
+/// The single variable an equi-join operand is anchored to, or `None` for an
+/// expression (e.g. a literal, an arithmetic expression) that doesn't pin it
+/// to one side of the join.
+fn single_var(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(name) | Expr::Property(name, _) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Looks for one `AND`-conjunct of the form `variable.property == literal`
+/// (in either operand order) where `stats` has an index on `label.property`,
+/// and splits it out as the seek key. The remaining conjuncts (if any) are
+/// rejoined into `residual`, applied after the seek.
+fn property_index_split(
+    predicate: &Expr,
+    variable: &str,
+    label: &str,
+    stats: &Statistics,
+) -> Option<(String, Literal, Option<Expr>)> {
+    let conjuncts = split_conjuncts(predicate.clone());
+
+    let indexed_property = |var: &str, prop: &str| (var == variable && stats.has_property_index(label, prop)).then(|| prop.to_string());
+    let seek_idx = conjuncts.iter().enumerate().find_map(|(i, atom)| {
+        if let Expr::BinaryOp(l, BinOp::Eq, r) = atom {
+            match (l.as_ref(), r.as_ref()) {
+                (Expr::Property(var, prop), Expr::Literal(value)) => {
+                    return indexed_property(var, prop).map(|prop| (i, prop, value.clone()));
+                }
+                (Expr::Literal(value), Expr::Property(var, prop)) => {
+                    return indexed_property(var, prop).map(|prop| (i, prop, value.clone()));
+                }
+                _ => {}
+            }
+        }
+        None
+    })?;
+
+    let (idx, property, value) = seek_idx;
+    let residual = conjuncts.into_iter().enumerate().filter(|(j, _)| *j != idx).map(|(_, atom)| atom).collect();
+    Some((property, value, join_conjuncts(residual)))
+}
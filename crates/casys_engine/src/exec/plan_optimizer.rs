@@ -0,0 +1,441 @@
+//! Predicate pushdown and common-subexpression elimination over the finished
+//! `PlanNode` tree.
+//!
+//! A `WHERE` clause lands as one `Filter` wrapping the whole `MATCH` plan, so
+//! `MATCH (a), (b) WHERE a.x = 1 AND b.y = 2` checks both conjuncts against
+//! every row of the `CartesianProduct`, even though `a.x = 1` only needs `a`
+//! to be bound and could run against the left scan alone. `push_down_predicates`
+//! splits the top-level predicate into conjuncts and re-inserts each one as
+//! deep as the variables it references allow, walking past `Expand` and
+//! `CartesianProduct` nodes via bound-variable tracking.
+//!
+//! Separately, `plan_match` conservatively re-applies every inline node
+//! property as a safety-net `Filter` at the very top of the plan, even when a
+//! `LabelScan`'s own `Filter` (or an `Expand`'s post-filter) already enforces
+//! it lower in the same input chain - so `MATCH (a {x:1})-[:R]->(b {y:2})`
+//! ends up checking `a.x=1` twice and `b.y=2` across both the Expand filter
+//! and the global one. `eliminate_common_filters` walks the plan once,
+//! bottom-up along each input chain, tracking which predicate atoms are
+//! already guaranteed, and drops any conjunct a lower `Filter` already
+//! established - folding away `Filter` nodes entirely once every conjunct
+//! they held turns out redundant.
+
+use std::collections::HashSet;
+
+use super::ast::{BinOp, Expr};
+use super::planner::PlanNode;
+
+/// Runs predicate pushdown, then hash-join promotion, then
+/// common-subexpression elimination - the order the planner wants: push
+/// filters next to the scans/expands they constrain (so only genuine
+/// cross-side conjuncts are left sitting over a `CartesianProduct`), turn
+/// those into `HashJoin`s, then drop whatever duplicates the pushdown (or
+/// the planner's own safety net) left behind.
+pub fn optimize_plan(plan: PlanNode) -> PlanNode {
+    eliminate_common_filters(build_hash_joins(push_down_predicates(plan)))
+}
+
+/// Removes predicate conjuncts already enforced lower in `plan`. Idempotent:
+/// running it twice is equivalent to running it once.
+pub fn eliminate_common_filters(plan: PlanNode) -> PlanNode {
+    eliminate(plan, &HashSet::new()).0
+}
+
+/// Splits every `Filter`'s predicate into conjuncts and reinserts each one as
+/// far down its input chain as the variables it references are already
+/// bound, so a conjunct over a single `CartesianProduct` branch (or below an
+/// `Expand`) runs there instead of after the whole chain has materialized.
+pub fn push_down_predicates(plan: PlanNode) -> PlanNode {
+    match plan {
+        PlanNode::Filter { input, predicate } => {
+            let input = push_down_predicates(*input);
+            split_conjuncts(predicate)
+                .into_iter()
+                .fold(input, |acc, atom| {
+                    let vars = referenced_vars(&atom);
+                    push_atom(acc, atom, &vars)
+                })
+        }
+        PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional } => {
+            PlanNode::Expand { input: Box::new(push_down_predicates(*input)), from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional }
+        }
+        PlanNode::Project { input, items } => {
+            PlanNode::Project { input: Box::new(push_down_predicates(*input)), items }
+        }
+        PlanNode::OrderBy { input, items } => {
+            PlanNode::OrderBy { input: Box::new(push_down_predicates(*input)), items }
+        }
+        PlanNode::Aggregate { input, group_by, aggregates } => {
+            PlanNode::Aggregate { input: Box::new(push_down_predicates(*input)), group_by, aggregates }
+        }
+        PlanNode::Limit { input, count } => {
+            PlanNode::Limit { input: Box::new(push_down_predicates(*input)), count }
+        }
+        PlanNode::MatchCreate { match_input, create_patterns } => {
+            PlanNode::MatchCreate { match_input: Box::new(push_down_predicates(*match_input)), create_patterns }
+        }
+        PlanNode::CartesianProduct { left, right } => {
+            PlanNode::CartesianProduct {
+                left: Box::new(push_down_predicates(*left)),
+                right: Box::new(push_down_predicates(*right)),
+            }
+        }
+        PlanNode::HashJoin { left, right, join_keys } => PlanNode::HashJoin {
+            left: Box::new(push_down_predicates(*left)),
+            right: Box::new(push_down_predicates(*right)),
+            join_keys,
+        },
+        PlanNode::ShortestPath { input, from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var } => {
+            PlanNode::ShortestPath {
+                input: Box::new(push_down_predicates(*input)),
+                from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var,
+            }
+        }
+        // `Fixpoint`'s `seed`/`recursive` aren't part of this input chain's
+        // variable scope (they run in their own `parent_tuple` context each
+        // round), so there's nothing here for a conjunct to be pushed into.
+        leaf @ (PlanNode::LabelScan { .. }
+        | PlanNode::FullScan { .. }
+        | PlanNode::Create { .. }
+        | PlanNode::UnwindCreate { .. }
+        | PlanNode::GraphAlgo { .. }
+        | PlanNode::Fixpoint { .. }) => leaf,
+    }
+}
+
+/// Promotes a `Filter` sitting directly over a `CartesianProduct` to a
+/// `HashJoin` once every conjunct that only needed one side has already been
+/// pushed down into it (by `push_down_predicates`, which must run first):
+/// whatever equi-join conjuncts remain, each anchored one-var-per-side, are
+/// pulled out as `join_keys`; anything else stays behind as a residual
+/// `Filter` over the join. A `CartesianProduct` with no enclosing `Filter`,
+/// or one where no conjunct is a cross-side equi-join, is left alone -
+/// `CartesianProduct` itself is the correct fallback for those.
+pub fn build_hash_joins(plan: PlanNode) -> PlanNode {
+    match plan {
+        PlanNode::Filter { input, predicate } => {
+            let input = build_hash_joins(*input);
+            let PlanNode::CartesianProduct { left, right } = input else {
+                return PlanNode::Filter { input: Box::new(input), predicate };
+            };
+            let left_vars = bound_vars(&left);
+            let right_vars = bound_vars(&right);
+
+            let mut join_keys = Vec::new();
+            let mut residual = Vec::new();
+            for atom in split_conjuncts(predicate) {
+                match equi_join_key(&atom, &left_vars, &right_vars) {
+                    Some(pair) => join_keys.push(pair),
+                    None => residual.push(atom),
+                }
+            }
+
+            if join_keys.is_empty() {
+                return PlanNode::Filter {
+                    input: Box::new(PlanNode::CartesianProduct { left, right }),
+                    predicate: join_conjuncts(residual).expect("no join key found means at least one atom became residual"),
+                };
+            }
+
+            let join = PlanNode::HashJoin { left, right, join_keys };
+            match join_conjuncts(residual) {
+                Some(predicate) => PlanNode::Filter { input: Box::new(join), predicate },
+                None => join,
+            }
+        }
+        PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional } => {
+            PlanNode::Expand { input: Box::new(build_hash_joins(*input)), from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional }
+        }
+        PlanNode::Project { input, items } => PlanNode::Project { input: Box::new(build_hash_joins(*input)), items },
+        PlanNode::OrderBy { input, items } => PlanNode::OrderBy { input: Box::new(build_hash_joins(*input)), items },
+        PlanNode::Aggregate { input, group_by, aggregates } => {
+            PlanNode::Aggregate { input: Box::new(build_hash_joins(*input)), group_by, aggregates }
+        }
+        PlanNode::Limit { input, count } => PlanNode::Limit { input: Box::new(build_hash_joins(*input)), count },
+        PlanNode::MatchCreate { match_input, create_patterns } => {
+            PlanNode::MatchCreate { match_input: Box::new(build_hash_joins(*match_input)), create_patterns }
+        }
+        PlanNode::CartesianProduct { left, right } => {
+            PlanNode::CartesianProduct { left: Box::new(build_hash_joins(*left)), right: Box::new(build_hash_joins(*right)) }
+        }
+        PlanNode::HashJoin { left, right, join_keys } => {
+            PlanNode::HashJoin { left: Box::new(build_hash_joins(*left)), right: Box::new(build_hash_joins(*right)), join_keys }
+        }
+        PlanNode::ShortestPath { input, from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var } => {
+            PlanNode::ShortestPath {
+                input: Box::new(build_hash_joins(*input)),
+                from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var,
+            }
+        }
+        leaf @ (PlanNode::LabelScan { .. }
+        | PlanNode::FullScan { .. }
+        | PlanNode::Create { .. }
+        | PlanNode::UnwindCreate { .. }
+        | PlanNode::GraphAlgo { .. }
+        | PlanNode::Fixpoint { .. }) => leaf,
+    }
+}
+
+/// An `AND`-conjunct of the form `left_var_expr == right_var_expr`, where one
+/// side is bound entirely by `left_vars` and the other entirely by
+/// `right_vars`, returned as `(left_expr, right_expr)` regardless of which
+/// operand order the predicate wrote them in.
+fn equi_join_key(atom: &Expr, left_vars: &HashSet<String>, right_vars: &HashSet<String>) -> Option<(Expr, Expr)> {
+    let Expr::BinaryOp(l, BinOp::Eq, r) = atom else { return None };
+    let (lv, rv) = (join_anchor(l)?, join_anchor(r)?);
+    if left_vars.contains(&lv) && right_vars.contains(&rv) {
+        return Some(((**l).clone(), (**r).clone()));
+    }
+    if right_vars.contains(&lv) && left_vars.contains(&rv) {
+        return Some(((**r).clone(), (**l).clone()));
+    }
+    None
+}
+
+/// The single variable an equi-join operand is anchored to, or `None` for an
+/// expression (a literal, an arithmetic expression) that doesn't pin it to
+/// one side of the join.
+fn join_anchor(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(name) | Expr::Property(name, _) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Re-inserts one conjunct as deep into `node` as `needed` (the variables it
+/// references) allows, attaching it as a `Filter` the moment descending
+/// further would cross a variable it needs but that isn't bound yet.
+fn push_atom(node: PlanNode, atom: Expr, needed: &HashSet<String>) -> PlanNode {
+    match node {
+        PlanNode::CartesianProduct { left, right } if needed.is_subset(&bound_vars(&left)) => {
+            PlanNode::CartesianProduct { left: Box::new(push_atom(*left, atom, needed)), right }
+        }
+        PlanNode::CartesianProduct { left, right } if needed.is_subset(&bound_vars(&right)) => {
+            PlanNode::CartesianProduct { left, right: Box::new(push_atom(*right, atom, needed)) }
+        }
+        PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional }
+            if needed.is_subset(&bound_vars(&input)) =>
+        {
+            let input = Box::new(push_atom(*input, atom, needed));
+            PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional }
+        }
+        PlanNode::Filter { input, predicate } => {
+            PlanNode::Filter { input: Box::new(push_atom(*input, atom, needed)), predicate }
+        }
+        other => PlanNode::Filter { input: Box::new(other), predicate: atom },
+    }
+}
+
+/// Variables a node has bound by the time rows reach whatever sits above it
+/// on the same input chain. Also used by `physical::to_physical` to decide
+/// `ExpandInto` vs `ExpandAll` and to split equi-join predicates.
+pub(crate) fn bound_vars(node: &PlanNode) -> HashSet<String> {
+    match node {
+        PlanNode::LabelScan { variable, .. } | PlanNode::FullScan { variable } => {
+            HashSet::from([variable.clone()])
+        }
+        PlanNode::Expand { input, edge_var, to_var, .. } => {
+            let mut vars = bound_vars(input);
+            vars.insert(to_var.clone());
+            if let Some(edge_var) = edge_var {
+                vars.insert(edge_var.clone());
+            }
+            vars
+        }
+        PlanNode::CartesianProduct { left, right } | PlanNode::HashJoin { left, right, .. } => {
+            let mut vars = bound_vars(left);
+            vars.extend(bound_vars(right));
+            vars
+        }
+        PlanNode::ShortestPath { input, path_var, cost_var, .. } => {
+            let mut vars = bound_vars(input);
+            vars.insert(path_var.clone());
+            vars.insert(cost_var.clone());
+            vars
+        }
+        PlanNode::GraphAlgo { yield_cols, .. } => yield_cols.iter().cloned().collect(),
+        PlanNode::Fixpoint { bind_var, .. } => HashSet::from([bind_var.clone()]),
+        PlanNode::Filter { input, .. }
+        | PlanNode::Project { input, .. }
+        | PlanNode::OrderBy { input, .. }
+        | PlanNode::Aggregate { input, .. }
+        | PlanNode::Limit { input, .. }
+        | PlanNode::MatchCreate { match_input: input, .. } => bound_vars(input),
+        PlanNode::Create { .. } | PlanNode::UnwindCreate { .. } => HashSet::new(),
+    }
+}
+
+/// Variables an expression reads, so `push_atom` knows how far down it's
+/// safe to travel. `Exists` may correlate against any outer variable and we
+/// don't look inside its subquery, so it conservatively reports a variable
+/// name no scan can ever bind - keeping the atom pinned at its current level.
+fn referenced_vars(expr: &Expr) -> HashSet<String> {
+    match expr {
+        Expr::Ident(name) | Expr::Property(name, _) => HashSet::from([name.clone()]),
+        Expr::BinaryOp(l, _, r) => {
+            let mut vars = referenced_vars(l);
+            vars.extend(referenced_vars(r));
+            vars
+        }
+        Expr::UnaryOp(_, e) | Expr::IsNull(e) | Expr::IsNotNull(e) | Expr::Aggregate(_, e) => referenced_vars(e),
+        Expr::FunctionCall(_, args) | Expr::List(args) => {
+            args.iter().flat_map(referenced_vars).collect()
+        }
+        Expr::In(l, r) => {
+            let mut vars = referenced_vars(l);
+            vars.extend(referenced_vars(r));
+            vars
+        }
+        Expr::Case(case) => {
+            let mut vars = HashSet::new();
+            if let Some(operand) = &case.operand {
+                vars.extend(referenced_vars(operand));
+            }
+            for (when, then) in &case.branches {
+                vars.extend(referenced_vars(when));
+                vars.extend(referenced_vars(then));
+            }
+            if let Some(else_branch) = &case.else_branch {
+                vars.extend(referenced_vars(else_branch));
+            }
+            vars
+        }
+        Expr::Exists(_) => HashSet::from(["__correlated_subquery__".to_string()]),
+        Expr::Cast(e, _) => referenced_vars(e),
+        Expr::Literal(_) | Expr::Parameter(_) => HashSet::new(),
+    }
+}
+
+/// Returns the rewritten node plus the set of atom keys it guarantees for
+/// whatever sits above it on the same input chain.
+fn eliminate(node: PlanNode, guaranteed: &HashSet<String>) -> (PlanNode, HashSet<String>) {
+    match node {
+        PlanNode::Filter { input, predicate } => {
+            let (new_input, mut below) = eliminate(*input, guaranteed);
+            let mut kept = Vec::new();
+            for atom in split_conjuncts(predicate) {
+                let key = atom_key(&atom);
+                if below.insert(key) {
+                    kept.push(atom);
+                }
+            }
+            match join_conjuncts(kept) {
+                Some(predicate) => (PlanNode::Filter { input: Box::new(new_input), predicate }, below),
+                None => (new_input, below),
+            }
+        }
+        PlanNode::Expand { input, from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional } => {
+            let (new_input, below) = eliminate(*input, guaranteed);
+            (PlanNode::Expand { input: Box::new(new_input), from_var, edge_var, to_var, edge_type, direction, depth, path_uniqueness, optional }, below)
+        }
+        PlanNode::Project { input, items } => {
+            let (new_input, below) = eliminate(*input, guaranteed);
+            (PlanNode::Project { input: Box::new(new_input), items }, below)
+        }
+        PlanNode::OrderBy { input, items } => {
+            let (new_input, below) = eliminate(*input, guaranteed);
+            (PlanNode::OrderBy { input: Box::new(new_input), items }, below)
+        }
+        PlanNode::Aggregate { input, group_by, aggregates } => {
+            let (new_input, below) = eliminate(*input, guaranteed);
+            (PlanNode::Aggregate { input: Box::new(new_input), group_by, aggregates }, below)
+        }
+        PlanNode::Limit { input, count } => {
+            let (new_input, below) = eliminate(*input, guaranteed);
+            (PlanNode::Limit { input: Box::new(new_input), count }, below)
+        }
+        PlanNode::MatchCreate { match_input, create_patterns } => {
+            let (new_input, below) = eliminate(*match_input, guaranteed);
+            (PlanNode::MatchCreate { match_input: Box::new(new_input), create_patterns }, below)
+        }
+        PlanNode::CartesianProduct { left, right } => {
+            // Each side only guarantees atoms over its own variables, but
+            // nothing stops them being merged into one combined set: the two
+            // sides can never name the same atom unless they share a
+            // variable, which a Cartesian product (by construction) doesn't.
+            let (new_left, left_guaranteed) = eliminate(*left, guaranteed);
+            let (new_right, right_guaranteed) = eliminate(*right, guaranteed);
+            let mut combined = left_guaranteed;
+            combined.extend(right_guaranteed);
+            (PlanNode::CartesianProduct { left: Box::new(new_left), right: Box::new(new_right) }, combined)
+        }
+        PlanNode::HashJoin { left, right, join_keys } => {
+            // Same reasoning as `CartesianProduct`: a `HashJoin` replaces one
+            // plus an equality filter, so its own join_keys conjuncts are
+            // already gone, not something left for a later Filter to repeat.
+            let (new_left, left_guaranteed) = eliminate(*left, guaranteed);
+            let (new_right, right_guaranteed) = eliminate(*right, guaranteed);
+            let mut combined = left_guaranteed;
+            combined.extend(right_guaranteed);
+            (PlanNode::HashJoin { left: Box::new(new_left), right: Box::new(new_right), join_keys }, combined)
+        }
+        PlanNode::ShortestPath { input, from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var } => {
+            let (new_input, below) = eliminate(*input, guaranteed);
+            (
+                PlanNode::ShortestPath { input: Box::new(new_input), from_var, to_var, edge_type, direction, weight_prop, heuristic, path_var, cost_var },
+                below,
+            )
+        }
+        leaf @ (PlanNode::LabelScan { .. }
+        | PlanNode::FullScan { .. }
+        | PlanNode::Create { .. }
+        | PlanNode::UnwindCreate { .. }
+        | PlanNode::GraphAlgo { .. }
+        | PlanNode::Fixpoint { .. }) => (leaf, guaranteed.clone()),
+    }
+}
+
+/// Splits a conjunction into its flat list of atoms, recursing through nested
+/// `AND`s so `(a AND b) AND c` and `a AND (b AND c)` both split to `[a, b, c]`.
+pub(crate) fn split_conjuncts(expr: Expr) -> Vec<Expr> {
+    match expr {
+        Expr::BinaryOp(l, BinOp::And, r) => {
+            let mut atoms = split_conjuncts(*l);
+            atoms.extend(split_conjuncts(*r));
+            atoms
+        }
+        other => vec![other],
+    }
+}
+
+/// Rebuilds the same left-associative `AND` chain shape `plan_match` builds
+/// predicates in, or `None` if every conjunct turned out redundant.
+pub(crate) fn join_conjuncts(atoms: Vec<Expr>) -> Option<Expr> {
+    let mut iter = atoms.into_iter();
+    let first = iter.next()?;
+    Some(iter.fold(first, |acc, atom| Expr::BinaryOp(Box::new(acc), BinOp::And, Box::new(atom))))
+}
+
+/// Canonical string key for one atom, so two structurally-equal atoms
+/// (including `a == b` vs `b == a` for a commutative comparison like
+/// `ID(x) != ID(y)`) hash the same regardless of how the planner happened to
+/// order their operands.
+fn atom_key(expr: &Expr) -> String {
+    format!("{:?}", canonicalize(expr))
+}
+
+fn canonicalize(expr: &Expr) -> Expr {
+    match expr {
+        Expr::BinaryOp(l, op @ (BinOp::Eq | BinOp::Ne), r) => {
+            let l = canonicalize(l);
+            let r = canonicalize(r);
+            if format!("{:?}", l) <= format!("{:?}", r) {
+                Expr::BinaryOp(Box::new(l), op.clone(), Box::new(r))
+            } else {
+                Expr::BinaryOp(Box::new(r), op.clone(), Box::new(l))
+            }
+        }
+        Expr::BinaryOp(l, op, r) => {
+            Expr::BinaryOp(Box::new(canonicalize(l)), op.clone(), Box::new(canonicalize(r)))
+        }
+        Expr::UnaryOp(op, e) => Expr::UnaryOp(op.clone(), Box::new(canonicalize(e))),
+        Expr::FunctionCall(name, args) => {
+            Expr::FunctionCall(name.clone(), args.iter().map(canonicalize).collect())
+        }
+        Expr::IsNull(e) => Expr::IsNull(Box::new(canonicalize(e))),
+        Expr::IsNotNull(e) => Expr::IsNotNull(Box::new(canonicalize(e))),
+        Expr::List(items) => Expr::List(items.iter().map(canonicalize).collect()),
+        Expr::In(l, r) => Expr::In(Box::new(canonicalize(l)), Box::new(canonicalize(r))),
+        other => other.clone(),
+    }
+}
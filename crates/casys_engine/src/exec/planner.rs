@@ -28,6 +28,15 @@ pub enum PlanNode {
         match_input: Box<PlanNode>,
         create_patterns: Vec<Pattern>,
     },
+    // Unwind then Create (for UNWIND ... AS var CREATE pattern): `expr` is
+    // evaluated once against the incoming tuple to a `Value::Array`, then
+    // `create_patterns` runs once per element, same as `MatchCreate` runs
+    // once per matched tuple.
+    UnwindCreate {
+        expr: Expr,
+        variable: String,
+        create_patterns: Vec<Pattern>,
+    },
     // Filter predicate
     Filter {
         input: Box<PlanNode>,
@@ -63,12 +72,68 @@ pub enum PlanNode {
         edge_type: Option<String>,
         direction: Direction,  // Left (<-), Right (->), Both (-)
         depth: Option<super::ast::DepthRange>,  // For variable-length paths
+        path_uniqueness: PathUniqueness,  // Relationship/node-repeat policy for variable-length paths
+        // Left-join semantics (Cypher's `OPTIONAL MATCH`): when set, an input
+        // tuple with no matching neighbor still produces one output tuple,
+        // binding `to_var` (and `edge_var`, if present) to `Value::Null`
+        // instead of being dropped. No GQL syntax sets this yet - it's built
+        // directly, the same way variable-length `Expand` was before any
+        // syntax surfaced it.
+        optional: bool,
     },
     // Cartesian product (for MATCH (a), (b) patterns)
     CartesianProduct {
         left: Box<PlanNode>,
         right: Box<PlanNode>,
     },
+    // Equi-join replacing a `CartesianProduct` plus an enclosing equality
+    // filter: `join_keys` is the list of (left_expr, right_expr) conjuncts
+    // `plan_optimizer::build_hash_joins` pulled out of that filter. Built by
+    // the optimizer, the same way it never hands back a `Filter` whose
+    // conjuncts turned out fully redundant.
+    HashJoin {
+        left: Box<PlanNode>,
+        right: Box<PlanNode>,
+        join_keys: Vec<(Expr, Expr)>,
+    },
+    // Weighted shortest path between two already-bound nodes, via A*
+    // (Dijkstra when `heuristic` is None). No GQL syntax produces this yet;
+    // it's built directly, the same way variable-length `Expand` was before
+    // any syntax surfaced it.
+    ShortestPath {
+        input: Box<PlanNode>,
+        from_var: String,
+        to_var: String,
+        edge_type: Option<String>,
+        direction: Direction,
+        weight_prop: Option<String>,
+        heuristic: Option<Expr>,
+        path_var: String,
+        cost_var: String,
+    },
+    // Whole-graph algorithm (betweenness/closeness centrality, ...), emitting
+    // one tuple per node. Like `ShortestPath`, no GQL syntax produces this
+    // yet - it's built directly, e.g. for a future `CALL` procedure syntax.
+    GraphAlgo {
+        name: String,
+        args: std::collections::HashMap<String, Literal>,
+        // [node_col, score_col]: the column names the output tuples bind.
+        yield_cols: Vec<String>,
+    },
+    // Recursive transitive closure: `seed` produces the initial frontier,
+    // then `recursive` runs once per round against only the delta added by
+    // the previous round (semi-naive evaluation), stopping once a round adds
+    // nothing new. `bind_var` is the column both `seed` and `recursive`
+    // project the current frontier node under, so each round's output feeds
+    // back in as the next round's `parent_tuple` and `recursive`'s own scan
+    // over `bind_var` resolves directly to that bound node instead of
+    // re-scanning the whole graph. Like `ShortestPath`/`GraphAlgo`, no GQL
+    // syntax produces this yet - it's built directly.
+    Fixpoint {
+        seed: Box<PlanNode>,
+        recursive: Box<PlanNode>,
+        bind_var: String,
+    },
 }
 
 pub struct Planner;
@@ -85,7 +150,15 @@ impl Planner {
             }
         }
         // Handle different clause combinations
-        let mut plan = if query.match_clause.is_some() && query.create_clause.is_some() {
+        let mut plan = if query.unwind_clause.is_some() && query.create_clause.is_some() {
+            // UNWIND ... AS var CREATE pattern
+            let unwind_clause = query.unwind_clause.as_ref().unwrap();
+            PlanNode::UnwindCreate {
+                expr: unwind_clause.expr.clone(),
+                variable: unwind_clause.variable.clone(),
+                create_patterns: query.create_clause.as_ref().unwrap().patterns.clone(),
+            }
+        } else if query.match_clause.is_some() && query.create_clause.is_some() {
             // MATCH ... CREATE pattern
             let match_plan = Self::plan_match(query.match_clause.as_ref().unwrap())?;
             PlanNode::MatchCreate {
@@ -128,7 +201,7 @@ impl Planner {
 
         // RETURN is optional for CREATE
         if query.return_clause.is_none() {
-            return Ok(ExecutionPlan { root: plan });
+            return Ok(ExecutionPlan { root: super::plan_optimizer::optimize_plan(plan) });
         }
         
         let return_clause = query.return_clause.as_ref().unwrap();
@@ -186,13 +259,45 @@ impl Planner {
             };
         }
 
-        let ep = ExecutionPlan { root: plan };
+        let ep = ExecutionPlan { root: super::plan_optimizer::optimize_plan(plan) };
         if std::env::var("CASYS_DEBUG_PLAN").ok().as_deref() == Some("1") {
             println!("PLAN: {:#?}", ep);
         }
         Ok(ep)
     }
 
+    /// Builds the `AND`-chained equality predicate for a node/edge pattern's
+    /// inline `{k: v, ...}` properties, or `None` if it has none. Each
+    /// `var.key = literal` atom is run through `coerce_binary` so a literal
+    /// that doesn't already match the property's static type (a float
+    /// literal against an int-typed key, a numeric written as a string)
+    /// gets an explicit `Cast` instead of silently failing to match later.
+    fn property_predicate(var: &str, properties: &std::collections::HashMap<String, Expr>) -> Result<Option<Expr>, EngineError> {
+        let mut iter = properties.iter();
+        let first = match iter.next() {
+            Some((k, v)) => Self::coerced_property_eq(var, k, v)?,
+            None => return Ok(None),
+        };
+        let mut predicate = first;
+        for (k, v) in iter {
+            let atom = Self::coerced_property_eq(var, k, v)?;
+            predicate = Expr::BinaryOp(Box::new(predicate), BinOp::And, Box::new(atom));
+        }
+        Ok(Some(predicate))
+    }
+
+    /// `var.key = value`, coerced via `coercion::coerce_binary`. `value` is
+    /// usually a `Literal` (the common `{k: v}` inline-filter case, where
+    /// coercion can reconcile its static type against the property), but may
+    /// be any expression - `coerce_binary` leaves anything else untouched for
+    /// `eval_binary_op` to resolve once it's a concrete `Value`.
+    fn coerced_property_eq(var: &str, key: &str, value: &Expr) -> Result<Expr, EngineError> {
+        let lhs = Expr::Property(var.to_string(), key.to_string());
+        let rhs = value.clone();
+        let (lhs, rhs) = super::coercion::coerce_binary(lhs, &BinOp::Eq, rhs)?;
+        Ok(Expr::BinaryOp(Box::new(lhs), BinOp::Eq, Box::new(rhs)))
+    }
+
     fn plan_match(match_clause: &MatchClause) -> Result<PlanNode, EngineError> {
         if match_clause.patterns.is_empty() {
             return Err(EngineError::InvalidArgument("empty MATCH clause".into()));
@@ -229,26 +334,7 @@ impl Planner {
                     // translate inline properties into a Filter and continue without adding a standalone scan.
                     if let Some(var) = &node.variable {
                         if bound_vars.contains(var) {
-                            if !node.properties.is_empty() {
-                                // Build predicate: AND of var.prop == literal
-                                let mut iter = node.properties.iter();
-                                let (first_k, first_v) = iter.next().unwrap();
-                                let mut predicate = Expr::BinaryOp(
-                                    Box::new(Expr::Property(var.clone(), first_k.clone())),
-                                    BinOp::Eq,
-                                    Box::new(Expr::Literal(first_v.clone())),
-                                );
-                                for (k, v) in iter {
-                                    predicate = Expr::BinaryOp(
-                                        Box::new(predicate),
-                                        BinOp::And,
-                                        Box::new(Expr::BinaryOp(
-                                            Box::new(Expr::Property(var.clone(), k.clone())),
-                                            BinOp::Eq,
-                                            Box::new(Expr::Literal(v.clone())),
-                                        )),
-                                    );
-                                }
+                            if let Some(predicate) = Self::property_predicate(var, &node.properties)? {
                                 plan_opt = Some(PlanNode::Filter { input: Box::new(plan_opt.take().unwrap()), predicate });
                             }
                             continue;
@@ -301,26 +387,7 @@ impl Planner {
                         PlanNode::FullScan { variable: var.clone() }
                     };
                     // If there are inline properties on the node pattern, add a Filter
-                    if !node.properties.is_empty() {
-                        // Build predicate: AND of var.prop == literal
-                        let mut iter = node.properties.iter();
-                        let (first_k, first_v) = iter.next().unwrap();
-                        let mut predicate = Expr::BinaryOp(
-                            Box::new(Expr::Property(var.clone(), first_k.clone())),
-                            BinOp::Eq,
-                            Box::new(Expr::Literal(first_v.clone())),
-                        );
-                        for (k, v) in iter {
-                            predicate = Expr::BinaryOp(
-                                Box::new(predicate),
-                                BinOp::And,
-                                Box::new(Expr::BinaryOp(
-                                    Box::new(Expr::Property(var.clone(), k.clone())),
-                                    BinOp::Eq,
-                                    Box::new(Expr::Literal(v.clone())),
-                                )),
-                            );
-                        }
+                    if let Some(predicate) = Self::property_predicate(&var, &node.properties)? {
                         node_plan = PlanNode::Filter { input: Box::new(node_plan), predicate };
                     }
                     if plan_opt.is_none() {
@@ -381,27 +448,8 @@ impl Planner {
                     };
                     // If the edge's from_node defines inline properties (e.g., (a:Label {k:v})),
                     // apply them as a Filter on the input plan to constrain the starting node.
-                    if !edge.from_node.properties.is_empty() {
-                        let mut iter = edge.from_node.properties.iter();
-                        if let Some((first_k, first_v)) = iter.next() {
-                            let mut pred = Expr::BinaryOp(
-                                Box::new(Expr::Property(from_var.clone(), first_k.clone())),
-                                BinOp::Eq,
-                                Box::new(Expr::Literal(first_v.clone())),
-                            );
-                            for (k, v) in iter {
-                                pred = Expr::BinaryOp(
-                                    Box::new(pred),
-                                    BinOp::And,
-                                    Box::new(Expr::BinaryOp(
-                                        Box::new(Expr::Property(from_var.clone(), k.clone())),
-                                        BinOp::Eq,
-                                        Box::new(Expr::Literal(v.clone())),
-                                    )),
-                                );
-                            }
-                            input_plan = PlanNode::Filter { input: Box::new(input_plan), predicate: pred };
-                        }
+                    if let Some(pred) = Self::property_predicate(&from_var, &edge.from_node.properties)? {
+                        input_plan = PlanNode::Filter { input: Box::new(input_plan), predicate: pred };
                     }
 
                     // Add expand wrapping previous plan
@@ -413,6 +461,8 @@ impl Planner {
                         edge_type: edge.edge_type.clone(),
                         direction: edge.direction.clone(),
                         depth: edge.depth.clone(),
+                        path_uniqueness: PathUniqueness::default(),
+                        optional: false,
                     };
                     // If an adjacent Node pattern binds the same to_var and defines inline properties,
                     // translate those properties into a post-Expand Filter predicate.
@@ -422,27 +472,8 @@ impl Planner {
                     if i > 0 {
                         if let Pattern::Node(prev_node) = &match_clause.patterns[i - 1] {
                             if let Some(prev_var) = &prev_node.variable {
-                                if prev_var == &to_var && !prev_node.properties.is_empty() {
-                                    let mut iter = prev_node.properties.iter();
-                                    if let Some((first_k, first_v)) = iter.next() {
-                                        let mut pred = Expr::BinaryOp(
-                                            Box::new(Expr::Property(to_var.clone(), first_k.clone())),
-                                            BinOp::Eq,
-                                            Box::new(Expr::Literal(first_v.clone())),
-                                        );
-                                        for (k, v) in iter {
-                                            pred = Expr::BinaryOp(
-                                                Box::new(pred),
-                                                BinOp::And,
-                                                Box::new(Expr::BinaryOp(
-                                                    Box::new(Expr::Property(to_var.clone(), k.clone())),
-                                                    BinOp::Eq,
-                                                    Box::new(Expr::Literal(v.clone())),
-                                                )),
-                                            );
-                                        }
-                                        predicate_opt = Some(pred);
-                                    }
+                                if prev_var == &to_var {
+                                    predicate_opt = Self::property_predicate(&to_var, &prev_node.properties)?;
                                 }
                             }
                         }
@@ -451,25 +482,8 @@ impl Planner {
                     if i + 1 < match_clause.patterns.len() {
                         if let Pattern::Node(next_node) = &match_clause.patterns[i + 1] {
                             if let Some(next_var) = &next_node.variable {
-                                if next_var == &to_var && !next_node.properties.is_empty() {
-                                    let mut iter = next_node.properties.iter();
-                                    if let Some((first_k, first_v)) = iter.next() {
-                                        let mut pred = Expr::BinaryOp(
-                                            Box::new(Expr::Property(to_var.clone(), first_k.clone())),
-                                            BinOp::Eq,
-                                            Box::new(Expr::Literal(first_v.clone())),
-                                        );
-                                        for (k, v) in iter {
-                                            pred = Expr::BinaryOp(
-                                                Box::new(pred),
-                                                BinOp::And,
-                                                Box::new(Expr::BinaryOp(
-                                                    Box::new(Expr::Property(to_var.clone(), k.clone())),
-                                                    BinOp::Eq,
-                                                    Box::new(Expr::Literal(v.clone())),
-                                                )),
-                                            );
-                                        }
+                                if next_var == &to_var {
+                                    if let Some(pred) = Self::property_predicate(&to_var, &next_node.properties)? {
                                         // Combine with previous predicate if present
                                         predicate_opt = Some(match predicate_opt.take() {
                                             Some(prev) => Expr::BinaryOp(Box::new(prev), BinOp::And, Box::new(pred)),
@@ -481,30 +495,11 @@ impl Planner {
                         }
                     }
                     // Include from_node inline properties as part of the predicate (enforced post-Expand as well)
-                    if !edge.from_node.properties.is_empty() {
-                        let mut iter = edge.from_node.properties.iter();
-                        if let Some((first_k, first_v)) = iter.next() {
-                            let mut from_pred = Expr::BinaryOp(
-                                Box::new(Expr::Property(from_var.clone(), first_k.clone())),
-                                BinOp::Eq,
-                                Box::new(Expr::Literal(first_v.clone())),
-                            );
-                            for (k, v) in iter {
-                                from_pred = Expr::BinaryOp(
-                                    Box::new(from_pred),
-                                    BinOp::And,
-                                    Box::new(Expr::BinaryOp(
-                                        Box::new(Expr::Property(from_var.clone(), k.clone())),
-                                        BinOp::Eq,
-                                        Box::new(Expr::Literal(v.clone())),
-                                    )),
-                                );
-                            }
-                            predicate_opt = Some(match predicate_opt.take() {
-                                Some(prev) => Expr::BinaryOp(Box::new(prev), BinOp::And, Box::new(from_pred)),
-                                None => from_pred,
-                            });
-                        }
+                    if let Some(from_pred) = Self::property_predicate(&from_var, &edge.from_node.properties)? {
+                        predicate_opt = Some(match predicate_opt.take() {
+                            Some(prev) => Expr::BinaryOp(Box::new(prev), BinOp::And, Box::new(from_pred)),
+                            None => from_pred,
+                        });
                     }
                     if let Some(pred) = predicate_opt {
                         expand_plan = PlanNode::Filter { input: Box::new(expand_plan), predicate: pred };
@@ -534,30 +529,11 @@ impl Planner {
             for pat in &match_clause.patterns {
                 if let Pattern::Node(node) = pat {
                     if let Some(var) = &node.variable {
-                        if !node.properties.is_empty() {
-                            let mut iter = node.properties.iter();
-                            if let Some((first_k, first_v)) = iter.next() {
-                                let mut pred = Expr::BinaryOp(
-                                    Box::new(Expr::Property(var.clone(), first_k.clone())),
-                                    BinOp::Eq,
-                                    Box::new(Expr::Literal(first_v.clone())),
-                                );
-                                for (k, v) in iter {
-                                    pred = Expr::BinaryOp(
-                                        Box::new(pred),
-                                        BinOp::And,
-                                        Box::new(Expr::BinaryOp(
-                                            Box::new(Expr::Property(var.clone(), k.clone())),
-                                            BinOp::Eq,
-                                            Box::new(Expr::Literal(v.clone())),
-                                        )),
-                                    );
-                                }
-                                global_pred_opt = Some(match global_pred_opt.take() {
-                                    Some(prev) => Expr::BinaryOp(Box::new(prev), BinOp::And, Box::new(pred)),
-                                    None => pred,
-                                });
-                            }
+                        if let Some(pred) = Self::property_predicate(var, &node.properties)? {
+                            global_pred_opt = Some(match global_pred_opt.take() {
+                                Some(prev) => Expr::BinaryOp(Box::new(prev), BinOp::And, Box::new(pred)),
+                                None => pred,
+                            });
                         }
                     }
                 }
@@ -0,0 +1,68 @@
+//! Provenance semirings for probabilistic/trust-weighted graphs. A
+//! `Provenance` implementation tells the executor how to combine the `f64`
+//! tags carried on `Tuple`s: `add` (⊕) merges alternative derivations of the
+//! same result, `mul` (⊗) combines the tags of tuples joined together along
+//! one derivation. Joins always use plain multiplication (both shipped
+//! instances agree on `mul`), so only `add` actually varies by semiring -
+//! pass the executor's configured instance to combine duplicate derivations
+//! (e.g. in a de-duplicating `RETURN DISTINCT` or grouping stage).
+
+/// A commutative semiring over provenance tags. `zero()`/`one()` are the
+/// additive/multiplicative identities; `add`/`mul` must be associative and
+/// commutative for the engine's left-to-right combination order not to
+/// matter.
+pub trait Provenance: Send + Sync {
+    fn zero(&self) -> f64;
+    fn one(&self) -> f64;
+    fn add(&self, a: f64, b: f64) -> f64;
+    fn mul(&self, a: f64, b: f64) -> f64;
+}
+
+/// The max/min-probability (a.k.a. "viterbi") semiring: the most likely
+/// derivation wins outright rather than accumulating support from every
+/// alternative. Common for confidence scores where derivations aren't
+/// actually independent events.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxMinProb;
+
+impl Provenance for MaxMinProb {
+    fn zero(&self) -> f64 {
+        0.0
+    }
+
+    fn one(&self) -> f64 {
+        1.0
+    }
+
+    fn add(&self, a: f64, b: f64) -> f64 {
+        a.max(b)
+    }
+
+    fn mul(&self, a: f64, b: f64) -> f64 {
+        a * b
+    }
+}
+
+/// The standard (independent-event) probability semiring: `add` is the
+/// inclusion-exclusion sum for independent events, so combining the same
+/// derivation twice never pushes the result past 1.0.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardProbability;
+
+impl Provenance for StandardProbability {
+    fn zero(&self) -> f64 {
+        0.0
+    }
+
+    fn one(&self) -> f64 {
+        1.0
+    }
+
+    fn add(&self, a: f64, b: f64) -> f64 {
+        a + b - a * b
+    }
+
+    fn mul(&self, a: f64, b: f64) -> f64 {
+        a * b
+    }
+}
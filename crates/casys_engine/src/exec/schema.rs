@@ -0,0 +1,112 @@
+//! Declared node/edge schema, populated by `DEFINE NODE`/`DEFINE EDGE` and
+//! consulted by `execute_create` so a labeled/typed `CREATE` is validated
+//! against its declaration instead of accepting any property bag. A
+//! label/edge type with no declaration is left alone - schema is opt-in per
+//! label, not a closed-world requirement the way a traditional DDL-first
+//! database would enforce it.
+
+use super::ast::{EdgeTypeDef, NodeTypeDef, PropertyDef, Typing};
+use crate::types::EngineError;
+use casys_core::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone)]
+pub struct SchemaRegistry {
+    nodes: HashMap<String, NodeTypeDef>,
+    edges: HashMap<String, EdgeTypeDef>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn define_node(&mut self, def: NodeTypeDef) {
+        self.nodes.insert(def.label.clone(), def);
+    }
+
+    pub fn define_edge(&mut self, def: EdgeTypeDef) {
+        self.edges.insert(def.edge_type.clone(), def);
+    }
+
+    pub fn node_def(&self, label: &str) -> Option<&NodeTypeDef> {
+        self.nodes.get(label)
+    }
+
+    pub fn edge_def(&self, edge_type: &str) -> Option<&EdgeTypeDef> {
+        self.edges.get(edge_type)
+    }
+
+    /// Validates/coerces `props` against the first of `labels` that has a
+    /// declaration (a node can carry several labels; only one needs a
+    /// `DEFINE`), or passes `props` through unchanged if none do.
+    pub fn validate_node(&self, labels: &[String], props: HashMap<String, Value>) -> Result<HashMap<String, Value>, EngineError> {
+        match labels.iter().find_map(|l| self.nodes.get(l)) {
+            Some(def) => Self::validate_properties(&def.properties, props),
+            None => Ok(props),
+        }
+    }
+
+    /// Validates/coerces `props` for `edge_type`, additionally checking that
+    /// `from_labels`/`to_labels` (the endpoint nodes' actual labels) contain
+    /// the declared source/target label. An endpoint with no labels at all
+    /// skips the check rather than failing closed - the same "unknown passes
+    /// through" stance as an undeclared label/edge type.
+    pub fn validate_edge(
+        &self,
+        edge_type: &str,
+        from_labels: &[String],
+        to_labels: &[String],
+        props: HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>, EngineError> {
+        let Some(def) = self.edges.get(edge_type) else { return Ok(props) };
+        if !from_labels.is_empty() && !from_labels.iter().any(|l| l == &def.from_label) {
+            return Err(EngineError::TypeMismatch(format!(
+                "edge type {} requires source label {}, got {:?}", edge_type, def.from_label, from_labels
+            )));
+        }
+        if !to_labels.is_empty() && !to_labels.iter().any(|l| l == &def.to_label) {
+            return Err(EngineError::TypeMismatch(format!(
+                "edge type {} requires target label {}, got {:?}", edge_type, def.to_label, to_labels
+            )));
+        }
+        Self::validate_properties(&def.properties, props)
+    }
+
+    /// Rejects unknown properties, fills declared-but-absent nullable fields
+    /// with `Value::Null` (erroring instead if the missing field isn't
+    /// nullable), and coerces every present value to its declared `Typing`.
+    fn validate_properties(declared: &[PropertyDef], mut props: HashMap<String, Value>) -> Result<HashMap<String, Value>, EngineError> {
+        let mut out = HashMap::with_capacity(declared.len());
+        for field in declared {
+            let value = match props.remove(&field.name) {
+                Some(value) => coerce(&field.name, &field.typing, value)?,
+                None if is_nullable(&field.typing) => Value::Null,
+                None => return Err(EngineError::TypeMismatch(format!("missing required property {}", field.name))),
+            };
+            out.insert(field.name.clone(), value);
+        }
+        if let Some((key, _)) = props.into_iter().next() {
+            return Err(EngineError::TypeMismatch(format!("undeclared property {key}")));
+        }
+        Ok(out)
+    }
+}
+
+fn is_nullable(typing: &Typing) -> bool {
+    matches!(typing, Typing::Nullable(_))
+}
+
+fn coerce(name: &str, typing: &Typing, value: Value) -> Result<Value, EngineError> {
+    match (typing, value) {
+        (Typing::Any, v) => Ok(v),
+        (Typing::Nullable(_), Value::Null) => Ok(Value::Null),
+        (Typing::Nullable(inner), v) => coerce(name, inner, v),
+        (Typing::Int, v @ Value::Int(_)) => Ok(v),
+        (Typing::Float, v @ Value::Float(_)) => Ok(v),
+        (Typing::Float, Value::Int(i)) => Ok(Value::Float(i as f64)),
+        (Typing::String, v @ Value::String(_)) => Ok(v),
+        (Typing::Bool, v @ Value::Bool(_)) => Ok(v),
+        (t, v) => Err(EngineError::TypeMismatch(format!("property {name} expected {t:?}, got {v:?}"))),
+    }
+}
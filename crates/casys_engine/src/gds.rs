@@ -0,0 +1,402 @@
+//! Graph algorithms - traversal, shortest paths, and connected components -
+//! that operate generically over any `GraphReadStore` via its
+//! `get_neighbors`/`get_neighbors_incoming` adjacency, rather than a
+//! concrete `InMemoryGraphStore`. So the same code runs against today's
+//! in-memory store and any future disk- or object-store-backed one, the
+//! traversals below only ever hold a visited set and a frontier in memory,
+//! never the whole graph.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use casys_core::{EngineError, GraphReadStore, NodeId, Value};
+
+/// Breadth-first traversal from `start` over outgoing edges (optionally
+/// restricted to `edge_type`), yielding one `NodeId` per step in BFS order.
+/// `next()` makes exactly one `get_neighbors` call per node it yields.
+pub struct Bfs<'a, S: GraphReadStore + ?Sized> {
+    store: &'a S,
+    edge_type: Option<&'a str>,
+    queue: VecDeque<NodeId>,
+    visited: HashSet<NodeId>,
+}
+
+impl<'a, S: GraphReadStore + ?Sized> Bfs<'a, S> {
+    pub fn new(store: &'a S, start: NodeId, edge_type: Option<&'a str>) -> Self {
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        Self { store, edge_type, queue: VecDeque::from([start]), visited }
+    }
+}
+
+impl<S: GraphReadStore + ?Sized> Iterator for Bfs<'_, S> {
+    type Item = Result<NodeId, EngineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.queue.pop_front()?;
+        match self.store.get_neighbors(current, self.edge_type) {
+            Ok(neighbors) => {
+                for (_, node) in neighbors {
+                    if self.visited.insert(node.id) {
+                        self.queue.push_back(node.id);
+                    }
+                }
+                Some(Ok(current))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Depth-first traversal, same shape as [`Bfs`] but with a stack instead of
+/// a queue so the yield order follows DFS rather than BFS.
+pub struct Dfs<'a, S: GraphReadStore + ?Sized> {
+    store: &'a S,
+    edge_type: Option<&'a str>,
+    stack: Vec<NodeId>,
+    visited: HashSet<NodeId>,
+}
+
+impl<'a, S: GraphReadStore + ?Sized> Dfs<'a, S> {
+    pub fn new(store: &'a S, start: NodeId, edge_type: Option<&'a str>) -> Self {
+        Self { store, edge_type, stack: vec![start], visited: HashSet::new() }
+    }
+}
+
+impl<S: GraphReadStore + ?Sized> Iterator for Dfs<'_, S> {
+    type Item = Result<NodeId, EngineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let current = self.stack.pop()?;
+            if !self.visited.insert(current) {
+                continue;
+            }
+            let neighbors = match self.store.get_neighbors(current, self.edge_type) {
+                Ok(n) => n,
+                Err(e) => return Some(Err(e)),
+            };
+            // Reversed so the first neighbor returned by get_neighbors is
+            // the first one popped (and thus visited next).
+            for (_, node) in neighbors.into_iter().rev() {
+                if !self.visited.contains(&node.id) {
+                    self.stack.push(node.id);
+                }
+            }
+            return Some(Ok(current));
+        }
+    }
+}
+
+fn reconstruct_path(came_from: &HashMap<NodeId, NodeId>, start: NodeId, goal: NodeId) -> Vec<NodeId> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while current != start {
+        current = came_from[&current];
+        path.push(current);
+    }
+    path.reverse();
+    path
+}
+
+/// Unweighted shortest path from `start` to `goal` (BFS-based, so it's
+/// shortest by hop count), or `None` if `goal` isn't reachable.
+pub fn shortest_path<S: GraphReadStore + ?Sized>(
+    store: &S,
+    start: NodeId,
+    goal: NodeId,
+    edge_type: Option<&str>,
+) -> Result<Option<Vec<NodeId>>, EngineError> {
+    if start == goal {
+        return Ok(Some(vec![start]));
+    }
+
+    let mut visited = HashSet::new();
+    visited.insert(start);
+    let mut queue = VecDeque::from([start]);
+    let mut came_from: HashMap<NodeId, NodeId> = HashMap::new();
+
+    while let Some(current) = queue.pop_front() {
+        for (_, node) in store.get_neighbors(current, edge_type)? {
+            if visited.insert(node.id) {
+                came_from.insert(node.id, current);
+                if node.id == goal {
+                    return Ok(Some(reconstruct_path(&came_from, start, goal)));
+                }
+                queue.push_back(node.id);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// One entry in Dijkstra's frontier, ordered by tentative distance with the
+/// comparison inverted so `BinaryHeap` (a max-heap) pops the smallest
+/// distance first; ties break on node id for a deterministic pop order.
+#[derive(PartialEq)]
+struct Frontier {
+    distance: f64,
+    node: NodeId,
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.partial_cmp(&self.distance).unwrap_or(Ordering::Equal).then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Weighted shortest path via Dijkstra: reads `weight_property` off each
+/// traversed edge as the edge's weight (an edge missing the property, or
+/// holding a non-numeric `Value`, weighs `1.0`), using a binary heap keyed
+/// by tentative distance. Returns the path and its total weight, or `None`
+/// if `goal` isn't reachable. Negative weights aren't supported - same
+/// caveat as the textbook algorithm.
+pub fn dijkstra_shortest_path<S: GraphReadStore + ?Sized>(
+    store: &S,
+    start: NodeId,
+    goal: NodeId,
+    weight_property: &str,
+    edge_type: Option<&str>,
+) -> Result<Option<(Vec<NodeId>, f64)>, EngineError> {
+    let mut best: HashMap<NodeId, f64> = HashMap::new();
+    let mut came_from: HashMap<NodeId, NodeId> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    best.insert(start, 0.0);
+    heap.push(Frontier { distance: 0.0, node: start });
+
+    while let Some(Frontier { distance, node }) = heap.pop() {
+        if node == goal {
+            return Ok(Some((reconstruct_path(&came_from, start, goal), distance)));
+        }
+        if distance > *best.get(&node).unwrap_or(&f64::INFINITY) {
+            continue; // a better path to `node` was already found and popped
+        }
+        for (edge, neighbor) in store.get_neighbors(node, edge_type)? {
+            let weight = match edge.properties.get(weight_property) {
+                Some(Value::Int(i)) => *i as f64,
+                Some(Value::Float(f)) => *f,
+                _ => 1.0,
+            };
+            let candidate = distance + weight;
+            if candidate < *best.get(&neighbor.id).unwrap_or(&f64::INFINITY) {
+                best.insert(neighbor.id, candidate);
+                came_from.insert(neighbor.id, node);
+                heap.push(Frontier { distance: candidate, node: neighbor.id });
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Weakly connected components among `node_ids`: BFS over the undirected
+/// union of `get_neighbors`/`get_neighbors_incoming`, so edge direction is
+/// ignored when grouping nodes into components.
+pub fn weakly_connected_components<S: GraphReadStore + ?Sized>(
+    store: &S,
+    node_ids: &[NodeId],
+) -> Result<Vec<Vec<NodeId>>, EngineError> {
+    let mut visited = HashSet::new();
+    let mut components = Vec::new();
+
+    for &start in node_ids {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            let mut neighbors: Vec<NodeId> = store.get_neighbors(current, None)?.into_iter().map(|(_, n)| n.id).collect();
+            neighbors.extend(store.get_neighbors_incoming(current, None)?.into_iter().map(|(_, n)| n.id));
+            for id in neighbors {
+                if visited.insert(id) {
+                    component.push(id);
+                    queue.push_back(id);
+                }
+            }
+        }
+        components.push(component);
+    }
+
+    Ok(components)
+}
+
+/// Betweenness centrality for every node in `node_ids`, via Brandes'
+/// algorithm: one BFS per source accumulating each node's shortest-path
+/// count `sigma` and predecessor list, then a reverse-BFS-order
+/// back-propagation of dependency `delta[v] += (sigma[v]/sigma[w]) * (1 +
+/// delta[w])`, summed into that node's score. Unweighted (hop-count
+/// shortest paths, like [`shortest_path`]) and over outgoing edges only.
+pub fn betweenness_centrality<S: GraphReadStore + ?Sized>(
+    store: &S,
+    node_ids: &[NodeId],
+    edge_type: Option<&str>,
+) -> Result<HashMap<NodeId, f64>, EngineError> {
+    let mut centrality: HashMap<NodeId, f64> = node_ids.iter().map(|&id| (id, 0.0)).collect();
+
+    for &s in node_ids {
+        let mut stack: Vec<NodeId> = Vec::new();
+        let mut preds: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        let mut sigma: HashMap<NodeId, f64> = HashMap::new();
+        let mut dist: HashMap<NodeId, u64> = HashMap::new();
+        sigma.insert(s, 1.0);
+        dist.insert(s, 0);
+        let mut queue = VecDeque::from([s]);
+
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            let dv = dist[&v];
+            for (_, n) in store.get_neighbors(v, edge_type)? {
+                let w = n.id;
+                if !dist.contains_key(&w) {
+                    dist.insert(w, dv + 1);
+                    queue.push_back(w);
+                }
+                if dist[&w] == dv + 1 {
+                    let sv = sigma[&v];
+                    *sigma.entry(w).or_insert(0.0) += sv;
+                    preds.entry(w).or_default().push(v);
+                }
+            }
+        }
+
+        let mut delta: HashMap<NodeId, f64> = HashMap::new();
+        while let Some(w) = stack.pop() {
+            let dw = delta.get(&w).copied().unwrap_or(0.0);
+            if let Some(ps) = preds.get(&w) {
+                for &v in ps {
+                    *delta.entry(v).or_insert(0.0) += (sigma[&v] / sigma[&w]) * (1.0 + dw);
+                }
+            }
+            if w != s {
+                *centrality.entry(w).or_insert(0.0) += dw;
+            }
+        }
+    }
+
+    Ok(centrality)
+}
+
+/// Closeness centrality for every node in `node_ids`: `(reachable - 1) /
+/// sum_of_distances`, where both are computed from a single BFS per node
+/// over outgoing edges. A node with no reachable neighbors scores `0.0`
+/// rather than dividing by zero.
+pub fn closeness_centrality<S: GraphReadStore + ?Sized>(
+    store: &S,
+    node_ids: &[NodeId],
+    edge_type: Option<&str>,
+) -> Result<HashMap<NodeId, f64>, EngineError> {
+    let mut centrality = HashMap::with_capacity(node_ids.len());
+
+    for &s in node_ids {
+        let mut dist: HashMap<NodeId, u64> = HashMap::new();
+        dist.insert(s, 0);
+        let mut queue = VecDeque::from([s]);
+
+        while let Some(v) = queue.pop_front() {
+            let dv = dist[&v];
+            for (_, n) in store.get_neighbors(v, edge_type)? {
+                if !dist.contains_key(&n.id) {
+                    dist.insert(n.id, dv + 1);
+                    queue.push_back(n.id);
+                }
+            }
+        }
+
+        let reachable = dist.len() as u64 - 1;
+        let sum_of_distances: u64 = dist.values().sum();
+        let score = if sum_of_distances > 0 { reachable as f64 / sum_of_distances as f64 } else { 0.0 };
+        centrality.insert(s, score);
+    }
+
+    Ok(centrality)
+}
+
+/// One node's place on Tarjan's explicit work stack: its outgoing
+/// neighbors (fetched once, on first visit) and how far through them
+/// we've iterated.
+struct TarjanFrame {
+    node: NodeId,
+    neighbors: Vec<NodeId>,
+    neighbor_idx: usize,
+    initialized: bool,
+}
+
+/// Strongly connected components among `node_ids`, via Tarjan's algorithm.
+/// Uses an explicit work stack rather than recursion, so a long dependency
+/// chain doesn't blow the call stack, tracking each node's DFS `index` and
+/// `lowlink` alongside it.
+pub fn strongly_connected_components<S: GraphReadStore + ?Sized>(
+    store: &S,
+    node_ids: &[NodeId],
+) -> Result<Vec<Vec<NodeId>>, EngineError> {
+    let mut next_index = 0usize;
+    let mut index: HashMap<NodeId, usize> = HashMap::new();
+    let mut lowlink: HashMap<NodeId, usize> = HashMap::new();
+    let mut on_stack: HashSet<NodeId> = HashSet::new();
+    let mut tarjan_stack: Vec<NodeId> = Vec::new();
+    let mut components = Vec::new();
+
+    for &root in node_ids {
+        if index.contains_key(&root) {
+            continue;
+        }
+
+        let mut work = vec![TarjanFrame { node: root, neighbors: Vec::new(), neighbor_idx: 0, initialized: false }];
+
+        while let Some(frame) = work.last_mut() {
+            if !frame.initialized {
+                index.insert(frame.node, next_index);
+                lowlink.insert(frame.node, next_index);
+                next_index += 1;
+                tarjan_stack.push(frame.node);
+                on_stack.insert(frame.node);
+                frame.neighbors = store.get_neighbors(frame.node, None)?.into_iter().map(|(_, n)| n.id).collect();
+                frame.initialized = true;
+            }
+
+            if frame.neighbor_idx < frame.neighbors.len() {
+                let next = frame.neighbors[frame.neighbor_idx];
+                frame.neighbor_idx += 1;
+                if !index.contains_key(&next) {
+                    work.push(TarjanFrame { node: next, neighbors: Vec::new(), neighbor_idx: 0, initialized: false });
+                } else if on_stack.contains(&next) {
+                    let merged = lowlink[&frame.node].min(index[&next]);
+                    lowlink.insert(frame.node, merged);
+                }
+                continue;
+            }
+
+            let node = frame.node;
+            work.pop();
+            if let Some(parent) = work.last() {
+                let merged = lowlink[&parent.node].min(lowlink[&node]);
+                lowlink.insert(parent.node, merged);
+            }
+            if lowlink[&node] == index[&node] {
+                let mut component = Vec::new();
+                loop {
+                    let w = tarjan_stack.pop().expect("node pushed onto tarjan_stack before being indexed");
+                    on_stack.remove(&w);
+                    component.push(w);
+                    if w == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    Ok(components)
+}
@@ -0,0 +1,56 @@
+//! Segment payload encoding: JSON (the original format, still the default)
+//! or a more compact MessagePack encoding, auto-detected on read so existing
+//! JSON segments keep loading unchanged.
+//!
+//! This sits below [`super::compression`] in the stack: `compression::compress`
+//! wraps whatever bytes [`encode_segment`] produces, so a segment on disk is
+//! `[compression tag][segment encoding tag?][payload]` - `compression::decompress`
+//! strips its tag first, then [`decode_segment`] strips (or infers the
+//! absence of) its own.
+
+use crate::types::EngineError;
+
+/// How a segment's JSON structure (nodes/edges, or a `WalRecord`) is
+/// encoded to bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentEncoding {
+    Json,
+    MessagePack,
+}
+
+/// Leading tag byte marking a MessagePack-encoded payload. `0x00` is safe:
+/// every payload this module was introduced to replace is JSON text, and
+/// JSON text never starts with a NUL byte, so [`decode_segment`] can tell a
+/// tagged MessagePack payload apart from an untagged legacy JSON one just by
+/// peeking at the first byte - no version bump needed.
+const MESSAGEPACK_TAG: u8 = 0x00;
+
+/// Encodes `value` per `encoding`. `Json` reproduces exactly what
+/// `serde_json::to_vec` always wrote (no tag byte), so a caller that never
+/// opts into `MessagePack` sees no on-disk format change at all.
+pub fn encode_segment(value: &serde_json::Value, encoding: SegmentEncoding) -> Result<Vec<u8>, EngineError> {
+    match encoding {
+        SegmentEncoding::Json => serde_json::to_vec(value)
+            .map_err(|e| EngineError::StorageIo(format!("encode segment (json): {e}"))),
+        SegmentEncoding::MessagePack => {
+            let mut out = vec![MESSAGEPACK_TAG];
+            rmp_serde::encode::write(&mut out, value)
+                .map_err(|e| EngineError::StorageIo(format!("encode segment (msgpack): {e}")))?;
+            Ok(out)
+        }
+    }
+}
+
+/// Decodes a payload written by [`encode_segment`], dispatching on the
+/// leading tag byte - `MESSAGEPACK_TAG` for the new format, anything else
+/// (including an empty buffer) for plain JSON, same as every segment
+/// written before this codec existed.
+pub fn decode_segment(data: &[u8]) -> Result<serde_json::Value, EngineError> {
+    if data.first() == Some(&MESSAGEPACK_TAG) {
+        rmp_serde::from_slice(&data[1..])
+            .map_err(|e| EngineError::StorageIo(format!("decode segment (msgpack): {e}")))
+    } else {
+        serde_json::from_slice(data)
+            .map_err(|e| EngineError::StorageIo(format!("decode segment (json): {e}")))
+    }
+}
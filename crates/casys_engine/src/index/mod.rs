@@ -5,9 +5,16 @@
 //! FS convenience methods (flush_to_fs/load_from_fs) require the `fs` feature.
 
 pub mod persistence;
+pub mod compression;
+pub mod codec;
+pub mod wal_store;
+pub mod raft_store;
+#[cfg(feature = "async")]
+pub mod watch;
 
 use crate::types::EngineError;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
 
 // Re-export graph types and traits from casys_core (AC5: backward compatibility)
 pub use casys_core::{
@@ -16,11 +23,93 @@ pub use casys_core::{
     GraphReadStore, GraphWriteStore,
 };
 
+/// `BTreeMap`-key wrapper giving `Value` the total ordering
+/// `casys_core::value_cmp` defines, so the property index can range-scan
+/// instead of only doing `HashMap` equality lookups like `label_index`.
+#[derive(Clone, Debug, PartialEq)]
+struct OrderedValue(Value);
+
+impl Eq for OrderedValue {}
+impl PartialOrd for OrderedValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for OrderedValue {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        casys_core::value_cmp(&self.0, &other.0)
+    }
+}
+
+fn map_bound(b: Bound<Value>) -> Bound<OrderedValue> {
+    match b {
+        Bound::Included(v) => Bound::Included(OrderedValue(v)),
+        Bound::Excluded(v) => Bound::Excluded(OrderedValue(v)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// One id's slot in the `nodes`/`edges` map: either its current value, or a
+/// tombstone recording that it was deleted. Kept in the map rather than
+/// removed outright so [`InMemoryGraphStore::node_version`] and idempotent
+/// WAL replay (`apply_node_delete`/`apply_edge_delete`) have something to
+/// compare a replayed delete's version against.
+#[derive(Clone, Debug)]
+enum Slot<T> {
+    Value(T),
+    Tombstone,
+}
+
+/// A slot plus the monotonically increasing version it was last written
+/// at. The version is bumped on every `add`/`delete` for that id, so a
+/// caller can pass the version it last read back on write to detect a lost
+/// update (`expected_version` not matching `version` - see
+/// [`InMemoryGraphStore::delete_node_versioned`]), and WAL replay can skip a delete
+/// whose version is already superseded.
+#[derive(Clone, Debug)]
+struct Versioned<T> {
+    slot: Slot<T>,
+    version: u64,
+}
+
+impl<T> Versioned<T> {
+    fn value(v: T) -> Self {
+        Self { slot: Slot::Value(v), version: 1 }
+    }
+
+    fn as_value(&self) -> Option<&T> {
+        match &self.slot {
+            Slot::Value(v) => Some(v),
+            Slot::Tombstone => None,
+        }
+    }
+
+    fn is_tombstone(&self) -> bool {
+        matches!(self.slot, Slot::Tombstone)
+    }
+}
+
+/// Which kind of element a `persistence::WalRecord::SetProperties` (or the
+/// `set_node_property`/`set_edge_property` helpers that emit one) targets -
+/// node and edge properties share no storage, so a property update has to
+/// say which map it belongs in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyTarget {
+    Node(NodeId),
+    Edge(EdgeId),
+}
+
 /// In-memory graph store with indexes
+#[derive(Clone)]
 pub struct InMemoryGraphStore {
-    pub(crate) nodes: HashMap<NodeId, Node>,
-    pub(crate) edges: HashMap<EdgeId, Edge>,
+    pub(crate) nodes: HashMap<NodeId, Versioned<Node>>,
+    pub(crate) edges: HashMap<EdgeId, Versioned<Edge>>,
     pub(crate) label_index: HashMap<String, Vec<NodeId>>,
+    /// Range-queryable counterpart of `label_index`: property name to a
+    /// `BTreeMap` from that property's value (ordered per `OrderedValue`)
+    /// to the node ids holding it, so `scan_by_property_range` can range-
+    /// scan instead of falling back to `scan_all` plus manual filtering.
+    pub(crate) property_index: HashMap<String, BTreeMap<OrderedValue, Vec<NodeId>>>,
     pub(crate) adjacency_out: HashMap<NodeId, Vec<EdgeId>>,
     pub(crate) adjacency_in: HashMap<NodeId, Vec<EdgeId>>,
     pub(crate) next_node_id: NodeId,
@@ -33,23 +122,256 @@ impl InMemoryGraphStore {
             nodes: HashMap::new(),
             edges: HashMap::new(),
             label_index: HashMap::new(),
+            property_index: HashMap::new(),
             adjacency_out: HashMap::new(),
             adjacency_in: HashMap::new(),
             next_node_id: 1,
             next_edge_id: 1,
         }
     }
+
+    /// Adds `node`'s properties into `property_index`. Called everywhere a
+    /// node's labels are added to `label_index` - `add_node`, `insert_node`,
+    /// segment loading's `deserialize_nodes`, and WAL replay's `AddNode` arm.
+    pub(crate) fn index_node_properties(&mut self, node: &Node) {
+        for (prop, value) in &node.properties {
+            self.property_index
+                .entry(prop.clone())
+                .or_insert_with(BTreeMap::new)
+                .entry(OrderedValue(value.clone()))
+                .or_insert_with(Vec::new)
+                .push(node.id);
+        }
+    }
+
+    /// Removes `node`'s properties from `property_index`, the counterpart
+    /// of `index_node_properties` run on delete.
+    fn deindex_node_properties(&mut self, node: &Node) {
+        for (prop, value) in &node.properties {
+            if let Some(by_value) = self.property_index.get_mut(prop) {
+                if let Some(ids) = by_value.get_mut(&OrderedValue(value.clone())) {
+                    ids.retain(|nid| *nid != node.id);
+                }
+            }
+        }
+    }
+
+    /// Live value for `id`, or `None` if it's never existed or is
+    /// tombstoned. Shared by the `GraphReadStore` methods and by
+    /// [`crate::merge`], which needs the same "tombstones are invisible"
+    /// view to compare branches.
+    pub(crate) fn node_value(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(&id).and_then(Versioned::as_value)
+    }
+
+    pub(crate) fn edge_value(&self, id: EdgeId) -> Option<&Edge> {
+        self.edges.get(&id).and_then(Versioned::as_value)
+    }
+
+    /// The version currently stored for `id` (bumped on every add/delete),
+    /// or `0` if `id` has never been written. Lets a caller detect a lost
+    /// update by comparing against the version it last read.
+    pub fn node_version(&self, id: NodeId) -> u64 {
+        self.nodes.get(&id).map(|v| v.version).unwrap_or(0)
+    }
+
+    pub fn edge_version(&self, id: EdgeId) -> u64 {
+        self.edges.get(&id).map(|v| v.version).unwrap_or(0)
+    }
+
+    /// Count of non-tombstoned nodes/edges, for segment metadata - counting
+    /// the raw maps would include tombstones still awaiting `compact`.
+    pub fn live_node_count(&self) -> u64 {
+        self.nodes.values().filter(|v| !v.is_tombstone()).count() as u64
+    }
+
+    pub fn live_edge_count(&self) -> u64 {
+        self.edges.values().filter(|v| !v.is_tombstone()).count() as u64
+    }
+
+    /// Tombstones `id`, bumping its version. If `expected_version` is
+    /// `Some` and doesn't match what's currently stored, returns
+    /// `EngineError::Concurrency` instead of applying the delete - the
+    /// caller lost a race with another writer.
+    pub fn delete_node_versioned(&mut self, id: NodeId, expected_version: Option<u64>) -> Result<(), EngineError> {
+        let current = self.node_version(id);
+        if let Some(expected) = expected_version {
+            if expected != current {
+                return Err(EngineError::Concurrency(format!(
+                    "node {id} expected version {expected}, found {current}"
+                )));
+            }
+        }
+        if let Some(node) = self.node_value(id).cloned() {
+            for label in &node.labels {
+                if let Some(ids) = self.label_index.get_mut(label) {
+                    ids.retain(|nid| *nid != id);
+                }
+            }
+            self.deindex_node_properties(&node);
+        }
+        self.nodes.insert(id, Versioned { slot: Slot::Tombstone, version: current + 1 });
+        Ok(())
+    }
+
+    /// Edge counterpart of [`Self::delete_node_versioned`]; also drops `id`
+    /// out of both adjacency indexes so it stops showing up as a dangling
+    /// neighbor edge.
+    pub fn delete_edge_versioned(&mut self, id: EdgeId, expected_version: Option<u64>) -> Result<(), EngineError> {
+        let current = self.edge_version(id);
+        if let Some(expected) = expected_version {
+            if expected != current {
+                return Err(EngineError::Concurrency(format!(
+                    "edge {id} expected version {expected}, found {current}"
+                )));
+            }
+        }
+        if let Some(edge) = self.edges.get(&id).and_then(Versioned::as_value) {
+            if let Some(ids) = self.adjacency_out.get_mut(&edge.from_node) {
+                ids.retain(|eid| *eid != id);
+            }
+            if let Some(ids) = self.adjacency_in.get_mut(&edge.to_node) {
+                ids.retain(|eid| *eid != id);
+            }
+        }
+        self.edges.insert(id, Versioned { slot: Slot::Tombstone, version: current + 1 });
+        Ok(())
+    }
+
+    /// Applies a delete recovered from WAL replay: a no-op if `version`
+    /// doesn't exceed what's already stored, so replaying the same segment
+    /// twice leaves the store unchanged.
+    pub fn apply_node_delete(&mut self, id: NodeId, version: u64) -> Result<(), EngineError> {
+        if version <= self.node_version(id) {
+            return Ok(());
+        }
+        if let Some(node) = self.node_value(id).cloned() {
+            for label in &node.labels {
+                if let Some(ids) = self.label_index.get_mut(label) {
+                    ids.retain(|nid| *nid != id);
+                }
+            }
+            self.deindex_node_properties(&node);
+        }
+        self.nodes.insert(id, Versioned { slot: Slot::Tombstone, version });
+        Ok(())
+    }
+
+    pub fn apply_edge_delete(&mut self, id: EdgeId, version: u64) -> Result<(), EngineError> {
+        if version <= self.edge_version(id) {
+            return Ok(());
+        }
+        if let Some(edge) = self.edges.get(&id).and_then(Versioned::as_value) {
+            if let Some(ids) = self.adjacency_out.get_mut(&edge.from_node) {
+                ids.retain(|eid| *eid != id);
+            }
+            if let Some(ids) = self.adjacency_in.get_mut(&edge.to_node) {
+                ids.retain(|eid| *eid != id);
+            }
+        }
+        self.edges.insert(id, Versioned { slot: Slot::Tombstone, version });
+        Ok(())
+    }
+
+    /// Sets (or, if `value` is `None`, removes) property `key` on node `id`,
+    /// bumping its version the same way `delete_node_versioned` does. Returns
+    /// `EngineError::Concurrency` if `expected_version` is `Some` and doesn't
+    /// match, same lost-update protection as the versioned deletes.
+    pub fn set_node_property_versioned(&mut self, id: NodeId, key: &str, value: Option<Value>, expected_version: Option<u64>) -> Result<(), EngineError> {
+        let current = self.node_version(id);
+        if let Some(expected) = expected_version {
+            if expected != current {
+                return Err(EngineError::Concurrency(format!(
+                    "node {id} expected version {expected}, found {current}"
+                )));
+            }
+        }
+        self.apply_node_property_set(id, key, value, current + 1);
+        Ok(())
+    }
+
+    /// Edge counterpart of [`Self::set_node_property_versioned`].
+    pub fn set_edge_property_versioned(&mut self, id: EdgeId, key: &str, value: Option<Value>, expected_version: Option<u64>) -> Result<(), EngineError> {
+        let current = self.edge_version(id);
+        if let Some(expected) = expected_version {
+            if expected != current {
+                return Err(EngineError::Concurrency(format!(
+                    "edge {id} expected version {expected}, found {current}"
+                )));
+            }
+        }
+        self.apply_edge_property_set(id, key, value, current + 1);
+        Ok(())
+    }
+
+    /// Applies a property set recovered from WAL replay: a no-op if
+    /// `version` doesn't exceed what's already stored (mirroring
+    /// `apply_node_delete`), and a no-op on a tombstoned or never-existing
+    /// node rather than resurrecting it. Keeps `property_index` in sync by
+    /// deindexing the old value (if any) before indexing the new one.
+    pub fn apply_node_property_set(&mut self, id: NodeId, key: &str, value: Option<Value>, version: u64) {
+        if version <= self.node_version(id) {
+            return;
+        }
+        let Some(mut node) = self.node_value(id).cloned() else { return };
+        if let Some(by_value) = self.property_index.get_mut(key) {
+            if let Some(old) = node.properties.get(key) {
+                if let Some(ids) = by_value.get_mut(&OrderedValue(old.clone())) {
+                    ids.retain(|nid| *nid != id);
+                }
+            }
+        }
+        match value {
+            Some(v) => {
+                self.property_index.entry(key.to_string()).or_insert_with(BTreeMap::new)
+                    .entry(OrderedValue(v.clone())).or_insert_with(Vec::new).push(id);
+                node.properties.insert(key.to_string(), v);
+            }
+            None => {
+                node.properties.remove(key);
+            }
+        }
+        self.nodes.insert(id, Versioned { slot: Slot::Value(node), version });
+    }
+
+    /// Edge counterpart of [`Self::apply_node_property_set`]. Edge
+    /// properties aren't range-indexed (only `property_index`, which is
+    /// node-only), so there's no index to keep in sync here.
+    pub fn apply_edge_property_set(&mut self, id: EdgeId, key: &str, value: Option<Value>, version: u64) {
+        if version <= self.edge_version(id) {
+            return;
+        }
+        let Some(mut edge) = self.edge_value(id).cloned() else { return };
+        match value {
+            Some(v) => {
+                edge.properties.insert(key.to_string(), v);
+            }
+            None => {
+                edge.properties.remove(key);
+            }
+        }
+        self.edges.insert(id, Versioned { slot: Slot::Value(edge), version });
+    }
+
+    /// Physically drops tombstones whose version is at or below
+    /// `watermark` - everything a caller has already confirmed no other
+    /// replica still needs to see. Live entries are untouched regardless of
+    /// version.
+    pub fn compact(&mut self, watermark: u64) {
+        self.nodes.retain(|_, v| !(v.is_tombstone() && v.version <= watermark));
+        self.edges.retain(|_, v| !(v.is_tombstone() && v.version <= watermark));
+    }
 }
 
 impl GraphReadStore for InMemoryGraphStore {
     fn scan_all(&self) -> Result<Vec<Node>, EngineError> {
-        Ok(self.nodes.values().cloned().collect())
+        Ok(self.nodes.values().filter_map(Versioned::as_value).cloned().collect())
     }
 
     fn scan_by_label(&self, label: &str) -> Result<Vec<Node>, EngineError> {
         if let Some(node_ids) = self.label_index.get(label) {
             Ok(node_ids.iter()
-                .filter_map(|id| self.nodes.get(id).cloned())
+                .filter_map(|id| self.node_value(*id).cloned())
                 .collect())
         } else {
             Ok(Vec::new())
@@ -57,7 +379,7 @@ impl GraphReadStore for InMemoryGraphStore {
     }
 
     fn get_node(&self, id: NodeId) -> Result<Option<Node>, EngineError> {
-        Ok(self.nodes.get(&id).cloned())
+        Ok(self.node_value(id).cloned())
     }
 
     fn get_neighbors(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError> {
@@ -65,13 +387,13 @@ impl GraphReadStore for InMemoryGraphStore {
 
         if let Some(edge_ids) = self.adjacency_out.get(&node_id) {
             for edge_id in edge_ids {
-                if let Some(edge) = self.edges.get(edge_id) {
+                if let Some(edge) = self.edge_value(*edge_id) {
                     if let Some(et) = edge_type {
                         if edge.edge_type != et {
                             continue;
                         }
                     }
-                    if let Some(node) = self.nodes.get(&edge.to_node) {
+                    if let Some(node) = self.node_value(edge.to_node) {
                         result.push((edge.clone(), node.clone()));
                     }
                 }
@@ -86,13 +408,13 @@ impl GraphReadStore for InMemoryGraphStore {
 
         if let Some(edge_ids) = self.adjacency_in.get(&node_id) {
             for edge_id in edge_ids {
-                if let Some(edge) = self.edges.get(edge_id) {
+                if let Some(edge) = self.edge_value(*edge_id) {
                     if let Some(et) = edge_type {
                         if edge.edge_type != et {
                             continue;
                         }
                     }
-                    if let Some(node) = self.nodes.get(&edge.from_node) {
+                    if let Some(node) = self.node_value(edge.from_node) {
                         result.push((edge.clone(), node.clone()));
                     }
                 }
@@ -101,6 +423,48 @@ impl GraphReadStore for InMemoryGraphStore {
 
         Ok(result)
     }
+
+    /// Ids of nodes whose `prop` property falls within `(lo, hi)` under
+    /// `property_index`'s ordering, ascending. A property no node has ever
+    /// had returns an empty result rather than an error, matching
+    /// `scan_by_label` on an unused label.
+    fn scan_by_property_range(&self, prop: &str, lo: std::ops::Bound<Value>, hi: std::ops::Bound<Value>) -> Result<Vec<NodeId>, EngineError> {
+        let Some(by_value) = self.property_index.get(prop) else { return Ok(Vec::new()) };
+        Ok(by_value
+            .range((map_bound(lo), map_bound(hi)))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect())
+    }
+}
+
+impl InMemoryGraphStore {
+    /// Inserts a node with an already-assigned id, rebuilding the label
+    /// index and bumping `next_node_id` past it same as `deserialize_nodes`
+    /// does for a loaded segment. Used by [`crate::merge`] to assemble a
+    /// merged store out of nodes taken from either side of a three-way
+    /// merge, where ids must be preserved rather than reassigned.
+    pub(crate) fn insert_node(&mut self, node: Node) {
+        let id = node.id;
+        for label in &node.labels {
+            self.label_index.entry(label.clone()).or_insert_with(Vec::new).push(id);
+        }
+        self.index_node_properties(&node);
+        self.nodes.insert(id, Versioned::value(node));
+        if id >= self.next_node_id {
+            self.next_node_id = id + 1;
+        }
+    }
+
+    /// Edge counterpart of [`Self::insert_node`].
+    pub(crate) fn insert_edge(&mut self, edge: Edge) {
+        let id = edge.id;
+        self.adjacency_out.entry(edge.from_node).or_insert_with(Vec::new).push(id);
+        self.adjacency_in.entry(edge.to_node).or_insert_with(Vec::new).push(id);
+        self.edges.insert(id, Versioned::value(edge));
+        if id >= self.next_edge_id {
+            self.next_edge_id = id + 1;
+        }
+    }
 }
 
 impl GraphWriteStore for InMemoryGraphStore {
@@ -109,7 +473,8 @@ impl GraphWriteStore for InMemoryGraphStore {
         self.next_node_id += 1;
 
         let node = Node { id, labels: labels.clone(), properties };
-        self.nodes.insert(id, node);
+        self.index_node_properties(&node);
+        self.nodes.insert(id, Versioned::value(node));
 
         // Update label index
         for label in labels {
@@ -130,7 +495,7 @@ impl GraphWriteStore for InMemoryGraphStore {
             edge_type,
             properties,
         };
-        self.edges.insert(id, edge);
+        self.edges.insert(id, Versioned::value(edge));
 
         // Update adjacency indexes
         self.adjacency_out.entry(from).or_insert_with(Vec::new).push(id);
@@ -138,4 +503,12 @@ impl GraphWriteStore for InMemoryGraphStore {
 
         Ok(id)
     }
+
+    fn delete_node(&mut self, id: NodeId) -> Result<(), EngineError> {
+        self.delete_node_versioned(id, None)
+    }
+
+    fn delete_edge(&mut self, id: EdgeId) -> Result<(), EngineError> {
+        self.delete_edge_versioned(id, None)
+    }
 }
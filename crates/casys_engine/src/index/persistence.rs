@@ -3,12 +3,11 @@
 //! This module uses the SegmentStore trait from casys_core for hexagonal architecture.
 //! Storage adapters (FS, S3, etc.) implement SegmentStore and are injected by the caller.
 
-use super::{InMemoryGraphStore, Node, Edge, Value};
-use casys_core::{NodeId, EdgeId, SegmentId, SegmentStore};
+use super::{InMemoryGraphStore, Node, Edge, Value, Versioned, PropertyTarget};
+use super::codec::{self, SegmentEncoding};
+use casys_core::{NodeId, EdgeId, SegmentId, SegmentStore, ManifestStore, ManifestMeta, WalSource, WalTailMeta};
 use crate::exec::executor::ValueExt; // Import extension trait for to_json/from_json
-use crate::types::{EngineError, DatabaseName};
-#[cfg(feature = "fs")]
-use crate::types::BranchName;
+use crate::types::{EngineError, DatabaseName, BranchName, Timestamp};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -27,11 +26,42 @@ pub enum WalRecord {
         edge_type: String,
         properties: HashMap<String, Value>,
     },
+    /// `version` is the id's version *after* the delete, matching what
+    /// `InMemoryGraphStore::apply_node_delete` expects - replay compares it
+    /// against the stored version rather than unconditionally tombstoning,
+    /// so replaying the same segment twice is a no-op.
+    DeleteNode {
+        id: NodeId,
+        version: u64,
+    },
+    DeleteEdge {
+        id: EdgeId,
+        version: u64,
+    },
+    /// Sets (`value: Some`) or removes (`value: None`) property `key` on
+    /// `target`. `version` plays the same role as on `DeleteNode`/
+    /// `DeleteEdge`: it's the target's version *after* the set, so replay
+    /// via `apply_node_property_set`/`apply_edge_property_set` is a no-op
+    /// when it's already been applied.
+    SetProperties {
+        target: PropertyTarget,
+        key: String,
+        value: Option<Value>,
+        version: u64,
+    },
 }
 
 impl WalRecord {
-    /// Sérialise le record en bytes (format simple: type(1) + JSON)
+    /// Sérialise le record en bytes, JSON-encoded (see `to_bytes_with_encoding`
+    /// for the more compact MessagePack option).
     pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_encoding(SegmentEncoding::Json)
+    }
+
+    /// Like `to_bytes`, but lets the caller pick the wire encoding -
+    /// `from_bytes` auto-detects it either way, so a WAL (or caller) can
+    /// switch encodings without needing a format-version bump.
+    pub fn to_bytes_with_encoding(&self, encoding: SegmentEncoding) -> Vec<u8> {
         let json = match self {
             WalRecord::AddNode { id, labels, properties } => {
                 serde_json::json!({
@@ -51,14 +81,41 @@ impl WalRecord {
                     "properties": serialize_props(properties)
                 })
             }
+            WalRecord::DeleteNode { id, version } => {
+                serde_json::json!({
+                    "type": "delete_node",
+                    "id": id,
+                    "version": version
+                })
+            }
+            WalRecord::DeleteEdge { id, version } => {
+                serde_json::json!({
+                    "type": "delete_edge",
+                    "id": id,
+                    "version": version
+                })
+            }
+            WalRecord::SetProperties { target, key, value, version } => {
+                let (target_kind, target_id) = match target {
+                    PropertyTarget::Node(id) => ("node", *id),
+                    PropertyTarget::Edge(id) => ("edge", *id),
+                };
+                serde_json::json!({
+                    "type": "set_properties",
+                    "target_kind": target_kind,
+                    "target_id": target_id,
+                    "key": key,
+                    "value": value.as_ref().map(ValueExt::to_json),
+                    "version": version
+                })
+            }
         };
-        serde_json::to_vec(&json).unwrap_or_default()
+        codec::encode_segment(&json, encoding).unwrap_or_default()
     }
 
-    /// Désérialise depuis bytes
+    /// Désérialise depuis bytes, auto-detecting JSON vs MessagePack.
     pub fn from_bytes(data: &[u8]) -> Result<Self, EngineError> {
-        let json: serde_json::Value = serde_json::from_slice(data)
-            .map_err(|e| EngineError::StorageIo(format!("WAL record parse: {}", e)))?;
+        let json = codec::decode_segment(data)?;
 
         let rec_type = json["type"].as_str()
             .ok_or_else(|| EngineError::StorageIo("missing type".into()))?;
@@ -79,6 +136,30 @@ impl WalRecord {
                 let properties = deserialize_props(&json["properties"])?;
                 Ok(WalRecord::AddEdge { id, from_node, to_node, edge_type, properties })
             }
+            "delete_node" => {
+                let id = json["id"].as_u64().unwrap_or(0);
+                let version = json["version"].as_u64().unwrap_or(0);
+                Ok(WalRecord::DeleteNode { id, version })
+            }
+            "delete_edge" => {
+                let id = json["id"].as_u64().unwrap_or(0);
+                let version = json["version"].as_u64().unwrap_or(0);
+                Ok(WalRecord::DeleteEdge { id, version })
+            }
+            "set_properties" => {
+                let target_id = json["target_id"].as_u64().unwrap_or(0);
+                let target = match json["target_kind"].as_str() {
+                    Some("edge") => PropertyTarget::Edge(target_id),
+                    _ => PropertyTarget::Node(target_id),
+                };
+                let key = json["key"].as_str().unwrap_or("").to_string();
+                // `null` means "remove" here (see `to_bytes`), so it must
+                // map back to `None` rather than `Value::from_json`'s usual
+                // `Some(Value::Null)` for a JSON null.
+                let value = json.get("value").filter(|v| !v.is_null()).and_then(Value::from_json);
+                let version = json["version"].as_u64().unwrap_or(0);
+                Ok(WalRecord::SetProperties { target, key, value, version })
+            }
             _ => Err(EngineError::StorageIo(format!("unknown WAL record type: {}", rec_type))),
         }
     }
@@ -134,7 +215,7 @@ impl InMemoryGraphStore {
     ) -> Result<(), EngineError> {
         // Serialize and write nodes segment
         let nodes_data = self.serialize_nodes()?;
-        let node_count = self.nodes.len() as u64;
+        let node_count = self.live_node_count();
         store.write_segment(
             root,
             db,
@@ -146,7 +227,7 @@ impl InMemoryGraphStore {
 
         // Serialize and write edges segment
         let edges_data = self.serialize_edges()?;
-        let edge_count = self.edges.len() as u64;
+        let edge_count = self.live_edge_count();
         store.write_segment(
             root,
             db,
@@ -209,8 +290,86 @@ impl InMemoryGraphStore {
         Ok(graph)
     }
 
+    /// Flushes the current state as a snapshot via `segments`, then
+    /// publishes a `manifest` entry pointing at it with `wal_tail` set to
+    /// `tail` - the last WAL record this snapshot already reflects.
+    ///
+    /// Generalizes `checkpoint_fs` to the granular `SegmentStore`/
+    /// `ManifestStore` ports instead of the filesystem-specific manifest and
+    /// WAL modules, so any backend wired through `CompositeBackend` (or
+    /// `WalBackedGraphStore`) gets the same compaction story. The manifest
+    /// write is the only step that moves the "load from here" pointer, so a
+    /// crash between the segment writes and the manifest write just leaves
+    /// the prior manifest - and the full WAL behind it - untouched.
+    pub fn checkpoint(
+        &self,
+        segments: &dyn SegmentStore,
+        manifest: &dyn ManifestStore,
+        root: &Path,
+        db: &DatabaseName,
+        branch: &BranchName,
+        tail: Option<WalTailMeta>,
+    ) -> Result<Timestamp, EngineError> {
+        self.flush(segments, root, db)?;
+
+        let now_ms: Timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let meta = ManifestMeta {
+            branch: branch.as_str().to_string(),
+            version_ts: now_ms,
+            segments: vec![SegmentId(NODE_SEGMENT_ID.to_string()), SegmentId(EDGE_SEGMENT_ID.to_string())],
+            wal_tail: tail,
+        };
+        manifest.write_manifest_meta(root, db, branch, &meta)?;
+
+        Ok(now_ms)
+    }
+
+    /// Loads the newest snapshot `manifest` has for `branch` (or an empty
+    /// graph if none has ever been published), then replays only the WAL
+    /// segments `source` reports past that snapshot's `wal_tail` - instead
+    /// of `checkpoint_fs`'s file deletion, unconsumed segments are simply
+    /// skipped by sequence number, so this also tolerates a `WalSource`
+    /// that never throws segments away.
+    #[must_use = "load_checkpoint returns a new graph store that should be used"]
+    pub fn load_checkpoint(
+        segments: &dyn SegmentStore,
+        manifest: &dyn ManifestStore,
+        source: &dyn WalSource,
+        root: &Path,
+        db: &DatabaseName,
+        branch: &BranchName,
+    ) -> Result<Self, EngineError> {
+        let meta = manifest.latest_manifest_meta(root, db, branch)?;
+        let mut graph = if meta.is_some() {
+            Self::load(segments, root, db)?
+        } else {
+            Self::new()
+        };
+
+        let since = meta.and_then(|m| m.wal_tail);
+        for tail in source.list_wal_segments(root, db, branch)? {
+            if since.as_ref().is_some_and(|s| (tail.epoch, tail.seq) <= (s.epoch, s.seq)) {
+                continue;
+            }
+            let raw = source.read_wal_segment(root, db, branch, &tail)?;
+            let records = raw.iter().map(|bytes| WalRecord::from_bytes(bytes)).collect::<Result<Vec<_>, _>>()?;
+            graph.replay_wal(&records)?;
+        }
+
+        Ok(graph)
+    }
+
     fn serialize_nodes(&self) -> Result<Vec<u8>, EngineError> {
-        let nodes: Vec<_> = self.nodes.values().collect();
+        self.serialize_nodes_with(SegmentEncoding::Json)
+    }
+
+    /// Like `serialize_nodes`, but lets the caller pick the wire encoding -
+    /// `deserialize_nodes` auto-detects it either way.
+    pub(crate) fn serialize_nodes_with(&self, encoding: SegmentEncoding) -> Result<Vec<u8>, EngineError> {
+        let nodes: Vec<&Node> = self.nodes.values().filter_map(Versioned::as_value).collect();
         let json = serde_json::json!({
             "count": nodes.len(),
             "nodes": nodes.iter().map(|n| {
@@ -222,12 +381,17 @@ impl InMemoryGraphStore {
             }).collect::<Vec<_>>()
         });
 
-        serde_json::to_vec(&json)
-            .map_err(|e| EngineError::StorageIo(format!("serialize nodes: {}", e)))
+        codec::encode_segment(&json, encoding)
     }
 
     fn serialize_edges(&self) -> Result<Vec<u8>, EngineError> {
-        let edges: Vec<_> = self.edges.values().collect();
+        self.serialize_edges_with(SegmentEncoding::Json)
+    }
+
+    /// Like `serialize_edges`, but lets the caller pick the wire encoding -
+    /// `deserialize_edges` auto-detects it either way.
+    pub(crate) fn serialize_edges_with(&self, encoding: SegmentEncoding) -> Result<Vec<u8>, EngineError> {
+        let edges: Vec<&Edge> = self.edges.values().filter_map(Versioned::as_value).collect();
         let json = serde_json::json!({
             "count": edges.len(),
             "edges": edges.iter().map(|e| {
@@ -241,13 +405,11 @@ impl InMemoryGraphStore {
             }).collect::<Vec<_>>()
         });
 
-        serde_json::to_vec(&json)
-            .map_err(|e| EngineError::StorageIo(format!("serialize edges: {}", e)))
+        codec::encode_segment(&json, encoding)
     }
 
     fn deserialize_nodes(&mut self, data: &[u8]) -> Result<(), EngineError> {
-        let json: serde_json::Value = serde_json::from_slice(data)
-            .map_err(|e| EngineError::StorageIo(format!("parse nodes: {}", e)))?;
+        let json = codec::decode_segment(data)?;
 
         if let Some(nodes_array) = json["nodes"].as_array() {
             for node_json in nodes_array {
@@ -257,7 +419,8 @@ impl InMemoryGraphStore {
                 let properties = deserialize_props(&node_json["properties"])?;
 
                 let node = Node { id, labels: labels.clone(), properties };
-                self.nodes.insert(id, node);
+                self.index_node_properties(&node);
+                self.nodes.insert(id, Versioned::value(node));
 
                 // Rebuild label index
                 for label in labels {
@@ -275,8 +438,7 @@ impl InMemoryGraphStore {
     }
 
     fn deserialize_edges(&mut self, data: &[u8]) -> Result<(), EngineError> {
-        let json: serde_json::Value = serde_json::from_slice(data)
-            .map_err(|e| EngineError::StorageIo(format!("parse edges: {}", e)))?;
+        let json = codec::decode_segment(data)?;
 
         if let Some(edges_array) = json["edges"].as_array() {
             for edge_json in edges_array {
@@ -287,7 +449,7 @@ impl InMemoryGraphStore {
                 let properties = deserialize_props(&edge_json["properties"])?;
 
                 let edge = Edge { id, from_node, to_node, edge_type, properties };
-                self.edges.insert(id, edge);
+                self.edges.insert(id, Versioned::value(edge));
 
                 // Rebuild adjacency indexes
                 self.adjacency_out.entry(from_node).or_insert_with(Vec::new).push(id);
@@ -313,7 +475,8 @@ impl InMemoryGraphStore {
                         labels: labels.clone(),
                         properties: properties.clone(),
                     };
-                    self.nodes.insert(*id, node);
+                    self.index_node_properties(&node);
+                    self.nodes.insert(*id, Versioned::value(node));
 
                     // Update indexes
                     for label in labels {
@@ -332,7 +495,7 @@ impl InMemoryGraphStore {
                         edge_type: edge_type.clone(),
                         properties: properties.clone(),
                     };
-                    self.edges.insert(*id, edge);
+                    self.edges.insert(*id, Versioned::value(edge));
 
                     // Update adjacency
                     self.adjacency_out.entry(*from_node).or_insert_with(Vec::new).push(*id);
@@ -342,6 +505,18 @@ impl InMemoryGraphStore {
                         self.next_edge_id = id + 1;
                     }
                 }
+                WalRecord::DeleteNode { id, version } => {
+                    self.apply_node_delete(*id, *version)?;
+                }
+                WalRecord::DeleteEdge { id, version } => {
+                    self.apply_edge_delete(*id, *version)?;
+                }
+                WalRecord::SetProperties { target, key, value, version } => {
+                    match target {
+                        PropertyTarget::Node(id) => self.apply_node_property_set(*id, key, value.clone(), *version),
+                        PropertyTarget::Edge(id) => self.apply_edge_property_set(*id, key, value.clone(), *version),
+                    }
+                }
             }
         }
         Ok(())
@@ -366,44 +541,464 @@ impl InMemoryGraphStore {
 #[cfg(feature = "fs")]
 mod fs_convenience {
     use super::*;
-    use casys_storage_fs::catalog;
+    use casys_storage_fs::{manifest as mf, wal};
+    use crate::types::Timestamp;
+    use crate::index::compression::{self, Codec, CompressionOptions, SnapshotDetails};
+
+    /// WAL growth (in bytes, summed across a branch's WAL files) past which
+    /// `commit_tx` folds accumulated records back into segments instead of
+    /// letting the WAL grow forever. Mirrors the log-plus-periodic-checkpoint
+    /// pattern: a real deployment would tune this (or key it off record
+    /// count) per workload, but a fixed threshold is enough for the MVP.
+    pub const CHECKPOINT_WAL_BYTES: u64 = 16 * 1024 * 1024;
+
+    /// Operation count (one per `WalRecord` a checkpoint folds in) past
+    /// which a caller should prefer `checkpoint_fs_compacting` over waiting
+    /// on `CHECKPOINT_WAL_BYTES`. Op-count counterpart of that threshold,
+    /// for the Bayou-style "periodic checkpoint plus operation log" model:
+    /// a deployment that wants bounded replay time (rather than bounded WAL
+    /// bytes) tunes on this instead.
+    pub const KEEP_STATE_EVERY: u64 = 1000;
 
     impl InMemoryGraphStore {
-        /// Convenience method to flush directly to filesystem.
+        /// Serializes the graph and writes it as two content-addressed
+        /// segments (nodes, then edges - `load_segments_from_fs` relies on
+        /// this order rather than a manifest field, since `mf::SegmentRef`
+        /// has no room for one without breaking the on-disk formats other
+        /// branches already wrote), returning the `SegmentRef`s a manifest
+        /// should point at.
         ///
-        /// This is a helper that constructs the FsSegmentStore internally.
-        /// For more control, use `flush()` with a custom SegmentStore.
+        /// Segments are written under `root` itself (db-scoped, not
+        /// branch-scoped) and named after `segments::content_id`, so two
+        /// branches - or two checkpoints of the same branch with no net
+        /// change - that serialize to the same bytes end up naming the same
+        /// file; `segments::write_segment` skips the write when it's
+        /// already there. That's what makes `FsBackend::create_branch`'s
+        /// existing `segments: base.segments.clone()` copy-on-write at the
+        /// segment level for free, with no changes needed there: the clone
+        /// just copies hash references, never segment bytes.
+        fn write_content_addressed_segments(
+            &self,
+            root: &Path,
+            db: &DatabaseName,
+            compression: CompressionOptions,
+        ) -> Result<Vec<mf::SegmentRef>, EngineError> {
+            self.write_content_addressed_segments_with_encoding(root, db, SegmentEncoding::Json, compression)
+        }
+
+        /// Like `write_content_addressed_segments`, but lets the caller pick
+        /// the segment's own serialization encoding (JSON or MessagePack) in
+        /// addition to the block `compression` applied on top of it -
+        /// `load_segments_from_fs` auto-detects both, same as it already
+        /// does for `compression`.
+        fn write_content_addressed_segments_with_encoding(
+            &self,
+            root: &Path,
+            db: &DatabaseName,
+            encoding: SegmentEncoding,
+            compression: CompressionOptions,
+        ) -> Result<Vec<mf::SegmentRef>, EngineError> {
+            use casys_storage_fs::segments::content_id;
+
+            let store = FsSegmentStoreImpl;
+
+            let nodes_data = compression::compress(&self.serialize_nodes_with(encoding)?, compression.codec, compression.level)?;
+            let nodes_id = content_id(&nodes_data);
+            store.write_segment(root, db, &SegmentId(nodes_id.clone()), &nodes_data, self.live_node_count(), 0)?;
+
+            let edges_data = compression::compress(&self.serialize_edges_with(encoding)?, compression.codec, compression.level)?;
+            let edges_id = content_id(&edges_data);
+            store.write_segment(root, db, &SegmentId(edges_id.clone()), &edges_data, 0, self.live_edge_count())?;
+
+            Ok(vec![
+                mf::SegmentRef { id: nodes_id, range: None, chunks: Vec::new() },
+                mf::SegmentRef { id: edges_id, range: None, chunks: Vec::new() },
+            ])
+        }
+
+        /// Convenience method to flush directly to filesystem, publishing a
+        /// manifest version over the resulting content-addressed segments
+        /// (preserving the branch's current `wal_tail`, if any) so
+        /// `load_from_fs` has something to look the segments up by.
         ///
         /// # Arguments
         /// * `root` - Storage root path
         /// * `db` - Database name
-        /// * `branch` - Branch name (used to construct segments directory)
+        /// * `branch` - Branch name
+        /// * `compression` - Codec/level to compress the nodes/edges segments
+        ///   with; `CompressionOptions::default()` (`Codec::None`) writes
+        ///   them uncompressed, same as before this option existed. The
+        ///   codec is tagged onto the segment bytes themselves, so
+        ///   `load_from_fs` auto-detects it - callers never need to remember
+        ///   which codec a given snapshot used.
         pub fn flush_to_fs(
             &self,
             root: &Path,
             db: &DatabaseName,
             branch: &BranchName,
+            compression: CompressionOptions,
         ) -> Result<(), EngineError> {
-            let segments_root = catalog::branch_dir(root, db, branch);
-            let store = FsSegmentStoreImpl;
-            self.flush(&store, &segments_root, db)
+            let segments = self.write_content_addressed_segments(root, db, compression)?;
+            let prior_tail = mf::latest_manifest(root, db, branch, None)?.and_then(|m| m.wal_tail);
+
+            let now_ms: Timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            let manifest = mf::Manifest {
+                branch: branch.as_str().to_string(),
+                version_ts: now_ms,
+                segments,
+                wal_tail: prior_tail,
+            };
+            mf::write_manifest(root, db, branch, &manifest, None)?;
+            Ok(())
+        }
+
+        /// Like `flush_to_fs`, but also lets the caller pick the segments'
+        /// own serialization encoding rather than always writing JSON -
+        /// `SegmentEncoding::MessagePack` is the more compact option for
+        /// graphs with many nodes/edges. Decoding auto-detects it, so this
+        /// can be mixed freely with plain `flush_to_fs` across checkpoints
+        /// of the same branch.
+        pub fn flush_to_fs_with_encoding(
+            &self,
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+            encoding: SegmentEncoding,
+            compression: CompressionOptions,
+        ) -> Result<(), EngineError> {
+            let segments = self.write_content_addressed_segments_with_encoding(root, db, encoding, compression)?;
+            let prior_tail = mf::latest_manifest(root, db, branch, None)?.and_then(|m| m.wal_tail);
+
+            let now_ms: Timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            let manifest = mf::Manifest {
+                branch: branch.as_str().to_string(),
+                version_ts: now_ms,
+                segments,
+                wal_tail: prior_tail,
+            };
+            mf::write_manifest(root, db, branch, &manifest, None)?;
+            Ok(())
         }
 
         /// Convenience method to load from filesystem.
         ///
         /// This is a helper that constructs the FsSegmentStore internally.
         /// For more control, use `load()` with a custom SegmentStore.
+        ///
+        /// Segments only reflect state as of the branch's last checkpoint,
+        /// so this also replays whatever WAL the manifest's `wal_tail`
+        /// hasn't absorbed yet - callers see every committed record
+        /// immediately, not just after the next checkpoint fires.
         pub fn load_from_fs(
             root: &Path,
             db: &DatabaseName,
             branch: &BranchName,
         ) -> Result<Self, EngineError> {
-            let segments_root = catalog::branch_dir(root, db, branch);
+            let mut graph = Self::load_segments_from_fs(root, db, branch)?;
+            let tail = mf::latest_manifest(root, db, branch, None)?.and_then(|m| m.wal_tail);
+            let (records, _, _) = pending_wal_records(root, db, branch, tail.as_ref())?;
+            graph.replay_wal(&records)?;
+            Ok(graph)
+        }
+
+        /// `load_from_fs` without the residual-WAL replay, i.e. exactly the
+        /// state the last checkpoint (or initial flush) wrote to segments.
+        /// `checkpoint_fs` uses this as its base so it can replay the
+        /// records it's folding in exactly once.
+        ///
+        /// Reads the branch's current manifest rather than assuming fixed
+        /// segment ids, since `write_content_addressed_segments` names
+        /// segments after their content hash - the manifest's `segments[0]`
+        /// is always the nodes segment and `segments[1]` the edges one, the
+        /// same order `write_content_addressed_segments` emits them in. No
+        /// manifest yet (a branch that's never been flushed) is an empty
+        /// graph, same as before.
+        fn load_segments_from_fs(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<Self, EngineError> {
+            let mut graph = Self::new();
+            let Some(meta) = mf::latest_manifest(root, db, branch, None)? else { return Ok(graph) };
+            let store = FsSegmentStoreImpl;
+
+            if let Some(nodes_ref) = meta.segments.first() {
+                let (data, _, _) = store.read_segment(root, db, &SegmentId(nodes_ref.id.clone()))?;
+                graph.deserialize_nodes(&compression::decompress(&data)?)?;
+            }
+            if let Some(edges_ref) = meta.segments.get(1) {
+                let (data, _, _) = store.read_segment(root, db, &SegmentId(edges_ref.id.clone()))?;
+                graph.deserialize_edges(&compression::decompress(&data)?)?;
+            }
+
+            Ok(graph)
+        }
+
+        /// Folds every WAL record newer than the branch's current manifest
+        /// `wal_tail` back into fresh node/edge segments, publishes a new
+        /// manifest version pointing at them with `wal_tail` advanced to the
+        /// last record consumed, and deletes the now-superseded WAL files.
+        ///
+        /// Other manifest versions for this branch (reachable via
+        /// `pitr_manifest_meta`) are untouched and keep their own, earlier
+        /// `wal_tail` - only the WAL files a checkpoint actually folded in
+        /// are deleted, so a PITR read between two checkpoints still finds
+        /// its nearest prior segment set's residual tail on disk and
+        /// `load_from_fs`-style replay keeps working for it.
+        pub fn checkpoint_fs(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<Timestamp, EngineError> {
+            let since = mf::latest_manifest(root, db, branch, None)?.and_then(|m| m.wal_tail);
+            let (records, newest_tail, consumed) = pending_wal_records(root, db, branch, since.as_ref())?;
+
+            let mut graph = Self::load_segments_from_fs(root, db, branch)?;
+            graph.replay_wal(&records)?;
+            // Checkpointing happens automatically off the back of `commit_tx`
+            // rather than a caller-supplied option, so it keeps the segments
+            // uncompressed; `flush_to_fs` is the opt-in compressed path.
+            let segments = graph.write_content_addressed_segments(root, db, CompressionOptions::default())?;
+
+            let now_ms: Timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            let manifest = mf::Manifest {
+                branch: branch.as_str().to_string(),
+                version_ts: now_ms,
+                segments,
+                wal_tail: newest_tail,
+            };
+            mf::write_manifest(root, db, branch, &manifest, None)?;
+
+            for path in consumed {
+                wal::delete_segment(&path)?;
+            }
+
+            Ok(now_ms)
+        }
+
+        /// Whether `branch`'s accumulated WAL has grown past
+        /// `CHECKPOINT_WAL_BYTES`, i.e. whether `commit_tx` should follow up
+        /// with `checkpoint_fs` before returning.
+        pub fn needs_checkpoint_fs(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<bool, EngineError> {
+            Ok(wal::total_bytes(root, db, branch)? >= CHECKPOINT_WAL_BYTES)
+        }
+
+        /// Op-count counterpart of `needs_checkpoint_fs`: whether `branch`
+        /// has accumulated at least `KEEP_STATE_EVERY` WAL records since its
+        /// last checkpoint. Reuses `pending_wal_records` - the same records
+        /// `checkpoint_fs` would fold in - so "operation count" here means
+        /// exactly what gets replayed, not a proxy for it.
+        pub fn needs_checkpoint_fs_by_ops(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<bool, EngineError> {
+            let since = mf::latest_manifest(root, db, branch, None)?.and_then(|m| m.wal_tail);
+            let (records, _, _) = pending_wal_records(root, db, branch, since.as_ref())?;
+            Ok(records.len() as u64 >= KEEP_STATE_EVERY)
+        }
+
+        /// Like `checkpoint_fs`, but also GCs every checkpoint older than the
+        /// one it just wrote down to `keep_checkpoints` of the most recent
+        /// manifest versions, instead of leaving `branch`'s full checkpoint
+        /// history in place.
+        ///
+        /// `checkpoint_fs` deliberately keeps every past version around -
+        /// `load_oldest_snapshot_from_fs`/`merge_branch` and
+        /// `list_snapshot_details` read arbitrarily old ones for PITR and
+        /// three-way merge - so this is an opt-in alternative for a
+        /// deployment that doesn't need that history and wants bounded
+        /// on-disk checkpoint growth instead (the Bayou-style "checkpoint +
+        /// operation log" compaction model, as opposed to "checkpoint +
+        /// full history").
+        ///
+        /// The new checkpoint (segments, then manifest) is written and
+        /// durable, and the WAL files it folded in are deleted, before any
+        /// older manifest is touched - the same ordering `checkpoint_fs`
+        /// already uses - so a crash partway through the GC still leaves a
+        /// replayable checkpoint (this one, or an older surviving one) plus
+        /// every WAL record not yet folded into it. `keep_checkpoints`
+        /// should be at least 1 so that invariant always has something to
+        /// fall back to.
+        pub fn checkpoint_fs_compacting(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+            keep_checkpoints: usize,
+        ) -> Result<Timestamp, EngineError> {
+            let now_ms = Self::checkpoint_fs(root, db, branch)?;
+
+            // `list_manifest_paths` sorts by filename, which is
+            // `manifest-<version_ts>.json`/`.v2` - i.e. by `version_ts` - so
+            // the checkpoint `checkpoint_fs` just published is always last.
+            let mut paths = mf::list_manifest_paths(root, db, branch)?;
+            if paths.len() > keep_checkpoints {
+                for stale in paths.drain(..paths.len() - keep_checkpoints) {
+                    mf::delete_manifest(&stale)?;
+                }
+            }
+
+            Ok(now_ms)
+        }
+
+        /// Deletes every WAL file already subsumed by `branch`'s current
+        /// manifest `wal_tail`, without taking a fresh checkpoint first -
+        /// unlike `checkpoint_fs`, which always writes a new checkpoint
+        /// before GCing the WAL it just folded in, this is for a caller
+        /// that already knows its last checkpoint is current (e.g. right
+        /// after `checkpoint_fs` ran via some other path) and just wants to
+        /// reclaim WAL space on a schedule of its own. Returns the number
+        /// of files removed; `Ok(0)` if `branch` has never been checkpointed,
+        /// since nothing is subsumed yet.
+        pub fn compact_wal_fs(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<usize, EngineError> {
+            let Some(since) = mf::latest_manifest(root, db, branch, None)?.and_then(|m| m.wal_tail) else {
+                return Ok(0);
+            };
+            let mut removed = 0;
+            for path in wal::list_wal_paths(root, db, branch)? {
+                let Some(name) = path.file_name().and_then(|s| s.to_str()) else { continue };
+                let Some((epoch, seq)) = wal::parse_wal_filename(name) else { continue };
+                if (epoch, seq) <= (since.epoch, since.seq) {
+                    wal::delete_segment(&path)?;
+                    removed += 1;
+                }
+            }
+            Ok(removed)
+        }
+
+        /// Loads the graph exactly as of `branch`'s very first published
+        /// snapshot (i.e. the manifest `create_branch` wrote at fork time, a
+        /// byte-for-byte copy of the parent branch's segments then), rather
+        /// than the latest one `load_from_fs` reads. `merge_branch` uses this
+        /// as the common-ancestor state for a three-way merge: whichever of
+        /// the two branches being merged forked later has an oldest snapshot
+        /// that's exactly the other branch's state at that point.
+        ///
+        /// A branch that's never been flushed has no manifests at all, so
+        /// this returns an empty graph the same way `load_segments_from_fs`
+        /// does for that case.
+        pub fn load_oldest_snapshot_from_fs(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<Self, EngineError> {
+            let mut oldest: Option<mf::Manifest> = None;
+            for path in mf::list_manifest_paths(root, db, branch)? {
+                let manifest = mf::read_manifest(&path, None)?;
+                if oldest.as_ref().map_or(true, |m| manifest.version_ts < m.version_ts) {
+                    oldest = Some(manifest);
+                }
+            }
+            let Some(manifest) = oldest else { return Ok(Self::new()) };
+
+            let mut graph = Self::new();
+            let store = FsSegmentStoreImpl;
+            if let Some(nodes_ref) = manifest.segments.first() {
+                let (data, _, _) = store.read_segment(root, db, &SegmentId(nodes_ref.id.clone()))?;
+                graph.deserialize_nodes(&compression::decompress(&data)?)?;
+            }
+            if let Some(edges_ref) = manifest.segments.get(1) {
+                let (data, _, _) = store.read_segment(root, db, &SegmentId(edges_ref.id.clone()))?;
+                graph.deserialize_edges(&compression::decompress(&data)?)?;
+            }
+            Ok(graph)
+        }
+
+        /// Codec and on-disk size of every manifest version (snapshot) this
+        /// branch has ever published, oldest first. The codec is read back
+        /// off the nodes segment's leading tag byte rather than tracked
+        /// separately, so it's always exactly what `load_from_fs` would
+        /// detect for that version.
+        pub fn list_snapshot_details(
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<Vec<SnapshotDetails>, EngineError> {
             let store = FsSegmentStoreImpl;
-            Self::load(&store, &segments_root, db)
+            let mut details = Vec::new();
+
+            for path in mf::list_manifest_paths(root, db, branch)? {
+                let manifest = mf::read_manifest(&path, None)?;
+                let mut codec = Codec::None;
+                let mut size_bytes = 0u64;
+                for (i, seg_ref) in manifest.segments.iter().enumerate() {
+                    let (data, _, _) = store.read_segment(root, db, &SegmentId(seg_ref.id.clone()))?;
+                    size_bytes += data.len() as u64;
+                    if i == 0 {
+                        if let Some(tag) = data.first().copied() {
+                            codec = codec_from_tag(tag);
+                        }
+                    }
+                }
+                details.push(SnapshotDetails { timestamp: manifest.version_ts, codec: codec.as_str(), size_bytes });
+            }
+
+            details.sort_by_key(|d| d.timestamp);
+            Ok(details)
+        }
+    }
+
+    /// Best-effort tag decode for `list_snapshot_details`: an unrecognized
+    /// (or pre-compression, never-tagged) leading byte just reports as
+    /// `Codec::None` rather than failing the whole listing.
+    fn codec_from_tag(tag: u8) -> Codec {
+        match tag {
+            1 => Codec::Gzip,
+            2 => Codec::Zlib,
+            3 => Codec::Brotli,
+            4 => Codec::Zstd,
+            _ => Codec::None,
         }
     }
 
+    /// Every WAL record newer than `since` (a manifest's `wal_tail`, or
+    /// `None` for the branch's full history), the newest `(epoch, seq)`
+    /// among them, and the paths they came from - shared by `load_from_fs`
+    /// (which only replays) and `checkpoint_fs` (which also folds the
+    /// result into segments and deletes those paths).
+    fn pending_wal_records(
+        root: &Path,
+        db: &DatabaseName,
+        branch: &BranchName,
+        since: Option<&mf::WalTail>,
+    ) -> Result<(Vec<WalRecord>, Option<mf::WalTail>, Vec<std::path::PathBuf>), EngineError> {
+        let mut records = Vec::new();
+        let mut newest_tail = since.cloned();
+        let mut paths = Vec::new();
+        for path in wal::list_wal_paths(root, db, branch)? {
+            let Some(name) = path.file_name().and_then(|s| s.to_str()) else { continue };
+            let Some((epoch, seq)) = wal::parse_wal_filename(name) else { continue };
+            if let Some(tail) = since {
+                if (epoch, seq) <= (tail.epoch, tail.seq) { continue; }
+            }
+            for bytes in wal::read_records(&path)? {
+                records.push(WalRecord::from_bytes(&bytes)?);
+            }
+            if newest_tail.as_ref().map(|t| (epoch, seq) > (t.epoch, t.seq)).unwrap_or(true) {
+                newest_tail = Some(mf::WalTail { epoch, seq });
+            }
+            paths.push(path);
+        }
+        Ok((records, newest_tail, paths))
+    }
+
     /// Filesystem SegmentStore implementation
     struct FsSegmentStoreImpl;
 
@@ -419,7 +1014,7 @@ mod fs_convenience {
         ) -> Result<(), EngineError> {
             use casys_storage_fs::segments::{Segment, write_segment};
             let seg = Segment::new(node_count, edge_count, data.to_vec());
-            write_segment(root, db, &segment_id.0, &seg)?;
+            write_segment(root, db, &segment_id.0, &seg, None)?;
             Ok(())
         }
 
@@ -430,7 +1025,7 @@ mod fs_convenience {
             segment_id: &SegmentId,
         ) -> Result<(Vec<u8>, u64, u64), EngineError> {
             use casys_storage_fs::segments::read_segment;
-            let seg = read_segment(root, db, &segment_id.0)?;
+            let seg = read_segment(root, db, &segment_id.0, None)?;
             Ok((seg.data, seg.header.node_count, seg.header.edge_count))
         }
     }
@@ -438,3 +1033,49 @@ mod fs_convenience {
 
 // Note: fs_convenience module adds methods to InMemoryGraphStore via impl blocks.
 // No re-exports needed - methods are automatically available when the module is compiled.
+
+// =============================================================================
+// Optional S3/object-storage convenience functions (only when `s3` feature is
+// enabled)
+// =============================================================================
+//
+// Unlike `fs_convenience`, these don't need their own content-addressing or
+// WAL-folding logic: `casys_storage_s3::ObjectStoreBackend` already
+// implements `SegmentStore`, `ManifestStore` and `WalSource` directly, so
+// `flush_to_s3`/`load_from_s3` are thin wrappers around the already
+// storage-agnostic `checkpoint`/`load_checkpoint` above.
+#[cfg(feature = "s3")]
+mod s3_convenience {
+    use super::*;
+    use casys_storage_s3::ObjectStoreBackend;
+
+    impl InMemoryGraphStore {
+        /// Convenience method mirroring `flush_to_fs`, but against
+        /// `ObjectStoreBackend` instead of the filesystem. Preserves the
+        /// branch's current `wal_tail`, the same way `flush_to_fs` does, so
+        /// `load_from_s3` still knows where to resume WAL replay from.
+        pub fn flush_to_s3(
+            &self,
+            backend: &ObjectStoreBackend,
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<Timestamp, EngineError> {
+            let prior_tail = backend.latest_manifest_meta(root, db, branch)?.and_then(|m| m.wal_tail);
+            self.checkpoint(backend, backend, root, db, branch, prior_tail)
+        }
+
+        /// Convenience method mirroring `load_from_fs`: loads the latest
+        /// snapshot `backend`'s manifest has for `branch`, then replays
+        /// whatever WAL records that snapshot's `wal_tail` hasn't absorbed
+        /// yet.
+        pub fn load_from_s3(
+            backend: &ObjectStoreBackend,
+            root: &Path,
+            db: &DatabaseName,
+            branch: &BranchName,
+        ) -> Result<Self, EngineError> {
+            Self::load_checkpoint(backend, backend, backend, root, db, branch)
+        }
+    }
+}
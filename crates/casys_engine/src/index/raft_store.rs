@@ -0,0 +1,243 @@
+//! Raft storage adapter: the log store and state machine a Raft consensus
+//! library (e.g. `openraft`, following its storage-adapter traits) needs in
+//! order to replicate a branch's graph across nodes, built entirely from
+//! ports this crate already has. [`RaftLogStore`] persists each proposed
+//! [`WalRecord`] as a numbered entry through the existing `SegmentStore`
+//! port; [`RaftStateMachine`] wraps an `InMemoryGraphStore` and applies
+//! committed entries to it in order, exactly like `WalBackedGraphStore`
+//! applies WAL records, and builds/installs snapshots by reusing
+//! `InMemoryGraphStore::flush`/`load` - no second on-disk graph format.
+//!
+//! [`RaftBackend`] is the `StorageCatalog` this adapter offers as an
+//! alternative to `PostgresBackend`'s stubbed-out centralized catalog: one
+//! log and state machine per branch, with reads served from whatever each
+//! branch has locally applied. `propose` (for a new branch) and
+//! `create_branch` also persist a small branch registry segment, which
+//! `list_branches` reads back so a restarted process can still name its
+//! branches before replaying anything. This module is the storage
+//! layer a `openraft::Raft` instance would sit on top of; the consensus
+//! loop itself - leader election, AppendEntries/InstallSnapshot RPCs between
+//! nodes - is out of scope here, so `RaftBackend::propose` applies
+//! its log entry locally as a single-node stand-in for what a real cluster
+//! would replicate before committing.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use casys_core::{BranchName, DatabaseName, EngineError, SegmentId, SegmentStore, StorageCatalog, Timestamp};
+
+use super::persistence::WalRecord;
+use super::InMemoryGraphStore;
+
+/// One log entry: a Raft log index plus the `WalRecord` it carries. Mirrors
+/// `openraft::Entry`, minus the term/leader bookkeeping a real consensus
+/// loop tracks alongside the log.
+#[derive(Debug, Clone)]
+pub struct RaftLogEntry {
+    pub index: u64,
+    pub record: WalRecord,
+}
+
+/// A branch's Raft log, backed by the same `SegmentStore` port every other
+/// backend uses - each entry is its own segment, named
+/// `raft-log-<branch>-<index>` so `read_range` addresses entries directly by
+/// index instead of scanning.
+pub struct RaftLogStore {
+    segments: Arc<dyn SegmentStore>,
+    root: PathBuf,
+    db: DatabaseName,
+    branch: BranchName,
+    last_index: Mutex<u64>,
+}
+
+impl RaftLogStore {
+    pub fn new(segments: Arc<dyn SegmentStore>, root: PathBuf, db: DatabaseName, branch: BranchName) -> Self {
+        Self { segments, root, db, branch, last_index: Mutex::new(0) }
+    }
+
+    fn segment_id(&self, index: u64) -> SegmentId {
+        SegmentId(format!("raft-log-{}-{:020}", self.branch.as_str(), index))
+    }
+
+    /// Appends `record` as the next log entry, returning its assigned
+    /// index. Mirrors a Raft leader appending to its own log before
+    /// replicating it to followers; the replication itself is the
+    /// consensus transport's job, not this store's.
+    pub fn append(&self, record: WalRecord) -> Result<u64, EngineError> {
+        let mut last = self.last_index.lock().unwrap();
+        let index = *last + 1;
+        self.segments.write_segment(&self.root, &self.db, &self.segment_id(index), &record.to_bytes(), 0, 0)?;
+        *last = index;
+        Ok(index)
+    }
+
+    /// Reads every entry with index in `range`, in order - what a follower
+    /// applies to catch up, or what a caller replays after installing a
+    /// snapshot to get back to the leader's tail.
+    pub fn read_range(&self, range: std::ops::Range<u64>) -> Result<Vec<RaftLogEntry>, EngineError> {
+        let mut out = Vec::with_capacity(range.len());
+        for index in range {
+            let (bytes, _, _) = self.segments.read_segment(&self.root, &self.db, &self.segment_id(index))?;
+            out.push(RaftLogEntry { index, record: WalRecord::from_bytes(&bytes)? });
+        }
+        Ok(out)
+    }
+
+    pub fn last_index(&self) -> u64 {
+        *self.last_index.lock().unwrap()
+    }
+}
+
+/// A branch's Raft state machine: an `InMemoryGraphStore` plus the index of
+/// the last entry applied to it, so `apply` is idempotent against a
+/// replayed or re-delivered entry - the same "ignore anything not strictly
+/// newer" discipline `apply_node_delete`/`apply_edge_delete` already use
+/// for WAL replay.
+pub struct RaftStateMachine {
+    pub graph: InMemoryGraphStore,
+    pub applied_index: u64,
+}
+
+impl RaftStateMachine {
+    pub fn new() -> Self {
+        Self { graph: InMemoryGraphStore::new(), applied_index: 0 }
+    }
+
+    /// Applies one committed entry. A no-op if `entry.index` isn't past
+    /// `applied_index`, so re-delivering an already-applied entry (as a
+    /// follower catching up from an overlapping range might) is harmless.
+    pub fn apply(&mut self, entry: &RaftLogEntry) -> Result<(), EngineError> {
+        if entry.index <= self.applied_index {
+            return Ok(());
+        }
+        self.graph.replay_wal(std::slice::from_ref(&entry.record))?;
+        self.applied_index = entry.index;
+        Ok(())
+    }
+
+    /// Writes the current graph through `segments` via `flush` - the same
+    /// node/edge segment pair `checkpoint` publishes for a WAL-backed store
+    /// - and returns `applied_index`, the Raft analogue of a checkpoint's
+    /// WAL tail: the last log entry this snapshot already reflects.
+    pub fn build_snapshot(&self, segments: &dyn SegmentStore, root: &Path, db: &DatabaseName) -> Result<u64, EngineError> {
+        self.graph.flush(segments, root, db)?;
+        Ok(self.applied_index)
+    }
+
+    /// Installs a snapshot a leader sent (or this node built earlier) by
+    /// loading it through `segments` the same way `InMemoryGraphStore::load`
+    /// always has, then fast-forwards `applied_index` to `snapshot_index` so
+    /// the caller knows to replay only log entries after it to catch all
+    /// the way up.
+    pub fn install_snapshot(&mut self, segments: &dyn SegmentStore, root: &Path, db: &DatabaseName, snapshot_index: u64) -> Result<(), EngineError> {
+        self.graph = InMemoryGraphStore::load(segments, root, db)?;
+        self.applied_index = snapshot_index;
+        Ok(())
+    }
+}
+
+impl Default for RaftStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One branch's log and applied state, together.
+struct BranchRaft {
+    log: RaftLogStore,
+    machine: RaftStateMachine,
+}
+
+/// The segment `list_branches` rehydrates from on a fresh `RaftBackend` -
+/// the Raft logs and snapshots themselves persist fine across a restart,
+/// but nothing short of this told a new process which branch names exist
+/// before it had replayed anything.
+const BRANCH_REGISTRY_SEGMENT: &str = "raft-branches";
+
+/// `StorageCatalog` backed by a Raft log and state machine per branch,
+/// rather than `PostgresBackend`'s centralized (and still unimplemented)
+/// catalog table. `create_branch` forks the source branch's locally-applied
+/// graph into a fresh log/state-machine pair; `list_branches` unions
+/// whatever this node currently has registered in memory with the persisted
+/// `BRANCH_REGISTRY_SEGMENT` - there's no remote catalog round trip to
+/// make, by design.
+pub struct RaftBackend {
+    segments: Arc<dyn SegmentStore>,
+    branches: Mutex<HashMap<BranchName, BranchRaft>>,
+}
+
+impl RaftBackend {
+    pub fn new(segments: Arc<dyn SegmentStore>) -> Self {
+        Self { segments, branches: Mutex::new(HashMap::new()) }
+    }
+
+    /// Proposes `record` to `branch`'s log and applies it to the branch's
+    /// state machine, returning the entry's assigned index. A single-node
+    /// stand-in for "propose, replicate to a quorum, then apply" - see the
+    /// module doc comment for what a real multi-node deployment still needs
+    /// on top of this.
+    pub fn propose(&self, root: &Path, db: &DatabaseName, branch: &BranchName, record: WalRecord) -> Result<u64, EngineError> {
+        let mut branches = self.branches.lock().unwrap();
+        let is_new_branch = !branches.contains_key(branch);
+        let entry = branches.entry(branch.clone()).or_insert_with(|| BranchRaft {
+            log: RaftLogStore::new(self.segments.clone(), root.to_path_buf(), db.clone(), branch.clone()),
+            machine: RaftStateMachine::new(),
+        });
+        let index = entry.log.append(record.clone())?;
+        entry.machine.apply(&RaftLogEntry { index, record })?;
+        if is_new_branch {
+            self.write_branch_registry(root, db, branches.keys())?;
+        }
+        Ok(index)
+    }
+
+    /// Reads the persisted branch-name registry, or an empty list if none
+    /// has been written yet - a fresh `root`/`db` that's never proposed or
+    /// created a branch.
+    fn read_branch_registry(&self, root: &Path, db: &DatabaseName) -> Vec<BranchName> {
+        let Ok((bytes, _, _)) = self.segments.read_segment(root, db, &SegmentId(BRANCH_REGISTRY_SEGMENT.to_string())) else {
+            return Vec::new();
+        };
+        serde_json::from_slice::<Vec<String>>(&bytes)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|name| BranchName::try_from(name.as_str()).ok())
+            .collect()
+    }
+
+    /// Overwrites the branch registry with `names`, so a `RaftBackend`
+    /// created after a restart can rehydrate it in `list_branches` instead
+    /// of reporting zero branches despite their logs/snapshots persisting
+    /// fine on `SegmentStore`.
+    fn write_branch_registry<'a>(&self, root: &Path, db: &DatabaseName, names: impl Iterator<Item = &'a BranchName>) -> Result<(), EngineError> {
+        let names: Vec<&str> = names.map(BranchName::as_str).collect();
+        let bytes = serde_json::to_vec(&names)
+            .map_err(|e| EngineError::InvalidArgument(format!("serializing branch registry: {e}")))?;
+        self.segments.write_segment(root, db, &SegmentId(BRANCH_REGISTRY_SEGMENT.to_string()), &bytes, 0, 0)
+    }
+}
+
+impl StorageCatalog for RaftBackend {
+    fn list_branches(&self, root: &Path, db: &DatabaseName) -> Result<Vec<BranchName>, EngineError> {
+        let mut names: std::collections::HashSet<BranchName> =
+            self.branches.lock().unwrap().keys().cloned().collect();
+        names.extend(self.read_branch_registry(root, db));
+        let mut out: Vec<BranchName> = names.into_iter().collect();
+        out.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        Ok(out)
+    }
+
+    fn create_branch(&self, root: &Path, db: &DatabaseName, from: &BranchName, new_branch: &BranchName, _at: Option<Timestamp>) -> Result<(), EngineError> {
+        let mut branches = self.branches.lock().unwrap();
+        let graph = branches.get(from).map(|b| b.machine.graph.clone()).unwrap_or_else(InMemoryGraphStore::new);
+        branches.insert(
+            new_branch.clone(),
+            BranchRaft {
+                log: RaftLogStore::new(self.segments.clone(), root.to_path_buf(), db.clone(), new_branch.clone()),
+                machine: RaftStateMachine { graph, applied_index: 0 },
+            },
+        );
+        self.write_branch_registry(root, db, branches.keys())
+    }
+}
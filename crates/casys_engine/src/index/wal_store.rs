@@ -0,0 +1,161 @@
+//! Durable `GraphWriteStore` wrapper around `InMemoryGraphStore`: every
+//! mutation is appended to a `WalSink` as a structured [`WalRecord`] before
+//! it's applied in memory, and [`WalBackedGraphStore::recover`] rebuilds a
+//! fresh store by replaying a `WalSource`'s segments in order. This gives
+//! the in-memory MVP crash recovery without touching `InMemoryGraphStore`
+//! itself - reads and writes both go through the wrapped store, so callers
+//! that only need durability can swap `InMemoryGraphStore` for this type
+//! with no other changes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use casys_core::{BranchName, DatabaseName, EdgeId, EngineError, ManifestStore, NodeId, SegmentStore, WalSink, WalSource, WalTailMeta};
+use crate::types::Timestamp;
+
+use super::persistence::WalRecord;
+use super::{Edge, GraphReadStore, GraphWriteStore, InMemoryGraphStore, Node, PropertyTarget, Value};
+
+pub struct WalBackedGraphStore {
+    inner: InMemoryGraphStore,
+    sink: Arc<dyn WalSink>,
+    root: PathBuf,
+    db: DatabaseName,
+    branch: BranchName,
+    /// The most recent tail `sink` handed back, i.e. what a checkpoint
+    /// taken right now would subsume. `None` until the first mutation.
+    tail: Option<WalTailMeta>,
+}
+
+impl WalBackedGraphStore {
+    pub fn new(inner: InMemoryGraphStore, sink: Arc<dyn WalSink>, root: PathBuf, db: DatabaseName, branch: BranchName) -> Self {
+        Self { inner, sink, root, db, branch, tail: None }
+    }
+
+    /// Appends one record, returning its WAL tail only once the append has
+    /// actually succeeded - callers apply the mutation in memory after this
+    /// returns `Ok`, never before.
+    fn append(&mut self, record: &WalRecord) -> Result<WalTailMeta, EngineError> {
+        let tail = self.sink.append_records(&self.root, &self.db, &self.branch, &[record.to_bytes()])?;
+        self.tail = Some(tail.clone());
+        Ok(tail)
+    }
+
+    /// Flushes the current state as a snapshot through `segments` and
+    /// publishes a `manifest` entry recording the WAL tail it subsumes, so a
+    /// later `recover_from_checkpoint` can skip every record up to that
+    /// point instead of replaying this branch's full history. Returns
+    /// `Ok(None)` without writing anything if nothing has been appended
+    /// since this store was constructed - there's no tail to subsume yet.
+    pub fn checkpoint(&self, segments: &dyn SegmentStore, manifest: &dyn ManifestStore) -> Result<Option<Timestamp>, EngineError> {
+        let Some(tail) = self.tail.clone() else { return Ok(None) };
+        let ts = InMemoryGraphStore::checkpoint(&self.inner, segments, manifest, &self.root, &self.db, &self.branch, Some(tail))?;
+        Ok(Some(ts))
+    }
+
+    /// Counterpart to `checkpoint`: loads the newest published snapshot (or
+    /// an empty graph if none exists yet) and replays only the WAL records
+    /// `source` reports past that snapshot's tail, rather than `recover`'s
+    /// full-history replay.
+    pub fn recover_from_checkpoint(
+        segments: &dyn SegmentStore,
+        manifest: &dyn ManifestStore,
+        source: &dyn WalSource,
+        root: &Path,
+        db: &DatabaseName,
+        branch: &BranchName,
+    ) -> Result<InMemoryGraphStore, EngineError> {
+        InMemoryGraphStore::load_checkpoint(segments, manifest, source, root, db, branch)
+    }
+
+    /// Rebuilds a graph store from scratch by listing `source`'s WAL
+    /// segments for `db`/`branch` and replaying them, in order, into a
+    /// fresh `InMemoryGraphStore`. Each record's `AddNode`/`AddEdge`/
+    /// `DeleteNode`/`DeleteEdge` is applied exactly as `replay_wal` would
+    /// during normal recovery, rebuilding `label_index`, `adjacency_out`,
+    /// and `adjacency_in` as a side effect.
+    pub fn recover(source: &dyn WalSource, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<InMemoryGraphStore, EngineError> {
+        let mut graph = InMemoryGraphStore::new();
+        for tail in source.list_wal_segments(root, db, branch)? {
+            let raw = source.read_wal_segment(root, db, branch, &tail)?;
+            let records = raw.iter().map(|bytes| WalRecord::from_bytes(bytes)).collect::<Result<Vec<_>, _>>()?;
+            graph.replay_wal(&records)?;
+        }
+        Ok(graph)
+    }
+
+    /// Sets (or, if `value` is `None`, removes) property `key` on node `id`,
+    /// appending a `SetProperties` WAL record before applying it in memory -
+    /// same durability-first ordering as `add_node`/`delete_node`. Not part
+    /// of `GraphWriteStore`, which has no property-update method yet;
+    /// callers that need a durable property update use this directly.
+    pub fn set_node_property(&mut self, id: NodeId, key: &str, value: Option<Value>) -> Result<(), EngineError> {
+        let version = self.inner.node_version(id) + 1;
+        self.append(&WalRecord::SetProperties { target: PropertyTarget::Node(id), key: key.to_string(), value: value.clone(), version })?;
+        self.inner.apply_node_property_set(id, key, value, version);
+        Ok(())
+    }
+
+    /// Edge counterpart of [`Self::set_node_property`].
+    pub fn set_edge_property(&mut self, id: EdgeId, key: &str, value: Option<Value>) -> Result<(), EngineError> {
+        let version = self.inner.edge_version(id) + 1;
+        self.append(&WalRecord::SetProperties { target: PropertyTarget::Edge(id), key: key.to_string(), value: value.clone(), version })?;
+        self.inner.apply_edge_property_set(id, key, value, version);
+        Ok(())
+    }
+}
+
+impl GraphReadStore for WalBackedGraphStore {
+    fn scan_all(&self) -> Result<Vec<Node>, EngineError> {
+        self.inner.scan_all()
+    }
+
+    fn scan_by_label(&self, label: &str) -> Result<Vec<Node>, EngineError> {
+        self.inner.scan_by_label(label)
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<Option<Node>, EngineError> {
+        self.inner.get_node(id)
+    }
+
+    fn get_neighbors(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError> {
+        self.inner.get_neighbors(node_id, edge_type)
+    }
+
+    fn get_neighbors_incoming(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError> {
+        self.inner.get_neighbors_incoming(node_id, edge_type)
+    }
+
+    fn scan_by_property_range(&self, prop: &str, lo: std::ops::Bound<Value>, hi: std::ops::Bound<Value>) -> Result<Vec<NodeId>, EngineError> {
+        self.inner.scan_by_property_range(prop, lo, hi)
+    }
+}
+
+impl GraphWriteStore for WalBackedGraphStore {
+    fn add_node(&mut self, labels: Vec<String>, properties: HashMap<String, Value>) -> Result<NodeId, EngineError> {
+        let id = self.inner.next_node_id;
+        self.append(&WalRecord::AddNode { id, labels: labels.clone(), properties: properties.clone() })?;
+        self.inner.insert_node(Node { id, labels, properties });
+        Ok(id)
+    }
+
+    fn add_edge(&mut self, from: NodeId, to: NodeId, edge_type: String, properties: HashMap<String, Value>) -> Result<EdgeId, EngineError> {
+        let id = self.inner.next_edge_id;
+        self.append(&WalRecord::AddEdge { id, from_node: from, to_node: to, edge_type: edge_type.clone(), properties: properties.clone() })?;
+        self.inner.insert_edge(Edge { id, from_node: from, to_node: to, edge_type, properties });
+        Ok(id)
+    }
+
+    fn delete_node(&mut self, id: NodeId) -> Result<(), EngineError> {
+        let version = self.inner.node_version(id) + 1;
+        self.append(&WalRecord::DeleteNode { id, version })?;
+        self.inner.apply_node_delete(id, version)
+    }
+
+    fn delete_edge(&mut self, id: EdgeId) -> Result<(), EngineError> {
+        let version = self.inner.edge_version(id) + 1;
+        self.append(&WalRecord::DeleteEdge { id, version })?;
+        self.inner.apply_edge_delete(id, version)
+    }
+}
@@ -0,0 +1,234 @@
+//! Change-notification wrapper around [`InMemoryGraphStore`]: wraps a store
+//! the same way [`super::wal_store::WalBackedGraphStore`] wraps one for
+//! durability, except here every `add_node`/`add_edge`/`delete_node`/
+//! `delete_edge` broadcasts a [`GraphChange`] - the mutated id, its new
+//! version (reusing the version counter `InMemoryGraphStore` already keeps
+//! for lost-update detection, see `node_version`/`edge_version`), and
+//! whether it was a create or a delete - after the mutation has already
+//! landed in `inner`. A watcher that reacts to the event by calling
+//! `get_node`/`get_neighbors` therefore always sees post-mutation state.
+//!
+//! This store only ever creates or tombstones an id: it exposes no
+//! property update (`GraphWriteStore` has none; `WalRecord::SetProperties`
+//! is written only by `WalBackedGraphStore::set_node_property`/
+//! `set_edge_property`), so [`ChangeKind`] has no `Updated` variant and
+//! property changes are not broadcast.
+//!
+//! [`watch_node`](WatchableGraphStore::watch_node) and
+//! [`watch_label`](WatchableGraphStore::watch_label) are both implemented
+//! as a filter over one shared `tokio::sync::broadcast` channel rather than
+//! a `Notify` (or channel) allocated per watched key - simplest thing that
+//! works for the expected number of concurrent watchers, at the cost of a
+//! clone-and-discard per irrelevant change for each subscriber; move to
+//! per-key fan-out if that ever shows up in a profile. A slow watcher that
+//! falls behind the channel's capacity misses the events it lagged on
+//! (`broadcast::error::RecvError::Lagged`) rather than blocking writers or
+//! buffering unboundedly.
+//!
+//! This crate doesn't otherwise depend on `futures`/`tokio-stream`, so
+//! [`ChangeStream`] is a thin pull-based wrapper (`.recv().await`) instead
+//! of a real `futures_core::Stream` impl - the same shape a `Stream::next`
+//! would produce, just driven by an explicit `.await` rather than an
+//! executor calling `poll_next`.
+
+use std::collections::HashMap;
+
+use tokio::sync::broadcast;
+
+use casys_core::{EdgeId, EngineError, GraphReadStore, GraphWriteStore, NodeId, Value};
+
+use super::{Edge, InMemoryGraphStore, Node};
+
+/// Default capacity of the shared broadcast channel: how many unconsumed
+/// changes a lagging watcher can fall behind by before it starts missing
+/// events. Plenty for a handful of watchers reacting promptly; bump it (or
+/// make it configurable) if that assumption stops holding.
+const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Deleted,
+}
+
+/// One change to a node, as delivered by [`WatchableGraphStore::watch_node`]
+/// and [`WatchableGraphStore::watch_label`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeChange {
+    pub id: NodeId,
+    pub labels: Vec<String>,
+    pub kind: ChangeKind,
+    pub version: u64,
+}
+
+/// Edge counterpart of [`NodeChange`], delivered only via
+/// [`WatchableGraphStore::watch_all`] - there's no `watch_edge`/
+/// `watch_edge_type` yet, since nothing in the backlog has asked for one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeChange {
+    pub id: EdgeId,
+    pub from_node: NodeId,
+    pub to_node: NodeId,
+    pub edge_type: String,
+    pub kind: ChangeKind,
+    pub version: u64,
+}
+
+/// Whole-store change feed item: either a [`NodeChange`] or an
+/// [`EdgeChange`], in mutation order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphChange {
+    Node(NodeChange),
+    Edge(EdgeChange),
+}
+
+/// Pull-based subscription handle returned by `watch_node`/`watch_label`/
+/// `watch_all`. See the module docs for why this isn't a real `Stream`.
+pub struct ChangeStream<T> {
+    receiver: broadcast::Receiver<GraphChange>,
+    filter_map: Box<dyn FnMut(GraphChange) -> Option<T> + Send>,
+}
+
+impl<T> ChangeStream<T> {
+    fn new(receiver: broadcast::Receiver<GraphChange>, filter_map: impl FnMut(GraphChange) -> Option<T> + Send + 'static) -> Self {
+        Self { receiver, filter_map: Box::new(filter_map) }
+    }
+
+    /// Waits for the next change this stream cares about, silently skipping
+    /// changes its filter rejects and resuming past any `Lagged` gap.
+    /// Returns `None` once the owning [`WatchableGraphStore`] (and every
+    /// clone of its sender) has been dropped - there's nothing left to wait
+    /// for.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(change) => {
+                    if let Some(out) = (self.filter_map)(change) {
+                        return Some(out);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// `GraphWriteStore` wrapper that broadcasts a [`GraphChange`] after every
+/// mutation, so indexers/cache-invalidators/live query subscribers can
+/// react to graph edits instead of polling `scan_all`.
+pub struct WatchableGraphStore {
+    inner: InMemoryGraphStore,
+    changes: broadcast::Sender<GraphChange>,
+}
+
+impl WatchableGraphStore {
+    pub fn new(inner: InMemoryGraphStore) -> Self {
+        let (changes, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { inner, changes }
+    }
+
+    pub fn inner(&self) -> &InMemoryGraphStore {
+        &self.inner
+    }
+
+    /// `send` only errors when there are zero receivers, which isn't a
+    /// failure here - a mutation with nobody watching is still a success.
+    fn publish(&self, change: GraphChange) {
+        let _ = self.changes.send(change);
+    }
+
+    /// Awaits the next create/delete affecting `id`.
+    pub fn watch_node(&self, id: NodeId) -> ChangeStream<NodeChange> {
+        ChangeStream::new(self.changes.subscribe(), move |change| match change {
+            GraphChange::Node(n) if n.id == id => Some(n),
+            _ => None,
+        })
+    }
+
+    /// Awaits the next create/delete of a node carrying `label`. A node
+    /// being deleted still carries the labels it had before the delete (see
+    /// `delete_node`/`delete_edge` below), so a watcher sees the delete even
+    /// though `scan_by_label` would no longer return the node.
+    pub fn watch_label(&self, label: &str) -> ChangeStream<NodeChange> {
+        let label = label.to_string();
+        ChangeStream::new(self.changes.subscribe(), move |change| match change {
+            GraphChange::Node(n) if n.labels.iter().any(|l| *l == label) => Some(n),
+            _ => None,
+        })
+    }
+
+    /// Awaits the next change to any node or edge in the store.
+    pub fn watch_all(&self) -> ChangeStream<GraphChange> {
+        ChangeStream::new(self.changes.subscribe(), Some)
+    }
+}
+
+impl GraphReadStore for WatchableGraphStore {
+    fn scan_all(&self) -> Result<Vec<Node>, EngineError> {
+        self.inner.scan_all()
+    }
+
+    fn scan_by_label(&self, label: &str) -> Result<Vec<Node>, EngineError> {
+        self.inner.scan_by_label(label)
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<Option<Node>, EngineError> {
+        self.inner.get_node(id)
+    }
+
+    fn get_neighbors(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError> {
+        self.inner.get_neighbors(node_id, edge_type)
+    }
+
+    fn get_neighbors_incoming(&self, node_id: NodeId, edge_type: Option<&str>) -> Result<Vec<(Edge, Node)>, EngineError> {
+        self.inner.get_neighbors_incoming(node_id, edge_type)
+    }
+
+    fn scan_by_property_range(&self, prop: &str, lo: std::ops::Bound<Value>, hi: std::ops::Bound<Value>) -> Result<Vec<NodeId>, EngineError> {
+        self.inner.scan_by_property_range(prop, lo, hi)
+    }
+}
+
+impl GraphWriteStore for WatchableGraphStore {
+    fn add_node(&mut self, labels: Vec<String>, properties: HashMap<String, Value>) -> Result<NodeId, EngineError> {
+        let id = self.inner.add_node(labels.clone(), properties)?;
+        let version = self.inner.node_version(id);
+        self.publish(GraphChange::Node(NodeChange { id, labels, kind: ChangeKind::Created, version }));
+        Ok(id)
+    }
+
+    fn add_edge(&mut self, from: NodeId, to: NodeId, edge_type: String, properties: HashMap<String, Value>) -> Result<EdgeId, EngineError> {
+        let id = self.inner.add_edge(from, to, edge_type.clone(), properties)?;
+        let version = self.inner.edge_version(id);
+        self.publish(GraphChange::Edge(EdgeChange {
+            id,
+            from_node: from,
+            to_node: to,
+            edge_type,
+            kind: ChangeKind::Created,
+            version,
+        }));
+        Ok(id)
+    }
+
+    fn delete_node(&mut self, id: NodeId) -> Result<(), EngineError> {
+        let labels = self.inner.node_value(id).map(|n| n.labels.clone()).unwrap_or_default();
+        self.inner.delete_node(id)?;
+        let version = self.inner.node_version(id);
+        self.publish(GraphChange::Node(NodeChange { id, labels, kind: ChangeKind::Deleted, version }));
+        Ok(())
+    }
+
+    fn delete_edge(&mut self, id: EdgeId) -> Result<(), EngineError> {
+        let edge = self.inner.edge_value(id).cloned();
+        self.inner.delete_edge(id)?;
+        let version = self.inner.edge_version(id);
+        let (from_node, to_node, edge_type) = match edge {
+            Some(e) => (e.from_node, e.to_node, e.edge_type),
+            None => (0, 0, String::new()),
+        };
+        self.publish(GraphChange::Edge(EdgeChange { id, from_node, to_node, edge_type, kind: ChangeKind::Deleted, version }));
+        Ok(())
+    }
+}
@@ -8,6 +8,13 @@ pub mod exec;
 pub mod txn;
 pub mod gds;
 pub mod ann;
+pub mod capabilities;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(all(feature = "fs", feature = "async"))]
+pub mod async_engine;
 
 // Optional higher-level facades (placeholders kept for future API surface)
 pub mod branch;
@@ -35,13 +42,32 @@ pub use types::{
 // Re-export casys_core::Value as the canonical Value type
 pub use casys_core::Value;
 
+pub use casys_core::Capabilities;
+
+#[cfg(all(feature = "fs", feature = "async"))]
+pub use async_engine::AsyncEngine;
+
 /// Engine is the embedded entrypoint. It owns the data directory and shared resources.
 pub struct Engine {
     data_dir: PathBuf,
     /// Writer locks per (db, branch) to enforce SW-MR
     writer_locks: Mutex<HashMap<(String, String), Arc<Mutex<()>>>>,
+    /// This data directory's negotiated format version/feature set; see
+    /// `capabilities::negotiate`.
+    capabilities: Capabilities,
     #[cfg(feature = "fs")]
     backend: Arc<dyn StorageBackend>,
+    #[cfg(feature = "metrics")]
+    metrics: metrics::MetricsRegistry,
+    /// Host-registered scalar functions, keyed by upper-cased name; consulted
+    /// by `execute_gql_on_store` so embedders (napi/pyo3) can expose callback
+    /// functions to GQL without the engine knowing anything about JS/Python.
+    external_functions: Mutex<HashMap<String, Arc<dyn exec::functions::ExternalFunctionInvoker>>>,
+    /// Declared node/edge types from `DEFINE NODE`/`DEFINE EDGE`, consulted
+    /// by `execute_gql_on_store`'s `CREATE` path. Like `external_functions`,
+    /// this is process-wide rather than per-database/branch - acceptable for
+    /// the same reason: there's one `Engine` per embedding process today.
+    schema_registry: Mutex<exec::schema::SchemaRegistry>,
 }
 
 /// Opaque handle to a database
@@ -69,13 +95,19 @@ impl Engine {
         let dir = data_dir.as_ref();
         std::fs::create_dir_all(dir)
             .map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", dir.display())))?;
+        let capabilities = capabilities::negotiate(dir, &[])?;
         #[cfg(feature = "fs")]
         let backend = Arc::new(casys_storage_fs::backend::FsBackend::new());
         Ok(Engine {
             data_dir: dir.to_path_buf(),
             writer_locks: Mutex::new(HashMap::new()),
+            capabilities,
             #[cfg(feature = "fs")]
             backend,
+            #[cfg(feature = "metrics")]
+            metrics: metrics::MetricsRegistry::default(),
+            external_functions: Mutex::new(HashMap::new()),
+            schema_registry: Mutex::new(exec::schema::SchemaRegistry::new()),
         })
     }
 
@@ -85,10 +117,78 @@ impl Engine {
         let dir = data_dir.as_ref();
         std::fs::create_dir_all(dir)
             .map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", dir.display())))?;
+        let capabilities = capabilities::negotiate(dir, &[])?;
         Ok(Engine {
             data_dir: dir.to_path_buf(),
             writer_locks: Mutex::new(HashMap::new()),
+            capabilities,
             backend,
+            #[cfg(feature = "metrics")]
+            metrics: metrics::MetricsRegistry::default(),
+            external_functions: Mutex::new(HashMap::new()),
+            schema_registry: Mutex::new(exec::schema::SchemaRegistry::new()),
+        })
+    }
+
+    /// Open (or create) an engine using the given data directory, encrypting
+    /// manifests/segments/chunks at rest per `config`. `EncryptionConfig::None`
+    /// behaves exactly like `open`.
+    #[cfg(feature = "fs")]
+    pub fn open_with_encryption<P: AsRef<Path>>(data_dir: P, config: &casys_core::EncryptionConfig) -> Result<Self, EngineError> {
+        let dir = data_dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", dir.display())))?;
+        let features: &[&str] = match config {
+            casys_core::EncryptionConfig::None => &[],
+            _ => &["encryption"],
+        };
+        let capabilities = capabilities::negotiate(dir, features)?;
+        let backend = Arc::new(casys_storage_fs::backend::FsBackend::with_encryption(dir, config)?);
+        let mut engine = Self::open_with_backend(data_dir, backend)?;
+        engine.capabilities = capabilities;
+        Ok(engine)
+    }
+
+    /// This data directory's negotiated format version/feature set, so
+    /// FFI/SDK callers can branch on what the store actually supports
+    /// (e.g. whether `encryption` or `binary-manifests` is enabled).
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Snapshots commit/snapshot/PITR-lookup counters and the process-wide
+    /// `atomic_write_file` byte count, pairing each branch's counters with
+    /// its current live-manifest/live-segment counts read fresh from disk.
+    /// Render with [`metrics::to_prometheus_text`] for a scrape endpoint, or
+    /// serialize directly.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_snapshot(&self) -> metrics::MetricsSnapshot {
+        self.metrics.snapshot(|db, branch| {
+            #[cfg(feature = "fs")]
+            {
+                let db_name = match DatabaseName::try_from(db) {
+                    Ok(d) => d,
+                    Err(_) => return (0, 0),
+                };
+                let br_name = match BranchName::try_from(branch) {
+                    Ok(b) => b,
+                    Err(_) => return (0, 0),
+                };
+                let live_manifests = casys_storage_fs::manifest::list_manifest_paths(&self.data_dir, &db_name, &br_name)
+                    .map(|v| v.len() as u64)
+                    .unwrap_or(0);
+                let live_segments = casys_storage_fs::manifest::latest_manifest_handle(&self.data_dir, &db_name, &br_name, None)
+                    .ok()
+                    .flatten()
+                    .map(|h| h.segment_count() as u64)
+                    .unwrap_or(0);
+                (live_manifests, live_segments)
+            }
+            #[cfg(not(feature = "fs"))]
+            {
+                let _ = (db, branch);
+                (0, 0)
+            }
         })
     }
 
@@ -107,6 +207,41 @@ impl Engine {
         Self::open_with_backend(data_dir, Arc::new(composite))
     }
 
+    /// Open an engine using a `CompositeBackend` composed from the S3/
+    /// object-storage adapter (`casys_storage_s3::ObjectStoreBackend`), the
+    /// same way `open_fs_composite` composes one from `FsBackend` - swap one
+    /// `Arc` clone for the other and every granular port still lines up.
+    /// `store` is the `ObjectStore` to talk to (a real `S3Client` behind the
+    /// `aws-sdk` feature on `casys_storage_s3`, or `InMemoryObjectStore` for
+    /// tests).
+    #[cfg(all(feature = "fs", feature = "s3"))]
+    pub fn open_s3_composite<P: AsRef<Path>>(
+        data_dir: P,
+        store: Arc<dyn casys_storage_s3::ObjectStore>,
+    ) -> Result<Self, EngineError> {
+        use casys_core::CompositeBackend;
+        let s3b = Arc::new(casys_storage_s3::ObjectStoreBackend::new(store));
+        let catalog: Arc<dyn casys_core::StorageCatalog> = s3b.clone();
+        let manifest: Arc<dyn casys_core::ManifestStore> = s3b.clone();
+        let segments: Arc<dyn casys_core::SegmentStore> = s3b.clone();
+        let wal_sink: Option<Arc<dyn casys_core::WalSink>> = Some(s3b.clone());
+        let wal_source: Option<Arc<dyn casys_core::WalSource>> = Some(s3b.clone());
+        let composite = CompositeBackend::new(catalog, manifest, segments, wal_sink, wal_source);
+        Self::open_with_backend(data_dir, Arc::new(composite))
+    }
+
+    /// Registers a host-provided scalar function under `name` (matched
+    /// case-insensitively, same as the parser's built-in function lookup),
+    /// so subsequent `execute_gql_on_store`/`execute_gql_batch_on_store`
+    /// calls can invoke it from a GQL expression. Registering the same name
+    /// twice replaces the earlier invoker.
+    pub fn register_external_function(&self, name: &str, invoker: Arc<dyn exec::functions::ExternalFunctionInvoker>) {
+        self.external_functions
+            .lock()
+            .expect("external_functions poisoned")
+            .insert(name.to_uppercase(), invoker);
+    }
+
     /// Open a logical database by name (created lazily upon first write).
     pub fn open_database(&self, name: &str) -> Result<DbHandle, EngineError> {
         let db = DatabaseName::try_from(name)?;
@@ -151,6 +286,10 @@ impl Engine {
     ) -> Result<(), EngineError> {
         let from_br = BranchName::try_from(from)?;
         let new_br = BranchName::try_from(new_branch)?;
+        #[cfg(feature = "metrics")]
+        if at.is_some() {
+            self.metrics.record_pitr_lookup(db.name.as_str(), from_br.as_str());
+        }
         self.backend.create_branch(self.data_dir(), &db.name, &from_br, &new_br, at)
     }
 
@@ -180,7 +319,12 @@ impl Engine {
     /// Create a snapshot on a branch and return its timestamp.
     #[cfg(feature = "fs")]
     pub fn snapshot(&self, branch: &BranchHandle, _label: Option<&str>) -> Result<Timestamp, EngineError> {
-        self.backend.snapshot(self.data_dir(), &branch.db, &branch.name)
+        let result = self.backend.snapshot(self.data_dir(), &branch.db, &branch.name);
+        #[cfg(feature = "metrics")]
+        if result.is_ok() {
+            self.metrics.record_snapshot(branch.db.as_str(), branch.name.as_str());
+        }
+        result
     }
 
     #[cfg(not(feature = "fs"))]
@@ -189,12 +333,30 @@ impl Engine {
     }
 
     /// Commit a set of WAL records then publish a new manifest (snapshot). Returns the manifest timestamp.
+    ///
+    /// Once the commit itself is durable, also folds the branch's WAL back
+    /// into segments via `checkpoint_branch` if it's grown past
+    /// `index::persistence::fs_convenience::CHECKPOINT_WAL_BYTES` - this is best-effort:
+    /// a checkpoint failure doesn't fail the (already-durable) commit, it
+    /// just leaves the WAL to keep growing until the next attempt.
     #[cfg(feature = "fs")]
     pub fn commit_tx(&self, branch: &BranchHandle, records: &[Vec<u8>]) -> Result<Timestamp, EngineError> {
         // Acquire writer lock for SW-MR
         let lock = self.branch_writer_lock(&branch.db, &branch.name);
         let _guard = lock.lock().expect("writer lock poisoned");
-        self.backend.commit_tx(self.data_dir(), &branch.db, &branch.name, records)
+        let commit = || self.backend.commit_tx(self.data_dir(), &branch.db, &branch.name, records);
+        let result = {
+            #[cfg(feature = "metrics")]
+            { self.metrics.time_commit(branch.db.as_str(), branch.name.as_str(), records.len(), commit) }
+            #[cfg(not(feature = "metrics"))]
+            { commit() }
+        };
+        if result.is_ok() {
+            if let Ok(true) = crate::index::InMemoryGraphStore::needs_checkpoint_fs(self.data_dir(), &branch.db, &branch.name) {
+                let _ = crate::index::InMemoryGraphStore::checkpoint_fs(self.data_dir(), &branch.db, &branch.name);
+            }
+        }
+        result
     }
 
     #[cfg(not(feature = "fs"))]
@@ -202,9 +364,95 @@ impl Engine {
         Err(EngineError::NotImplemented("commit_tx requires fs feature".into()))
     }
 
-    /// Merge one branch into another.
-    pub fn merge_branch(&self, _db: &DbHandle, _src: &str, _dst: &str) -> Result<(), EngineError> {
-        Err(EngineError::NotImplemented("merge_branch".into()))
+    /// Explicitly fold `branch`'s accumulated WAL back into segments,
+    /// rather than waiting for `commit_tx`'s automatic threshold. Returns
+    /// the new manifest's timestamp.
+    #[cfg(feature = "fs")]
+    pub fn checkpoint_branch(&self, branch: &BranchHandle) -> Result<Timestamp, EngineError> {
+        let lock = self.branch_writer_lock(&branch.db, &branch.name);
+        let _guard = lock.lock().expect("writer lock poisoned");
+        crate::index::InMemoryGraphStore::checkpoint_fs(self.data_dir(), &branch.db, &branch.name)
+    }
+
+    #[cfg(not(feature = "fs"))]
+    pub fn checkpoint_branch(&self, _branch: &BranchHandle) -> Result<Timestamp, EngineError> {
+        Err(EngineError::NotImplemented("checkpoint_branch requires fs feature".into()))
+    }
+
+    /// Three-way merge `source` into `target`, resolving anything both
+    /// branches changed since they last shared state via `strategy`. The
+    /// common ancestor is reconstructed from whichever branch's *oldest*
+    /// snapshot is more recent - `create_branch` always forks by copying the
+    /// parent's current manifest, so that oldest snapshot is exactly the
+    /// other branch's state at the fork point. Flushes the merge result onto
+    /// `target` unless `FailOnConflict` found a conflict, in which case
+    /// `target` is left untouched and the conflicting ids are reported.
+    #[cfg(feature = "fs")]
+    pub fn merge_branch(
+        &self,
+        db: &DbHandle,
+        source: &BranchHandle,
+        target: &BranchHandle,
+        strategy: merge::MergeStrategy,
+    ) -> Result<merge::MergeSummary, EngineError> {
+        let lock = self.branch_writer_lock(&target.db, &target.name);
+        let _guard = lock.lock().expect("writer lock poisoned");
+
+        let source_store = self.load_branch(db, source)?;
+        let target_store = self.load_branch(db, target)?;
+
+        let source_oldest = crate::index::InMemoryGraphStore::load_oldest_snapshot_from_fs(
+            self.data_dir(), &db.name, &source.name,
+        )?;
+        let target_oldest = crate::index::InMemoryGraphStore::load_oldest_snapshot_from_fs(
+            self.data_dir(), &db.name, &target.name,
+        )?;
+        let source_oldest_ts = self.list_snapshot_timestamps(db, source)?.into_iter().min();
+        let target_oldest_ts = self.list_snapshot_timestamps(db, target)?.into_iter().min();
+        // Whichever branch forked later has the more recent "oldest" snapshot,
+        // and that snapshot is the other branch's state at the fork point.
+        let base = match (source_oldest_ts, target_oldest_ts) {
+            (Some(s), Some(t)) if s >= t => source_oldest,
+            (Some(_), Some(_)) => target_oldest,
+            _ => crate::index::InMemoryGraphStore::new(),
+        };
+
+        let source_clock = self.list_snapshot_timestamps(db, source)?.into_iter().max().unwrap_or(0);
+        let target_clock = self.list_snapshot_timestamps(db, target)?.into_iter().max().unwrap_or(0);
+
+        let (merged, summary) = merge::merge_three_way(
+            &base, &source_store, &target_store,
+            source_clock, target_clock,
+            source.name.as_str(), target.name.as_str(),
+            strategy,
+        );
+
+        if strategy == merge::MergeStrategy::FailOnConflict && summary.conflicted > 0 {
+            return Ok(summary);
+        }
+
+        self.flush_branch(db, target, &merged, None)?;
+
+        // Best-effort, like `commit_tx`'s checkpoint fold: a merge folds two
+        // branches' history onto `target`, which is exactly when chunks only
+        // the pre-merge manifests referenced are likeliest to have gone
+        // stale. A GC failure doesn't undo the (already-durable) merge.
+        if let Ok(branches) = self.list_branches(db) {
+            let _ = self.backend.gc(self.data_dir(), &db.name, &branches);
+        }
+
+        Ok(summary)
+    }
+
+    #[cfg(not(feature = "fs"))]
+    pub fn merge_branch(
+        &self,
+        _db: &DbHandle,
+        _source: &BranchHandle,
+        _target: &BranchHandle,
+        _strategy: merge::MergeStrategy,
+    ) -> Result<merge::MergeSummary, EngineError> {
+        Err(EngineError::NotImplemented("merge_branch requires fs feature".into()))
     }
 
     /// Return the engine data directory.
@@ -212,14 +460,28 @@ impl Engine {
         &self.data_dir
     }
 
-    /// Flush an in-memory store to on-disk segments for the given branch (requires `fs`).
+    /// Flush an in-memory store to on-disk segments for the given branch
+    /// (requires `fs`). `compression` is `None` for the plain, uncompressed
+    /// write this method always did before `CompressionOptions` existed.
     #[cfg(feature = "fs")]
-    pub fn flush_branch(&self, db: &DbHandle, branch: &BranchHandle, store: &crate::index::InMemoryGraphStore) -> Result<(), EngineError> {
-        store.flush_to_fs(self.data_dir(), &db.name, &branch.name)
+    pub fn flush_branch(
+        &self,
+        db: &DbHandle,
+        branch: &BranchHandle,
+        store: &crate::index::InMemoryGraphStore,
+        compression: Option<crate::index::compression::CompressionOptions>,
+    ) -> Result<(), EngineError> {
+        store.flush_to_fs(self.data_dir(), &db.name, &branch.name, compression.unwrap_or_default())
     }
 
     #[cfg(not(feature = "fs"))]
-    pub fn flush_branch(&self, _db: &DbHandle, _branch: &BranchHandle, _store: &crate::index::InMemoryGraphStore) -> Result<(), EngineError> {
+    pub fn flush_branch(
+        &self,
+        _db: &DbHandle,
+        _branch: &BranchHandle,
+        _store: &crate::index::InMemoryGraphStore,
+        _compression: Option<crate::index::compression::CompressionOptions>,
+    ) -> Result<(), EngineError> {
         Err(EngineError::NotImplemented("flush_branch requires fs feature".into()))
     }
 
@@ -245,6 +507,20 @@ impl Engine {
         Err(EngineError::NotImplemented("list_snapshot_timestamps requires fs feature".into()))
     }
 
+    /// Codec and on-disk size of every snapshot a branch has published
+    /// (requires `fs`); unlike `list_snapshot_timestamps`, this reads the
+    /// content-addressed segments `flush_to_fs` wrote for each manifest
+    /// version so it doesn't go stale if the codec changes between flushes.
+    #[cfg(feature = "fs")]
+    pub fn list_snapshot_details(&self, db: &DbHandle, branch: &BranchHandle) -> Result<Vec<crate::index::compression::SnapshotDetails>, EngineError> {
+        crate::index::InMemoryGraphStore::list_snapshot_details(self.data_dir(), &db.name, &branch.name)
+    }
+
+    #[cfg(not(feature = "fs"))]
+    pub fn list_snapshot_details(&self, _db: &DbHandle, _branch: &BranchHandle) -> Result<Vec<crate::index::compression::SnapshotDetails>, EngineError> {
+        Err(EngineError::NotImplemented("list_snapshot_details requires fs feature".into()))
+    }
+
     /// Execute a GQL query against the provided in-memory store, with optional JSON parameters.
     /// This centralizes parsing, planning, and execution inside the engine so wrappers stay thin.
     pub fn execute_gql_on_store(
@@ -258,8 +534,24 @@ impl Engine {
         use crate::index::{GraphReadStore, GraphWriteStore};
         use std::collections::HashMap;
 
-        // Parse & plan
-        let ast = parser::parse(&gql.0)?;
+        // Parse & plan. The constant-folding pass can be disabled for debugging
+        // (e.g. to compare EXPLAIN output pre/post optimization), mirroring the
+        // CASYS_DEBUG_PLAN-style env toggles used elsewhere in the planner/executor.
+        let optimize = std::env::var("CASYS_DISABLE_OPTIMIZER").ok().as_deref() != Some("1");
+        let ast = parser::parse_with_options(&gql.0, optimize)?;
+
+        // DEFINE NODE/DEFINE EDGE is DDL, not a query - it just populates the
+        // engine-wide schema registry future CREATEs validate against, so it
+        // never reaches the planner/executor.
+        if let Some(define) = &ast.define_clause {
+            let mut registry = self.schema_registry.lock().expect("schema_registry poisoned");
+            match &define.target {
+                exec::ast::DefineTarget::Node(def) => registry.define_node(def.clone()),
+                exec::ast::DefineTarget::Edge(def) => registry.define_edge(def.clone()),
+            }
+            return Ok(QueryResult { columns: Vec::new(), rows: Vec::new(), stats: None });
+        }
+
         let _required_params = ast.extract_parameters();
         let plan = Planner::plan(&ast)?;
 
@@ -271,14 +563,17 @@ impl Engine {
             }
         }
 
+        let external_functions = self.external_functions.lock().expect("external_functions poisoned").clone();
+
         // Execute with write handle when CREATE is present; otherwise read-only path
         if ast.create_clause.is_some() {
+            let schema = self.schema_registry.lock().expect("schema_registry poisoned").clone();
             let write: Option<&mut dyn GraphWriteStore> = Some(store);
             let executor = if param_exec.is_empty() {
                 Executor::new_no_read()
             } else {
                 Executor::with_parameters_no_read(param_exec)
-            };
+            }.with_external_functions(external_functions).with_schema(schema);
             executor.execute(&plan, write)
         } else {
             let read = store as &dyn GraphReadStore;
@@ -286,8 +581,42 @@ impl Engine {
                 Executor::new(read)
             } else {
                 Executor::with_parameters(read, param_exec)
-            };
+            }.with_external_functions(external_functions);
             executor.execute(&plan, None)
         }
     }
+
+    /// Execute an ordered list of GQL statements against `store`, taking the
+    /// store lock only once instead of once per statement. Returns the
+    /// per-statement results in order.
+    ///
+    /// If `atomic` is true, any statement failing aborts the whole batch and
+    /// the store is rolled back to its pre-batch state (mirroring the
+    /// all-or-nothing batch semantics of grouped read/write APIs); the error
+    /// is tagged with the zero-based index of the failing statement. If
+    /// `atomic` is false, a failing statement stops the batch but the effects
+    /// of the statements executed so far are kept.
+    pub fn execute_gql_batch_on_store(
+        &self,
+        store: &mut crate::index::InMemoryGraphStore,
+        statements: &[(GqlQuery, Option<std::collections::HashMap<String, serde_json::Value>>)],
+        atomic: bool,
+    ) -> Result<Vec<QueryResult>, (usize, EngineError)> {
+        let rollback = if atomic { Some(store.clone()) } else { None };
+
+        let mut results = Vec::with_capacity(statements.len());
+        for (i, (gql, params)) in statements.iter().enumerate() {
+            match self.execute_gql_on_store(store, gql, params.clone()) {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    if let Some(snapshot) = rollback {
+                        *store = snapshot;
+                    }
+                    return Err((i, e));
+                }
+            }
+        }
+
+        Ok(results)
+    }
 }
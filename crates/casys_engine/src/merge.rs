@@ -0,0 +1,233 @@
+//! Three-way branch merge with last-writer-wins or fail-on-conflict
+//! resolution, used by [`crate::Engine::merge_branch`].
+//!
+//! `InMemoryGraphStore` tombstones deletes with a per-id version rather than
+//! removing the entry outright (see `index::InMemoryGraphStore::delete_node`),
+//! but that version isn't a logical clock shared across branches, so this
+//! still uses each branch's own latest commit timestamp as its Lamport
+//! counter for last-writer-wins ties, with the branch name breaking a tie
+//! between equal timestamps. Resolution compares each side's *live* view
+//! (tombstones read back as absent, same as `GraphReadStore`) against
+//! `base`: an id present in `base` but absent from a branch there is
+//! treated as that branch having deleted it, so the other branch's
+//! unchanged copy doesn't resurrect it.
+
+use std::collections::{BTreeSet, HashMap};
+
+use casys_core::{EdgeId, NodeId};
+
+use crate::index::{Edge, InMemoryGraphStore, Node};
+use crate::Timestamp;
+
+/// How to resolve an element both branches changed differently relative to
+/// their common ancestor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep whichever side has the higher `(clock, branch name)` pair.
+    LastWriterWins,
+    /// Leave the target untouched and report every conflicting id instead.
+    FailOnConflict,
+}
+
+/// A node or edge id a merge reported a conflict against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictId {
+    Node(NodeId),
+    Edge(EdgeId),
+}
+
+/// Counts of what a merge did, plus the ids it couldn't resolve
+/// automatically. `conflicts` is only non-empty when `strategy` was
+/// `FailOnConflict` and at least one element diverged; when that happens the
+/// target is left exactly as it was.
+#[derive(Debug, Clone, Default)]
+pub struct MergeSummary {
+    pub added: u64,
+    pub updated: u64,
+    pub deleted: u64,
+    pub conflicted: u64,
+    pub conflicts: Vec<ConflictId>,
+}
+
+/// `(logical clock, branch name)` - the tiebreak described in the module doc
+/// comment. Compared lexicographically, so a higher clock always wins
+/// regardless of branch name, and two branches committing at the same
+/// millisecond break the tie on name.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct LamportClock<'a> {
+    ts: Timestamp,
+    branch: &'a str,
+}
+
+/// What to do with one element (a single node or edge id) once both sides
+/// have been compared against the base.
+enum Resolution<'a, T> {
+    /// Put this value in the merged store.
+    Keep(&'a T),
+    /// Neither side has it any more (or never did).
+    Absent,
+    /// Both sides changed it differently and the tiebreak didn't separate
+    /// them (`FailOnConflict`, or equal `LastWriterWins` clocks).
+    Conflict,
+}
+
+fn resolve<'a, T: PartialEq>(
+    base: Option<&'a T>,
+    source: Option<&'a T>,
+    target: Option<&'a T>,
+    source_clock: LamportClock,
+    target_clock: LamportClock,
+    strategy: MergeStrategy,
+) -> Resolution<'a, T> {
+    let source_changed = source != base;
+    let target_changed = target != base;
+
+    match (source_changed, target_changed) {
+        (false, false) => match target {
+            Some(v) => Resolution::Keep(v),
+            None => Resolution::Absent,
+        },
+        (false, true) => match target {
+            Some(v) => Resolution::Keep(v),
+            None => Resolution::Absent,
+        },
+        (true, false) => match source {
+            Some(v) => Resolution::Keep(v),
+            None => Resolution::Absent,
+        },
+        (true, true) => {
+            if source == target {
+                match target {
+                    Some(v) => Resolution::Keep(v),
+                    None => Resolution::Absent,
+                }
+            } else if strategy == MergeStrategy::FailOnConflict {
+                // Any true divergence - edit/edit or delete/edit - is a
+                // conflict under this strategy; `merge_three_way` leaves
+                // `target` untouched once any conflict is reported, so
+                // there's no resolution to pick here.
+                Resolution::Conflict
+            } else {
+                match (source, target) {
+                    (Some(s), Some(t)) => {
+                        if source_clock > target_clock {
+                            Resolution::Keep(s)
+                        } else if target_clock > source_clock {
+                            Resolution::Keep(t)
+                        } else {
+                            Resolution::Conflict
+                        }
+                    }
+                    // One side deleted its copy while the other kept editing
+                    // its own. Under LastWriterWins this is still decided by
+                    // the clocks, same direction as the edit/edit case above:
+                    // the delete only wins if its clock is strictly newer
+                    // than the edit's, so an equal or older delete clock
+                    // keeps the edit instead of resurrecting-by-default or
+                    // deleting-by-default.
+                    (None, Some(t)) => {
+                        if source_clock > target_clock { Resolution::Absent } else { Resolution::Keep(t) }
+                    }
+                    (Some(s), None) => {
+                        if target_clock > source_clock { Resolution::Absent } else { Resolution::Keep(s) }
+                    }
+                    (None, None) => Resolution::Absent,
+                }
+            }
+        }
+    }
+}
+
+/// Merges `source` into `target` relative to their common ancestor `base`,
+/// returning the resulting store (a clone of `target`, untouched, if the
+/// merge failed due to `FailOnConflict` conflicts) and a summary of what
+/// happened.
+pub(crate) fn merge_three_way(
+    base: &InMemoryGraphStore,
+    source: &InMemoryGraphStore,
+    target: &InMemoryGraphStore,
+    source_clock: Timestamp,
+    target_clock: Timestamp,
+    source_branch: &str,
+    target_branch: &str,
+    strategy: MergeStrategy,
+) -> (InMemoryGraphStore, MergeSummary) {
+    let source_clock = LamportClock { ts: source_clock, branch: source_branch };
+    let target_clock = LamportClock { ts: target_clock, branch: target_branch };
+
+    let mut summary = MergeSummary::default();
+
+    let node_ids: BTreeSet<NodeId> = base.nodes.keys()
+        .chain(source.nodes.keys())
+        .chain(target.nodes.keys())
+        .copied()
+        .collect();
+
+    let mut resolved_nodes: HashMap<NodeId, Option<Node>> = HashMap::with_capacity(node_ids.len());
+    for id in node_ids {
+        match resolve(base.node_value(id), source.node_value(id), target.node_value(id), source_clock, target_clock, strategy) {
+            Resolution::Keep(node) => { resolved_nodes.insert(id, Some(node.clone())); }
+            Resolution::Absent => { resolved_nodes.insert(id, None); }
+            Resolution::Conflict => {
+                summary.conflicted += 1;
+                summary.conflicts.push(ConflictId::Node(id));
+                resolved_nodes.insert(id, target.node_value(id).cloned());
+            }
+        }
+    }
+
+    let edge_ids: BTreeSet<EdgeId> = base.edges.keys()
+        .chain(source.edges.keys())
+        .chain(target.edges.keys())
+        .copied()
+        .collect();
+
+    let mut resolved_edges: HashMap<EdgeId, Option<Edge>> = HashMap::with_capacity(edge_ids.len());
+    for id in edge_ids {
+        match resolve(base.edge_value(id), source.edge_value(id), target.edge_value(id), source_clock, target_clock, strategy) {
+            Resolution::Keep(edge) => { resolved_edges.insert(id, Some(edge.clone())); }
+            Resolution::Absent => { resolved_edges.insert(id, None); }
+            Resolution::Conflict => {
+                summary.conflicted += 1;
+                summary.conflicts.push(ConflictId::Edge(id));
+                resolved_edges.insert(id, target.edge_value(id).cloned());
+            }
+        }
+    }
+
+    if strategy == MergeStrategy::FailOnConflict && summary.conflicted > 0 {
+        return (target.clone(), summary);
+    }
+
+    let mut merged = InMemoryGraphStore::new();
+
+    for (id, node) in resolved_nodes {
+        let was_present = target.node_value(id);
+        if let Some(n) = node {
+            match was_present {
+                Some(prev) if *prev == n => {}
+                Some(_) => summary.updated += 1,
+                None => summary.added += 1,
+            }
+            merged.insert_node(n);
+        } else if was_present.is_some() {
+            summary.deleted += 1;
+        }
+    }
+
+    for (id, edge) in resolved_edges {
+        let was_present = target.edge_value(id);
+        if let Some(e) = edge {
+            match was_present {
+                Some(prev) if *prev == e => {}
+                Some(_) => summary.updated += 1,
+                None => summary.added += 1,
+            }
+            merged.insert_edge(e);
+        } else if was_present.is_some() {
+            summary.deleted += 1;
+        }
+    }
+
+    (merged, summary)
+}
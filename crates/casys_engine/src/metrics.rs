@@ -0,0 +1,152 @@
+//! Lightweight per-branch operational counters, behind the `metrics`
+//! feature so embedders who don't want the bookkeeping overhead (an atomic
+//! increment or two per call) don't pay for it. Gives operators the kind of
+//! visibility Garage's admin/metrics module provides - commit throughput,
+//! manifest/segment counts, PITR lookup volume - without standing up a
+//! separate metrics server: `Engine::metrics_snapshot()` returns a
+//! serializable struct callers can expose however they like (HTTP handler,
+//! log line, etc.), and [`to_prometheus_text`] renders it in Prometheus's
+//! text exposition format for the common case.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+
+#[derive(Default)]
+struct BranchCounters {
+    commit_count: AtomicU64,
+    commit_records_total: AtomicU64,
+    commit_latency_us_total: AtomicU64,
+    snapshot_count: AtomicU64,
+    pitr_lookup_count: AtomicU64,
+}
+
+/// Per-engine metrics registry. Cheap to share: wrap in `Arc` if multiple
+/// owners need it, same as `Engine` itself.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    branches: Mutex<HashMap<(String, String), Arc<BranchCounters>>>,
+}
+
+impl MetricsRegistry {
+    fn branch(&self, db: &str, branch: &str) -> Arc<BranchCounters> {
+        let mut map = self.branches.lock().expect("metrics registry poisoned");
+        map.entry((db.to_string(), branch.to_string()))
+            .or_insert_with(|| Arc::new(BranchCounters::default()))
+            .clone()
+    }
+
+    pub(crate) fn record_commit(&self, db: &str, branch: &str, record_count: usize, latency: std::time::Duration) {
+        let c = self.branch(db, branch);
+        c.commit_count.fetch_add(1, Ordering::Relaxed);
+        c.commit_records_total.fetch_add(record_count as u64, Ordering::Relaxed);
+        c.commit_latency_us_total.fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_snapshot(&self, db: &str, branch: &str) {
+        self.branch(db, branch).snapshot_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_pitr_lookup(&self, db: &str, branch: &str) {
+        self.branch(db, branch).pitr_lookup_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Times `f`, records it as a commit of `record_count` records, and
+    /// returns `f`'s result.
+    pub(crate) fn time_commit<T>(&self, db: &str, branch: &str, record_count: usize, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.record_commit(db, branch, record_count, started.elapsed());
+        result
+    }
+
+    /// Snapshots all counters into a serializable struct, pairing each
+    /// branch's counters with its current live-manifest/live-segment
+    /// counts (read fresh from disk, not tracked incrementally) and the
+    /// process-wide bytes written through `atomic_write_file` so far.
+    pub fn snapshot(&self, live_counts: impl Fn(&str, &str) -> (u64, u64)) -> MetricsSnapshot {
+        let map = self.branches.lock().expect("metrics registry poisoned");
+        let per_branch = map
+            .iter()
+            .map(|((db, branch), c)| {
+                let commit_count = c.commit_count.load(Ordering::Relaxed);
+                let (live_manifests, live_segments) = live_counts(db, branch);
+                BranchMetrics {
+                    db: db.clone(),
+                    branch: branch.clone(),
+                    commit_count,
+                    commit_records_total: c.commit_records_total.load(Ordering::Relaxed),
+                    commit_latency_us_avg: if commit_count > 0 {
+                        c.commit_latency_us_total.load(Ordering::Relaxed) / commit_count
+                    } else {
+                        0
+                    },
+                    snapshot_count: c.snapshot_count.load(Ordering::Relaxed),
+                    pitr_lookup_count: c.pitr_lookup_count.load(Ordering::Relaxed),
+                    live_manifests,
+                    live_segments,
+                }
+            })
+            .collect();
+        MetricsSnapshot {
+            bytes_written_total: bytes_written_total(),
+            branches: per_branch,
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+fn bytes_written_total() -> u64 {
+    casys_storage_fs::util::bytes_written_total()
+}
+
+#[cfg(not(feature = "fs"))]
+fn bytes_written_total() -> u64 {
+    0
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchMetrics {
+    pub db: String,
+    pub branch: String,
+    pub commit_count: u64,
+    pub commit_records_total: u64,
+    pub commit_latency_us_avg: u64,
+    pub snapshot_count: u64,
+    pub pitr_lookup_count: u64,
+    pub live_manifests: u64,
+    pub live_segments: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    pub bytes_written_total: u64,
+    pub branches: Vec<BranchMetrics>,
+}
+
+/// Renders a snapshot in Prometheus's text exposition format.
+pub fn to_prometheus_text(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    out.push_str("# TYPE casys_bytes_written_total counter\n");
+    out.push_str(&format!("casys_bytes_written_total {}\n", snapshot.bytes_written_total));
+
+    let metrics: &[(&str, &str, fn(&BranchMetrics) -> u64)] = &[
+        ("casys_commit_total", "counter", |b| b.commit_count),
+        ("casys_commit_records_total", "counter", |b| b.commit_records_total),
+        ("casys_commit_latency_us_avg", "gauge", |b| b.commit_latency_us_avg),
+        ("casys_snapshot_total", "counter", |b| b.snapshot_count),
+        ("casys_pitr_lookup_total", "counter", |b| b.pitr_lookup_count),
+        ("casys_live_manifests", "gauge", |b| b.live_manifests),
+        ("casys_live_segments", "gauge", |b| b.live_segments),
+    ];
+    for (name, ty, get) in metrics {
+        out.push_str(&format!("# TYPE {name} {ty}\n"));
+        for b in &snapshot.branches {
+            out.push_str(&format!("{name}{{db=\"{}\",branch=\"{}\"}} {}\n", b.db, b.branch, get(b)));
+        }
+    }
+    out
+}
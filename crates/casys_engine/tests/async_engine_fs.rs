@@ -0,0 +1,28 @@
+// Integration test: async (tokio) facade for commit/flush/load (chunk2-6)
+
+#[cfg(all(feature = "fs", feature = "async"))]
+#[tokio::test]
+async fn commit_tx_flush_and_load_round_trip_through_the_async_facade() {
+    use casys_engine::{AsyncEngine, Engine};
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("async_engine_{now}"));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let engine = Arc::new(Engine::open(&root).unwrap());
+    let async_engine = AsyncEngine::new(engine.clone());
+
+    let db = engine.open_database("acme").unwrap();
+    let branch = engine.open_branch(&db, "main").unwrap();
+
+    async_engine.commit_tx(&branch, vec![b"record-one".to_vec()]).await.unwrap();
+
+    let store = Arc::new(casys_engine::index::InMemoryGraphStore::new());
+    async_engine.flush_branch(&db, &branch, store, None).await.unwrap();
+
+    let loaded = async_engine.load_branch(&db, &branch).await.unwrap();
+    let _ = loaded;
+}
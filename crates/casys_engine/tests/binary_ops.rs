@@ -0,0 +1,78 @@
+//! Tests for `Executor::eval_binary_op`'s arithmetic and null-coalescing
+//! operators (`casys_engine::exec::executor`).
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::Planner;
+use casys_engine::index::InMemoryGraphStore;
+
+fn single_row_result(query: &str) -> Value {
+    let mut graph = InMemoryGraphStore::new();
+    graph.add_node(vec!["Task".to_string()], HashMap::new()).unwrap();
+
+    let parsed = parse(query).unwrap();
+    let plan = Planner::plan(&parsed).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    result.rows[0][0].clone()
+}
+
+#[test]
+fn mod_computes_the_remainder() {
+    assert_eq!(single_row_result("MATCH (n:Task) RETURN 7 % 3"), Value::Int(1));
+}
+
+#[test]
+fn mod_by_zero_is_an_invalid_argument_not_a_panic() {
+    let mut graph = InMemoryGraphStore::new();
+    graph.add_node(vec!["Task".to_string()], HashMap::new()).unwrap();
+    let parsed = parse("MATCH (n:Task) RETURN 7 % 0").unwrap();
+    let plan = Planner::plan(&parsed).unwrap();
+
+    assert!(Executor::new(&graph).execute(&plan, None).is_err());
+}
+
+#[test]
+fn pow_coerces_to_float() {
+    assert_eq!(single_row_result("MATCH (n:Task) RETURN 2 ^ 3"), Value::Float(8.0));
+}
+
+#[test]
+fn pow_with_a_negative_exponent_is_an_invalid_argument_not_a_panic() {
+    let mut graph = InMemoryGraphStore::new();
+    graph.add_node(vec!["Task".to_string()], HashMap::new()).unwrap();
+    let parsed = parse("MATCH (n:Task) RETURN 2 ^ -1").unwrap();
+    let plan = Planner::plan(&parsed).unwrap();
+
+    assert!(Executor::new(&graph).execute(&plan, None).is_err());
+}
+
+#[test]
+fn string_add_concatenates() {
+    assert_eq!(
+        single_row_result("MATCH (n:Task) RETURN \"foo\" + \"bar\""),
+        Value::String("foobar".to_string())
+    );
+}
+
+#[test]
+fn coalesce_prefers_the_left_operand_when_it_is_not_null() {
+    assert_eq!(single_row_result("MATCH (n:Task) RETURN 1 ?? 2"), Value::Int(1));
+}
+
+#[test]
+fn coalesce_falls_back_to_the_right_operand_on_null() {
+    assert_eq!(single_row_result("MATCH (n:Task) RETURN null ?? 2"), Value::Int(2));
+}
+
+#[test]
+fn coalesce_allows_mixed_operand_types() {
+    assert_eq!(
+        single_row_result("MATCH (n:Task) RETURN null ?? \"fallback\""),
+        Value::String("fallback".to_string())
+    );
+}
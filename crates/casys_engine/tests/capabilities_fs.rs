@@ -0,0 +1,65 @@
+// Integration test: format/capability negotiation on Engine::open (chunk2-5)
+
+#[cfg(feature = "fs")]
+#[test]
+fn capabilities_are_recorded_and_agree_across_reopens() {
+    use casys_engine as engine;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::fs;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("engine_capabilities_{}", now));
+    fs::create_dir_all(&root).unwrap();
+
+    let eng = engine::Engine::open(&root).unwrap();
+    assert_eq!(eng.capabilities().version.major, engine::capabilities::CURRENT_VERSION.major);
+    assert!(!eng.capabilities().has("encryption"));
+
+    // Reopening the same directory sees the same recorded capabilities.
+    let reopened = engine::Engine::open(&root).unwrap();
+    assert_eq!(reopened.capabilities(), eng.capabilities());
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn open_with_encryption_records_the_encryption_feature() {
+    use casys_engine as engine;
+    use casys_core::EncryptionConfig;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::fs;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("engine_capabilities_enc_{}", now));
+    fs::create_dir_all(&root).unwrap();
+
+    let config = EncryptionConfig::Passphrase("correct horse battery staple".to_string());
+    let eng = engine::Engine::open_with_encryption(&root, &config).unwrap();
+    assert!(eng.capabilities().has("encryption"));
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn a_future_major_version_is_rejected_as_incompatible() {
+    use casys_engine as engine;
+    use casys_core::{Capabilities, EngineError, FormatVersion};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::fs;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("engine_capabilities_future_{}", now));
+    fs::create_dir_all(&root).unwrap();
+
+    let from_the_future = Capabilities {
+        version: FormatVersion { major: engine::capabilities::CURRENT_VERSION.major + 1, minor: 0 },
+        features: Vec::new(),
+    };
+    fs::write(root.join("capabilities.json"), serde_json::to_vec(&from_the_future).unwrap()).unwrap();
+
+    match engine::Engine::open(&root) {
+        Err(EngineError::IncompatibleFormat { found, .. }) => assert_eq!(found.version.major, from_the_future.version.major),
+        other => panic!("expected IncompatibleFormat, got {other:?}"),
+    }
+}
@@ -0,0 +1,134 @@
+//! Tests for comparison type coercion (`casys_engine::exec::coercion`) and,
+//! for the quoted-number-vs-property case that coercion defers, the runtime
+//! comparison in `Executor::eval_binary_op` that actually resolves it.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::ast::{BinOp, CastType, Expr, Literal};
+use casys_engine::exec::coercion::coerce_binary;
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::{Planner, PlanNode};
+use casys_engine::index::InMemoryGraphStore;
+
+#[test]
+fn widens_int_and_float_literals_to_float() {
+    let lhs = Expr::Literal(Literal::Int(1));
+    let rhs = Expr::Literal(Literal::Float(1.5));
+    let (lhs, rhs) = coerce_binary(lhs, &BinOp::Lt, rhs).unwrap();
+
+    assert_eq!(lhs, Expr::Cast(Box::new(Expr::Literal(Literal::Int(1))), CastType::Float));
+    assert_eq!(rhs, Expr::Literal(Literal::Float(1.5)));
+}
+
+#[test]
+fn leaves_quoted_number_literal_against_property_untouched() {
+    // Whether `"5"` should mean the number 5 or the string "5" depends on
+    // `a.x`'s actual runtime type, which isn't known at plan time - so
+    // coercion leaves both sides alone and `eval_binary_op` resolves it once
+    // `a.x`'s real value is in hand (see the runtime tests below).
+    let lhs = Expr::Property("a".to_string(), "x".to_string());
+    let rhs = Expr::Literal(Literal::String("5".to_string()));
+    let (lhs, rhs) = coerce_binary(lhs, &BinOp::Eq, rhs).unwrap();
+
+    assert_eq!(lhs, Expr::Property("a".to_string(), "x".to_string()));
+    assert_eq!(rhs, Expr::Literal(Literal::String("5".to_string())));
+}
+
+#[test]
+fn leaves_non_numeric_string_literal_against_property_untouched() {
+    let lhs = Expr::Property("a".to_string(), "name".to_string());
+    let rhs = Expr::Literal(Literal::String("alice".to_string()));
+    let (lhs, rhs) = coerce_binary(lhs, &BinOp::Eq, rhs).unwrap();
+
+    assert_eq!(lhs, Expr::Property("a".to_string(), "name".to_string()));
+    assert_eq!(rhs, Expr::Literal(Literal::String("alice".to_string())));
+}
+
+#[test]
+fn errors_on_incomparable_literal_types() {
+    let lhs = Expr::Literal(Literal::Bool(true));
+    let rhs = Expr::Literal(Literal::String("nope".to_string()));
+
+    assert!(coerce_binary(lhs, &BinOp::Eq, rhs).is_err());
+}
+
+#[test]
+fn non_comparison_operators_pass_through_unchanged() {
+    let lhs = Expr::Literal(Literal::Int(1));
+    let rhs = Expr::Literal(Literal::Float(2.0));
+    let (lhs, rhs) = coerce_binary(lhs.clone(), &BinOp::Add, rhs.clone()).unwrap();
+
+    assert_eq!(lhs, Expr::Literal(Literal::Int(1)));
+    assert_eq!(rhs, Expr::Literal(Literal::Float(2.0)));
+}
+
+#[test]
+fn inline_quoted_number_property_is_not_cast_in_plan() {
+    let query = parse("MATCH (a {x: \"5\"}) RETURN a").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    let predicate = match &plan.root {
+        PlanNode::Filter { predicate, .. } => predicate,
+        PlanNode::Project { input, .. } => match input.as_ref() {
+            PlanNode::Filter { predicate, .. } => predicate,
+            other => panic!("expected a Filter under Project, got {:#?}", other),
+        },
+        other => panic!("expected a Filter somewhere in the plan, got {:#?}", other),
+    };
+
+    assert!(!format!("{:?}", predicate).contains("Cast"), "predicate: {:#?}", predicate);
+}
+
+fn node_with_property(key: &str, value: Value) -> InMemoryGraphStore {
+    let mut store = InMemoryGraphStore::new();
+    let mut props = HashMap::new();
+    props.insert(key.to_string(), value);
+    store.add_node(vec!["Item".to_string()], props).unwrap();
+    store
+}
+
+fn matches_x_equals_quoted_5(store: &InMemoryGraphStore) -> bool {
+    let query = parse("MATCH (a {x: \"5\"}) RETURN a").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(store).execute(&plan, None).unwrap();
+    !result.rows.is_empty()
+}
+
+#[test]
+fn quoted_number_matches_a_property_actually_holding_that_int() {
+    let store = node_with_property("x", Value::Int(5));
+    assert!(matches_x_equals_quoted_5(&store));
+}
+
+#[test]
+fn quoted_number_matches_a_property_actually_holding_that_float() {
+    let store = node_with_property("x", Value::Float(5.0));
+    assert!(matches_x_equals_quoted_5(&store));
+}
+
+#[test]
+fn quoted_number_matches_a_property_holding_the_same_string() {
+    // The bug this guards against: a property that genuinely holds the
+    // string "5" used to get compared against `Cast("5", Int)`, which
+    // `eval_binary_op` rejected as a type mismatch - and `PlanNode::Filter`
+    // silently swallowed that error as "no match" instead of the true match
+    // it should have been. Coercion no longer casts eagerly, so this now
+    // reaches the (String, String) comparison branch directly.
+    let store = node_with_property("x", Value::String("5".to_string()));
+    assert!(matches_x_equals_quoted_5(&store));
+}
+
+#[test]
+fn quoted_number_does_not_falsely_match_an_unrelated_numeric_string_property() {
+    let store = node_with_property("zip", Value::String("30".to_string()));
+    let query = parse("MATCH (a {zip: \"30\"}) RETURN a").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&store).execute(&plan, None).unwrap();
+    assert_eq!(result.rows.len(), 1);
+
+    let other_store = node_with_property("zip", Value::String("31".to_string()));
+    let result = Executor::new(&other_store).execute(&plan, None).unwrap();
+    assert!(result.rows.is_empty());
+}
@@ -0,0 +1,80 @@
+//! Tests for the discrimination-index pre-pass `Executor` applies to a
+//! `Filter` sitting directly over a `LabelScan`/`FullScan`: equality
+//! conjuncts on `var.prop` probe the property index instead of scanning and
+//! filtering every node.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::Planner;
+use casys_engine::index::InMemoryGraphStore;
+
+fn node_with_status(graph: &mut InMemoryGraphStore, status: &str, priority: i64) -> u64 {
+    let mut props = HashMap::new();
+    props.insert("status".to_string(), Value::String(status.to_string()));
+    props.insert("priority".to_string(), Value::Int(priority));
+    graph.add_node(vec!["Task".to_string()], props).unwrap()
+}
+
+#[test]
+fn equality_filter_only_scans_the_matching_nodes() {
+    let mut graph = InMemoryGraphStore::new();
+    let active = node_with_status(&mut graph, "active", 1);
+    node_with_status(&mut graph, "done", 2);
+    node_with_status(&mut graph, "done", 3);
+
+    let query = parse("MATCH (n:Task) WHERE n.status = \"active\" RETURN n").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), active);
+    // Only the one matching node should have been materialized, not all three.
+    assert_eq!(result.stats.unwrap().scanned, 1);
+}
+
+#[test]
+fn intersects_two_indexable_equality_conjuncts() {
+    let mut graph = InMemoryGraphStore::new();
+    let target = node_with_status(&mut graph, "active", 5);
+    node_with_status(&mut graph, "active", 9);
+    node_with_status(&mut graph, "done", 5);
+
+    let query = parse("MATCH (n:Task) WHERE n.status = \"active\" AND n.priority = 5 RETURN n").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), target);
+}
+
+#[test]
+fn non_equality_residual_is_still_applied_after_the_index_probe() {
+    let mut graph = InMemoryGraphStore::new();
+    let keep = node_with_status(&mut graph, "active", 5);
+    node_with_status(&mut graph, "active", 1);
+
+    let query = parse("MATCH (n:Task) WHERE n.status = \"active\" AND n.priority > 2 RETURN n").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), keep);
+}
+
+#[test]
+fn int_literal_matches_a_property_stored_as_float() {
+    let mut graph = InMemoryGraphStore::new();
+    let mut props = HashMap::new();
+    props.insert("score".to_string(), Value::Float(5.0));
+    let target = graph.add_node(vec!["Task".to_string()], props).unwrap();
+
+    let query = parse("MATCH (n:Task) WHERE n.score = 5 RETURN n").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), target);
+}
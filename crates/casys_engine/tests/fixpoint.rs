@@ -0,0 +1,80 @@
+//! Tests for `PlanNode::Fixpoint`'s semi-naive transitive-closure execution.
+//!
+//! No GQL syntax surfaces this node yet, so each test builds the plan
+//! directly, the same way `shortest_path.rs` does for `PlanNode::ShortestPath`.
+
+use std::collections::HashMap;
+
+use casys_core::GraphWriteStore;
+use casys_engine::exec::ast::{Direction, Expr, PathUniqueness, ReturnItem};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::planner::{ExecutionPlan, PlanNode};
+use casys_engine::index::InMemoryGraphStore;
+
+/// All nodes reachable from a `:Start` node via `:FOLLOWS`, bound under `n`
+/// each round: `seed` picks the start node(s), `recursive` expands the
+/// current frontier by one hop and rebinds the result back onto `n`.
+fn reachability_plan() -> PlanNode {
+    PlanNode::Project {
+        input: Box::new(PlanNode::Fixpoint {
+            seed: Box::new(PlanNode::LabelScan { variable: "n".to_string(), label: "Start".to_string() }),
+            recursive: Box::new(PlanNode::Project {
+                input: Box::new(PlanNode::Expand {
+                    input: Box::new(PlanNode::FullScan { variable: "n".to_string() }),
+                    from_var: "n".to_string(),
+                    edge_var: None,
+                    to_var: "next".to_string(),
+                    edge_type: Some("FOLLOWS".to_string()),
+                    direction: Direction::Right,
+                    depth: None,
+                    path_uniqueness: PathUniqueness::default(),
+                    optional: false,
+                }),
+                items: vec![ReturnItem { expr: Expr::Ident("next".to_string()), alias: Some("n".to_string()) }],
+            }),
+            bind_var: "n".to_string(),
+        }),
+        items: vec![ReturnItem { expr: Expr::Ident("n".to_string()), alias: None }],
+    }
+}
+
+#[test]
+fn closure_reaches_every_node_along_a_chain() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+    let d = graph.add_node(vec![], HashMap::new()).unwrap();
+    let unreached = graph.add_node(vec![], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "FOLLOWS".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "FOLLOWS".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(c, d, "FOLLOWS".to_string(), HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: reachability_plan() };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    let ids: Vec<u64> = result.rows.iter().map(|row| row[0].as_u64().unwrap()).collect();
+    assert_eq!(ids.len(), 4, "expected a, b, c, d with no duplicates: {:?}", ids);
+    for expected in [a, b, c, d] {
+        assert!(ids.contains(&expected), "{} missing from closure: {:?}", expected, ids);
+    }
+    assert!(!ids.contains(&unreached));
+}
+
+#[test]
+fn converges_instead_of_looping_on_a_cycle() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "FOLLOWS".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, a, "FOLLOWS".to_string(), HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: reachability_plan() };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    let ids: Vec<u64> = result.rows.iter().map(|row| row[0].as_u64().unwrap()).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&a) && ids.contains(&b));
+}
@@ -0,0 +1,150 @@
+//! Tests for `casys_engine::gds`'s traversal/pathfinding algorithms against
+//! `InMemoryGraphStore`.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::gds::{self, Bfs, Dfs};
+use casys_engine::index::InMemoryGraphStore;
+
+fn chain(len: usize) -> (InMemoryGraphStore, Vec<u64>) {
+    let mut graph = InMemoryGraphStore::new();
+    let ids: Vec<u64> = (0..len).map(|_| graph.add_node(vec![], HashMap::new()).unwrap()).collect();
+    for pair in ids.windows(2) {
+        graph.add_edge(pair[0], pair[1], "NEXT".to_string(), HashMap::new()).unwrap();
+    }
+    (graph, ids)
+}
+
+#[test]
+fn bfs_visits_every_reachable_node_exactly_once() {
+    let (graph, ids) = chain(4);
+
+    let visited: Vec<u64> = Bfs::new(&graph, ids[0], None).collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(visited, ids);
+}
+
+#[test]
+fn dfs_visits_every_reachable_node_exactly_once() {
+    let (graph, ids) = chain(4);
+
+    let visited: Vec<u64> = Dfs::new(&graph, ids[0], None).collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(visited.len(), ids.len());
+    assert_eq!(visited[0], ids[0]);
+}
+
+#[test]
+fn shortest_path_finds_the_hop_minimal_route() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec![], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(a, c, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let path = gds::shortest_path(&graph, a, c, None).unwrap().unwrap();
+
+    assert_eq!(path, vec![a, c]);
+}
+
+#[test]
+fn shortest_path_returns_none_when_unreachable() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec![], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+
+    assert!(gds::shortest_path(&graph, a, b, None).unwrap().is_none());
+}
+
+#[test]
+fn dijkstra_prefers_the_lower_weight_route_over_fewer_hops() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec![], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+
+    let mut direct_props = HashMap::new();
+    direct_props.insert("cost".to_string(), Value::Float(10.0));
+    graph.add_edge(a, c, "ROUTE".to_string(), direct_props).unwrap();
+
+    let mut leg1 = HashMap::new();
+    leg1.insert("cost".to_string(), Value::Float(1.0));
+    graph.add_edge(a, b, "ROUTE".to_string(), leg1).unwrap();
+    let mut leg2 = HashMap::new();
+    leg2.insert("cost".to_string(), Value::Float(1.0));
+    graph.add_edge(b, c, "ROUTE".to_string(), leg2).unwrap();
+
+    let (path, distance) = gds::dijkstra_shortest_path(&graph, a, c, "cost", None).unwrap().unwrap();
+
+    assert_eq!(path, vec![a, b, c]);
+    assert_eq!(distance, 2.0);
+}
+
+#[test]
+fn weakly_connected_components_groups_nodes_ignoring_edge_direction() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec![], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+    let isolated = graph.add_node(vec![], HashMap::new()).unwrap();
+    graph.add_edge(b, a, "LINK".to_string(), HashMap::new()).unwrap(); // reversed: b -> a
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let mut components = gds::weakly_connected_components(&graph, &[a, b, c, isolated]).unwrap();
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    components.sort_by_key(|c| c[0]);
+
+    assert_eq!(components, vec![vec![a, b, c], vec![isolated]]);
+}
+
+#[test]
+fn strongly_connected_components_separates_a_cycle_from_a_one_way_edge() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec![], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, a, "LINK".to_string(), HashMap::new()).unwrap(); // a <-> b cycle
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap(); // c is one-way only
+
+    let mut components = gds::strongly_connected_components(&graph, &[a, b, c]).unwrap();
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    components.sort_by_key(|c| c.len());
+
+    assert_eq!(components, vec![vec![c], vec![a, b]]);
+}
+
+#[test]
+fn betweenness_scores_the_middle_node_of_a_path_highest() {
+    let (graph, ids) = chain(3);
+
+    let scores = gds::betweenness_centrality(&graph, &ids, None).unwrap();
+
+    assert_eq!(scores[&ids[0]], 0.0);
+    assert_eq!(scores[&ids[1]], 1.0);
+    assert_eq!(scores[&ids[2]], 0.0);
+}
+
+#[test]
+fn closeness_scores_the_center_of_a_star_highest() {
+    let mut graph = InMemoryGraphStore::new();
+    let center = graph.add_node(vec![], HashMap::new()).unwrap();
+    let leaves: Vec<u64> = (0..3).map(|_| graph.add_node(vec![], HashMap::new()).unwrap()).collect();
+    for &leaf in &leaves {
+        graph.add_edge(center, leaf, "LINK".to_string(), HashMap::new()).unwrap();
+    }
+    let mut ids = leaves.clone();
+    ids.push(center);
+
+    let scores = gds::closeness_centrality(&graph, &ids, None).unwrap();
+
+    assert_eq!(scores[&center], 1.0);
+    assert_eq!(scores[&leaves[0]], 0.0);
+}
@@ -0,0 +1,89 @@
+//! Tests for `PlanNode::GraphAlgo`'s betweenness/closeness centrality execution.
+//!
+//! No GQL syntax surfaces this node yet, so each test builds the plan
+//! directly, the same way `shortest_path.rs` does for `PlanNode::ShortestPath`.
+
+use std::collections::HashMap;
+
+use casys_core::GraphWriteStore;
+use casys_engine::exec::ast::{Expr, ReturnItem};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::planner::{ExecutionPlan, PlanNode};
+use casys_engine::index::InMemoryGraphStore;
+
+fn graph_algo_plan(name: &str) -> PlanNode {
+    PlanNode::Project {
+        input: Box::new(PlanNode::GraphAlgo {
+            name: name.to_string(),
+            args: HashMap::new(),
+            yield_cols: vec!["n".to_string(), "score".to_string()],
+        }),
+        items: vec![
+            ReturnItem { expr: Expr::Ident("n".to_string()), alias: None },
+            ReturnItem { expr: Expr::Ident("score".to_string()), alias: None },
+        ],
+    }
+}
+
+#[test]
+fn betweenness_scores_the_middle_node_of_a_path_highest() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec![], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: graph_algo_plan("betweenness") };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 3);
+    let score_of = |id: u64| {
+        result
+            .rows
+            .iter()
+            .find(|row| row[0].as_u64().unwrap() == id)
+            .map(|row| row[1].as_f64().unwrap())
+            .unwrap()
+    };
+    assert_eq!(score_of(a), 0.0);
+    assert_eq!(score_of(b), 1.0);
+    assert_eq!(score_of(c), 0.0);
+}
+
+#[test]
+fn closeness_scores_the_center_of_a_star_highest() {
+    let mut graph = InMemoryGraphStore::new();
+    let center = graph.add_node(vec![], HashMap::new()).unwrap();
+    let leaves: Vec<u64> = (0..3).map(|_| graph.add_node(vec![], HashMap::new()).unwrap()).collect();
+    for &leaf in &leaves {
+        graph.add_edge(center, leaf, "LINK".to_string(), HashMap::new()).unwrap();
+    }
+
+    let plan = ExecutionPlan { root: graph_algo_plan("closeness") };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 4);
+    let score_of = |id: u64| {
+        result
+            .rows
+            .iter()
+            .find(|row| row[0].as_u64().unwrap() == id)
+            .map(|row| row[1].as_f64().unwrap())
+            .unwrap()
+    };
+    assert_eq!(score_of(center), 1.0); // 3 reachable in 3 hops total
+    assert_eq!(score_of(leaves[0]), 0.0); // leaves can't reach each other
+}
+
+#[test]
+fn unknown_algorithm_name_is_an_invalid_argument() {
+    let graph = InMemoryGraphStore::new();
+    let plan = ExecutionPlan { root: graph_algo_plan("eigenvector") };
+    let executor = Executor::new(&graph);
+
+    let err = executor.execute(&plan, None).unwrap_err();
+    assert!(matches!(err, casys_engine::types::EngineError::InvalidArgument(_)));
+}
@@ -0,0 +1,57 @@
+//! Tests for `PlanNode::HashJoin`'s execution, reached here through the full
+//! parse -> plan -> execute pipeline since (unlike `ShortestPath`/`GraphAlgo`)
+//! `plan_optimizer::build_hash_joins` promotes ordinary `WHERE a.x = b.y`
+//! equi-joins to it automatically.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::Planner;
+use casys_engine::index::InMemoryGraphStore;
+
+#[test]
+fn joins_matching_rows_across_both_branches() {
+    let mut graph = InMemoryGraphStore::new();
+    let mut a1 = HashMap::new();
+    a1.insert("x".to_string(), Value::Int(1));
+    let a1_id = graph.add_node(vec!["A".to_string()], a1).unwrap();
+    let mut a2 = HashMap::new();
+    a2.insert("x".to_string(), Value::Int(2));
+    graph.add_node(vec!["A".to_string()], a2).unwrap();
+
+    let mut b1 = HashMap::new();
+    b1.insert("y".to_string(), Value::Int(1));
+    let b1_id = graph.add_node(vec!["B".to_string()], b1).unwrap();
+    let mut b2 = HashMap::new();
+    b2.insert("y".to_string(), Value::Int(3));
+    graph.add_node(vec!["B".to_string()], b2).unwrap();
+
+    let query = parse("MATCH (a:A), (b:B) WHERE a.x = b.y RETURN a, b").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), a1_id);
+    assert_eq!(result.rows[0][1].as_u64().unwrap(), b1_id);
+}
+
+#[test]
+fn no_rows_when_no_join_key_matches() {
+    let mut graph = InMemoryGraphStore::new();
+    let mut a1 = HashMap::new();
+    a1.insert("x".to_string(), Value::Int(1));
+    graph.add_node(vec!["A".to_string()], a1).unwrap();
+    let mut b1 = HashMap::new();
+    b1.insert("y".to_string(), Value::Int(99));
+    graph.add_node(vec!["B".to_string()], b1).unwrap();
+
+    let query = parse("MATCH (a:A), (b:B) WHERE a.x = b.y RETURN a, b").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert!(result.rows.is_empty());
+}
@@ -0,0 +1,235 @@
+// Integration test: Engine::merge_branch's three-way conflict resolution,
+// covering the cases merge.rs's `resolve` has to get right: a clean
+// passthrough when only one side changed, a no-op when both sides made the
+// identical change, genuinely divergent edits under both `MergeStrategy`
+// variants, and the delete-vs-edit divergence under both variants (this is
+// the case `resolve` used to hard-code as `Conflict` regardless of
+// strategy).
+
+#[cfg(feature = "fs")]
+fn temp_engine(name: &str) -> (casys_engine::Engine, casys_engine::DbHandle) {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir()
+        .unwrap()
+        .join("target")
+        .join("tmp")
+        .join(format!("merge_{name}_{now}"));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let eng = casys_engine::Engine::open_fs_composite(&root).unwrap();
+    let db = eng.open_database("testdb").unwrap();
+    (eng, db)
+}
+
+/// Flushes a store holding a single `Person` node named `name` onto `main`,
+/// then forks `source` and `target` from it a few milliseconds apart so
+/// both branches start from that identical state but get distinct fork
+/// timestamps.
+#[cfg(feature = "fs")]
+fn fork_two_branches_from(eng: &casys_engine::Engine, db: &casys_engine::DbHandle, name: &str) {
+    use casys_engine::index::GraphWriteStore;
+
+    let main = eng.open_branch(db, "main").unwrap();
+    let mut store = casys_engine::index::InMemoryGraphStore::new();
+    store
+        .add_node(
+            vec!["Person".to_string()],
+            std::collections::HashMap::from([("name".to_string(), casys_engine::Value::String(name.to_string()))]),
+        )
+        .unwrap();
+    eng.flush_branch(db, &main, &store, None).unwrap();
+
+    eng.create_branch(db, "main", "source", None).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    eng.create_branch(db, "main", "target", None).unwrap();
+}
+
+/// Loads `branch`, renames its one node to `name`, and flushes it back.
+/// Callers sequence calls with a sleep between them to control which
+/// branch's edit clock ends up newer.
+#[cfg(feature = "fs")]
+fn rename_only_node(eng: &casys_engine::Engine, db: &casys_engine::DbHandle, branch: &casys_engine::BranchHandle, name: &str) {
+    use casys_engine::index::GraphReadStore;
+
+    let mut store = eng.load_branch(db, branch).unwrap();
+    let id = store.scan_all().unwrap()[0].id;
+    store
+        .set_node_property_versioned(id, "name", Some(casys_engine::Value::String(name.to_string())), None)
+        .unwrap();
+    eng.flush_branch(db, branch, &store, None).unwrap();
+}
+
+/// Loads `branch` and deletes its one node, then flushes it back.
+#[cfg(feature = "fs")]
+fn delete_only_node(eng: &casys_engine::Engine, db: &casys_engine::DbHandle, branch: &casys_engine::BranchHandle) {
+    use casys_engine::index::{GraphReadStore, GraphWriteStore};
+
+    let mut store = eng.load_branch(db, branch).unwrap();
+    let id = store.scan_all().unwrap()[0].id;
+    store.delete_node(id).unwrap();
+    eng.flush_branch(db, branch, &store, None).unwrap();
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn a_change_on_only_one_side_passes_through_unchanged() {
+    use casys_engine::index::GraphReadStore;
+    use casys_engine::merge::MergeStrategy;
+
+    let (eng, db) = temp_engine("single_side");
+    fork_two_branches_from(&eng, &db, "Alice");
+
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    rename_only_node(&eng, &db, &source, "Alicia");
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::LastWriterWins).unwrap();
+    assert_eq!(summary.conflicted, 0);
+    assert_eq!(summary.updated, 1);
+
+    let merged = eng.load_branch(&db, &target).unwrap();
+    let nodes = merged.scan_all().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].properties["name"], casys_engine::Value::String("Alicia".to_string()));
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn identical_edits_on_both_sides_are_not_a_conflict() {
+    use casys_engine::index::GraphReadStore;
+    use casys_engine::merge::MergeStrategy;
+
+    let (eng, db) = temp_engine("identical_edit");
+    fork_two_branches_from(&eng, &db, "Alice");
+
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    rename_only_node(&eng, &db, &source, "Alicia");
+    rename_only_node(&eng, &db, &target, "Alicia");
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::LastWriterWins).unwrap();
+    assert_eq!(summary.conflicted, 0);
+
+    let merged = eng.load_branch(&db, &target).unwrap();
+    let nodes = merged.scan_all().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].properties["name"], casys_engine::Value::String("Alicia".to_string()));
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn divergent_edits_under_last_writer_wins_keep_the_newer_clock() {
+    use casys_engine::index::GraphReadStore;
+    use casys_engine::merge::MergeStrategy;
+
+    let (eng, db) = temp_engine("divergent_lww");
+    fork_two_branches_from(&eng, &db, "Alice");
+
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    rename_only_node(&eng, &db, &target, "Bob");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    // source's edit is the later clock, so merging it into target must
+    // overwrite target's own conflicting edit rather than keep it.
+    rename_only_node(&eng, &db, &source, "Carol");
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::LastWriterWins).unwrap();
+    assert_eq!(summary.conflicted, 0);
+
+    let merged = eng.load_branch(&db, &target).unwrap();
+    let nodes = merged.scan_all().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].properties["name"], casys_engine::Value::String("Carol".to_string()));
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn divergent_edits_under_fail_on_conflict_are_reported_and_target_is_untouched() {
+    use casys_engine::index::GraphReadStore;
+    use casys_engine::merge::{ConflictId, MergeStrategy};
+
+    let (eng, db) = temp_engine("divergent_foc");
+    fork_two_branches_from(&eng, &db, "Alice");
+
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    rename_only_node(&eng, &db, &source, "Carol");
+    rename_only_node(&eng, &db, &target, "Bob");
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::FailOnConflict).unwrap();
+    assert_eq!(summary.conflicted, 1);
+    assert!(matches!(summary.conflicts[0], ConflictId::Node(_)));
+
+    // FailOnConflict leaves target exactly as it was.
+    let untouched = eng.load_branch(&db, &target).unwrap();
+    let nodes = untouched.scan_all().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].properties["name"], casys_engine::Value::String("Bob".to_string()));
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn delete_vs_edit_under_last_writer_wins_keeps_whichever_clock_is_newer() {
+    use casys_engine::index::GraphReadStore;
+    use casys_engine::merge::MergeStrategy;
+
+    // Edit newer than delete: the edit wins and the node survives.
+    let (eng, db) = temp_engine("delete_edit_lww_edit_newer");
+    fork_two_branches_from(&eng, &db, "Alice");
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    delete_only_node(&eng, &db, &source);
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    rename_only_node(&eng, &db, &target, "Bob");
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::LastWriterWins).unwrap();
+    assert_eq!(summary.conflicted, 0);
+    let merged = eng.load_branch(&db, &target).unwrap();
+    let nodes = merged.scan_all().unwrap();
+    assert_eq!(nodes.len(), 1, "a strictly newer edit must keep the node rather than resurrect the delete's default");
+    assert_eq!(nodes[0].properties["name"], casys_engine::Value::String("Bob".to_string()));
+
+    // Delete newer than edit: the delete wins and the node is gone.
+    let (eng, db) = temp_engine("delete_edit_lww_delete_newer");
+    fork_two_branches_from(&eng, &db, "Alice");
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    rename_only_node(&eng, &db, &target, "Bob");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    delete_only_node(&eng, &db, &source);
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::LastWriterWins).unwrap();
+    assert_eq!(summary.conflicted, 0);
+    let merged = eng.load_branch(&db, &target).unwrap();
+    assert!(merged.scan_all().unwrap().is_empty(), "a strictly newer delete must remove the node rather than keep the older edit");
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn delete_vs_edit_under_fail_on_conflict_is_always_a_conflict() {
+    use casys_engine::index::GraphReadStore;
+    use casys_engine::merge::MergeStrategy;
+
+    let (eng, db) = temp_engine("delete_edit_foc");
+    fork_two_branches_from(&eng, &db, "Alice");
+    let source = eng.open_branch(&db, "source").unwrap();
+    let target = eng.open_branch(&db, "target").unwrap();
+    delete_only_node(&eng, &db, &source);
+    rename_only_node(&eng, &db, &target, "Bob");
+
+    let summary = eng.merge_branch(&db, &source, &target, MergeStrategy::FailOnConflict).unwrap();
+    assert_eq!(summary.conflicted, 1);
+
+    let untouched = eng.load_branch(&db, &target).unwrap();
+    let nodes = untouched.scan_all().unwrap();
+    assert_eq!(nodes.len(), 1, "FailOnConflict must leave target's edit exactly as it was");
+    assert_eq!(nodes[0].properties["name"], casys_engine::Value::String("Bob".to_string()));
+}
+
+#[cfg(not(feature = "fs"))]
+#[test]
+fn skip_merge_tests_without_fs() {
+    // This test is a no-op when the fs feature is not enabled
+}
@@ -0,0 +1,31 @@
+// Integration test: engine metrics/observability subsystem (chunk2-7)
+
+#[cfg(all(feature = "fs", feature = "metrics"))]
+#[test]
+fn commit_and_snapshot_counters_reflect_activity() {
+    use casys_engine as engine;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::fs;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("engine_metrics_{now}"));
+    fs::create_dir_all(&root).unwrap();
+
+    let eng = engine::Engine::open(&root).unwrap();
+    let db = eng.open_database("acme").unwrap();
+    let branch = eng.open_branch(&db, "main").unwrap();
+
+    eng.commit_tx(&branch, &[b"one".to_vec(), b"two".to_vec()]).unwrap();
+    eng.snapshot(&branch, None).unwrap();
+
+    let snap = eng.metrics_snapshot();
+    assert!(snap.bytes_written_total > 0);
+    let b = snap.branches.iter().find(|b| b.db == "acme" && b.branch == "main").expect("branch metrics present");
+    assert_eq!(b.commit_count, 1);
+    assert_eq!(b.commit_records_total, 2);
+    assert_eq!(b.snapshot_count, 1);
+
+    let text = engine::metrics::to_prometheus_text(&snap);
+    assert!(text.contains("casys_commit_total{db=\"acme\",branch=\"main\"} 1"));
+}
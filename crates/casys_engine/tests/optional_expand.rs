@@ -0,0 +1,106 @@
+//! Tests for `Expand`'s `optional` flag (Cypher's `OPTIONAL MATCH` / left-join
+//! semantics), both for a single-hop and a variable-length expansion.
+//!
+//! No GQL syntax sets this flag yet, so each test builds the
+//! `PlanNode::Expand` directly, the same way `path_uniqueness.rs` does for
+//! path-uniqueness modes.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::ast::{DepthRange, Direction, Expr, PathUniqueness, ReturnItem};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::planner::{ExecutionPlan, PlanNode};
+use casys_engine::index::InMemoryGraphStore;
+
+fn single_hop_plan(optional: bool) -> PlanNode {
+    PlanNode::Project {
+        input: Box::new(PlanNode::Expand {
+            input: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+            from_var: "a".to_string(),
+            edge_var: Some("r".to_string()),
+            to_var: "b".to_string(),
+            edge_type: Some("KNOWS".to_string()),
+            direction: Direction::Right,
+            depth: None,
+            path_uniqueness: PathUniqueness::default(),
+            optional,
+        }),
+        items: vec![
+            ReturnItem { expr: Expr::Ident("a".to_string()), alias: None },
+            ReturnItem { expr: Expr::Ident("b".to_string()), alias: None },
+        ],
+    }
+}
+
+fn variable_length_plan(optional: bool) -> PlanNode {
+    PlanNode::Project {
+        input: Box::new(PlanNode::Expand {
+            input: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+            from_var: "a".to_string(),
+            edge_var: None,
+            to_var: "b".to_string(),
+            edge_type: Some("KNOWS".to_string()),
+            direction: Direction::Right,
+            depth: Some(DepthRange { min: 1, max: 3 }),
+            path_uniqueness: PathUniqueness::default(),
+            optional,
+        }),
+        items: vec![
+            ReturnItem { expr: Expr::Ident("a".to_string()), alias: None },
+            ReturnItem { expr: Expr::Ident("b".to_string()), alias: None },
+        ],
+    }
+}
+
+#[test]
+fn non_optional_single_hop_drops_unmatched_rows() {
+    let mut graph = InMemoryGraphStore::new();
+    graph.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: single_hop_plan(false) };
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 0);
+}
+
+#[test]
+fn optional_single_hop_keeps_the_row_with_a_null_target() {
+    let mut graph = InMemoryGraphStore::new();
+    let lonely = graph.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: single_hop_plan(true) };
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), lonely);
+    assert_eq!(result.rows[0][1], Value::Null);
+}
+
+#[test]
+fn optional_single_hop_does_not_affect_rows_with_a_match() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "KNOWS".to_string(), HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: single_hop_plan(true) };
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), a);
+    assert_eq!(result.rows[0][1].as_u64().unwrap(), b);
+}
+
+#[test]
+fn optional_variable_length_keeps_the_row_with_a_null_target() {
+    let mut graph = InMemoryGraphStore::new();
+    let lonely = graph.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: variable_length_plan(true) };
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][0].as_u64().unwrap(), lonely);
+    assert_eq!(result.rows[0][1], Value::Null);
+}
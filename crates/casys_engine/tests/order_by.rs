@@ -0,0 +1,114 @@
+//! Tests for `PlanNode::OrderBy`'s execution, including the spill-to-disk
+//! path taken once the row count passes `Executor::with_sort_spill_threshold`.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::Planner;
+use casys_engine::index::InMemoryGraphStore;
+
+fn graph_with_scores(scores: &[Option<i64>]) -> InMemoryGraphStore {
+    let mut graph = InMemoryGraphStore::new();
+    for score in scores {
+        let mut props = HashMap::new();
+        if let Some(s) = score {
+            props.insert("score".to_string(), Value::Int(*s));
+        }
+        graph.add_node(vec!["Item".to_string()], props).unwrap();
+    }
+    graph
+}
+
+fn scores_in_order(rows: &[Vec<serde_json::Value>]) -> Vec<Option<i64>> {
+    rows.iter().map(|row| row[0].as_i64()).collect()
+}
+
+#[test]
+fn sorts_ascending_in_memory() {
+    let graph = graph_with_scores(&[Some(3), Some(1), Some(2)]);
+    let query = parse("MATCH (a:Item) RETURN a.score ORDER BY a.score").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(scores_in_order(&result.rows), vec![Some(1), Some(2), Some(3)]);
+}
+
+#[test]
+fn sorts_descending_in_memory() {
+    let graph = graph_with_scores(&[Some(3), Some(1), Some(2)]);
+    let query = parse("MATCH (a:Item) RETURN a.score ORDER BY a.score DESC").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let result = Executor::new(&graph).execute(&plan, None).unwrap();
+
+    assert_eq!(scores_in_order(&result.rows), vec![Some(3), Some(2), Some(1)]);
+}
+
+#[test]
+fn nulls_sort_last_regardless_of_direction() {
+    let graph = graph_with_scores(&[Some(1), None, Some(2)]);
+
+    let query = parse("MATCH (a:Item) RETURN a.score ORDER BY a.score").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let asc = Executor::new(&graph).execute(&plan, None).unwrap();
+    assert_eq!(scores_in_order(&asc.rows), vec![Some(1), Some(2), None]);
+
+    let query = parse("MATCH (a:Item) RETURN a.score ORDER BY a.score DESC").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+    let desc = Executor::new(&graph).execute(&plan, None).unwrap();
+    assert_eq!(scores_in_order(&desc.rows), vec![Some(2), Some(1), None]);
+}
+
+#[test]
+fn spilling_to_disk_matches_the_in_memory_sort() {
+    // Distinct values, so there's no tie for the merge's run-index tiebreak
+    // to resolve differently than the in-memory sort's original-order
+    // tiebreak - the two are expected to agree on ordering, not on
+    // tie-breaking.
+    let values: Vec<Option<i64>> = vec![
+        Some(11), Some(3), Some(19), Some(7), Some(23), Some(1), Some(15),
+        Some(9), Some(21), Some(5), Some(17), Some(13), None,
+    ];
+    let graph = graph_with_scores(&values);
+    let query = parse("MATCH (a:Item) RETURN a.score ORDER BY a.score").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    let in_memory = Executor::new(&graph).execute(&plan, None).unwrap();
+    // Force every chunk (and the final merge) to spill: a threshold smaller
+    // than the row count, small enough to produce several runs.
+    let spilled = Executor::new(&graph).with_sort_spill_threshold(4).execute(&plan, None).unwrap();
+
+    assert_eq!(scores_in_order(&spilled.rows), scores_in_order(&in_memory.rows));
+    let sorted = scores_in_order(&in_memory.rows);
+    assert_eq!(sorted.last(), Some(&None), "Null should sort last: {sorted:?}");
+    assert!(sorted[..sorted.len() - 1].windows(2).all(|w| w[0].unwrap() <= w[1].unwrap()));
+}
+
+#[test]
+fn spilling_to_disk_respects_descending_and_multi_key_order() {
+    let mut graph = InMemoryGraphStore::new();
+    for i in 0..12 {
+        let mut props = HashMap::new();
+        props.insert("group".to_string(), Value::Int(i % 3));
+        props.insert("score".to_string(), Value::Int(i));
+        graph.add_node(vec!["Item".to_string()], props).unwrap();
+    }
+    let query = parse("MATCH (a:Item) RETURN a.group, a.score ORDER BY a.group, a.score DESC").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    let in_memory = Executor::new(&graph).execute(&plan, None).unwrap();
+    let spilled = Executor::new(&graph).with_sort_spill_threshold(3).execute(&plan, None).unwrap();
+
+    assert_eq!(format!("{:?}", spilled.rows), format!("{:?}", in_memory.rows));
+
+    let mut prev: Option<(i64, i64)> = None;
+    for row in &in_memory.rows {
+        let group = row[0].as_i64().unwrap();
+        let score = row[1].as_i64().unwrap();
+        if let Some((pg, ps)) = prev {
+            assert!((group, std::cmp::Reverse(score)) >= (pg, std::cmp::Reverse(ps)));
+        }
+        prev = Some((group, score));
+    }
+}
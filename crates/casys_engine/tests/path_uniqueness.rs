@@ -0,0 +1,94 @@
+//! Tests for `Expand`'s path-uniqueness modes (`Walk`/`Trail`/`AcyclicPath`)
+//! over a small cyclic graph.
+//!
+//! No GQL syntax surfaces these modes yet, so each test builds the
+//! `PlanNode::Expand` directly rather than going through `parser::parse`.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphReadStore, GraphWriteStore};
+use casys_engine::exec::ast::{Direction, DepthRange, Expr, PathUniqueness, ReturnItem};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::planner::{ExecutionPlan, PlanNode};
+use casys_engine::index::InMemoryGraphStore;
+
+/// A(start) -> B -> C -> A (triangle) plus A -> D and C -> D, so D is
+/// reachable directly at depth 1 (excluded by `min_depth: 2` below) and
+/// again at depth 3 by going around the triangle first.
+fn build_graph() -> (InMemoryGraphStore, u64, u64) {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec![], HashMap::new()).unwrap();
+    let d = graph.add_node(vec![], HashMap::new()).unwrap();
+
+    graph.add_edge(a, b, "R".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "R".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(c, a, "R".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(a, d, "R".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(c, d, "R".to_string(), HashMap::new()).unwrap();
+
+    (graph, a, d)
+}
+
+fn reachable_ids(graph: &InMemoryGraphStore, uniqueness: PathUniqueness) -> Vec<u64> {
+    let plan = ExecutionPlan {
+        root: PlanNode::Project {
+            input: Box::new(PlanNode::Expand {
+                input: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Start".to_string() }),
+                from_var: "a".to_string(),
+                edge_var: None,
+                to_var: "b".to_string(),
+                edge_type: None,
+                direction: Direction::Right,
+                depth: Some(DepthRange { min: 2, max: 3 }),
+                path_uniqueness: uniqueness,
+                optional: false,
+            }),
+            items: vec![ReturnItem { expr: Expr::Ident("b".to_string()), alias: None }],
+        },
+    };
+
+    let executor = Executor::new(graph);
+    let result = executor.execute(&plan, None).unwrap();
+    let mut ids: Vec<u64> = result
+        .rows
+        .into_iter()
+        .map(|row| row[0].as_u64().expect("b column should be a NodeId"))
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn walk_misses_the_node_only_reachable_by_revisiting_a_dead_end() {
+    let (graph, _a, _d) = build_graph();
+    let ids = reachable_ids(&graph, PathUniqueness::Walk);
+
+    // D's shortest walk (depth 1) is outside the [2, 3] window, and Walk's
+    // global per-node visited set never lets the BFS reach it again via the
+    // longer A-B-C-D trail, so only C (depth 2) comes back.
+    assert_eq!(ids.len(), 1, "ids: {:?}", ids);
+}
+
+#[test]
+fn trail_finds_the_node_via_its_longer_non_repeating_route() {
+    let (graph, _a, d) = build_graph();
+    let ids = reachable_ids(&graph, PathUniqueness::Trail);
+
+    // A-B-C-D at depth 3 never repeats an edge, so Trail (unlike Walk) still
+    // finds D once it's back within the [2, 3] window.
+    assert_eq!(ids.len(), 2, "ids: {:?}", ids);
+    assert!(ids.contains(&d), "ids: {:?}", ids);
+}
+
+#[test]
+fn acyclic_path_finds_the_same_route_here_since_it_repeats_no_node_either() {
+    let (graph, _a, d) = build_graph();
+    let ids = reachable_ids(&graph, PathUniqueness::AcyclicPath);
+
+    // A-B-C-D also never repeats a node, so AcyclicPath agrees with Trail on
+    // this particular graph even though it's the stricter of the two modes.
+    assert_eq!(ids.len(), 2, "ids: {:?}", ids);
+    assert!(ids.contains(&d), "ids: {:?}", ids);
+}
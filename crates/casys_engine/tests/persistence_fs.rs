@@ -24,7 +24,7 @@ fn persistence_roundtrip_flush_load() {
     let _ = eng.execute_gql_on_store(&mut store, &create, None).unwrap();
 
     // Flush to disk (segments)
-    eng.flush_branch(&db, &br, &store).unwrap();
+    eng.flush_branch(&db, &br, &store, None).unwrap();
 
     // Load back into a new store
     let mut loaded = eng.load_branch(&db, &br).unwrap();
@@ -0,0 +1,157 @@
+//! Tests for the logical-to-physical lowering (`casys_engine::exec::physical`).
+
+use casys_engine::exec::ast::{BinOp, Direction, Expr, Literal};
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::physical::{to_physical, PhysicalPlan, Statistics};
+use casys_engine::exec::planner::{Planner, PlanNode};
+
+fn plan_for(query: &str) -> PlanNode {
+    let query = parse(query).unwrap();
+    Planner::plan(&query).unwrap().root
+}
+
+#[test]
+fn label_scan_and_filter_stay_separate_without_an_index() {
+    let plan = plan_for("MATCH (a:Person {age: 30}) RETURN a");
+    let physical = to_physical(&plan, &Statistics::new()).unwrap();
+
+    assert!(
+        format!("{:?}", physical).contains("FilterExec"),
+        "expected a plain FilterExec with no index registered: {:#?}",
+        physical
+    );
+}
+
+#[test]
+fn property_index_seek_replaces_label_scan_and_filter_when_indexed() {
+    let plan = plan_for("MATCH (a:Person {age: 30}) RETURN a");
+    let stats = Statistics::new().with_property_index("Person", "age");
+    let physical = to_physical(&plan, &stats).unwrap();
+
+    let seek = match &physical {
+        PhysicalPlan::ProjectExec { input, .. } => input.as_ref(),
+        other => other,
+    };
+    match seek {
+        PhysicalPlan::NodeByPropertyIndexSeek { label, property, value, .. } => {
+            assert_eq!(label, "Person");
+            assert_eq!(property, "age");
+            assert_eq!(value, &Literal::Int(30));
+        }
+        other => panic!("expected a NodeByPropertyIndexSeek, got {:#?}", other),
+    }
+}
+
+#[test]
+fn index_seek_keeps_a_residual_filter_for_the_remaining_conjunct() {
+    let plan = PlanNode::Filter {
+        input: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+        predicate: Expr::BinaryOp(
+            Box::new(Expr::BinaryOp(
+                Box::new(Expr::Property("a".to_string(), "age".to_string())),
+                BinOp::Eq,
+                Box::new(Expr::Literal(Literal::Int(30))),
+            )),
+            BinOp::And,
+            Box::new(Expr::BinaryOp(
+                Box::new(Expr::Property("a".to_string(), "active".to_string())),
+                BinOp::Eq,
+                Box::new(Expr::Literal(Literal::Bool(true))),
+            )),
+        ),
+    };
+    let stats = Statistics::new().with_property_index("Person", "age");
+    let physical = to_physical(&plan, &stats).unwrap();
+
+    match physical {
+        PhysicalPlan::FilterExec { input, predicate } => {
+            assert!(matches!(*input, PhysicalPlan::NodeByPropertyIndexSeek { .. }), "input: {:#?}", input);
+            assert!(format!("{:?}", predicate).contains("active"), "predicate: {:#?}", predicate);
+        }
+        other => panic!("expected a FilterExec wrapping the seek, got {:#?}", other),
+    }
+}
+
+#[test]
+fn expand_all_is_chosen_when_the_to_var_is_fresh() {
+    let plan = PlanNode::Expand {
+        input: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+        from_var: "a".to_string(),
+        edge_var: None,
+        to_var: "b".to_string(),
+        edge_type: None,
+        direction: Direction::Right,
+        depth: None,
+        path_uniqueness: Default::default(),
+        optional: false,
+    };
+    let physical = to_physical(&plan, &Statistics::new()).unwrap();
+
+    assert!(matches!(physical, PhysicalPlan::ExpandAll { .. }), "physical: {:#?}", physical);
+}
+
+#[test]
+fn expand_into_is_chosen_when_the_to_var_is_already_bound() {
+    let plan = PlanNode::Expand {
+        input: Box::new(PlanNode::CartesianProduct {
+            left: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+            right: Box::new(PlanNode::LabelScan { variable: "b".to_string(), label: "Person".to_string() }),
+        }),
+        from_var: "a".to_string(),
+        edge_var: None,
+        to_var: "b".to_string(),
+        edge_type: None,
+        direction: Direction::Right,
+        depth: None,
+        path_uniqueness: Default::default(),
+        optional: false,
+    };
+    let physical = to_physical(&plan, &Statistics::new()).unwrap();
+
+    assert!(matches!(physical, PhysicalPlan::ExpandInto { .. }), "physical: {:#?}", physical);
+}
+
+#[test]
+fn hash_join_is_chosen_for_an_equi_join_filter_over_a_cartesian_product() {
+    let plan = PlanNode::Filter {
+        input: Box::new(PlanNode::CartesianProduct {
+            left: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+            right: Box::new(PlanNode::LabelScan { variable: "b".to_string(), label: "Person".to_string() }),
+        }),
+        predicate: Expr::BinaryOp(
+            Box::new(Expr::Property("a".to_string(), "id".to_string())),
+            BinOp::Eq,
+            Box::new(Expr::Property("b".to_string(), "id".to_string())),
+        ),
+    };
+    let physical = to_physical(&plan, &Statistics::new()).unwrap();
+
+    match physical {
+        PhysicalPlan::HashJoin { join_keys, residual, .. } => {
+            assert_eq!(
+                join_keys,
+                vec![(Expr::Property("a".to_string(), "id".to_string()), Expr::Property("b".to_string(), "id".to_string()))]
+            );
+            assert!(residual.is_none());
+        }
+        other => panic!("expected a HashJoin, got {:#?}", other),
+    }
+}
+
+#[test]
+fn nested_loop_join_is_the_fallback_without_an_equi_join_conjunct() {
+    let plan = PlanNode::Filter {
+        input: Box::new(PlanNode::CartesianProduct {
+            left: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Person".to_string() }),
+            right: Box::new(PlanNode::LabelScan { variable: "b".to_string(), label: "Person".to_string() }),
+        }),
+        predicate: Expr::BinaryOp(
+            Box::new(Expr::Property("a".to_string(), "age".to_string())),
+            BinOp::Lt,
+            Box::new(Expr::Property("b".to_string(), "age".to_string())),
+        ),
+    };
+    let physical = to_physical(&plan, &Statistics::new()).unwrap();
+
+    assert!(matches!(physical, PhysicalPlan::NestedLoopJoin { predicate: Some(_), .. }), "physical: {:#?}", physical);
+}
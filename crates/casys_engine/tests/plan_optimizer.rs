@@ -0,0 +1,140 @@
+//! Tests for the plan-level predicate pushdown and common-subexpression
+//! elimination passes (`casys_engine::exec::plan_optimizer`).
+
+use casys_engine::exec::ast::Expr;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::{Planner, PlanNode};
+
+fn count_filters(node: &PlanNode) -> usize {
+    match node {
+        PlanNode::Filter { input, .. } => 1 + count_filters(input),
+        PlanNode::Expand { input, .. }
+        | PlanNode::Project { input, .. }
+        | PlanNode::OrderBy { input, .. }
+        | PlanNode::Aggregate { input, .. }
+        | PlanNode::Limit { input, .. }
+        | PlanNode::MatchCreate { match_input: input, .. } => count_filters(input),
+        PlanNode::CartesianProduct { left, right } | PlanNode::HashJoin { left, right, .. } => {
+            count_filters(left) + count_filters(right)
+        }
+        PlanNode::ShortestPath { input, .. } => count_filters(input),
+        PlanNode::LabelScan { .. }
+        | PlanNode::FullScan { .. }
+        | PlanNode::Create { .. }
+        | PlanNode::UnwindCreate { .. }
+        | PlanNode::GraphAlgo { .. }
+        | PlanNode::Fixpoint { .. } => 0,
+    }
+}
+
+#[test]
+fn collapses_duplicate_property_filter_from_both_expand_and_safety_net() {
+    let query = parse("MATCH (a {x:1})-[:R]->(b {y:2}) RETURN a").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    // The planner's own safety net re-applies `a.x=1` at the Expand filter and
+    // again as a top-level Filter; CSE should collapse all of that to the one
+    // Filter actually needed to constrain the starting scan.
+    assert_eq!(count_filters(&plan.root), 1, "plan: {:#?}", plan.root);
+}
+
+#[test]
+fn keeps_distinct_filters_on_unrelated_variables() {
+    let query = parse("MATCH (a {x:1}), (c {z:3}) RETURN a, c").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    assert_eq!(count_filters(&plan.root), 2, "plan: {:#?}", plan.root);
+}
+
+#[test]
+fn is_idempotent() {
+    let query = parse("MATCH (a {x:1})-[:R]->(b {y:2}) RETURN a").unwrap();
+    let once = Planner::plan(&query).unwrap();
+    let twice = casys_engine::exec::plan_optimizer::eliminate_common_filters(once.root.clone());
+
+    assert_eq!(format!("{:?}", once.root), format!("{:?}", twice));
+}
+
+/// Unwraps `Project -> ... -> CartesianProduct`, skipping any pass-through
+/// nodes in between, so tests can inspect what ended up directly under each
+/// branch of the join.
+fn cartesian_branches(node: &PlanNode) -> (&PlanNode, &PlanNode) {
+    match node {
+        PlanNode::CartesianProduct { left, right } => (left, right),
+        PlanNode::Filter { input, .. }
+        | PlanNode::Project { input, .. }
+        | PlanNode::OrderBy { input, .. }
+        | PlanNode::Aggregate { input, .. }
+        | PlanNode::Limit { input, .. }
+        | PlanNode::MatchCreate { match_input: input, .. } => cartesian_branches(input),
+        other => panic!("expected a CartesianProduct somewhere in the plan, got {:#?}", other),
+    }
+}
+
+#[test]
+fn pushes_where_conjuncts_onto_their_own_cartesian_branch() {
+    let query = parse("MATCH (a:A), (b:B) WHERE a.x = 1 AND b.y = 2 RETURN a, b").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    // Each conjunct only needs one side's variable, so pushdown should leave
+    // no Filter sitting above the join itself - just one per branch.
+    assert_eq!(count_filters(&plan.root), 2, "plan: {:#?}", plan.root);
+    let (left, right) = cartesian_branches(&plan.root);
+    assert!(matches!(left, PlanNode::Filter { .. }), "plan: {:#?}", plan.root);
+    assert!(matches!(right, PlanNode::Filter { .. }), "plan: {:#?}", plan.root);
+}
+
+/// Unwraps `Project -> ... -> HashJoin`, the same way `cartesian_branches`
+/// does for a plain `CartesianProduct`.
+fn hash_join(node: &PlanNode) -> (&PlanNode, &PlanNode, &[(Expr, Expr)]) {
+    match node {
+        PlanNode::HashJoin { left, right, join_keys } => (left, right, join_keys),
+        PlanNode::Filter { input, .. }
+        | PlanNode::Project { input, .. }
+        | PlanNode::OrderBy { input, .. }
+        | PlanNode::Aggregate { input, .. }
+        | PlanNode::Limit { input, .. }
+        | PlanNode::MatchCreate { match_input: input, .. } => hash_join(input),
+        other => panic!("expected a HashJoin somewhere in the plan, got {:#?}", other),
+    }
+}
+
+#[test]
+fn promotes_a_cross_branch_equi_join_to_a_hash_join() {
+    let query = parse("MATCH (a:A), (b:B) WHERE a.x = b.y RETURN a, b").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    // `a.x = b.y` needs variables from both branches, so it can't be pushed
+    // below the join - but since it's an equi-join conjunct, it's pulled out
+    // as a HashJoin key rather than left sitting above a CartesianProduct.
+    assert_eq!(count_filters(&plan.root), 0, "plan: {:#?}", plan.root);
+    let (left, right, join_keys) = hash_join(&plan.root);
+    assert!(matches!(left, PlanNode::LabelScan { .. }), "plan: {:#?}", plan.root);
+    assert!(matches!(right, PlanNode::LabelScan { .. }), "plan: {:#?}", plan.root);
+    assert_eq!(
+        join_keys,
+        vec![(Expr::Property("a".to_string(), "x".to_string()), Expr::Property("b".to_string(), "y".to_string()))]
+    );
+}
+
+#[test]
+fn falls_back_to_cartesian_product_without_an_equi_join_conjunct() {
+    let query = parse("MATCH (a:A), (b:B) WHERE a.x < b.y RETURN a, b").unwrap();
+    let plan = Planner::plan(&query).unwrap();
+
+    // `<` isn't an equi-join, so there's no key to hash on - it stays a
+    // Filter over a CartesianProduct.
+    assert_eq!(count_filters(&plan.root), 1, "plan: {:#?}", plan.root);
+    let (left, right) = cartesian_branches(&plan.root);
+    assert!(matches!(left, PlanNode::LabelScan { .. }), "plan: {:#?}", plan.root);
+    assert!(matches!(right, PlanNode::LabelScan { .. }), "plan: {:#?}", plan.root);
+}
+
+#[test]
+fn push_down_predicates_is_idempotent() {
+    let query = parse("MATCH (a:A), (b:B) WHERE a.x = 1 AND b.y = 2 RETURN a, b").unwrap();
+    let once = Planner::plan(&query).unwrap();
+    let twice = casys_engine::exec::plan_optimizer::push_down_predicates(once.root.clone());
+
+    assert_eq!(format!("{:?}", once.root), format!("{:?}", twice));
+}
@@ -0,0 +1,66 @@
+//! Tests for `InMemoryGraphStore`'s property range index: range scans
+//! return matching ids in ascending order, the index tracks adds/deletes,
+//! and an unset property scans as empty rather than erroring.
+
+use std::collections::HashMap;
+use std::ops::Bound;
+
+use casys_core::{GraphReadStore, GraphWriteStore, Value};
+use casys_engine::index::InMemoryGraphStore;
+
+fn node_with_age(graph: &mut InMemoryGraphStore, age: i64) -> u64 {
+    let mut props = HashMap::new();
+    props.insert("age".to_string(), Value::Int(age));
+    graph.add_node(vec!["Person".to_string()], props).unwrap()
+}
+
+#[test]
+fn scan_by_property_range_returns_matches_in_ascending_order() {
+    let mut graph = InMemoryGraphStore::new();
+    let teen = node_with_age(&mut graph, 17);
+    let adult = node_with_age(&mut graph, 18);
+    let senior = node_with_age(&mut graph, 65);
+
+    let working_age = graph
+        .scan_by_property_range("age", Bound::Included(Value::Int(18)), Bound::Excluded(Value::Int(65)))
+        .unwrap();
+
+    assert_eq!(working_age, vec![adult]);
+    assert!(!working_age.contains(&teen));
+    assert!(!working_age.contains(&senior));
+}
+
+#[test]
+fn scan_by_property_range_is_unbounded_on_either_side() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = node_with_age(&mut graph, 10);
+    let b = node_with_age(&mut graph, 20);
+    let c = node_with_age(&mut graph, 30);
+
+    let all = graph.scan_by_property_range("age", Bound::Unbounded, Bound::Unbounded).unwrap();
+    assert_eq!(all, vec![a, b, c]);
+
+    let at_least_20 = graph.scan_by_property_range("age", Bound::Included(Value::Int(20)), Bound::Unbounded).unwrap();
+    assert_eq!(at_least_20, vec![b, c]);
+}
+
+#[test]
+fn scan_by_property_range_on_an_unset_property_is_empty() {
+    let mut graph = InMemoryGraphStore::new();
+    node_with_age(&mut graph, 42);
+
+    let result = graph.scan_by_property_range("height", Bound::Unbounded, Bound::Unbounded).unwrap();
+    assert!(result.is_empty());
+}
+
+#[test]
+fn deleting_a_node_removes_it_from_the_property_index() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = node_with_age(&mut graph, 25);
+    let b = node_with_age(&mut graph, 30);
+
+    graph.delete_node(a).unwrap();
+
+    let result = graph.scan_by_property_range("age", Bound::Unbounded, Bound::Unbounded).unwrap();
+    assert_eq!(result, vec![b]);
+}
@@ -0,0 +1,53 @@
+// Integration test: RaftBackend::list_branches survives a restart against a
+// real FsBackend (not the MockSegments double in raft_store.rs, which
+// overwrites unconditionally and would never catch a regression in
+// write_segment's overwrite semantics).
+
+#[cfg(feature = "fs")]
+#[test]
+fn list_branches_recovers_every_branch_name_after_a_restart_against_fs_backend() {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use casys_core::{BranchName, DatabaseName, StorageCatalog};
+    use casys_engine::index::persistence::WalRecord;
+    use casys_engine::index::raft_store::RaftBackend;
+    use casys_storage_fs::backend::FsBackend;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir()
+        .unwrap()
+        .join("target")
+        .join("tmp")
+        .join(format!("raft_store_fs_{now}"));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let main = BranchName::try_from("main").unwrap();
+    let feature = BranchName::try_from("feature").unwrap();
+    let segments = Arc::new(FsBackend::new());
+
+    {
+        let backend = RaftBackend::new(segments.clone());
+        backend.propose(&root, &db, &main, WalRecord::AddNode { id: 1, labels: vec!["Person".to_string()], properties: HashMap::new() }).unwrap();
+        // A second branch registration is what previously got silently
+        // dropped: `write_branch_registry` writes the growing branch list
+        // under the same fixed "raft-branches" segment id every time, so
+        // only the first write landed once `write_segment` started
+        // skipping already-existing segment ids.
+        backend.create_branch(&root, &db, &main, &feature, None).unwrap();
+    }
+
+    let restarted = RaftBackend::new(segments);
+    let branches = restarted.list_branches(&root, &db).unwrap();
+    assert_eq!(branches.len(), 2, "both branches registered before the restart must still be reported");
+    assert!(branches.contains(&main));
+    assert!(branches.contains(&feature));
+}
+
+#[cfg(not(feature = "fs"))]
+#[test]
+fn skip_raft_store_fs_without_fs() {
+    // This test is a no-op when the fs feature is not enabled
+}
@@ -0,0 +1,151 @@
+//! Tests for `PlanNode::ShortestPath`'s A*/Dijkstra execution.
+//!
+//! No GQL syntax surfaces this node yet, so each test builds the plan
+//! directly, the same way `path_uniqueness.rs` does for variable-length
+//! `Expand`.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::ast::{BinOp, Direction, Expr, Literal, ReturnItem};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::planner::{ExecutionPlan, PlanNode};
+use casys_engine::index::InMemoryGraphStore;
+use casys_engine::types::EngineError;
+
+fn shortest_path_plan(
+    edge_type: Option<&str>,
+    weight_prop: Option<&str>,
+    heuristic: Option<Expr>,
+) -> PlanNode {
+    PlanNode::Project {
+        input: Box::new(PlanNode::ShortestPath {
+            input: Box::new(PlanNode::CartesianProduct {
+                left: Box::new(PlanNode::LabelScan { variable: "a".to_string(), label: "Start".to_string() }),
+                right: Box::new(PlanNode::LabelScan { variable: "b".to_string(), label: "Goal".to_string() }),
+            }),
+            from_var: "a".to_string(),
+            to_var: "b".to_string(),
+            edge_type: edge_type.map(str::to_string),
+            direction: Direction::Right,
+            weight_prop: weight_prop.map(str::to_string),
+            heuristic,
+            path_var: "path".to_string(),
+            cost_var: "cost".to_string(),
+        }),
+        items: vec![
+            ReturnItem { expr: Expr::Ident("path".to_string()), alias: None },
+            ReturnItem { expr: Expr::Ident("cost".to_string()), alias: None },
+        ],
+    }
+}
+
+#[test]
+fn prefers_the_lower_weight_route_over_fewer_hops() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+
+    let mut direct_props = HashMap::new();
+    direct_props.insert("cost".to_string(), Value::Float(10.0));
+    graph.add_edge(a, c, "ROUTE".to_string(), direct_props).unwrap();
+
+    let mut leg1 = HashMap::new();
+    leg1.insert("cost".to_string(), Value::Float(1.0));
+    graph.add_edge(a, b, "ROUTE".to_string(), leg1).unwrap();
+    let mut leg2 = HashMap::new();
+    leg2.insert("cost".to_string(), Value::Float(1.0));
+    graph.add_edge(b, c, "ROUTE".to_string(), leg2).unwrap();
+
+    let plan = ExecutionPlan { root: shortest_path_plan(Some("ROUTE"), Some("cost"), None) };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    let path = result.rows[0][0].as_array().unwrap();
+    assert_eq!(path.len(), 3);
+    assert_eq!(path[0].as_u64().unwrap(), a);
+    assert_eq!(path[1].as_u64().unwrap(), b);
+    assert_eq!(path[2].as_u64().unwrap(), c);
+    assert_eq!(result.rows[0][1].as_f64().unwrap(), 2.0);
+}
+
+#[test]
+fn defaults_unweighted_edges_to_a_weight_of_one() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: shortest_path_plan(None, Some("cost"), None) };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][1].as_f64().unwrap(), 2.0);
+}
+
+#[test]
+fn returns_no_rows_when_the_goal_is_unreachable() {
+    let mut graph = InMemoryGraphStore::new();
+    graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+
+    let plan = ExecutionPlan { root: shortest_path_plan(None, None, None) };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert!(result.rows.is_empty());
+}
+
+#[test]
+fn rejects_negative_edge_weights() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+    let mut props = HashMap::new();
+    props.insert("cost".to_string(), Value::Float(-1.0));
+    graph.add_edge(a, c, "ROUTE".to_string(), props).unwrap();
+
+    let plan = ExecutionPlan { root: shortest_path_plan(Some("ROUTE"), Some("cost"), None) };
+    let executor = Executor::new(&graph);
+    let err = executor.execute(&plan, None).unwrap_err();
+
+    assert!(matches!(err, EngineError::InvalidArgument(_)));
+}
+
+#[test]
+fn a_custom_heuristic_still_finds_the_optimal_route() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+
+    let mut direct_props = HashMap::new();
+    direct_props.insert("cost".to_string(), Value::Float(5.0));
+    graph.add_edge(a, c, "ROUTE".to_string(), direct_props).unwrap();
+    let mut leg1 = HashMap::new();
+    leg1.insert("cost".to_string(), Value::Float(1.0));
+    graph.add_edge(a, b, "ROUTE".to_string(), leg1).unwrap();
+    let mut leg2 = HashMap::new();
+    leg2.insert("cost".to_string(), Value::Float(1.0));
+    graph.add_edge(b, c, "ROUTE".to_string(), leg2).unwrap();
+
+    // h = 0 for every candidate - an always-admissible (if useless) heuristic,
+    // just exercising that a `heuristic` expression plugs into A* at all.
+    let heuristic = Expr::BinaryOp(
+        Box::new(Expr::Literal(Literal::Int(0))),
+        BinOp::Mul,
+        Box::new(Expr::Literal(Literal::Int(1))),
+    );
+
+    let plan = ExecutionPlan { root: shortest_path_plan(Some("ROUTE"), Some("cost"), Some(heuristic)) };
+    let executor = Executor::new(&graph);
+    let result = executor.execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0][1].as_f64().unwrap(), 2.0);
+}
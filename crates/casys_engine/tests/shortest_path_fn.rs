@@ -0,0 +1,127 @@
+//! Tests for the `shortestPath(...)` scalar function (`Executor::dijkstra_path`,
+//! wired up through the `FunctionCall` arm of `eval_expr`), run through the
+//! full parse/plan/execute pipeline the same way `binary_ops.rs` does.
+
+use std::collections::HashMap;
+
+use casys_core::{GraphWriteStore, Value};
+use casys_engine::exec::executor::Executor;
+use casys_engine::exec::parser::parse;
+use casys_engine::exec::planner::Planner;
+use casys_engine::index::InMemoryGraphStore;
+
+fn single_row_result(graph: &InMemoryGraphStore, query: &str) -> Value {
+    let parsed = parse(query).unwrap();
+    let plan = Planner::plan(&parsed).unwrap();
+    let result = Executor::new(graph).execute(&plan, None).unwrap();
+
+    assert_eq!(result.rows.len(), 1);
+    result.rows[0][0].clone()
+}
+
+#[test]
+fn prefers_the_lower_weight_route_over_fewer_hops() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+
+    let mut direct_props = HashMap::new();
+    direct_props.insert("weight".to_string(), Value::Float(10.0));
+    graph.add_edge(a, c, "ROUTE".to_string(), direct_props).unwrap();
+    let mut leg1 = HashMap::new();
+    leg1.insert("weight".to_string(), Value::Float(1.0));
+    graph.add_edge(a, b, "ROUTE".to_string(), leg1).unwrap();
+    let mut leg2 = HashMap::new();
+    leg2.insert("weight".to_string(), Value::Float(1.0));
+    graph.add_edge(b, c, "ROUTE".to_string(), leg2).unwrap();
+
+    let path = single_row_result(
+        &graph,
+        "MATCH (a:Start), (c:Goal) RETURN shortestPath(a, c, \"ROUTE\")",
+    );
+    let path = path.as_array().unwrap();
+
+    assert_eq!(path.len(), 5, "expected [a, edge, b, edge, c], got {:?}", path);
+    assert_eq!(path[0].as_u64().unwrap(), a);
+    assert_eq!(path[2].as_u64().unwrap(), b);
+    assert_eq!(path[4].as_u64().unwrap(), c);
+}
+
+#[test]
+fn defaults_unweighted_edges_to_a_weight_of_one() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let path = single_row_result(
+        &graph,
+        "MATCH (a:Start), (b:Goal) RETURN shortestPath(a, b)",
+    );
+    let path = path.as_array().unwrap();
+
+    assert_eq!(path, &[Value::NodeId(a), Value::Int(1), Value::NodeId(b)]);
+}
+
+#[test]
+fn matches_a_target_given_as_a_label_instead_of_a_node() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let path = single_row_result(&graph, "MATCH (a:Start) RETURN shortestPath(a, \"Goal\")");
+    let path = path.as_array().unwrap();
+
+    assert_eq!(path.len(), 5);
+    assert_eq!(path[4].as_u64().unwrap(), c);
+}
+
+#[test]
+fn min_depth_excludes_a_shorter_route_that_reaches_the_target_too_early() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let b = graph.add_node(vec![], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+    graph.add_edge(a, c, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+    graph.add_edge(b, c, "LINK".to_string(), HashMap::new()).unwrap();
+
+    let path = single_row_result(
+        &graph,
+        "MATCH (a:Start), (c:Goal) RETURN shortestPath(a, c, null, \"weight\", 2)",
+    );
+    let path = path.as_array().unwrap();
+
+    assert_eq!(path.len(), 5, "should take the longer a-b-c route, got {:?}", path);
+}
+
+#[test]
+fn returns_null_when_the_target_is_unreachable() {
+    let mut graph = InMemoryGraphStore::new();
+    graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+
+    let result = single_row_result(&graph, "MATCH (a:Start) RETURN shortestPath(a, \"Goal\")");
+
+    assert_eq!(result, Value::Null);
+}
+
+#[test]
+fn rejects_negative_edge_weights() {
+    let mut graph = InMemoryGraphStore::new();
+    let a = graph.add_node(vec!["Start".to_string()], HashMap::new()).unwrap();
+    let c = graph.add_node(vec!["Goal".to_string()], HashMap::new()).unwrap();
+    let mut props = HashMap::new();
+    props.insert("weight".to_string(), Value::Float(-1.0));
+    graph.add_edge(a, c, "ROUTE".to_string(), props).unwrap();
+
+    let parsed = parse("MATCH (a:Start), (c:Goal) RETURN shortestPath(a, c)").unwrap();
+    let plan = Planner::plan(&parsed).unwrap();
+    let err = Executor::new(&graph).execute(&plan, None).unwrap_err();
+
+    assert!(matches!(err, casys_engine::types::EngineError::InvalidArgument(_)));
+}
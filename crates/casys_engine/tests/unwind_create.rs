@@ -0,0 +1,59 @@
+//! Tests for `UNWIND ... AS var CREATE ...`: one output tuple (and one set
+//! of created nodes) per element of the unwound list, driven through
+//! `casys_engine::Engine::execute_gql_on_store` end to end.
+
+use casys_engine as engine;
+use std::collections::HashMap;
+
+fn rows_param(rows: serde_json::Value) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("rows".to_string(), rows);
+    params
+}
+
+#[test]
+fn unwind_create_emits_one_tuple_per_row() {
+    let eng = engine::Engine::open(std::env::temp_dir().join("casys_unwind_create_emits")).unwrap();
+    let mut store = engine::index::InMemoryGraphStore::new();
+
+    let rows = serde_json::json!([{"name": "Alice"}, {"name": "Bob"}, {"name": "Carol"}]);
+    let create = engine::types::GqlQuery(
+        "UNWIND $rows AS row CREATE (p:Person {name: row.name}) RETURN p.name".to_string(),
+    );
+    let res = eng.execute_gql_on_store(&mut store, &create, Some(rows_param(rows))).unwrap();
+
+    assert_eq!(res.rows.len(), 3);
+    let names: Vec<_> = res.rows.iter().map(|r| r[0].as_str().unwrap().to_string()).collect();
+    assert_eq!(names, vec!["Alice", "Bob", "Carol"]);
+
+    let check = engine::types::GqlQuery("MATCH (p:Person) RETURN p.name".to_string());
+    let res = eng.execute_gql_on_store(&mut store, &check, None).unwrap();
+    assert_eq!(res.rows.len(), 3);
+}
+
+#[test]
+fn unwind_create_on_empty_list_creates_nothing() {
+    let eng = engine::Engine::open(std::env::temp_dir().join("casys_unwind_create_empty")).unwrap();
+    let mut store = engine::index::InMemoryGraphStore::new();
+
+    let rows = serde_json::json!([]);
+    let create = engine::types::GqlQuery("UNWIND $rows AS row CREATE (p:Person {name: row.name})".to_string());
+    let res = eng.execute_gql_on_store(&mut store, &create, Some(rows_param(rows))).unwrap();
+    assert_eq!(res.rows.len(), 0);
+
+    let check = engine::types::GqlQuery("MATCH (p:Person) RETURN p.name".to_string());
+    let res = eng.execute_gql_on_store(&mut store, &check, None).unwrap();
+    assert_eq!(res.rows.len(), 0);
+}
+
+#[test]
+fn plain_create_without_unwind_still_returns_a_single_row() {
+    let eng = engine::Engine::open(std::env::temp_dir().join("casys_unwind_create_plain")).unwrap();
+    let mut store = engine::index::InMemoryGraphStore::new();
+
+    let create = engine::types::GqlQuery("CREATE (p:Person {name: 'Solo'}) RETURN p.name".to_string());
+    let res = eng.execute_gql_on_store(&mut store, &create, None).unwrap();
+
+    assert_eq!(res.rows.len(), 1);
+    assert_eq!(res.rows[0][0], serde_json::Value::String("Solo".to_string()));
+}
@@ -0,0 +1,51 @@
+// Integration test: a real on-disk WAL (casys_storage_fs::wal, via
+// FsBackend::commit_tx) survives a simulated crash - i.e. mutations appended
+// through commit_tx but never explicitly flushed/checkpointed are still
+// there after reopening the branch from scratch via load_from_fs.
+
+#[cfg(feature = "fs")]
+#[test]
+fn committed_records_survive_without_an_explicit_flush() {
+    use casys_engine as engine;
+    use casys_engine::index::persistence::WalRecord;
+    use casys_engine::index::GraphReadStore;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir()
+        .unwrap()
+        .join("target")
+        .join("tmp")
+        .join(format!("wal_crash_recovery_{now}"));
+    fs::create_dir_all(&root).unwrap();
+
+    let eng = engine::Engine::open(&root).unwrap();
+    let db = eng.open_database("testdb").unwrap();
+    let br = eng.open_branch(&db, "main").unwrap();
+
+    // No flush/checkpoint here - only commit_tx, which appends to the WAL
+    // and fsyncs it (WalWriter::flush), then publishes the tail.
+    let record = WalRecord::AddNode {
+        id: 1,
+        labels: vec!["Person".to_string()],
+        properties: [("name".to_string(), engine::Value::String("Alice".to_string()))].into_iter().collect(),
+    };
+    eng.commit_tx(&br, &[record.to_bytes()]).unwrap();
+
+    // Simulate a crash: drop the engine, reopen the branch from segments +
+    // residual WAL with a brand new Engine/store, nothing carried in memory.
+    drop(eng);
+    let eng2 = engine::Engine::open(&root).unwrap();
+    let loaded = eng2.load_branch(&db, &br).unwrap();
+
+    let node = loaded.get_node(1).unwrap();
+    assert!(node.is_some(), "mutation committed via commit_tx should survive a reload with no prior flush");
+    assert_eq!(node.unwrap().labels, vec!["Person".to_string()]);
+}
+
+#[cfg(not(feature = "fs"))]
+#[test]
+fn skip_wal_crash_recovery_without_fs() {
+    // This test is a no-op when the fs feature is not enabled
+}
@@ -0,0 +1,168 @@
+//! Unit tests for `WalBackedGraphStore`: mutations land in a `WalSink` and
+//! `recover` replays them back into an equivalent `InMemoryGraphStore`.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use casys_core::{
+    BranchName, DatabaseName, EngineError, GraphReadStore, GraphWriteStore, ManifestMeta, ManifestStore, SegmentId,
+    SegmentStore, WalSink, WalSource, WalTailMeta,
+};
+use casys_engine::index::wal_store::WalBackedGraphStore;
+use casys_engine::index::InMemoryGraphStore;
+
+/// In-memory `WalSink`/`WalSource` that tracks one append-only log of
+/// records per `(db, branch)`, the way `FsBackend`'s WAL directory would.
+#[derive(Default)]
+struct MockWal {
+    log: Mutex<Vec<Vec<u8>>>,
+}
+
+impl WalSink for MockWal {
+    fn append_records(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName, records: &[Vec<u8>]) -> Result<WalTailMeta, EngineError> {
+        let mut log = self.log.lock().unwrap();
+        log.extend(records.iter().cloned());
+        Ok(WalTailMeta { epoch: 0, seq: log.len() as u64 })
+    }
+}
+
+impl WalSource for MockWal {
+    fn list_wal_segments(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName) -> Result<Vec<WalTailMeta>, EngineError> {
+        Ok(vec![WalTailMeta { epoch: 0, seq: self.log.lock().unwrap().len() as u64 }])
+    }
+
+    fn read_wal_segment(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName, _tail: &WalTailMeta) -> Result<Vec<Vec<u8>>, EngineError> {
+        Ok(self.log.lock().unwrap().clone())
+    }
+}
+
+fn names() -> (DatabaseName, BranchName) {
+    (DatabaseName::try_from("testdb").unwrap(), BranchName::try_from("main").unwrap())
+}
+
+/// In-memory `SegmentStore`, the way `checkpoint`'s segment writes land.
+#[derive(Default)]
+struct MockSegments {
+    data: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl SegmentStore for MockSegments {
+    fn write_segment(&self, _root: &Path, _db: &DatabaseName, segment_id: &SegmentId, data: &[u8], _node_count: u64, _edge_count: u64) -> Result<(), EngineError> {
+        self.data.lock().unwrap().insert(segment_id.0.clone(), data.to_vec());
+        Ok(())
+    }
+
+    fn read_segment(&self, _root: &Path, _db: &DatabaseName, segment_id: &SegmentId) -> Result<(Vec<u8>, u64, u64), EngineError> {
+        self.data
+            .lock()
+            .unwrap()
+            .get(&segment_id.0)
+            .map(|d| (d.clone(), 0, 0))
+            .ok_or_else(|| EngineError::NotFound(segment_id.0.clone()))
+    }
+}
+
+/// In-memory `ManifestStore` holding just the latest entry per branch -
+/// enough to exercise `checkpoint`'s watermark pointer.
+#[derive(Default)]
+struct MockManifest {
+    latest: Mutex<Option<ManifestMeta>>,
+}
+
+impl ManifestStore for MockManifest {
+    fn list_snapshot_timestamps(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName) -> Result<Vec<u64>, EngineError> {
+        Ok(self.latest.lock().unwrap().iter().map(|m| m.version_ts).collect())
+    }
+
+    fn latest_manifest_meta(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName) -> Result<Option<ManifestMeta>, EngineError> {
+        Ok(self.latest.lock().unwrap().clone())
+    }
+
+    fn pitr_manifest_meta(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName, _at: u64) -> Result<Option<ManifestMeta>, EngineError> {
+        Ok(self.latest.lock().unwrap().clone())
+    }
+
+    fn read_manifest_meta(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName, _ts: u64) -> Result<Option<ManifestMeta>, EngineError> {
+        Ok(self.latest.lock().unwrap().clone())
+    }
+
+    fn write_manifest_meta(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName, meta: &ManifestMeta) -> Result<(), EngineError> {
+        *self.latest.lock().unwrap() = Some(meta.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn add_node_appends_a_wal_record() {
+    let wal = Arc::new(MockWal::default());
+    let (db, branch) = names();
+    let mut store = WalBackedGraphStore::new(InMemoryGraphStore::new(), wal.clone(), PathBuf::from("."), db, branch);
+
+    store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    assert_eq!(wal.log.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn recover_replays_adds_and_deletes_into_an_equivalent_store() {
+    let wal = Arc::new(MockWal::default());
+    let (db, branch) = names();
+    let mut store = WalBackedGraphStore::new(InMemoryGraphStore::new(), wal.clone(), PathBuf::from("."), db.clone(), branch.clone());
+
+    let a = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    let b = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    store.add_edge(a, b, "KNOWS".to_string(), HashMap::new()).unwrap();
+    store.delete_node(b).unwrap();
+
+    let recovered = WalBackedGraphStore::recover(wal.as_ref(), Path::new("."), &db, &branch).unwrap();
+
+    assert!(recovered.get_node(a).unwrap().is_some());
+    assert!(recovered.get_node(b).unwrap().is_none());
+    assert!(recovered.get_neighbors(a, None).unwrap().is_empty());
+}
+
+#[test]
+fn checkpoint_with_no_mutations_yet_writes_nothing() {
+    let wal = Arc::new(MockWal::default());
+    let (db, branch) = names();
+    let store = WalBackedGraphStore::new(InMemoryGraphStore::new(), wal, PathBuf::from("."), db, branch);
+    let segments = MockSegments::default();
+    let manifest = MockManifest::default();
+
+    let result = store.checkpoint(&segments, &manifest).unwrap();
+
+    assert!(result.is_none());
+    assert!(manifest.latest.lock().unwrap().is_none());
+}
+
+#[test]
+fn checkpoint_then_recover_sees_checkpointed_state_without_replaying_its_wal() {
+    let wal = Arc::new(MockWal::default());
+    let (db, branch) = names();
+    let mut store = WalBackedGraphStore::new(InMemoryGraphStore::new(), wal.clone(), PathBuf::from("."), db.clone(), branch.clone());
+    let segments = MockSegments::default();
+    let manifest = MockManifest::default();
+
+    let a = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    store.checkpoint(&segments, &manifest).unwrap();
+
+    // A mutation after the checkpoint should still show up on recovery.
+    let b = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    let recovered = WalBackedGraphStore::recover_from_checkpoint(&segments, &manifest, wal.as_ref(), Path::new("."), &db, &branch).unwrap();
+
+    assert!(recovered.get_node(a).unwrap().is_some());
+    assert!(recovered.get_node(b).unwrap().is_some());
+}
+
+#[test]
+fn recover_from_an_empty_wal_yields_an_empty_store() {
+    let wal = Arc::new(MockWal::default());
+    let (db, branch) = names();
+
+    let recovered = WalBackedGraphStore::recover(wal.as_ref(), Path::new("."), &db, &branch).unwrap();
+
+    assert!(recovered.scan_all().unwrap().is_empty());
+}
@@ -0,0 +1,53 @@
+// Integration test: WalBackedGraphStore::checkpoint against a real
+// FsBackend (not the MockSegments double in wal_store.rs, which overwrites
+// unconditionally and would never catch a regression in write_segment's
+// overwrite semantics).
+
+#[cfg(feature = "fs")]
+#[test]
+fn a_second_checkpoint_against_fs_backend_is_recoverable() {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use casys_core::{BranchName, DatabaseName};
+    use casys_engine::index::wal_store::WalBackedGraphStore;
+    use casys_engine::index::{GraphReadStore, GraphWriteStore, InMemoryGraphStore};
+    use casys_storage_fs::backend::FsBackend;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir()
+        .unwrap()
+        .join("target")
+        .join("tmp")
+        .join(format!("wal_store_fs_{now}"));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+    let fsb = Arc::new(FsBackend::new());
+
+    let mut store = WalBackedGraphStore::new(InMemoryGraphStore::new(), fsb.clone(), root.clone(), db.clone(), branch.clone());
+
+    let a = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    store.checkpoint(fsb.as_ref(), fsb.as_ref()).unwrap();
+
+    // A second checkpoint writes the "nodes"/"edges" segments again under
+    // the same fixed ids - `write_segment` must overwrite them, not treat
+    // the first checkpoint's files as already-correct content-addressed
+    // blobs, or this node is lost even though the manifest claims it's
+    // captured in the snapshot.
+    let b = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    store.checkpoint(fsb.as_ref(), fsb.as_ref()).unwrap();
+
+    let recovered = WalBackedGraphStore::recover_from_checkpoint(fsb.as_ref(), fsb.as_ref(), fsb.as_ref(), &root, &db, &branch).unwrap();
+
+    assert!(recovered.get_node(a).unwrap().is_some());
+    assert!(recovered.get_node(b).unwrap().is_some(), "second checkpoint's node must survive a reload from the published snapshot");
+}
+
+#[cfg(not(feature = "fs"))]
+#[test]
+fn skip_wal_store_fs_without_fs() {
+    // This test is a no-op when the fs feature is not enabled
+}
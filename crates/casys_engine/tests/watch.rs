@@ -0,0 +1,56 @@
+// Integration test: change-notification API over InMemoryGraphStore (chunk8-7)
+
+#[cfg(feature = "async")]
+use std::collections::HashMap;
+
+#[cfg(feature = "async")]
+use casys_core::GraphWriteStore;
+#[cfg(feature = "async")]
+use casys_engine::index::watch::{ChangeKind, GraphChange, WatchableGraphStore};
+#[cfg(feature = "async")]
+use casys_engine::index::InMemoryGraphStore;
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn watch_node_sees_its_own_create_and_delete_but_not_other_nodes() {
+    let mut store = WatchableGraphStore::new(InMemoryGraphStore::new());
+    let other = store.add_node(vec![], HashMap::new()).unwrap();
+    let watched = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    let mut watcher = store.watch_node(watched);
+    store.add_edge(other, watched, "LINK".to_string(), HashMap::new()).unwrap();
+    store.delete_node(watched).unwrap();
+
+    let change = watcher.recv().await.unwrap();
+    assert_eq!(change.id, watched);
+    assert_eq!(change.kind, ChangeKind::Deleted);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn watch_label_fires_for_any_node_with_that_label() {
+    let mut store = WatchableGraphStore::new(InMemoryGraphStore::new());
+    let mut watcher = store.watch_label("Person");
+
+    store.add_node(vec!["Company".to_string()], HashMap::new()).unwrap();
+    let alice = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    let change = watcher.recv().await.unwrap();
+    assert_eq!(change.id, alice);
+    assert_eq!(change.kind, ChangeKind::Created);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn watch_all_reports_both_node_and_edge_changes_in_order() {
+    let mut store = WatchableGraphStore::new(InMemoryGraphStore::new());
+    let mut watcher = store.watch_all();
+
+    let a = store.add_node(vec![], HashMap::new()).unwrap();
+    let b = store.add_node(vec![], HashMap::new()).unwrap();
+    store.add_edge(a, b, "LINK".to_string(), HashMap::new()).unwrap();
+
+    assert!(matches!(watcher.recv().await.unwrap(), GraphChange::Node(_)));
+    assert!(matches!(watcher.recv().await.unwrap(), GraphChange::Node(_)));
+    assert!(matches!(watcher.recv().await.unwrap(), GraphChange::Edge(_)));
+}
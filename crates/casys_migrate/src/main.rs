@@ -0,0 +1,54 @@
+//! `casys-migrate`: dump a database to a portable stream, or replay one into
+//! a (possibly different) data directory. A thin wrapper over
+//! `casys_migrate::{export_database, import_database}` - see that module for
+//! the actual backend-agnostic logic; this binary just wires stdin/stdout
+//! and a `FsBackend` on each end, since that's the only backend this repo
+//! currently ships as a standalone crate. Swapping in `ObjectStoreBackend`
+//! (or any other `StorageCatalog + ManifestStore + SegmentStore + WalSource
+//! + WalSink` adapter) only requires changing which backend value is passed
+//! to `export_database`/`import_database`, not the CLI itself.
+
+use std::convert::TryFrom;
+use std::io::{stdin, stdout};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use casys_core::{DatabaseName, EngineError};
+use casys_storage_fs::backend::FsBackend;
+
+fn usage() -> &'static str {
+    "usage:\n  casys-migrate export <data-dir> <db-name>   > dump.ndjson\n  casys-migrate import <data-dir> <db-name>   < dump.ndjson"
+}
+
+fn run() -> Result<(), EngineError> {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().ok_or_else(|| EngineError::InvalidArgument(usage().into()))?;
+    let root = args.next().ok_or_else(|| EngineError::InvalidArgument(usage().into()))?;
+    let db_name = args.next().ok_or_else(|| EngineError::InvalidArgument(usage().into()))?;
+
+    let root = PathBuf::from(root);
+    let db = DatabaseName::try_from(db_name.as_str())?;
+    let backend = FsBackend::new();
+
+    match command.as_str() {
+        "export" => {
+            let mut out = stdout().lock();
+            casys_migrate::export_database(&mut out, &backend, &backend, &backend, Some(&backend), &root, &db)
+        }
+        "import" => {
+            let input = stdin().lock();
+            casys_migrate::import_database(input, &backend, &backend, Some(&backend), &root, &db)
+        }
+        _ => Err(EngineError::InvalidArgument(usage().into())),
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("casys-migrate: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
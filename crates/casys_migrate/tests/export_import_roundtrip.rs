@@ -0,0 +1,166 @@
+//! Round-trips export_database/import_database through two independent
+//! in-memory backends, so the test exercises only the `casys_core` port
+//! surface - exactly what a migration between two unrelated `StorageBackend`
+//! implementations (e.g. filesystem to object store) would do.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use casys_core::{
+    BranchName, DatabaseName, EngineError, ManifestMeta, ManifestStore, SegmentId, SegmentStore,
+    StorageCatalog, Timestamp, WalSink, WalSource, WalTailMeta,
+};
+use casys_migrate::{export_database, import_database};
+
+#[derive(Default)]
+struct MemBackend {
+    branches: Mutex<Vec<String>>,
+    manifests: Mutex<HashMap<(String, Timestamp), ManifestMeta>>,
+    segments: Mutex<HashMap<String, (Vec<u8>, u64, u64)>>,
+    wal: Mutex<HashMap<String, Vec<((u64, u64), Vec<Vec<u8>>)>>>,
+}
+
+impl StorageCatalog for MemBackend {
+    fn list_branches(&self, _root: &Path, _db: &DatabaseName) -> Result<Vec<BranchName>, EngineError> {
+        self.branches.lock().unwrap().iter().map(|b| BranchName::try_from(b.as_str())).collect()
+    }
+
+    fn create_branch(&self, _root: &Path, _db: &DatabaseName, _from: &BranchName, _new_branch: &BranchName, _at: Option<Timestamp>) -> Result<(), EngineError> {
+        unimplemented!("import_database writes manifests directly, never via create_branch")
+    }
+}
+
+impl ManifestStore for MemBackend {
+    fn list_snapshot_timestamps(&self, _root: &Path, _db: &DatabaseName, branch: &BranchName) -> Result<Vec<Timestamp>, EngineError> {
+        let mut ts: Vec<Timestamp> = self.manifests.lock().unwrap().keys()
+            .filter(|(b, _)| b == branch.as_str())
+            .map(|(_, ts)| *ts)
+            .collect();
+        ts.sort_unstable();
+        Ok(ts)
+    }
+
+    fn latest_manifest_meta(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Option<ManifestMeta>, EngineError> {
+        let ts = self.list_snapshot_timestamps(root, db, branch)?;
+        match ts.last() {
+            Some(ts) => self.read_manifest_meta(root, db, branch, *ts),
+            None => Ok(None),
+        }
+    }
+
+    fn pitr_manifest_meta(&self, _root: &Path, _db: &DatabaseName, _branch: &BranchName, _at: Timestamp) -> Result<Option<ManifestMeta>, EngineError> {
+        unimplemented!("not exercised by export/import")
+    }
+
+    fn read_manifest_meta(&self, _root: &Path, _db: &DatabaseName, branch: &BranchName, ts: Timestamp) -> Result<Option<ManifestMeta>, EngineError> {
+        Ok(self.manifests.lock().unwrap().get(&(branch.as_str().to_string(), ts)).cloned())
+    }
+
+    fn write_manifest_meta(&self, _root: &Path, _db: &DatabaseName, branch: &BranchName, meta: &ManifestMeta) -> Result<(), EngineError> {
+        let mut branches = self.branches.lock().unwrap();
+        if !branches.iter().any(|b| b == branch.as_str()) {
+            branches.push(branch.as_str().to_string());
+        }
+        self.manifests.lock().unwrap().insert((branch.as_str().to_string(), meta.version_ts), meta.clone());
+        Ok(())
+    }
+}
+
+impl SegmentStore for MemBackend {
+    fn write_segment(&self, _root: &Path, _db: &DatabaseName, segment_id: &SegmentId, data: &[u8], node_count: u64, edge_count: u64) -> Result<(), EngineError> {
+        self.segments.lock().unwrap().insert(segment_id.0.clone(), (data.to_vec(), node_count, edge_count));
+        Ok(())
+    }
+
+    fn read_segment(&self, _root: &Path, _db: &DatabaseName, segment_id: &SegmentId) -> Result<(Vec<u8>, u64, u64), EngineError> {
+        self.segments.lock().unwrap().get(&segment_id.0).cloned()
+            .ok_or_else(|| EngineError::NotFound(segment_id.0.clone()))
+    }
+}
+
+impl WalSink for MemBackend {
+    fn append_records(&self, _root: &Path, _db: &DatabaseName, branch: &BranchName, records: &[Vec<u8>]) -> Result<WalTailMeta, EngineError> {
+        let mut wal = self.wal.lock().unwrap();
+        let entries = wal.entry(branch.as_str().to_string()).or_default();
+        let seq = entries.len() as u64;
+        entries.push(((0, seq), records.to_vec()));
+        Ok(WalTailMeta { epoch: 0, seq })
+    }
+}
+
+impl WalSource for MemBackend {
+    fn list_wal_segments(&self, _root: &Path, _db: &DatabaseName, branch: &BranchName) -> Result<Vec<WalTailMeta>, EngineError> {
+        Ok(self.wal.lock().unwrap().get(branch.as_str()).map(|entries| {
+            entries.iter().map(|((epoch, seq), _)| WalTailMeta { epoch: *epoch, seq: *seq }).collect()
+        }).unwrap_or_default())
+    }
+
+    fn read_wal_segment(&self, _root: &Path, _db: &DatabaseName, branch: &BranchName, tail: &WalTailMeta) -> Result<Vec<Vec<u8>>, EngineError> {
+        Ok(self.wal.lock().unwrap().get(branch.as_str())
+            .and_then(|entries| entries.iter().find(|((epoch, seq), _)| *epoch == tail.epoch && *seq == tail.seq))
+            .map(|(_, records)| records.clone())
+            .unwrap_or_default())
+    }
+}
+
+fn db() -> DatabaseName { DatabaseName::try_from("migtest").unwrap() }
+fn branch(name: &str) -> BranchName { BranchName::try_from(name).unwrap() }
+
+#[test]
+fn export_then_import_round_trips_manifests_segments_and_wal() {
+    let source = MemBackend::default();
+    let root = Path::new("/tmp/unused-root");
+    let database = db();
+
+    source.write_segment(root, &database, &SegmentId("nodes".into()), b"node-bytes", 3, 0).unwrap();
+    source.write_segment(root, &database, &SegmentId("edges".into()), b"edge-bytes", 0, 2).unwrap();
+    let tail = source.append_records(root, &database, &branch("main"), &[b"rec-1".to_vec(), b"rec-2".to_vec()]).unwrap();
+    source.write_manifest_meta(root, &database, &branch("main"), &ManifestMeta {
+        branch: "main".into(),
+        version_ts: 100,
+        segments: vec![SegmentId("nodes".into()), SegmentId("edges".into())],
+        wal_tail: Some(tail),
+    }).unwrap();
+
+    let mut stream = Vec::new();
+    export_database(&mut stream, &source, &source, &source, Some(&source), root, &database).unwrap();
+
+    let target = MemBackend::default();
+    import_database(stream.as_slice(), &target, &target, Some(&target), root, &database).unwrap();
+
+    let meta = target.latest_manifest_meta(root, &database, &branch("main")).unwrap().unwrap();
+    assert_eq!(meta.version_ts, 100);
+    assert_eq!(meta.segments.len(), 2);
+    assert_eq!(meta.wal_tail, Some(WalTailMeta { epoch: 0, seq: 0 }));
+
+    let (node_data, node_count, edge_count) = target.read_segment(root, &database, &SegmentId("nodes".into())).unwrap();
+    assert_eq!(node_data, b"node-bytes");
+    assert_eq!((node_count, edge_count), (3, 0));
+
+    let wal_tails = target.list_wal_segments(root, &database, &branch("main")).unwrap();
+    assert_eq!(wal_tails.len(), 1);
+    let records = target.read_wal_segment(root, &database, &branch("main"), &wal_tails[0]).unwrap();
+    assert_eq!(records, vec![b"rec-1".to_vec(), b"rec-2".to_vec()]);
+}
+
+#[test]
+fn export_deduplicates_segments_shared_across_manifest_versions() {
+    let source = MemBackend::default();
+    let root = Path::new("/tmp/unused-root");
+    let database = db();
+
+    source.write_segment(root, &database, &SegmentId("nodes".into()), b"v1", 1, 0).unwrap();
+    source.write_manifest_meta(root, &database, &branch("main"), &ManifestMeta {
+        branch: "main".into(), version_ts: 1, segments: vec![SegmentId("nodes".into())], wal_tail: None,
+    }).unwrap();
+    source.write_manifest_meta(root, &database, &branch("main"), &ManifestMeta {
+        branch: "main".into(), version_ts: 2, segments: vec![SegmentId("nodes".into())], wal_tail: None,
+    }).unwrap();
+
+    let mut stream = Vec::new();
+    export_database(&mut stream, &source, &source, &source, None, root, &database).unwrap();
+    let text = String::from_utf8(stream).unwrap();
+    assert_eq!(text.matches("\"kind\":\"segment\"").count(), 1);
+    assert_eq!(text.matches("\"kind\":\"manifest\"").count(), 2);
+}
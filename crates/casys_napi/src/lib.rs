@@ -1,12 +1,14 @@
 //! Casys N-API wrapper for TypeScript/Node.js
 //! Thin FFI layer mirroring casys_pyo3 pattern: JSON conversions only.
 
-use napi::{bindgen_prelude::*, JsObject, JsString, JsNumber};
+use napi::{bindgen_prelude::*, JsObject, JsString, JsNumber, JsBoolean, JsBigInt, JsFunction, Ref, Task, ValueType};
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use casys_engine as engine;
 use engine::types::{DatabaseName, BranchName, GqlQuery};
 use engine::index::InMemoryGraphStore;
+use engine::exec::executor::ValueExt;
 
 /// Casys Engine wrapper for Node.js
 #[napi]
@@ -73,27 +75,199 @@ impl CasysEngine {
     Ok(())
   }
 
-  /// List snapshot timestamps for a branch (requires fs feature)
+  /// List snapshots for a branch (requires fs feature), each reported as
+  /// `{ timestamp, codec, sizeBytes }`.
   #[napi]
   #[cfg(feature = "fs")]
-  pub fn list_snapshots(&self, db_name: String, branch_name: String) -> napi::Result<Vec<f64>> {
+  pub fn list_snapshots(&self, db_name: String, branch_name: String, env: Env) -> napi::Result<Vec<JsObject>> {
     let engine = self.inner.lock().unwrap();
     let db_handle = engine.open_database(&db_name)
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open database: {:?}", e)))?;
     let branch_handle = engine.open_branch(&db_handle, &branch_name)
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open branch: {:?}", e)))?;
 
-    let timestamps = engine.list_snapshot_timestamps(&db_handle, &branch_handle)
+    let details = engine.list_snapshot_details(&db_handle, &branch_handle)
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to list snapshots: {:?}", e)))?;
 
-    Ok(timestamps.iter().map(|ts| *ts as f64).collect())
+    details.iter().map(|d| {
+      let obj = env.create_object()?;
+      obj.set("timestamp", env.create_double(d.timestamp as f64)?)?;
+      obj.set("codec", env.create_string(d.codec)?)?;
+      obj.set("sizeBytes", env.create_double(d.size_bytes as f64)?)?;
+      Ok(obj)
+    }).collect()
   }
 
   #[napi]
   #[cfg(not(feature = "fs"))]
-  pub fn list_snapshots(&self, _db_name: String, _branch_name: String) -> napi::Result<Vec<f64>> {
+  pub fn list_snapshots(&self, _db_name: String, _branch_name: String, _env: Env) -> napi::Result<Vec<JsObject>> {
     Err(napi::Error::new(napi::Status::GenericFailure, "list_snapshots() requires the 'fs' feature"))
   }
+
+  /// Merge `source_branch` into `target_branch` (requires fs feature).
+  /// `strategy` is `"lastWriterWins"` or `"failOnConflict"`; the result is
+  /// reported as `{ added, updated, deleted, conflicted, conflicts }`, where
+  /// `conflicts` is an array of `{ kind: "node" | "edge", id }`. With
+  /// `failOnConflict`, a non-empty `conflicts` means `target_branch` was left
+  /// untouched.
+  #[napi]
+  #[cfg(feature = "fs")]
+  pub fn merge_branch(&self, db_name: String, source_branch: String, target_branch: String, strategy: String, env: Env) -> napi::Result<JsObject> {
+    let engine = self.inner.lock().unwrap();
+    let db_handle = engine.open_database(&db_name)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open database: {:?}", e)))?;
+    let source_handle = engine.open_branch(&db_handle, &source_branch)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open branch: {:?}", e)))?;
+    let target_handle = engine.open_branch(&db_handle, &target_branch)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open branch: {:?}", e)))?;
+
+    let strategy = match strategy.as_str() {
+      "lastWriterWins" => engine::merge::MergeStrategy::LastWriterWins,
+      "failOnConflict" => engine::merge::MergeStrategy::FailOnConflict,
+      other => return Err(napi::Error::new(napi::Status::InvalidArg, format!("unknown merge strategy: {other}"))),
+    };
+
+    let summary = engine.merge_branch(&db_handle, &source_handle, &target_handle, strategy)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to merge branch: {:?}", e)))?;
+
+    let obj = env.create_object()?;
+    obj.set("added", env.create_double(summary.added as f64)?)?;
+    obj.set("updated", env.create_double(summary.updated as f64)?)?;
+    obj.set("deleted", env.create_double(summary.deleted as f64)?)?;
+    obj.set("conflicted", env.create_double(summary.conflicted as f64)?)?;
+    let conflicts = env.create_array_with_length(summary.conflicts.len())?;
+    let mut conflicts = conflicts;
+    for (i, c) in summary.conflicts.iter().enumerate() {
+      let (kind, id) = match c {
+        engine::merge::ConflictId::Node(id) => ("node", *id),
+        engine::merge::ConflictId::Edge(id) => ("edge", *id),
+      };
+      let entry = env.create_object()?;
+      entry.set("kind", env.create_string(kind)?)?;
+      entry.set("id", env.create_double(id as f64)?)?;
+      conflicts.set_element(i as u32, entry)?;
+    }
+    obj.set("conflicts", conflicts)?;
+    Ok(obj)
+  }
+
+  #[napi]
+  #[cfg(not(feature = "fs"))]
+  pub fn merge_branch(&self, _db_name: String, _source_branch: String, _target_branch: String, _strategy: String, _env: Env) -> napi::Result<JsObject> {
+    Err(napi::Error::new(napi::Status::GenericFailure, "merge_branch() requires the 'fs' feature"))
+  }
+
+  /// Register a scalar function under `name`, matched case-insensitively,
+  /// so that any GQL query run against a branch opened from this engine can
+  /// call it like a built-in (e.g. `myFunc(x, y)`). `callback` is invoked
+  /// synchronously for each call, with the call's arguments converted
+  /// through the same JSON bridge `query`/`batch` use, and must return a
+  /// JSON-representable value. Only call a function registered this way
+  /// from `queryAsync`, which runs off the JS thread - `query()`/`batch()` run
+  /// synchronously on whatever thread called them, and a query that calls a
+  /// registered function from the JS thread itself has no JS tick left to
+  /// run the callback on, so it errors instead of deadlocking.
+  #[napi]
+  pub fn register_function(&self, name: String, callback: JsFunction, env: Env) -> napi::Result<()> {
+    let invoker = JsFunctionInvoker::new(&env, callback)?;
+    self.inner.lock().unwrap().register_external_function(&name, Arc::new(invoker));
+    Ok(())
+  }
+}
+
+/// One pending call into a registered scalar function: the JSON-encoded
+/// arguments plus the channel [`JsFunctionInvoker::invoke`] is blocked on,
+/// waiting for the JS-thread dispatch closure to send back the JSON-encoded
+/// result (or an error message).
+struct PendingCall {
+  args: Vec<serde_json::Value>,
+  reply: mpsc::SyncSender<Result<serde_json::Value, String>>,
+}
+
+/// Bridges a JS callback registered via [`CasysEngine::register_function`]
+/// to [`engine::exec::functions::ExternalFunctionInvoker`], so the GQL
+/// executor can call back into JS without knowing anything about napi.
+///
+/// `ExternalFunctionInvoker::invoke` runs synchronously and isn't
+/// necessarily on the JS thread (it may be called from `query_async`'s
+/// libuv worker), so the actual call has to be scheduled onto the JS thread
+/// via a `ThreadsafeFunction` and `invoke` blocks on a oneshot channel for
+/// the reply. The threadsafe function itself is bound to a no-op stub,
+/// purely so we get a dispatch closure that runs on the JS thread on
+/// demand; the real callback is invoked manually from inside that closure
+/// (via a persistent `Ref`) so we can read its return value directly
+/// instead of relying on `ThreadsafeFunction`'s normal fire-and-forget
+/// auto-invoke of whichever function it was created from.
+///
+/// That blocking wait is only safe off the JS thread. `query()`/`batch()`
+/// run synchronously on whatever thread called them - including the JS
+/// thread itself, when there's no `query_async`/libuv worker involved - and
+/// the dispatch closure above can only ever run on a JS event-loop tick.
+/// Blocking the JS thread on `rx.recv()` while waiting for a JS tick that
+/// thread itself would have to process is an unconditional deadlock, so
+/// `invoke` checks the calling thread against `js_thread_id` (captured at
+/// registration time, which always happens on the JS thread) and returns an
+/// error instead of hanging when they match.
+struct JsFunctionInvoker {
+  dispatch: ThreadsafeFunction<PendingCall, ErrorStrategy::Fatal>,
+  js_thread_id: std::thread::ThreadId,
+}
+
+impl JsFunctionInvoker {
+  fn new(env: &Env, callback: JsFunction) -> napi::Result<Self> {
+    let callback_ref: Ref<()> = env.create_reference(callback)?;
+    let stub = env.create_function_from_closure("casysRegisteredFunctionDispatch", |ctx| ctx.env.get_undefined())?;
+
+    let dispatch = stub.create_threadsafe_function(0, move |ctx: ThreadSafeCallContext<PendingCall>| {
+      let PendingCall { args, reply } = ctx.value;
+
+      let outcome = (|| -> napi::Result<serde_json::Value> {
+        let func: JsFunction = ctx.env.get_reference_value(&callback_ref)?;
+        let js_args = args.iter()
+          .map(|v| json_to_js(&ctx.env, v))
+          .collect::<napi::Result<Vec<_>>>()?;
+        let ret = func.call(None, &js_args)?;
+        js_to_json(&ctx.env, &ret)
+      })();
+
+      let _ = reply.send(outcome.map_err(|e| e.to_string()));
+      Ok(Vec::<JsUnknown>::new())
+    })?;
+
+    Ok(Self { dispatch, js_thread_id: std::thread::current().id() })
+  }
+}
+
+/// Whether the calling thread is `js_thread_id`, i.e. whether blocking on
+/// the `ThreadsafeFunction` dispatch from here would deadlock. A free,
+/// `pub` function (rather than a private method on `JsFunctionInvoker`) so
+/// `tests/registered_function_thread.rs` can exercise the guard directly -
+/// constructing a real `JsFunctionInvoker` needs a live `napi::Env`, which
+/// only an actual Node.js host can provide.
+pub fn invoking_would_deadlock(js_thread_id: std::thread::ThreadId) -> bool {
+  std::thread::current().id() == js_thread_id
+}
+
+impl engine::exec::functions::ExternalFunctionInvoker for JsFunctionInvoker {
+  fn invoke(&self, name: &str, args: Vec<engine::Value>) -> Result<engine::Value, engine::EngineError> {
+    if invoking_would_deadlock(self.js_thread_id) {
+      return Err(engine::EngineError::InvalidArgument(format!(
+        "registered function '{name}' called from a synchronous query() on the JS thread - use queryAsync() when a query calls a registered function, since the callback can only run on a JS tick and this thread is the one blocked waiting for it"
+      )));
+    }
+
+    let json_args: Vec<serde_json::Value> = args.iter().map(|v| v.to_json()).collect();
+    let (tx, rx) = mpsc::sync_channel(1);
+
+    self.dispatch.call(PendingCall { args: json_args, reply: tx }, ThreadsafeFunctionCallMode::Blocking);
+
+    let json_result = rx.recv()
+      .map_err(|e| engine::EngineError::StorageIo(format!("registered function callback channel closed: {e}")))?
+      .map_err(engine::EngineError::InvalidArgument)?;
+
+    engine::Value::from_json(&json_result)
+      .ok_or_else(|| engine::EngineError::InvalidArgument("registered function returned a value the engine can't represent".into()))
+  }
 }
 
 /// Branch handle for Node.js
@@ -107,69 +281,102 @@ pub struct CasysBranch {
 
 #[napi]
 impl CasysBranch {
-  /// Execute a GQL query against the branch store
+  /// Execute a GQL query against the branch store. By default `rows` is a
+  /// JSON string the caller must parse; pass `raw: true` to get rows back as
+  /// native JS arrays/objects instead.
   #[napi]
-  pub fn query(&self, gql: String, params: Option<JsObject>, env: Env) -> napi::Result<JsObject> {
-    let mut params_json: std::collections::HashMap<String, serde_json::Value> = std::collections::HashMap::new();
-    
-    if let Some(p) = params {
-      let keys: Vec<String> = p.get_property_names()?
-        .into_iter()
-        .filter_map(|k| k.as_string().ok())
-        .collect();
-      
-      for key in keys {
-        if let Ok(val) = p.get::<_, JsUnknown>(&key) {
-          if let Ok(json_val) = js_to_json(&env, &val) {
-            params_json.insert(key, json_val);
-          }
-        }
-      }
-    }
+  pub fn query(&self, gql: String, params: Option<JsObject>, raw: Option<bool>, env: Env) -> napi::Result<JsObject> {
+    let params_json = extract_params(&env, params)?;
 
     let gql = GqlQuery(gql);
     let mut store = self.store.lock().unwrap();
     let engine = self.engine.lock().unwrap();
-    let result = engine.execute_gql_on_store(&mut *store, &gql, if params_json.is_empty() { None } else { Some(params_json) })
+    let result = engine.execute_gql_on_store(&mut *store, &gql, params_json)
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Execution error: {:?}", e)))?;
 
-    let obj = env.create_object()?;
-    
-    // Columns
-    let cols: Vec<String> = result.columns.iter().map(|c| c.name.clone()).collect();
-    obj.set("columns", env.create_string_from_std(serde_json::to_string(&cols).unwrap())?)?;
-    
-    // Rows
-    let rows_json = serde_json::to_string(&result.rows).unwrap();
-    obj.set("rows", env.create_string_from_std(rows_json)?)?;
+    result_to_js_object(&env, &result, raw.unwrap_or(false))
+  }
 
-    Ok(obj)
+  /// Execute an ordered list of GQL statements in a single FFI call, taking
+  /// the store/engine locks only once. If `atomic` is true, any statement
+  /// failing rolls the whole batch back and the error identifies which
+  /// statement (by index) failed; otherwise the effects of the statements
+  /// that already ran are kept.
+  #[napi]
+  pub fn batch(&self, statements: Vec<JsObject>, atomic: Option<bool>, raw: Option<bool>, env: Env) -> napi::Result<JsObject> {
+    let mut parsed = Vec::with_capacity(statements.len());
+    for stmt in statements {
+      let gql = stmt.get_named_property::<JsString>("gql")?.into_utf8()?.into_owned();
+      let params: Option<JsObject> = if stmt.has_named_property("params")? {
+        Some(stmt.get_named_property::<JsObject>("params")?)
+      } else {
+        None
+      };
+      parsed.push((GqlQuery(gql), extract_params(&env, params)?));
+    }
+
+    let mut store = self.store.lock().unwrap();
+    let engine = self.engine.lock().unwrap();
+    let results = engine.execute_gql_batch_on_store(&mut *store, &parsed, atomic.unwrap_or(false))
+      .map_err(|(index, e)| napi::Error::new(napi::Status::GenericFailure, format!("Execution error at statement {}: {:?}", index, e)))?;
+
+    let raw = raw.unwrap_or(false);
+    let arr = env.create_array_with_length(results.len())?;
+    for (i, result) in results.iter().enumerate() {
+      arr.set_element(i as u32, result_to_js_object(&env, result, raw)?)?;
+    }
+    Ok(arr)
+  }
+
+  /// Execute a GQL query and return a [`CasysCursor`] that pages through the
+  /// result instead of serializing every row into one `JsObject` up front.
+  #[napi]
+  pub fn query_cursor(&self, gql: String, params: Option<JsObject>, page_size: u32, raw: Option<bool>, env: Env) -> napi::Result<CasysCursor> {
+    let params_json = extract_params(&env, params)?;
+
+    let gql = GqlQuery(gql);
+    let mut store = self.store.lock().unwrap();
+    let engine = self.engine.lock().unwrap();
+    let result = engine.execute_gql_on_store(&mut *store, &gql, params_json)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Execution error: {:?}", e)))?;
+
+    let columns: Vec<String> = result.columns.iter().map(|c| c.name.clone()).collect();
+    Ok(CasysCursor {
+      columns,
+      rows: result.rows,
+      offset: 0,
+      page_size: page_size.max(1) as usize,
+      raw: raw.unwrap_or(false),
+    })
   }
 
-  /// Flush store to disk (requires fs feature)
+  /// Flush store to disk (requires fs feature). `options` is e.g.
+  /// `{ compression: "zstd", level: 9 }`; omit it (or `compression`) to
+  /// write the segments uncompressed, same as before this option existed.
   #[napi]
   #[cfg(feature = "fs")]
-  pub fn flush(&self) -> napi::Result<()> {
+  pub fn flush(&self, options: Option<JsObject>) -> napi::Result<()> {
     let db = DatabaseName::try_from(self.db_name.as_str())
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Invalid database name: {:?}", e)))?;
     let branch = BranchName::try_from(self.branch_name.as_str())
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Invalid branch name: {:?}", e)))?;
-    
+    let compression = parse_compression_options(options)?;
+
     let engine = self.engine.lock().unwrap();
     let db_handle = engine.open_database(db.as_str())
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open database: {:?}", e)))?;
     let branch_handle = engine.open_branch(&db_handle, branch.as_str())
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open branch: {:?}", e)))?;
     let store = self.store.lock().unwrap();
-    engine.flush_branch(&db_handle, &branch_handle, &store)
+    engine.flush_branch(&db_handle, &branch_handle, &store, compression)
       .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Flush error: {:?}", e)))?;
-    
+
     Ok(())
   }
 
   #[napi]
   #[cfg(not(feature = "fs"))]
-  pub fn flush(&self) -> napi::Result<()> {
+  pub fn flush(&self, _options: Option<JsObject>) -> napi::Result<()> {
     Err(napi::Error::new(napi::Status::GenericFailure, "flush() requires the 'fs' feature"))
   }
 
@@ -200,24 +407,388 @@ impl CasysBranch {
   pub fn load(&self) -> napi::Result<()> {
     Err(napi::Error::new(napi::Status::GenericFailure, "load() requires the 'fs' feature"))
   }
+
+  /// Execute a GQL query on libuv's threadpool, resolving a Promise instead of
+  /// blocking the event loop. Params are converted to JSON up front since
+  /// JsObject isn't Send; the query itself runs off-thread.
+  #[napi]
+  pub fn query_async(&self, gql: String, params: Option<JsObject>, raw: Option<bool>, env: Env) -> napi::Result<AsyncTask<QueryTask>> {
+    let params_json = extract_params(&env, params)?;
+
+    Ok(AsyncTask::new(QueryTask {
+      store: self.store.clone(),
+      engine: self.engine.clone(),
+      gql: GqlQuery(gql),
+      params: params_json,
+      raw: raw.unwrap_or(false),
+    }))
+  }
+
+  /// Flush store to disk off-thread (requires fs feature). `options` has the
+  /// same shape as [`CasysBranch::flush`]'s.
+  #[napi]
+  #[cfg(feature = "fs")]
+  pub fn flush_async(&self, options: Option<JsObject>) -> napi::Result<AsyncTask<FlushTask>> {
+    let compression = parse_compression_options(options)?;
+
+    Ok(AsyncTask::new(FlushTask {
+      db_name: self.db_name.clone(),
+      branch_name: self.branch_name.clone(),
+      store: self.store.clone(),
+      engine: self.engine.clone(),
+      compression,
+    }))
+  }
+
+  #[napi]
+  #[cfg(not(feature = "fs"))]
+  pub fn flush_async(&self, _options: Option<JsObject>) -> napi::Result<()> {
+    Err(napi::Error::new(napi::Status::GenericFailure, "flushAsync() requires the 'fs' feature"))
+  }
+
+  /// Load store from disk off-thread (requires fs feature)
+  #[napi]
+  #[cfg(feature = "fs")]
+  pub fn load_async(&self) -> napi::Result<AsyncTask<LoadTask>> {
+    Ok(AsyncTask::new(LoadTask {
+      db_name: self.db_name.clone(),
+      branch_name: self.branch_name.clone(),
+      store: self.store.clone(),
+      engine: self.engine.clone(),
+    }))
+  }
+
+  #[napi]
+  #[cfg(not(feature = "fs"))]
+  pub fn load_async(&self) -> napi::Result<()> {
+    Err(napi::Error::new(napi::Status::GenericFailure, "loadAsync() requires the 'fs' feature"))
+  }
 }
 
-/// Convert JS value to serde_json::Value
-fn js_to_json(env: &Env, val: &JsUnknown) -> napi::Result<serde_json::Value> {
-  if let Ok(null) = val.coerce_to_null() {
-    return Ok(serde_json::Value::Null);
+/// Opaque pagination handle returned by [`CasysBranch::query_cursor`]. Holds
+/// the already-materialized result rows and an offset so `next()` can page
+/// through them without re-running the query or re-serializing everything
+/// up front.
+#[napi]
+pub struct CasysCursor {
+  columns: Vec<String>,
+  rows: Vec<Vec<serde_json::Value>>,
+  offset: usize,
+  page_size: usize,
+  raw: bool,
+}
+
+#[napi]
+impl CasysCursor {
+  /// Return the next page of rows plus a `done` flag indicating whether the
+  /// cursor has been exhausted.
+  #[napi]
+  pub fn next(&mut self, env: Env) -> napi::Result<JsObject> {
+    let end = (self.offset + self.page_size).min(self.rows.len());
+    let page = &self.rows[self.offset..end];
+    let done = end >= self.rows.len();
+
+    let obj = build_result_object(&env, &self.columns, page, self.raw)?;
+    obj.set("done", env.get_boolean(done)?)?;
+
+    self.offset = end;
+    Ok(obj)
   }
-  if let Ok(b) = val.coerce_to_bool() {
-    return Ok(serde_json::Value::Bool(b.get_value()?));
+}
+
+/// Query result with rows left as JSON values; JSON-stringified (or, if
+/// `raw` was requested, converted to native JS) only in `resolve`, back on
+/// the JS thread.
+pub struct QueryOutput {
+  columns: Vec<String>,
+  rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// `napi::Task` for [`CasysBranch::query_async`]: `compute` runs on libuv's
+/// threadpool and does the actual locking + GQL execution, `resolve` runs
+/// back on the JS thread and only builds the result object.
+pub struct QueryTask {
+  store: Arc<Mutex<InMemoryGraphStore>>,
+  engine: Arc<Mutex<engine::Engine>>,
+  gql: GqlQuery,
+  params: Option<std::collections::HashMap<String, serde_json::Value>>,
+  raw: bool,
+}
+
+impl Task for QueryTask {
+  type Output = QueryOutput;
+  type JsValue = JsObject;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let mut store = self.store.lock().unwrap();
+    let engine = self.engine.lock().unwrap();
+    let result = engine.execute_gql_on_store(&mut *store, &self.gql, self.params.clone())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Execution error: {:?}", e)))?;
+
+    Ok(QueryOutput {
+      columns: result.columns.iter().map(|c| c.name.clone()).collect(),
+      rows: result.rows,
+    })
+  }
+
+  fn resolve(&mut self, env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    build_result_object(&env, &output.columns, &output.rows, self.raw)
   }
-  if let Ok(n) = val.coerce_to_number() {
-    let num = n.get_double()?;
-    if let Some(json_num) = serde_json::Number::from_f64(num) {
-      return Ok(serde_json::Value::Number(json_num));
+}
+
+/// `napi::Task` for [`CasysBranch::flush_async`]: the actual flush runs on
+/// libuv's threadpool, `resolve` just hands back `undefined`.
+#[cfg(feature = "fs")]
+pub struct FlushTask {
+  db_name: String,
+  branch_name: String,
+  store: Arc<Mutex<InMemoryGraphStore>>,
+  engine: Arc<Mutex<engine::Engine>>,
+  compression: Option<engine::index::compression::CompressionOptions>,
+}
+
+#[cfg(feature = "fs")]
+impl Task for FlushTask {
+  type Output = ();
+  type JsValue = ();
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let db = DatabaseName::try_from(self.db_name.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Invalid database name: {:?}", e)))?;
+    let branch = BranchName::try_from(self.branch_name.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Invalid branch name: {:?}", e)))?;
+
+    let engine = self.engine.lock().unwrap();
+    let db_handle = engine.open_database(db.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open database: {:?}", e)))?;
+    let branch_handle = engine.open_branch(&db_handle, branch.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open branch: {:?}", e)))?;
+    let store = self.store.lock().unwrap();
+    engine.flush_branch(&db_handle, &branch_handle, &store, self.compression)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Flush error: {:?}", e)))?;
+
+    Ok(())
+  }
+
+  fn resolve(&mut self, _env: Env, _output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(())
+  }
+}
+
+/// `napi::Task` for [`CasysBranch::load_async`]: the actual load runs on
+/// libuv's threadpool, `resolve` swaps the loaded store in on the JS thread.
+#[cfg(feature = "fs")]
+pub struct LoadTask {
+  db_name: String,
+  branch_name: String,
+  store: Arc<Mutex<InMemoryGraphStore>>,
+  engine: Arc<Mutex<engine::Engine>>,
+}
+
+#[cfg(feature = "fs")]
+impl Task for LoadTask {
+  type Output = InMemoryGraphStore;
+  type JsValue = ();
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let db = DatabaseName::try_from(self.db_name.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Invalid database name: {:?}", e)))?;
+    let branch = BranchName::try_from(self.branch_name.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Invalid branch name: {:?}", e)))?;
+
+    let engine = self.engine.lock().unwrap();
+    let db_handle = engine.open_database(db.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open database: {:?}", e)))?;
+    let branch_handle = engine.open_branch(&db_handle, branch.as_str())
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Failed to open branch: {:?}", e)))?;
+    engine.load_branch(&db_handle, &branch_handle)
+      .map_err(|e| napi::Error::new(napi::Status::GenericFailure, format!("Load error: {:?}", e)))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    let mut store = self.store.lock().unwrap();
+    *store = output;
+    Ok(())
+  }
+}
+
+/// Collect a JS params object into the JSON map `execute_gql_on_store` expects,
+/// or `None` if no object (or an empty one) was passed.
+fn extract_params(env: &Env, params: Option<JsObject>) -> napi::Result<Option<std::collections::HashMap<String, serde_json::Value>>> {
+  let mut params_json: std::collections::HashMap<String, serde_json::Value> = std::collections::HashMap::new();
+
+  if let Some(p) = params {
+    let keys: Vec<String> = p.get_property_names()?
+      .into_iter()
+      .filter_map(|k| k.as_string().ok())
+      .collect();
+
+    for key in keys {
+      if let Ok(val) = p.get::<_, JsUnknown>(&key) {
+        if let Ok(json_val) = js_to_json(env, &val) {
+          params_json.insert(key, json_val);
+        }
+      }
+    }
+  }
+
+  Ok(if params_json.is_empty() { None } else { Some(params_json) })
+}
+
+/// Parse the `{ compression: string, level: number }` option object accepted
+/// by `flush`/`flushAsync`. `None` (or an object with no `compression` key)
+/// means "use the default", which leaves segments uncompressed.
+#[cfg(feature = "fs")]
+fn parse_compression_options(options: Option<JsObject>) -> napi::Result<Option<engine::index::compression::CompressionOptions>> {
+  let options = match options {
+    Some(o) => o,
+    None => return Ok(None),
+  };
+
+  if !options.has_named_property("compression")? {
+    return Ok(None);
+  }
+
+  let codec: String = options.get_named_property::<JsString>("compression")?.into_utf8()?.as_str()?.to_string();
+  let codec = codec.parse::<engine::index::compression::Codec>()
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, format!("Invalid compression codec: {:?}", e)))?;
+
+  let level = if options.has_named_property("level")? {
+    options.get_named_property::<JsNumber>("level")?.get_int32()?
+  } else {
+    0
+  };
+
+  Ok(Some(engine::index::compression::CompressionOptions { codec, level }))
+}
+
+/// Build the `{ columns, rows }` result object shared by `query`, `batch`,
+/// `queryAsync` and `CasysCursor::next`. `columns` is always a JSON string; `rows` is a JSON
+/// string unless `raw` is set, in which case it's a native JS array of rows
+/// so the caller doesn't have to re-parse it.
+fn build_result_object(env: &Env, columns: &[String], rows: &[Vec<serde_json::Value>], raw: bool) -> napi::Result<JsObject> {
+  let obj = env.create_object()?;
+  obj.set("columns", env.create_string_from_std(serde_json::to_string(columns).unwrap())?)?;
+
+  if raw {
+    let rows_arr = env.create_array_with_length(rows.len())?;
+    for (i, row) in rows.iter().enumerate() {
+      let row_arr = env.create_array_with_length(row.len())?;
+      for (j, cell) in row.iter().enumerate() {
+        row_arr.set_element(j as u32, json_to_js(env, cell)?)?;
+      }
+      rows_arr.set_element(i as u32, row_arr)?;
     }
+    obj.set("rows", rows_arr)?;
+  } else {
+    obj.set("rows", env.create_string_from_std(serde_json::to_string(rows).unwrap())?)?;
   }
-  if let Ok(s) = val.coerce_to_string() {
-    return Ok(serde_json::Value::String(s.into_utf8()?.into_owned()));
+
+  Ok(obj)
+}
+
+fn result_to_js_object(env: &Env, result: &engine::QueryResult, raw: bool) -> napi::Result<JsObject> {
+  let cols: Vec<String> = result.columns.iter().map(|c| c.name.clone()).collect();
+  build_result_object(env, &cols, &result.rows, raw)
+}
+
+/// Convert a JS value to `serde_json::Value`, recursing into arrays and
+/// plain objects and preserving integral numbers (including `BigInt`) as
+/// JSON integers rather than routing everything through `f64`.
+fn js_to_json(env: &Env, val: &JsUnknown) -> napi::Result<serde_json::Value> {
+  match val.get_type()? {
+    ValueType::Undefined | ValueType::Null => Ok(serde_json::Value::Null),
+    ValueType::Boolean => {
+      let b = unsafe { val.cast::<JsBoolean>() };
+      Ok(serde_json::Value::Bool(b.get_value()?))
+    }
+    ValueType::Number => {
+      let n = unsafe { val.cast::<JsNumber>() };
+      let d = n.get_double()?;
+      // Numbers that round-trip exactly through i64 are rendered as JSON
+      // integers (e.g. `5` not `5.0`); other finite values fall back to the
+      // f64 representation, and non-finite ones (NaN, +/-Infinity) become null.
+      if d.fract() == 0.0 && d.abs() < 9_007_199_254_740_992.0 {
+        Ok(serde_json::Value::Number((d as i64).into()))
+      } else {
+        Ok(serde_json::Number::from_f64(d).map(serde_json::Value::Number).unwrap_or(serde_json::Value::Null))
+      }
+    }
+    ValueType::BigInt => {
+      let mut bigint = unsafe { val.cast::<JsBigInt>() };
+      let (signed, lossless) = bigint.get_i64()?;
+      if lossless {
+        return Ok(serde_json::Value::Number(signed.into()));
+      }
+      let (unsigned, lossless) = bigint.get_u64()?;
+      if lossless {
+        return Ok(serde_json::Value::Number(unsigned.into()));
+      }
+      // Magnitude doesn't fit in 64 bits either way; keep the digits as a
+      // string rather than silently truncating.
+      Ok(serde_json::Value::String(val.coerce_to_string()?.into_utf8()?.into_owned()))
+    }
+    ValueType::String => {
+      let s = unsafe { val.cast::<JsString>() };
+      Ok(serde_json::Value::String(s.into_utf8()?.into_owned()))
+    }
+    ValueType::Object => {
+      let obj = unsafe { val.cast::<JsObject>() };
+      if obj.is_array()? {
+        let len = obj.get_array_length()?;
+        let mut arr = Vec::with_capacity(len as usize);
+        for i in 0..len {
+          let item: JsUnknown = obj.get_element(i)?;
+          arr.push(js_to_json(env, &item)?);
+        }
+        Ok(serde_json::Value::Array(arr))
+      } else {
+        let keys: Vec<String> = obj.get_property_names()?
+          .into_iter()
+          .filter_map(|k| k.as_string().ok())
+          .collect();
+        let mut map = serde_json::Map::with_capacity(keys.len());
+        for key in keys {
+          if let Ok(v) = obj.get::<_, JsUnknown>(&key) {
+            map.insert(key, js_to_json(env, &v)?);
+          }
+        }
+        Ok(serde_json::Value::Object(map))
+      }
+    }
+    _ => Ok(serde_json::Value::Null),
+  }
+}
+
+/// The reverse of [`js_to_json`]: build a native JS value from a parsed GQL
+/// result cell so callers can opt into `raw` rows instead of a JSON string.
+fn json_to_js(env: &Env, val: &serde_json::Value) -> napi::Result<JsUnknown> {
+  match val {
+    serde_json::Value::Null => Ok(env.get_null()?.into_unknown()),
+    serde_json::Value::Bool(b) => Ok(env.get_boolean(*b)?.into_unknown()),
+    serde_json::Value::Number(n) => {
+      if let Some(i) = n.as_i64() {
+        Ok(env.create_int64(i)?.into_unknown())
+      } else if let Some(u) = n.as_u64() {
+        Ok(env.create_bigint_from_u64(u)?.into_unknown())
+      } else {
+        Ok(env.create_double(n.as_f64().unwrap_or(0.0))?.into_unknown())
+      }
+    }
+    serde_json::Value::String(s) => Ok(env.create_string(s)?.into_unknown()),
+    serde_json::Value::Array(items) => {
+      let arr = env.create_array_with_length(items.len())?;
+      for (i, item) in items.iter().enumerate() {
+        arr.set_element(i as u32, json_to_js(env, item)?)?;
+      }
+      Ok(arr.into_unknown())
+    }
+    serde_json::Value::Object(map) => {
+      let obj = env.create_object()?;
+      for (k, v) in map {
+        obj.set(k, json_to_js(env, v)?)?;
+      }
+      Ok(obj.into_unknown())
+    }
   }
-  Ok(serde_json::Value::Null)
 }
@@ -0,0 +1,23 @@
+// Integration test: JsFunctionInvoker's JS-thread deadlock guard.
+//
+// `query()` runs synchronously on whatever thread calls it, including the
+// JS thread itself, but `ExternalFunctionInvoker::invoke`'s reply can only
+// ever arrive via a JS event-loop tick - so invoking a registered function
+// from a plain `query()` call made on the JS thread must error instead of
+// blocking forever. The full FFI path needs a live `napi::Env` (a real
+// Node.js host), which this crate has no harness for, so this exercises
+// `invoking_would_deadlock` - the exact check `invoke` makes - directly.
+
+use std::thread;
+
+#[test]
+fn same_thread_as_registration_would_deadlock() {
+    let here = thread::current().id();
+    assert!(casys_napi::invoking_would_deadlock(here));
+}
+
+#[test]
+fn a_different_thread_than_registration_would_not_deadlock() {
+    let registration_thread = thread::spawn(|| thread::current().id()).join().unwrap();
+    assert!(!casys_napi::invoking_would_deadlock(registration_thread));
+}
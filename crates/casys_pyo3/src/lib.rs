@@ -213,12 +213,12 @@ impl CasysBranch {
         let branch_handle = engine.open_branch(&db_handle, branch.as_str())
             .map_err(|e| PyRuntimeError::new_err(format!("Failed to open branch: {:?}", e)))?;
         let store = self.store.lock().unwrap();
-        engine.flush_branch(&db_handle, &branch_handle, &store)
+        engine.flush_branch(&db_handle, &branch_handle, &store, None)
             .map_err(|e| PyRuntimeError::new_err(format!("Flush error: {:?}", e)))?;
-        
+
         Ok(())
     }
-    
+
     #[cfg(not(feature = "fs"))]
     fn flush(&self) -> PyResult<()> {
         Err(PyRuntimeError::new_err("flush() requires the 'fs' feature. Rebuild casys_pyo3 with --features fs."))
@@ -1,30 +1,140 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::path::Path;
 
 use casys_core::{
-    BranchName, DatabaseName, EngineError, StorageBackend, Timestamp,
+    BranchName, DatabaseName, EncryptionConfig, EngineError, StorageBackend, Timestamp,
     StorageCatalog, ManifestStore, SegmentStore, WalSink, WalSource,
     ManifestMeta, SegmentId, WalTailMeta,
 };
 
-use crate::{manifest as mf, catalog, wal, segments};
+use crate::{manifest as mf, catalog, keyheader, lock, wal, segments, chunkstore};
+use crate::chunker::ChunkerConfig;
+use crate::chunkstore::ChunkId;
+use crate::crypto::DataKey;
 
-pub struct FsBackend;
+/// How long `create_branch`/`snapshot`/`commit_tx` wait for another process's
+/// writer lock before giving up with `EngineError::Locked`.
+const WRITER_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct FsBackend {
+    encryption: Option<DataKey>,
+}
 
 impl FsBackend {
-    pub fn new() -> Self { Self }
+    pub fn new() -> Self {
+        Self { encryption: None }
+    }
+
+    /// Opens (or initializes) `root`'s encryption-at-rest key per `config`.
+    /// `EncryptionConfig::None` behaves exactly like `new`.
+    pub fn with_encryption(root: &Path, config: &EncryptionConfig) -> Result<Self, EngineError> {
+        Ok(Self { encryption: keyheader::resolve_data_key(root, config)? })
+    }
+
+    /// `snapshot`'s body, assuming the caller already holds `branch`'s
+    /// writer lock. Shared by `StorageBackend::snapshot` and `commit_tx`,
+    /// which takes the lock once for both the WAL append and this publish.
+    fn snapshot_locked(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Timestamp, EngineError> {
+        let base = mf::latest_manifest(root, db, branch, self.encryption.as_ref())?;
+        let now_ms: Timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        let manifest = mf::Manifest {
+            branch: branch.as_str().to_string(),
+            version_ts: now_ms,
+            segments: base.as_ref().map(|m| m.segments.clone()).unwrap_or_default(),
+            wal_tail: base.as_ref().and_then(|m| m.wal_tail.clone()),
+        };
+        let _ = mf::write_manifest(root, db, branch, &manifest, self.encryption.as_ref())?;
+        Ok(now_ms)
+    }
+
+    /// The chunk ids `write_segment` stored for `segment_id`, or empty if
+    /// the segment isn't on disk (or predates chunked writes) - best-effort,
+    /// since a manifest's `SegmentRef.chunks` is an optimization for GC and
+    /// dedup bookkeeping, not something reads depend on.
+    fn segment_chunk_ids(&self, root: &Path, db: &DatabaseName, segment_id: &str) -> Vec<String> {
+        let path = segments::segment_path(root, db, segment_id);
+        segments::Segment::read_from_path(&path, self.encryption.as_ref())
+            .ok()
+            .and_then(|seg| decode_chunk_pointer(&seg.data).ok())
+            .map(|ids| ids.into_iter().map(|c| c.0).collect())
+            .unwrap_or_default()
+    }
+
+    /// Converts `meta` to a `Manifest`, looking up each segment's
+    /// already-written chunk ids so the resulting `Manifest`'s `SegmentRef.chunks` reflects what's
+    /// actually on disk - `ManifestMeta` itself has no room for them.
+    fn from_meta(&self, root: &Path, db: &DatabaseName, meta: &ManifestMeta) -> mf::Manifest {
+        mf::Manifest {
+            branch: meta.branch.clone(),
+            version_ts: meta.version_ts,
+            segments: meta.segments.iter().map(|id| mf::SegmentRef {
+                chunks: self.segment_chunk_ids(root, db, &id.0),
+                id: id.0.clone(),
+                range: None,
+            }).collect(),
+            wal_tail: meta.wal_tail.as_ref().map(|w| mf::WalTail { epoch: w.epoch, seq: w.seq }),
+        }
+    }
+}
+
+impl Default for FsBackend {
+    fn default() -> Self { Self::new() }
+}
+
+/// Encodes the ordered chunk ids `chunkstore::write_chunked` produced for a
+/// segment's data as that segment's on-disk body: the actual bytes live
+/// under `chunks/`, content-addressed, so this pointer record is all
+/// `segments/<id>.seg` needs to hold.
+fn encode_chunk_pointer(ids: &[ChunkId]) -> Vec<u8> {
+    let ids: Vec<&str> = ids.iter().map(|c| c.0.as_str()).collect();
+    serde_json::to_vec(&ids).expect("chunk id list serializes to valid utf8 json")
+}
+
+fn decode_chunk_pointer(bytes: &[u8]) -> Result<Vec<ChunkId>, EngineError> {
+    let ids: Vec<String> = serde_json::from_slice(bytes)
+        .map_err(|e| EngineError::StorageIo(format!("parse chunk pointer: {e}")))?;
+    Ok(ids.into_iter().map(ChunkId).collect())
 }
 
 impl SegmentStore for FsBackend {
+    /// Splits `data` via content-defined chunking and writes each chunk to
+    /// the chunk store, then stores the ordered chunk ids under
+    /// `segment_id` instead of the raw bytes - so two segments that share
+    /// most of their content (the common case across snapshots/branches)
+    /// only pay to store the bytes that actually differ.
     fn write_segment(&self, root: &Path, db: &DatabaseName, segment_id: &SegmentId, data: &[u8], node_count: u64, edge_count: u64) -> Result<(), EngineError> {
-        let seg = segments::Segment::new(node_count, edge_count, data.to_vec());
-        let _ = segments::write_segment(root, db, &segment_id.0, &seg)?;
+        let path = segments::segment_path(root, db, &segment_id.0);
+        // Only a content-addressed id guarantees that an existing file
+        // already holds these exact bytes; fixed ids like `"nodes"`/
+        // `"raft-branches"` are reused across writes precisely because
+        // their contents change, so those must always overwrite.
+        if segment_id.0.starts_with("sha256:") && path.exists() {
+            return Ok(());
+        }
+        let chunk_ids = chunkstore::write_chunked(root, db, data, &ChunkerConfig::default(), self.encryption.as_ref())?;
+        let seg = segments::Segment::new(node_count, edge_count, encode_chunk_pointer(&chunk_ids));
+        seg.write_to_path(&path, self.encryption.as_ref())?;
         Ok(())
     }
 
+    /// Reassembles `data` from the chunk ids `write_segment` stored, then
+    /// re-derives the content hash check `segments::read_segment` normally
+    /// does - against the reassembled data, since what's on disk under
+    /// `segment_id` is now a pointer record, not `data` itself.
     fn read_segment(&self, root: &Path, db: &DatabaseName, segment_id: &SegmentId) -> Result<(Vec<u8>, u64, u64), EngineError> {
-        let seg = segments::read_segment(root, db, &segment_id.0)?;
-        Ok((seg.data, seg.header.node_count, seg.header.edge_count))
+        let path = segments::segment_path(root, db, &segment_id.0);
+        let seg = segments::Segment::read_from_path(&path, self.encryption.as_ref())?;
+        let chunk_ids = decode_chunk_pointer(&seg.data)?;
+        let data = chunkstore::read_chunked(root, db, &chunk_ids, self.encryption.as_ref())?;
+        if segment_id.0.starts_with("sha256:") {
+            let recomputed = segments::content_id(&data);
+            if recomputed != segment_id.0 {
+                return Err(EngineError::Corruption(format!(
+                    "segment {}: content hash mismatch (recomputed {recomputed})", segment_id.0
+                )));
+            }
+        }
+        Ok((data, seg.header.node_count, seg.header.edge_count))
     }
 }
 
@@ -37,24 +147,16 @@ fn to_meta(m: &mf::Manifest) -> ManifestMeta {
     }
 }
 
-fn from_meta(meta: &ManifestMeta) -> mf::Manifest {
-    mf::Manifest {
-        branch: meta.branch.clone(),
-        version_ts: meta.version_ts,
-        segments: meta.segments.iter().map(|id| mf::SegmentRef { id: id.0.clone(), range: None }).collect(),
-        wal_tail: meta.wal_tail.as_ref().map(|w| mf::WalTail { epoch: w.epoch, seq: w.seq }),
-    }
-}
-
 impl StorageCatalog for FsBackend {
     fn list_branches(&self, root: &Path, db: &DatabaseName) -> Result<Vec<BranchName>, EngineError> {
         catalog::list_branches(root, db)
     }
 
     fn create_branch(&self, root: &Path, db: &DatabaseName, from: &BranchName, new_branch: &BranchName, at: Option<Timestamp>) -> Result<(), EngineError> {
+        let _lock = lock::acquire_timeout(&catalog::branch_dir(root, db, new_branch), WRITER_LOCK_TIMEOUT)?;
         let base = match at {
-            Some(ts) => mf::pitr_manifest(root, db, from, ts)?,
-            None => mf::latest_manifest(root, db, from)?,
+            Some(ts) => mf::pitr_manifest(root, db, from, ts, self.encryption.as_ref())?,
+            None => mf::latest_manifest(root, db, from, self.encryption.as_ref())?,
         };
         let now_ms: Timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
         let manifest = mf::Manifest {
@@ -63,7 +165,7 @@ impl StorageCatalog for FsBackend {
             segments: base.as_ref().map(|m| m.segments.clone()).unwrap_or_default(),
             wal_tail: base.as_ref().and_then(|m| m.wal_tail.clone()),
         };
-        let _ = mf::write_manifest(root, db, new_branch, &manifest)?;
+        let _ = mf::write_manifest(root, db, new_branch, &manifest, self.encryption.as_ref())?;
         Ok(())
     }
 }
@@ -73,18 +175,19 @@ impl ManifestStore for FsBackend {
         let paths = mf::list_manifest_paths(root, db, branch)?;
         let mut ts = Vec::with_capacity(paths.len());
         for p in paths {
-            let m = mf::read_manifest(&p)?;
-            ts.push(m.version_ts);
+            // For a v2 manifest `version_ts` comes from the header, so no
+            // segment record is parsed; a JSON manifest is read in full.
+            ts.push(mf::read_manifest_view(&p, self.encryption.as_ref())?.version_ts());
         }
         Ok(ts)
     }
 
     fn latest_manifest_meta(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Option<ManifestMeta>, EngineError> {
-        Ok(mf::latest_manifest(root, db, branch)?.map(|m| to_meta(&m)))
+        Ok(mf::latest_manifest(root, db, branch, self.encryption.as_ref())?.map(|m| to_meta(&m)))
     }
 
     fn pitr_manifest_meta(&self, root: &Path, db: &DatabaseName, branch: &BranchName, at: Timestamp) -> Result<Option<ManifestMeta>, EngineError> {
-        Ok(mf::pitr_manifest(root, db, branch, at)?.map(|m| to_meta(&m)))
+        Ok(mf::pitr_manifest(root, db, branch, at, self.encryption.as_ref())?.map(|m| to_meta(&m)))
     }
 
     fn read_manifest_meta(&self, root: &Path, db: &DatabaseName, branch: &BranchName, ts: Timestamp) -> Result<Option<ManifestMeta>, EngineError> {
@@ -93,13 +196,13 @@ impl ManifestStore for FsBackend {
         if !path.exists() {
             return Ok(None);
         }
-        let m = mf::read_manifest(&path)?;
+        let m = mf::read_manifest(&path, self.encryption.as_ref())?;
         Ok(Some(to_meta(&m)))
     }
 
     fn write_manifest_meta(&self, root: &Path, db: &DatabaseName, branch: &BranchName, meta: &ManifestMeta) -> Result<(), EngineError> {
-        let m = from_meta(meta);
-        let _ = mf::write_manifest(root, db, branch, &m)?;
+        let m = self.from_meta(root, db, meta);
+        let _ = mf::write_manifest(root, db, branch, &m, self.encryption.as_ref())?;
         Ok(())
     }
 }
@@ -110,9 +213,10 @@ impl StorageBackend for FsBackend {
     }
 
     fn create_branch(&self, root: &Path, db: &DatabaseName, from: &BranchName, new_branch: &BranchName, at: Option<Timestamp>) -> Result<(), EngineError> {
+        let _lock = lock::acquire_timeout(&catalog::branch_dir(root, db, new_branch), WRITER_LOCK_TIMEOUT)?;
         let base = match at {
-            Some(ts) => mf::pitr_manifest(root, db, from, ts)?,
-            None => mf::latest_manifest(root, db, from)?,
+            Some(ts) => mf::pitr_manifest(root, db, from, ts, self.encryption.as_ref())?,
+            None => mf::latest_manifest(root, db, from, self.encryption.as_ref())?,
         };
         let now_ms: Timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
         let manifest = mf::Manifest {
@@ -121,41 +225,47 @@ impl StorageBackend for FsBackend {
             segments: base.as_ref().map(|m| m.segments.clone()).unwrap_or_default(),
             wal_tail: base.as_ref().and_then(|m| m.wal_tail.clone()),
         };
-        let _ = mf::write_manifest(root, db, new_branch, &manifest)?;
+        let _ = mf::write_manifest(root, db, new_branch, &manifest, self.encryption.as_ref())?;
         Ok(())
     }
 
     fn snapshot(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Timestamp, EngineError> {
-        let base = mf::latest_manifest(root, db, branch)?;
-        let now_ms: Timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
-        let manifest = mf::Manifest {
-            branch: branch.as_str().to_string(),
-            version_ts: now_ms,
-            segments: base.as_ref().map(|m| m.segments.clone()).unwrap_or_default(),
-            wal_tail: base.as_ref().and_then(|m| m.wal_tail.clone()),
-        };
-        let _ = mf::write_manifest(root, db, branch, &manifest)?;
-        Ok(now_ms)
+        let _lock = lock::acquire_timeout(&catalog::branch_dir(root, db, branch), WRITER_LOCK_TIMEOUT)?;
+        self.snapshot_locked(root, db, branch)
     }
 
     fn commit_tx(&self, root: &Path, db: &DatabaseName, branch: &BranchName, records: &[Vec<u8>]) -> Result<Timestamp, EngineError> {
+        // Held across both the WAL append and the snapshot that publishes
+        // it; `snapshot_locked` skips re-acquiring (a second, independent
+        // flock on the same file from this process would just block on
+        // itself, since flock ownership is per open file description, not
+        // per-process).
+        let _lock = lock::acquire_timeout(&catalog::branch_dir(root, db, branch), WRITER_LOCK_TIMEOUT)?;
         let mut w = wal::WalWriter::open(root, db, branch, 4 * 1024 * 1024)?;
         for rec in records {
             w.write_record(rec)?;
         }
         w.flush()?;
-        self.snapshot(root, db, branch)
+        self.snapshot_locked(root, db, branch)
     }
 
     fn list_snapshot_timestamps(&self, root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Vec<Timestamp>, EngineError> {
         let paths = mf::list_manifest_paths(root, db, branch)?;
         let mut ts = Vec::with_capacity(paths.len());
         for p in paths {
-            let m = mf::read_manifest(&p)?;
-            ts.push(m.version_ts);
+            // For a v2 manifest `version_ts` comes from the header, so no
+            // segment record is parsed; a JSON manifest is read in full.
+            ts.push(mf::read_manifest_view(&p, self.encryption.as_ref())?.version_ts());
         }
         Ok(ts)
     }
+
+    /// Sweeps `db`'s chunk store via `chunkstore::gc_unreferenced`. Callers
+    /// (currently `Engine::merge_branch`, best-effort) must pass every
+    /// branch of `db`, per that function's safety requirement.
+    fn gc(&self, root: &Path, db: &DatabaseName, branches: &[BranchName]) -> Result<usize, EngineError> {
+        chunkstore::gc_unreferenced(root, db, branches, self.encryption.as_ref())
+    }
 }
 
 // -----------------------
@@ -163,12 +273,7 @@ impl StorageBackend for FsBackend {
 // -----------------------
 
 fn parse_seq_from_name(name: &str) -> Option<(u64, u64)> {
-    if !name.starts_with("wal-") || !name.ends_with(".wal") { return None; }
-    let core = &name[4..name.len()-4];
-    let mut it = core.split('-');
-    let epoch = it.next()?.parse::<u64>().ok()?;
-    let seq = it.next()?.parse::<u64>().ok()?;
-    Some((epoch, seq))
+    wal::parse_wal_filename(name)
 }
 
 impl WalSink for FsBackend {
@@ -176,16 +281,11 @@ impl WalSink for FsBackend {
         let mut w = wal::WalWriter::open(root, db, branch, 4 * 1024 * 1024)?;
         for rec in records { w.write_record(rec)?; }
         w.flush()?;
-        // Determine current tail by scanning latest file
-        let paths = wal::list_wal_paths(root, db, branch)?;
-        if let Some(last) = paths.last() {
-            if let Some(name) = last.file_name().and_then(|s| s.to_str()) {
-                if let Some((epoch, seq)) = parse_seq_from_name(name) {
-                    return Ok(WalTailMeta { epoch, seq });
-                }
-            }
-        }
-        Ok(WalTailMeta { epoch: 0, seq: 0 })
+        // `flush()` already published this tail to the head index, so the
+        // writer itself can hand it back - no need to re-list the WAL
+        // directory just to re-derive what was just written.
+        let (epoch, seq) = w.tail();
+        Ok(WalTailMeta { epoch, seq })
     }
 }
 
@@ -203,15 +303,8 @@ impl WalSource for FsBackend {
     }
 
     fn read_wal_segment(&self, root: &Path, db: &DatabaseName, branch: &BranchName, tail: &WalTailMeta) -> Result<Vec<Vec<u8>>, EngineError> {
-        for p in wal::list_wal_paths(root, db, branch)? {
-            if let Some(name) = p.file_name().and_then(|s| s.to_str()) {
-                if let Some((epoch, seq)) = parse_seq_from_name(name) {
-                    if epoch == tail.epoch && seq == tail.seq {
-                        return wal::read_records(&p);
-                    }
-                }
-            }
-        }
-        Ok(Vec::new())
+        // The `(epoch, seq)` naming scheme lets us build the path directly,
+        // rather than listing every WAL file just to find the one match.
+        wal::read_records_at(root, db, branch, tail.epoch, tail.seq)
     }
 }
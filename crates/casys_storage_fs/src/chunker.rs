@@ -0,0 +1,83 @@
+//! Content-defined chunking (CDC) via a buzhash rolling hash.
+//!
+//! Splits a byte stream into variable-length chunks whose boundaries depend
+//! only on the data itself: a boundary is declared wherever the rolling hash
+//! of the trailing `WINDOW` bytes satisfies `hash & mask == 0`, clamped by
+//! `min_chunk`/`max_chunk` so no chunk is absurdly small or large. Because the
+//! boundary only depends on nearby bytes, inserting or deleting data shifts
+//! chunk boundaries only in the edited region - everything else rechunks
+//! identically, which is what makes dedup against prior segments effective.
+
+use std::sync::OnceLock;
+
+/// Sliding window size (in bytes) the rolling hash is computed over.
+const WINDOW: usize = 48;
+
+fn buzhash_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        // splitmix64, seeded with a fixed constant so the table (and thus
+        // chunk boundaries) are stable across runs and processes.
+        let mut table = [0u64; 256];
+        let mut seed: u64 = 0x9E3779B97F4A7C15;
+        for slot in table.iter_mut() {
+            seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            *slot = z ^ (z >> 31);
+        }
+        table
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct ChunkerConfig {
+    pub min_chunk: usize,
+    pub max_chunk: usize,
+    /// A boundary is declared when `hash & mask == 0`; lower bits set ->
+    /// smaller average chunk size.
+    pub mask: u64,
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        Self {
+            min_chunk: 4 * 1024,
+            max_chunk: 1024 * 1024,
+            mask: (1 << 13) - 1, // ~8KiB average chunk size
+        }
+    }
+}
+
+/// Splits `data` into content-defined chunk ranges `[start, end)`. Empty
+/// input yields no chunks; otherwise every byte of `data` is covered by
+/// exactly one contiguous range.
+pub fn chunk_boundaries(data: &[u8], config: &ChunkerConfig) -> Vec<(usize, usize)> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let table = buzhash_table();
+    let mut ranges = Vec::new();
+    let mut start = 0usize;
+    let mut hash: u64 = 0;
+
+    for i in 0..data.len() {
+        let chunk_len = i - start + 1;
+        hash = hash.rotate_left(1) ^ table[data[i] as usize];
+        if chunk_len > WINDOW {
+            let leaving = data[i - WINDOW];
+            hash ^= table[leaving as usize].rotate_left((WINDOW % 64) as u32);
+        }
+
+        let at_content_boundary = chunk_len >= config.min_chunk && (hash & config.mask) == 0;
+        let at_max_size = chunk_len >= config.max_chunk;
+        let at_end = i == data.len() - 1;
+        if at_content_boundary || at_max_size || at_end {
+            ranges.push((start, i + 1));
+            start = i + 1;
+            hash = 0;
+        }
+    }
+    ranges
+}
@@ -0,0 +1,135 @@
+//! Content-addressed chunk store: each chunk produced by `chunker` is hashed
+//! with SHA-256 and written exactly once under `chunks/<prefix>/<hex>`, so
+//! segments that only mutate a fraction of the graph reference mostly the
+//! same chunks across snapshots and branches. Mirrors the chunk/chunker/
+//! chunkstore split in the Obnam2 backup engine, recast for this crate's
+//! manifest + segment model.
+
+use std::{collections::HashSet, fs, path::{Path, PathBuf}};
+
+use sha2::{Digest, Sha256};
+
+use casys_core::{BranchName, DatabaseName, EngineError};
+
+use crate::chunker::{chunk_boundaries, ChunkerConfig};
+use crate::crypto::{self, DataKey};
+use crate::manifest;
+use crate::util::atomic_write_file;
+
+/// A content id of the form `sha256:<hex>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChunkId(pub String);
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+fn hash_chunk(data: &[u8]) -> ChunkId {
+    let digest = Sha256::digest(data);
+    ChunkId(format!("sha256:{}", hex_encode(&digest)))
+}
+
+fn chunks_dir(root: &Path, db: &DatabaseName) -> PathBuf {
+    root.join(db.as_str()).join("chunks")
+}
+
+fn chunk_path(root: &Path, db: &DatabaseName, id: &ChunkId) -> PathBuf {
+    let hex = id.0.strip_prefix("sha256:").unwrap_or(&id.0);
+    let prefix = if hex.len() >= 2 { &hex[..2] } else { "00" };
+    chunks_dir(root, db).join(prefix).join(hex)
+}
+
+/// Writes `data` under its content hash, skipping the write if a chunk with
+/// that id is already on disk (the dedup step). The hash - and therefore
+/// dedup - is always computed over plaintext, so encryption doesn't disturb
+/// it; `key`, when present, only seals the bytes actually written to disk.
+pub fn put_chunk(root: &Path, db: &DatabaseName, data: &[u8], key: Option<&DataKey>) -> Result<ChunkId, EngineError> {
+    let id = hash_chunk(data);
+    let path = chunk_path(root, db, &id);
+    if !path.exists() {
+        let bytes = match key {
+            Some(k) => crypto::seal(k, data)?,
+            None => data.to_vec(),
+        };
+        atomic_write_file(&path, &bytes)
+            .map_err(|e| EngineError::StorageIo(format!("atomic_write_file({}): {e}", path.display())))?;
+    }
+    Ok(id)
+}
+
+pub fn read_chunk(root: &Path, db: &DatabaseName, id: &ChunkId, key: Option<&DataKey>) -> Result<Vec<u8>, EngineError> {
+    let path = chunk_path(root, db, id);
+    let bytes = fs::read(&path).map_err(|e| EngineError::StorageIo(format!("read({}): {e}", path.display())))?;
+    match key {
+        Some(k) => crypto::open(k, &bytes),
+        None => Ok(bytes),
+    }
+}
+
+/// Splits `data` via content-defined chunking and writes each chunk exactly
+/// once. Returns the ordered chunk ids needed to reconstruct `data`.
+pub fn write_chunked(root: &Path, db: &DatabaseName, data: &[u8], config: &ChunkerConfig, key: Option<&DataKey>) -> Result<Vec<ChunkId>, EngineError> {
+    chunk_boundaries(data, config)
+        .into_iter()
+        .map(|(start, end)| put_chunk(root, db, &data[start..end], key))
+        .collect()
+}
+
+/// Reassembles `data` from its ordered chunk ids.
+pub fn read_chunked(root: &Path, db: &DatabaseName, ids: &[ChunkId], key: Option<&DataKey>) -> Result<Vec<u8>, EngineError> {
+    let mut out = Vec::new();
+    for id in ids {
+        out.extend(read_chunk(root, db, id, key)?);
+    }
+    Ok(out)
+}
+
+/// Mark-and-sweep GC: walks every manifest across `branches` to compute
+/// the set of reachable chunk ids, then deletes every chunk file on disk
+/// that isn't in that set. Callers must pass every branch of `db` that might
+/// still reference a chunk, or live chunks will be collected.
+pub fn gc_unreferenced(root: &Path, db: &DatabaseName, branches: &[BranchName], key: Option<&DataKey>) -> Result<usize, EngineError> {
+    let mut live: HashSet<String> = HashSet::new();
+    for branch in branches {
+        for path in manifest::list_manifest_paths(root, db, branch)? {
+            let m = manifest::read_manifest(&path, key)?;
+            for seg in &m.segments {
+                live.extend(seg.chunks.iter().cloned());
+            }
+        }
+    }
+
+    let dir = chunks_dir(root, db);
+    let prefix_iter = match fs::read_dir(&dir) {
+        Ok(it) => it,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(EngineError::StorageIo(format!("read_dir({}): {e}", dir.display()))),
+    };
+
+    let mut deleted = 0usize;
+    for prefix_entry in prefix_iter {
+        let prefix_path = prefix_entry
+            .map_err(|e| EngineError::StorageIo(format!("read_dir entry: {e}")))?
+            .path();
+        if !prefix_path.is_dir() {
+            continue;
+        }
+        let entries = fs::read_dir(&prefix_path)
+            .map_err(|e| EngineError::StorageIo(format!("read_dir({}): {e}", prefix_path.display())))?;
+        for entry in entries {
+            let path = entry.map_err(|e| EngineError::StorageIo(format!("read_dir entry: {e}")))?.path();
+            if let Some(hex) = path.file_name().and_then(|s| s.to_str()) {
+                let id = format!("sha256:{hex}");
+                if !live.contains(&id) {
+                    fs::remove_file(&path).map_err(|e| EngineError::StorageIo(format!("remove_file({}): {e}", path.display())))?;
+                    deleted += 1;
+                }
+            }
+        }
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,91 @@
+//! AEAD sealing for encryption-at-rest. Every sealed file on disk is
+//! `nonce || ciphertext || tag`, encrypted with ChaCha20-Poly1305 under a
+//! 256-bit data key. Passphrase-derived keys go through Argon2id so a weak
+//! passphrase doesn't translate directly into a weak key.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use casys_core::EngineError;
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// A resolved 256-bit data key used to seal/open segments, chunks, and
+/// manifests. Distinct from the passphrase/raw key a caller supplies -
+/// see `keyheader` for how one is derived from the other.
+#[derive(Clone)]
+pub struct DataKey(pub [u8; KEY_LEN]);
+
+pub fn random_key() -> DataKey {
+    let mut bytes = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    DataKey(bytes)
+}
+
+pub fn random_salt() -> [u8; SALT_LEN] {
+    let mut bytes = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Derives a data key from a human passphrase and a per-data-dir salt via
+/// Argon2id, so brute-forcing the passphrase costs real memory+time per guess.
+pub fn derive_key_from_passphrase(passphrase: &str, salt: &[u8; SALT_LEN]) -> Result<DataKey, EngineError> {
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| EngineError::InvalidArgument(format!("argon2id key derivation failed: {e}")))?;
+    Ok(DataKey(key))
+}
+
+/// Seals `plaintext` under `key`, returning `nonce || ciphertext || tag`
+/// with a fresh random 96-bit nonce.
+pub fn seal(key: &DataKey, plaintext: &[u8]) -> Result<Vec<u8>, EngineError> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key.0));
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext)
+        .map_err(|e| EngineError::StorageIo(format!("AEAD seal failed: {e}")))?;
+    let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Reverses `seal`. Fails (rather than returning garbage) on a wrong key,
+/// truncated file, or tampered ciphertext, since ChaCha20-Poly1305 is AEAD.
+pub fn open(key: &DataKey, sealed: &[u8]) -> Result<Vec<u8>, EngineError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(EngineError::StorageIo("sealed data shorter than nonce".into()));
+    }
+    let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key.0));
+    let nonce = Nonce::from_slice(nonce_bytes);
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| EngineError::StorageIo("AEAD open failed: wrong key or corrupted data".into()))
+}
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+pub fn hex_decode(hex: &str) -> Result<Vec<u8>, EngineError> {
+    if hex.len() % 2 != 0 {
+        return Err(EngineError::StorageIo("hex string has odd length".into()));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| EngineError::StorageIo(format!("invalid hex: {e}"))))
+        .collect()
+}
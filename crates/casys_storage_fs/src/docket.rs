@@ -0,0 +1,41 @@
+//! Per-branch docket file: a tiny atomic pointer naming the current manifest,
+//! so finding the head is one small read instead of a `read_dir` + filename
+//! sort (`manifest::list_manifest_paths`). Mirrors Mercurial's dirstate-v2
+//! docket file, recast for this crate's per-branch manifest directories.
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use casys_core::{BranchName, DatabaseName, EngineError};
+
+use crate::manifest::branch_dir;
+use crate::util::atomic_write_file;
+
+const DOCKET_FILENAME: &str = "docket";
+
+fn docket_path(root: &Path, db: &DatabaseName, branch: &BranchName) -> PathBuf {
+    branch_dir(root, db, branch).join(DOCKET_FILENAME)
+}
+
+/// Atomically records `manifest_filename` as the current head for `branch`.
+/// Callers write this right after publishing a new manifest file.
+pub fn write_docket(root: &Path, db: &DatabaseName, branch: &BranchName, manifest_filename: &str) -> Result<(), EngineError> {
+    let path = docket_path(root, db, branch);
+    atomic_write_file(&path, manifest_filename.as_bytes())
+        .map_err(|e| EngineError::StorageIo(format!("atomic_write_file({}): {e}", path.display())))
+}
+
+/// Resolves the docket's pointer to a full manifest path, if a docket has
+/// been written yet. Returns `Ok(None)` rather than erroring so callers can
+/// fall back to `list_manifest_paths` for branches predating the docket.
+pub fn read_docket(root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Option<PathBuf>, EngineError> {
+    let path = docket_path(root, db, branch);
+    match fs::read(&path) {
+        Ok(bytes) => {
+            let name = String::from_utf8(bytes)
+                .map_err(|e| EngineError::StorageIo(format!("invalid docket contents ({}): {e}", path.display())))?;
+            Ok(Some(branch_dir(root, db, branch).join(name)))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(EngineError::StorageIo(format!("read({}): {e}", path.display()))),
+    }
+}
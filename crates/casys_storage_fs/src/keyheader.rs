@@ -0,0 +1,106 @@
+//! Per-data-dir key header: a small unencrypted file recording the cipher,
+//! KDF parameters, and a wrapped (sealed) copy of the random data key that
+//! actually encrypts segments/chunks/manifests. Opening with the right
+//! key/passphrase unwraps it; opening with the wrong one fails the AEAD tag
+//! check in `crypto::open`, which is how the engine verifies a passphrase.
+//!
+//! This envelope layer (wrap a random data key under a passphrase-derived
+//! key, rather than deriving the data key directly) means rotating a
+//! passphrase only needs to re-wrap the header, not re-encrypt every segment.
+
+use std::{fs, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use casys_core::{EncryptionConfig, EngineError};
+
+use crate::crypto::{self, DataKey};
+use crate::util::atomic_write_file;
+
+const KEY_HEADER_FILENAME: &str = "key-header.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeyHeaderFile {
+    cipher: String,
+    kdf: String, // "none" | "argon2id"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>, // hex, present when kdf == "argon2id"
+    wrapped_key: String,  // hex of crypto::seal(wrapping_key, data_key)
+}
+
+fn key_header_path(root: &Path) -> PathBuf {
+    root.join(KEY_HEADER_FILENAME)
+}
+
+fn read_header(path: &Path) -> Result<KeyHeaderFile, EngineError> {
+    let data = fs::read(path).map_err(|e| EngineError::StorageIo(format!("read({}): {e}", path.display())))?;
+    serde_json::from_slice(&data).map_err(|e| EngineError::StorageIo(format!("parse key header ({}): {e}", path.display())))
+}
+
+fn write_header(path: &Path, header: &KeyHeaderFile) -> Result<(), EngineError> {
+    let bytes = serde_json::to_vec_pretty(header).map_err(|e| EngineError::StorageIo(format!("serialize key header: {e}")))?;
+    atomic_write_file(path, &bytes).map_err(|e| EngineError::StorageIo(format!("atomic_write_file({}): {e}", path.display())))
+}
+
+/// Resolves `config` into the data key that seals/opens this data directory,
+/// creating the key header on first use and verifying against it thereafter.
+/// Returns `Ok(None)` for `EncryptionConfig::None` (plaintext storage).
+pub fn resolve_data_key(root: &Path, config: &EncryptionConfig) -> Result<Option<DataKey>, EngineError> {
+    match config {
+        EncryptionConfig::None => Ok(None),
+        EncryptionConfig::Key(raw) => {
+            let wrapping_key = DataKey(*raw);
+            unwrap_or_init(root, "none", None, &wrapping_key).map(Some)
+        }
+        EncryptionConfig::Passphrase(passphrase) => {
+            let path = key_header_path(root);
+            let salt = if path.exists() {
+                let header = read_header(&path)?;
+                let hex = header.salt.ok_or_else(|| {
+                    EngineError::StorageIo("key header is missing the argon2id salt".into())
+                })?;
+                let bytes = crypto::hex_decode(&hex)?;
+                let mut salt = [0u8; crypto::SALT_LEN];
+                if bytes.len() != salt.len() {
+                    return Err(EngineError::StorageIo("key header salt has the wrong length".into()));
+                }
+                salt.copy_from_slice(&bytes);
+                salt
+            } else {
+                crypto::random_salt()
+            };
+            let wrapping_key = crypto::derive_key_from_passphrase(passphrase, &salt)?;
+            unwrap_or_init(root, "argon2id", Some(salt), &wrapping_key).map(Some)
+        }
+    }
+}
+
+/// Unwraps the data key recorded in this directory's key header, or - if no
+/// header exists yet - generates a fresh random data key, wraps it under
+/// `wrapping_key`, and persists the header.
+fn unwrap_or_init(root: &Path, kdf: &str, salt: Option<[u8; crypto::SALT_LEN]>, wrapping_key: &DataKey) -> Result<DataKey, EngineError> {
+    let path = key_header_path(root);
+    if path.exists() {
+        let header = read_header(&path)?;
+        let wrapped = crypto::hex_decode(&header.wrapped_key)?;
+        let raw = crypto::open(wrapping_key, &wrapped)
+            .map_err(|_| EngineError::InvalidArgument("wrong encryption key or passphrase for this data directory".into()))?;
+        if raw.len() != crypto::KEY_LEN {
+            return Err(EngineError::StorageIo("unwrapped data key has the wrong length".into()));
+        }
+        let mut key = [0u8; crypto::KEY_LEN];
+        key.copy_from_slice(&raw);
+        Ok(DataKey(key))
+    } else {
+        let data_key = crypto::random_key();
+        let wrapped = crypto::seal(wrapping_key, &data_key.0)?;
+        let header = KeyHeaderFile {
+            cipher: "chacha20poly1305".to_string(),
+            kdf: kdf.to_string(),
+            salt: salt.map(|s| crypto::hex_encode(&s)),
+            wrapped_key: crypto::hex_encode(&wrapped),
+        };
+        write_header(&path, &header)?;
+        Ok(data_key)
+    }
+}
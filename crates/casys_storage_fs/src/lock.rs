@@ -0,0 +1,168 @@
+//! Cross-process single-writer enforcement: a `writer.lock` file per branch,
+//! held with an OS advisory lock (`flock` on Unix, `LockFileEx` on Windows,
+//! via the `fs4` crate) so two processes opening the same `data_dir` can
+//! never both `commit_tx` the same branch. This sits below `Engine`'s
+//! in-process `Mutex<HashMap<..>>` (casys_engine), which only serializes
+//! writers within one process; `FsBackend` takes this lock in addition for
+//! `create_branch`/`snapshot`/`commit_tx`.
+//!
+//! The lock file's contents (pid/hostname/epoch) are diagnostic metadata
+//! only - correctness comes from the OS-level advisory lock, which the
+//! kernel releases automatically when the owning process exits, so a crash
+//! can never wedge the branch. `try_acquire` surfaces contention as a
+//! distinct `EngineError::Locked` instead of blocking; `acquire_timeout`
+//! polls `try_acquire` until it succeeds or the deadline passes. This
+//! mirrors the `try_with_lock_no_wait` / blocking-with-timeout split used by
+//! Mercurial's `hg-core` repo lock.
+
+use std::{
+    fs,
+    io::{Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use fs4::FileExt;
+use serde::{Deserialize, Serialize};
+
+use casys_core::EngineError;
+
+const LOCK_FILENAME: &str = "writer.lock";
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LockOwner {
+    pid: u32,
+    hostname: String,
+    acquired_at_ms: u64,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        LockOwner {
+            pid: std::process::id(),
+            hostname: hostname(),
+            acquired_at_ms: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+        }
+    }
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn lock_path(branch_dir: &Path) -> PathBuf {
+    branch_dir.join(LOCK_FILENAME)
+}
+
+#[cfg(unix)]
+fn pid_is_alive(pid: u32) -> bool {
+    // Signal 0 does no signaling, just existence/permission checks; ESRCH
+    // means no process with that pid exists.
+    let rc = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(not(unix))]
+fn pid_is_alive(_pid: u32) -> bool {
+    // No portable liveness check here; the OS-level advisory lock (released
+    // automatically on process exit) is what actually prevents wedging.
+    true
+}
+
+/// Reads the lock file's recorded owner, if present and parseable.
+fn read_owner(path: &Path) -> Option<LockOwner> {
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Holds `branch_dir`'s writer lock until dropped. The lock file itself is
+/// left in place on drop (only the advisory lock is released) so the next
+/// acquirer can overwrite it without racing a delete.
+pub struct WriterLockGuard {
+    file: fs::File,
+    #[allow(dead_code)] // kept for diagnostics/debug formatting
+    path: PathBuf,
+}
+
+impl Drop for WriterLockGuard {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Attempts to acquire `branch_dir`'s writer lock without blocking. Returns
+/// `EngineError::Locked` immediately if another live process holds it. If
+/// the recorded owner's pid is no longer running, retries once (covering a
+/// writer that crashed after publishing its metadata but whose advisory
+/// lock the OS hasn't visibly dropped yet on this filesystem).
+pub fn try_acquire(branch_dir: &Path) -> Result<WriterLockGuard, EngineError> {
+    fs::create_dir_all(branch_dir)
+        .map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", branch_dir.display())))?;
+    let path = lock_path(branch_dir);
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(&path)
+        .map_err(|e| EngineError::StorageIo(format!("open({}): {e}", path.display())))?;
+
+    if file.try_lock_exclusive().is_err() {
+        let owner_is_stale = read_owner(&path).map(|o| !pid_is_alive(o.pid)).unwrap_or(false);
+        if !owner_is_stale || file.try_lock_exclusive().is_err() {
+            return Err(EngineError::Locked(format!("branch writer lock held: {}", path.display())));
+        }
+    }
+
+    let bytes = serde_json::to_vec(&LockOwner::current())
+        .map_err(|e| EngineError::StorageIo(format!("serialize lock owner: {e}")))?;
+    let mut f = &file;
+    f.set_len(0).map_err(|e| EngineError::StorageIo(format!("truncate({}): {e}", path.display())))?;
+    f.seek(SeekFrom::Start(0)).map_err(|e| EngineError::StorageIo(format!("seek({}): {e}", path.display())))?;
+    f.write_all(&bytes).map_err(|e| EngineError::StorageIo(format!("write({}): {e}", path.display())))?;
+    f.sync_all().map_err(|e| EngineError::StorageIo(format!("fsync({}): {e}", path.display())))?;
+
+    Ok(WriterLockGuard { file, path })
+}
+
+/// Like `try_acquire`, but polls until the lock is free or `timeout` elapses.
+pub fn acquire_timeout(branch_dir: &Path, timeout: Duration) -> Result<WriterLockGuard, EngineError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match try_acquire(branch_dir) {
+            Ok(guard) => return Ok(guard),
+            Err(e @ EngineError::Locked(_)) => {
+                if Instant::now() >= deadline {
+                    return Err(e);
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Async mirror of [`acquire_timeout`] for async callers (`casys_engine`'s
+/// `#[cfg(feature = "async")]` facade). `try_acquire` itself is non-blocking
+/// (`try_lock_exclusive` never waits on the OS), so it's safe to call
+/// directly from async context; what would otherwise block the runtime is
+/// the *poll loop* on contention, so that sleeps on `tokio::time::sleep`
+/// instead of `std::thread::sleep`, yielding the executor thread to other
+/// tasks between attempts rather than parking it.
+#[cfg(feature = "async")]
+pub async fn acquire_timeout_async(branch_dir: &Path, timeout: Duration) -> Result<WriterLockGuard, EngineError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match try_acquire(branch_dir) {
+            Ok(guard) => return Ok(guard),
+            Err(e @ EngineError::Locked(_)) => {
+                if Instant::now() >= deadline {
+                    return Err(e);
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
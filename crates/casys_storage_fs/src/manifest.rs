@@ -3,6 +3,7 @@ use std::{fs, io, path::{Path, PathBuf}};
 use serde::{Deserialize, Serialize};
 
 use casys_core::{BranchName, DatabaseName, EngineError, Timestamp};
+use crate::crypto::{self, DataKey};
 use crate::util::atomic_write_file;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,9 +14,14 @@ pub struct Range {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentRef {
-    pub id: String, // sha256:...
+    pub id: String, // sha256:... (monolithic blob id; legacy/non-chunked path)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub range: Option<Range>,
+    /// Ordered content-addressed chunk ids from `chunkstore::write_chunked`,
+    /// empty for segments written via the legacy monolithic path. Reassemble
+    /// with `chunkstore::read_chunked`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,28 +46,44 @@ impl Manifest {
     }
 }
 
-fn branch_dir(root: &Path, db: &DatabaseName, branch: &BranchName) -> PathBuf {
+pub(crate) fn branch_dir(root: &Path, db: &DatabaseName, branch: &BranchName) -> PathBuf {
     root.join(db.as_str()).join("branches").join(branch.as_str())
 }
 
-pub fn write_manifest(root: &Path, db: &DatabaseName, branch: &BranchName, m: &Manifest) -> Result<PathBuf, EngineError> {
+/// Writes `m`, sealing its JSON bytes under `key` when encryption is
+/// enabled. The filename (`manifest-<version_ts>.json`) is never encrypted,
+/// so `list_manifest_paths`/`pitr_manifest` keep working without a key.
+/// Also updates the branch's docket so `latest_manifest` can find this file
+/// with one small read instead of a directory scan.
+pub fn write_manifest(root: &Path, db: &DatabaseName, branch: &BranchName, m: &Manifest, key: Option<&DataKey>) -> Result<PathBuf, EngineError> {
     let dir = branch_dir(root, db, branch);
     fs::create_dir_all(&dir).map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", dir.display())))?;
-    let path = dir.join(m.filename());
+    let filename = m.filename();
+    let path = dir.join(&filename);
     let bytes = serde_json::to_vec_pretty(m).map_err(|e| EngineError::StorageIo(format!("serialize manifest: {e}")))?;
+    let bytes = match key {
+        Some(k) => crypto::seal(k, &bytes)?,
+        None => bytes,
+    };
     atomic_write_file(&path, &bytes).map_err(|e| EngineError::StorageIo(format!("atomic_write_file({}): {e}", path.display())))?;
+    crate::docket::write_docket(root, db, branch, &filename)?;
     Ok(path)
 }
 
-pub fn read_manifest(path: &Path) -> Result<Manifest, EngineError> {
+pub fn read_manifest(path: &Path, key: Option<&DataKey>) -> Result<Manifest, EngineError> {
     let data = fs::read(path).map_err(|e| EngineError::StorageIo(format!("read({}): {e}", path.display())))?;
+    let data = match key {
+        Some(k) => crypto::open(k, &data)?,
+        None => data,
+    };
     let m: Manifest = serde_json::from_slice(&data).map_err(|e| EngineError::StorageIo(format!("parse manifest ({}): {e}", path.display())))?;
     Ok(m)
 }
 
+/// Expects `manifest-<ts>.json` or `manifest-<ts>.v2`.
 fn parse_ts_from_filename(file_name: &str) -> Option<Timestamp> {
-    // Expect manifest-<ts>.json
-    let name = file_name.strip_prefix("manifest-")?.strip_suffix(".json")?;
+    let name = file_name.strip_prefix("manifest-")?;
+    let name = name.strip_suffix(".json").or_else(|| name.strip_suffix(".v2"))?;
     name.parse::<u64>().ok()
 }
 
@@ -75,7 +97,7 @@ pub fn list_manifest_paths(root: &Path, db: &DatabaseName, branch: &BranchName)
         let entry = entry.map_err(|e| EngineError::StorageIo(format!("read_dir entry: {e}")))?;
         let p = entry.path();
         if let Some(name) = p.file_name().and_then(|s| s.to_str()) {
-            if name.starts_with("manifest-") && name.ends_with(".json") && parse_ts_from_filename(name).is_some() {
+            if name.starts_with("manifest-") && parse_ts_from_filename(name).is_some() {
                 paths.push(p);
             }
         }
@@ -84,16 +106,87 @@ pub fn list_manifest_paths(root: &Path, db: &DatabaseName, branch: &BranchName)
     Ok(paths)
 }
 
-pub fn latest_manifest(root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Option<Manifest>, EngineError> {
+/// Finds the current manifest for `branch` and parses it. Tries the branch's
+/// docket first (one small atomic read naming the head file) before falling
+/// back to `list_manifest_paths`' `read_dir` + sort, so branches written
+/// before the docket existed - or with a stale/missing docket - still work.
+pub fn latest_manifest(root: &Path, db: &DatabaseName, branch: &BranchName, key: Option<&DataKey>) -> Result<Option<Manifest>, EngineError> {
+    latest_manifest_handle(root, db, branch, key)?.map(ManifestView::into_manifest).transpose()
+}
+
+/// A manifest as read by `latest_manifest_handle`/`pitr_manifest_handle`:
+/// either the eager JSON `Manifest` or a packed `manifest_bin::ManifestHandle`
+/// whose segment records are parsed on demand. Callers that only need
+/// `version_ts`/`wal_tail` never pay for the `Json` variant's full
+/// `Vec<SegmentRef>` when the branch is already on `manifest.v2`.
+pub enum ManifestView {
+    Json(Manifest),
+    Binary(crate::manifest_bin::ManifestHandle),
+}
+
+impl ManifestView {
+    pub fn version_ts(&self) -> Timestamp {
+        match self {
+            ManifestView::Json(m) => m.version_ts,
+            ManifestView::Binary(h) => h.version_ts(),
+        }
+    }
+
+    /// Cheap: available from the v2 header without parsing any segment record.
+    pub fn wal_tail(&self) -> Option<WalTail> {
+        match self {
+            ManifestView::Json(m) => m.wal_tail.clone(),
+            ManifestView::Binary(h) => h.wal_tail().cloned(),
+        }
+    }
+
+    pub fn segment_count(&self) -> usize {
+        match self {
+            ManifestView::Json(m) => m.segments.len(),
+            ManifestView::Binary(h) => h.segment_count(),
+        }
+    }
+
+    /// Materializes the full `Manifest`, parsing every segment record of a
+    /// `Binary` view.
+    pub fn into_manifest(self) -> Result<Manifest, EngineError> {
+        match self {
+            ManifestView::Json(m) => Ok(m),
+            ManifestView::Binary(h) => h.into_manifest(),
+        }
+    }
+}
+
+/// Reads `path` as JSON or `manifest.v2`, dispatching on its extension.
+/// `list_manifest_paths` can return either, so any caller walking its
+/// output (rather than going through `latest_manifest`/`pitr_manifest`)
+/// should read through this rather than the JSON-only `read_manifest`.
+pub fn read_manifest_view(path: &Path, key: Option<&DataKey>) -> Result<ManifestView, EngineError> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("v2") => Ok(ManifestView::Binary(crate::manifest_bin::read_manifest_v2(path, key)?)),
+        _ => Ok(ManifestView::Json(read_manifest(path, key)?)),
+    }
+}
+
+/// Like `latest_manifest`, but defers segment parsing: the docket's pointer
+/// is read once and dispatched on extension, so a `manifest.v2` branch never
+/// materializes `Vec<SegmentRef>` unless the caller asks for it. Branches
+/// predating the docket still fall back to `list_manifest_paths`.
+pub fn latest_manifest_handle(root: &Path, db: &DatabaseName, branch: &BranchName, key: Option<&DataKey>) -> Result<Option<ManifestView>, EngineError> {
+    if let Some(path) = crate::docket::read_docket(root, db, branch)? {
+        if path.exists() {
+            return read_manifest_view(&path, key).map(Some);
+        }
+    }
     let mut paths = list_manifest_paths(root, db, branch)?;
     if let Some(p) = paths.pop() {
-        read_manifest(&p).map(Some)
+        read_manifest_view(&p, key).map(Some)
     } else {
         Ok(None)
     }
 }
 
-pub fn pitr_manifest(root: &Path, db: &DatabaseName, branch: &BranchName, at: Timestamp) -> Result<Option<Manifest>, EngineError> {
+fn pitr_manifest_path(root: &Path, db: &DatabaseName, branch: &BranchName, at: Timestamp) -> Result<Option<PathBuf>, EngineError> {
     let paths = list_manifest_paths(root, db, branch)?;
     let mut best: Option<(Timestamp, PathBuf)> = None;
     for p in paths {
@@ -108,5 +201,29 @@ pub fn pitr_manifest(root: &Path, db: &DatabaseName, branch: &BranchName, at: Ti
             }
         }
     }
-    if let Some((_, p)) = best { read_manifest(&p).map(Some) } else { Ok(None) }
+    Ok(best.map(|(_, p)| p))
+}
+
+/// Like `pitr_manifest`, but defers segment parsing the same way
+/// `latest_manifest_handle` does.
+pub fn pitr_manifest_handle(root: &Path, db: &DatabaseName, branch: &BranchName, at: Timestamp, key: Option<&DataKey>) -> Result<Option<ManifestView>, EngineError> {
+    match pitr_manifest_path(root, db, branch, at)? {
+        Some(p) => read_manifest_view(&p, key).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn pitr_manifest(root: &Path, db: &DatabaseName, branch: &BranchName, at: Timestamp, key: Option<&DataKey>) -> Result<Option<Manifest>, EngineError> {
+    pitr_manifest_handle(root, db, branch, at, key)?.map(ManifestView::into_manifest).transpose()
+}
+
+/// Removes a manifest version file. Mirrors `wal::delete_segment`: a
+/// missing file isn't an error, so a GC pass retried after a partial
+/// failure is a no-op rather than erroring out.
+pub fn delete_manifest(path: &Path) -> Result<(), EngineError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(EngineError::StorageIo(format!("remove_file({}): {e}", path.display()))),
+    }
 }
@@ -0,0 +1,255 @@
+//! Binary manifest format ("manifest.v2"): a fixed header and an offset
+//! table followed by packed, fixed-layout segment records, so a reader that
+//! only needs `wal_tail` or a single segment never has to parse - or
+//! allocate - the rest. JSON manifests (`manifest::read_manifest`) remain
+//! the default and are read exactly as before; this format is opt-in via
+//! `write_manifest_v2`/`read_manifest_v2`. Loosely mirrors Mercurial's
+//! dirstate-v2 packed-record design.
+//!
+//! Layout (all integers little-endian):
+//!   magic: u32, format_version: u16, version_ts: u64,
+//!   wal_tail_present: u8, wal_tail_epoch: u64, wal_tail_seq: u64,
+//!   branch_len: u16, branch: [u8; branch_len],
+//!   segment_count: u32, offset_table: [(offset: u32, len: u32); segment_count],
+//!   segment_records: packed, addressed by the offset table
+//!
+//! Each segment record is:
+//!   id_len: u16, id: [u8; id_len],
+//!   range_present: u8, tx_min: u64, tx_max: u64,
+//!   chunk_count: u16, chunks: [(len: u16, bytes: [u8; len]); chunk_count]
+
+use std::path::{Path, PathBuf};
+
+use casys_core::{BranchName, DatabaseName, EngineError, Timestamp};
+
+use crate::crypto::{self, DataKey};
+use crate::manifest::{self, Manifest, Range, SegmentRef, WalTail};
+use crate::util::atomic_write_file;
+
+const MAGIC: u32 = 0x4d414e32; // "MAN2"
+const FORMAT_VERSION: u16 = 1;
+
+pub fn filename(version_ts: Timestamp) -> String {
+    format!("manifest-{version_ts}.v2")
+}
+
+fn encode_segment(buf: &mut Vec<u8>, seg: &SegmentRef) {
+    let id = seg.id.as_bytes();
+    buf.extend_from_slice(&(id.len() as u16).to_le_bytes());
+    buf.extend_from_slice(id);
+    match &seg.range {
+        Some(r) => {
+            buf.push(1);
+            buf.extend_from_slice(&r.tx_min.to_le_bytes());
+            buf.extend_from_slice(&r.tx_max.to_le_bytes());
+        }
+        None => {
+            buf.push(0);
+            buf.extend_from_slice(&0u64.to_le_bytes());
+            buf.extend_from_slice(&0u64.to_le_bytes());
+        }
+    }
+    buf.extend_from_slice(&(seg.chunks.len() as u16).to_le_bytes());
+    for c in &seg.chunks {
+        let bytes = c.as_bytes();
+        buf.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+        buf.extend_from_slice(bytes);
+    }
+}
+
+fn decode_segment(bytes: &[u8]) -> Result<SegmentRef, EngineError> {
+    let mut r = ByteReader::new(bytes);
+    let id_len = r.u16()? as usize;
+    let id = r.utf8(id_len)?;
+    let range_present = r.u8()?;
+    let tx_min = r.u64()?;
+    let tx_max = r.u64()?;
+    let range = if range_present == 1 { Some(Range { tx_min, tx_max }) } else { None };
+    let chunk_count = r.u16()? as usize;
+    let mut chunks = Vec::with_capacity(chunk_count);
+    for _ in 0..chunk_count {
+        let len = r.u16()? as usize;
+        chunks.push(r.utf8(len)?);
+    }
+    Ok(SegmentRef { id, range, chunks })
+}
+
+/// Encodes `m` into the packed binary layout described in the module docs.
+fn encode(m: &Manifest) -> Vec<u8> {
+    let mut records = Vec::new();
+    let mut offsets = Vec::with_capacity(m.segments.len());
+    for seg in &m.segments {
+        let start = records.len() as u32;
+        encode_segment(&mut records, seg);
+        offsets.push((start, records.len() as u32 - start));
+    }
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&MAGIC.to_le_bytes());
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&m.version_ts.to_le_bytes());
+    let (present, epoch, seq) = match &m.wal_tail {
+        Some(w) => (1u8, w.epoch, w.seq),
+        None => (0u8, 0, 0),
+    };
+    out.push(present);
+    out.extend_from_slice(&epoch.to_le_bytes());
+    out.extend_from_slice(&seq.to_le_bytes());
+    let branch = m.branch.as_bytes();
+    out.extend_from_slice(&(branch.len() as u16).to_le_bytes());
+    out.extend_from_slice(branch);
+    out.extend_from_slice(&(offsets.len() as u32).to_le_bytes());
+    for (off, len) in &offsets {
+        out.extend_from_slice(&off.to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes());
+    }
+    out.extend_from_slice(&records);
+    out
+}
+
+/// A parsed v2 header and offset table, with segment records left packed
+/// and unparsed until `segment`/`segments` is called.
+pub struct ManifestHandle {
+    branch: String,
+    version_ts: Timestamp,
+    wal_tail: Option<WalTail>,
+    offsets: Vec<(u32, u32)>,
+    records: Vec<u8>,
+}
+
+impl ManifestHandle {
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn version_ts(&self) -> Timestamp {
+        self.version_ts
+    }
+
+    /// Available without parsing a single segment record.
+    pub fn wal_tail(&self) -> Option<&WalTail> {
+        self.wal_tail.as_ref()
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Parses just the `i`th segment record.
+    pub fn segment(&self, i: usize) -> Result<SegmentRef, EngineError> {
+        let (off, len) = *self.offsets.get(i)
+            .ok_or_else(|| EngineError::InvalidArgument(format!("segment index {i} out of range")))?;
+        decode_segment(&self.records[off as usize..(off + len) as usize])
+    }
+
+    /// Parses every segment record on demand; each item is decoded only when
+    /// the iterator actually reaches it.
+    pub fn segments(&self) -> impl Iterator<Item = Result<SegmentRef, EngineError>> + '_ {
+        (0..self.segment_count()).map(move |i| self.segment(i))
+    }
+
+    /// Parses every segment record and rebuilds the JSON-model `Manifest`,
+    /// for callers that need the whole thing (e.g. bridging to `ManifestMeta`).
+    pub fn into_manifest(self) -> Result<Manifest, EngineError> {
+        let segments = self.segments().collect::<Result<Vec<_>, _>>()?;
+        Ok(Manifest { branch: self.branch, version_ts: self.version_ts, segments, wal_tail: self.wal_tail })
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], EngineError> {
+        if self.pos + n > self.bytes.len() {
+            return Err(EngineError::StorageIo("manifest.v2: unexpected end of data".into()));
+        }
+        let slice = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, EngineError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, EngineError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, EngineError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, EngineError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn utf8(&mut self, len: usize) -> Result<String, EngineError> {
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|e| EngineError::StorageIo(format!("manifest.v2: invalid utf8: {e}")))
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<ManifestHandle, EngineError> {
+    let mut r = ByteReader::new(bytes);
+    let magic = r.u32()?;
+    if magic != MAGIC {
+        return Err(EngineError::StorageIo(format!("invalid manifest.v2 magic: {magic:#x}")));
+    }
+    let format_version = r.u16()?;
+    if format_version != FORMAT_VERSION {
+        return Err(EngineError::StorageIo(format!("unsupported manifest.v2 format version: {format_version}")));
+    }
+    let version_ts = r.u64()?;
+    let wal_tail_present = r.u8()?;
+    let epoch = r.u64()?;
+    let seq = r.u64()?;
+    let wal_tail = if wal_tail_present == 1 { Some(WalTail { epoch, seq }) } else { None };
+    let branch_len = r.u16()? as usize;
+    let branch = r.utf8(branch_len)?;
+    let segment_count = r.u32()? as usize;
+    let mut offsets = Vec::with_capacity(segment_count);
+    for _ in 0..segment_count {
+        let off = r.u32()?;
+        let len = r.u32()?;
+        offsets.push((off, len));
+    }
+    let records = bytes[r.pos..].to_vec();
+    Ok(ManifestHandle { branch, version_ts, wal_tail, offsets, records })
+}
+
+/// Writes `m` in the packed `manifest.v2` layout, sealing it under `key`
+/// when encryption is enabled, and updates the branch's docket to point at
+/// it (the docket is format-agnostic: `manifest::latest_manifest_handle`
+/// dispatches on the pointed-at file's extension).
+pub fn write_manifest_v2(root: &Path, db: &DatabaseName, branch: &BranchName, m: &Manifest, key: Option<&DataKey>) -> Result<PathBuf, EngineError> {
+    let dir = manifest::branch_dir(root, db, branch);
+    std::fs::create_dir_all(&dir).map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", dir.display())))?;
+    let name = filename(m.version_ts);
+    let path = dir.join(&name);
+    let bytes = encode(m);
+    let bytes = match key {
+        Some(k) => crypto::seal(k, &bytes)?,
+        None => bytes,
+    };
+    atomic_write_file(&path, &bytes).map_err(|e| EngineError::StorageIo(format!("atomic_write_file({}): {e}", path.display())))?;
+    crate::docket::write_docket(root, db, branch, &name)?;
+    Ok(path)
+}
+
+/// Reads a `manifest.v2` file without eagerly parsing its segment records.
+pub fn read_manifest_v2(path: &Path, key: Option<&DataKey>) -> Result<ManifestHandle, EngineError> {
+    let data = std::fs::read(path).map_err(|e| EngineError::StorageIo(format!("read({}): {e}", path.display())))?;
+    let data = match key {
+        Some(k) => crypto::open(k, &data)?,
+        None => data,
+    };
+    decode(&data)
+}
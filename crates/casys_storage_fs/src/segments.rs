@@ -4,8 +4,29 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use sha2::{Digest, Sha256};
+
 use casys_core::{DatabaseName, EngineError};
 
+use crate::crypto::{self, DataKey};
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+/// Content id of the form `sha256:<hex>` over a segment's plaintext data,
+/// suitable for use as a `SegmentId`: a caller that writes a segment under
+/// its own `content_id` gets free copy-on-write sharing across branches
+/// (two branches whose data serializes identically end up pointing at the
+/// very same file) and `read_segment` can catch bit-rot by recomputing it.
+pub fn content_id(data: &[u8]) -> String {
+    format!("sha256:{}", hex_encode(&Sha256::digest(data)))
+}
+
 const SEGMENT_MAGIC: u32 = 0x43415353; // "CASS" for Casys
 const SEGMENT_VERSION: u16 = 1;
 
@@ -70,27 +91,41 @@ impl Segment {
         }
     }
 
-    pub fn write_to_path(&self, path: &Path) -> Result<(), EngineError> {
+    /// Writes the header+data to `path`, sealing the combined bytes under
+    /// `key` when encryption is enabled. The checksum in the header is always
+    /// computed over the plaintext `data`, before sealing.
+    pub fn write_to_path(&self, path: &Path, key: Option<&DataKey>) -> Result<(), EngineError> {
         if let Some(p) = path.parent() {
             fs::create_dir_all(p).map_err(|e| EngineError::StorageIo(format!("create_dir_all: {e}")))?;
         }
+        let mut bytes = self.header.to_bytes();
+        bytes.extend_from_slice(&self.data);
+        let bytes = match key {
+            Some(k) => crypto::seal(k, &bytes)?,
+            None => bytes,
+        };
         let mut f = File::create(path).map_err(|e| EngineError::StorageIo(format!("create({}): {e}", path.display())))?;
-        f.write_all(&self.header.to_bytes())
-            .and_then(|_| f.write_all(&self.data))
+        f.write_all(&bytes)
             .and_then(|_| f.sync_all())
             .map_err(|e| EngineError::StorageIo(format!("write segment: {e}")))
     }
 
-    pub fn read_from_path(path: &Path) -> Result<Self, EngineError> {
+    pub fn read_from_path(path: &Path, key: Option<&DataKey>) -> Result<Self, EngineError> {
         let mut f = File::open(path).map_err(|e| EngineError::StorageIo(format!("open({}): {e}", path.display())))?;
-        let mut hdr_bytes = vec![0u8; 26];
-        f.read_exact(&mut hdr_bytes).map_err(|e| EngineError::StorageIo(format!("read header: {e}")))?;
-        let header = SegmentHeader::from_bytes(&hdr_bytes)?;
-        let mut data = Vec::new();
-        f.read_to_end(&mut data).map_err(|e| EngineError::StorageIo(format!("read data: {e}")))?;
+        let mut bytes = Vec::new();
+        f.read_to_end(&mut bytes).map_err(|e| EngineError::StorageIo(format!("read({}): {e}", path.display())))?;
+        let bytes = match key {
+            Some(k) => crypto::open(k, &bytes)?,
+            None => bytes,
+        };
+        if bytes.len() < 26 {
+            return Err(EngineError::StorageIo("segment file too short".into()));
+        }
+        let header = SegmentHeader::from_bytes(&bytes[..26])?;
+        let data = bytes[26..].to_vec();
         let computed = crc32fast::hash(&data);
         if computed != header.checksum {
-            return Err(EngineError::StorageIo(format!("checksum mismatch: expected {:#x}, got {:#x}", header.checksum, computed)));
+            return Err(EngineError::Corruption(format!("checksum mismatch: expected {:#x}, got {:#x}", header.checksum, computed)));
         }
         Ok(Self { header, data })
     }
@@ -107,13 +142,35 @@ pub fn segment_path(root: &Path, db: &DatabaseName, segment_id: &str) -> PathBuf
     dir.join(prefix).join(format!("{}.seg", segment_id))
 }
 
-pub fn write_segment(root: &Path, db: &DatabaseName, segment_id: &str, seg: &Segment) -> Result<PathBuf, EngineError> {
+/// Writes `seg` under `segment_id`. When `segment_id` is a `content_id`
+/// (the write-then-name-it-after-the-hash pattern), a file already there
+/// means some earlier write already durably stored these exact bytes, so
+/// the write is skipped - this is what makes branching copy-on-write at
+/// the segment level rather than just a convention callers have to honor.
+pub fn write_segment(root: &Path, db: &DatabaseName, segment_id: &str, seg: &Segment, key: Option<&DataKey>) -> Result<PathBuf, EngineError> {
     let path = segment_path(root, db, segment_id);
-    seg.write_to_path(&path)?;
+    if segment_id.starts_with("sha256:") && path.exists() {
+        return Ok(path);
+    }
+    seg.write_to_path(&path, key)?;
     Ok(path)
 }
 
-pub fn read_segment(root: &Path, db: &DatabaseName, segment_id: &str) -> Result<Segment, EngineError> {
+/// Reads the segment stored under `segment_id`. When `segment_id` looks
+/// like a `content_id` (starts with `sha256:`), the data's hash is
+/// recomputed and compared against it - on top of the header's CRC32, which
+/// only catches corruption of this one write, this also catches the file
+/// having been swapped for different (but otherwise well-formed) bytes.
+pub fn read_segment(root: &Path, db: &DatabaseName, segment_id: &str, key: Option<&DataKey>) -> Result<Segment, EngineError> {
     let path = segment_path(root, db, segment_id);
-    Segment::read_from_path(&path)
+    let seg = Segment::read_from_path(&path, key)?;
+    if segment_id.starts_with("sha256:") {
+        let recomputed = content_id(&seg.data);
+        if recomputed != segment_id {
+            return Err(EngineError::Corruption(format!(
+                "segment {segment_id}: content hash mismatch (recomputed {recomputed})"
+            )));
+        }
+    }
+    Ok(seg)
 }
@@ -1,10 +1,23 @@
 use std::{fs, io, path::Path};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub fn fsync_dir(dir: &Path) -> io::Result<()> {
     let file = fs::File::open(dir)?;
     file.sync_all()
 }
 
+/// Process-wide count of bytes written through [`atomic_write_file`] /
+/// [`atomic_write_file_async`], for `casys_engine`'s `metrics` feature
+/// (`Engine::metrics_snapshot()`). Incremented on every successful write,
+/// regardless of caller (manifests, docket, chunks, key headers, ...).
+static BYTES_WRITTEN: AtomicU64 = AtomicU64::new(0);
+
+/// Total bytes written through `atomic_write_file`/`atomic_write_file_async`
+/// since process start.
+pub fn bytes_written_total() -> u64 {
+    BYTES_WRITTEN.load(Ordering::Relaxed)
+}
+
 pub fn atomic_write_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
     use std::io::Write;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -22,5 +35,43 @@ pub fn atomic_write_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
     }
 
     fs::rename(&tmp_path, path)?;
-    fsync_dir(parent)
+    fsync_dir(parent)?;
+    BYTES_WRITTEN.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Async mirror of [`fsync_dir`], for callers running on a tokio runtime
+/// (e.g. `casys_engine`'s `#[cfg(feature = "async")]` facade) that must not
+/// block the reactor thread on the directory-entry fsync.
+#[cfg(feature = "async")]
+pub async fn fsync_dir_async(dir: &Path) -> io::Result<()> {
+    let file = tokio::fs::File::open(dir).await?;
+    file.sync_all().await
+}
+
+/// Async mirror of [`atomic_write_file`] built on `tokio::fs`, preserving
+/// the same write-temp -> fsync file -> rename -> fsync dir ordering so a
+/// crash at any point still leaves either the old file or the fully-written
+/// new one, never a partial write.
+#[cfg(feature = "async")]
+pub async fn atomic_write_file_async(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let parent = path.parent().ok_or_else(|| io::Error::new(io::ErrorKind::Other, "missing parent"))?;
+    tokio::fs::create_dir_all(parent).await?;
+
+    let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let tmp_path = parent.join(format!(".{}.tmp-{}", path.file_name().unwrap().to_string_lossy(), ts));
+
+    {
+        let mut f = tokio::fs::File::create(&tmp_path).await?;
+        f.write_all(bytes).await?;
+        f.sync_all().await?;
+    }
+
+    tokio::fs::rename(&tmp_path, path).await?;
+    fsync_dir_async(parent).await?;
+    BYTES_WRITTEN.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+    Ok(())
 }
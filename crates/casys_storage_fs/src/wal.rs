@@ -14,8 +14,11 @@ fn wal_filename(epoch: u64, seq: u64) -> String {
     format!("wal-{}-{}.wal", epoch, seq)
 }
 
-fn parse_seq_from_name(name: &str) -> Option<(u64, u64)> {
-    // wal-<epoch>-<seq>.wal
+/// Recovers `(epoch, seq)` from a `wal-<epoch>-<seq>.wal` filename. Public
+/// so callers that need to compare WAL segments against a manifest's
+/// `wal_tail` (e.g. checkpointing) don't have to re-derive the naming
+/// scheme themselves.
+pub fn parse_wal_filename(name: &str) -> Option<(u64, u64)> {
     if !name.starts_with("wal-") || !name.ends_with(".wal") { return None; }
     let core = &name[4..name.len()-4];
     let mut it = core.split('-');
@@ -24,6 +27,48 @@ fn parse_seq_from_name(name: &str) -> Option<(u64, u64)> {
     Some((epoch, seq))
 }
 
+fn parse_seq_from_name(name: &str) -> Option<(u64, u64)> {
+    parse_wal_filename(name)
+}
+
+const WAL_HEAD_FILENAME: &str = "wal-head.json";
+
+/// The WAL's current tail for a branch: which file is active and how many
+/// records it holds. Lets `append_records` report its tail and
+/// `WalWriter::open` resume without either one listing the WAL directory.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WalHead {
+    pub epoch: u64,
+    pub seq: u64,
+    pub record_count: u64,
+}
+
+fn wal_head_path(root: &Path, db: &DatabaseName, branch: &BranchName) -> PathBuf {
+    wal_dir(root, db, branch).join(WAL_HEAD_FILENAME)
+}
+
+/// Atomically records `head` as the WAL's current tail. Mirrors
+/// `docket::write_docket`'s one-small-file pointer pattern, just carrying a
+/// couple more fields than a single filename.
+fn write_wal_head(root: &Path, db: &DatabaseName, branch: &BranchName, head: &WalHead) -> Result<(), EngineError> {
+    let path = wal_head_path(root, db, branch);
+    let bytes = serde_json::to_vec(head).map_err(|e| EngineError::StorageIo(format!("serialize wal-head: {e}")))?;
+    crate::util::atomic_write_file(&path, &bytes).map_err(|e| EngineError::StorageIo(format!("atomic_write_file({}): {e}", path.display())))
+}
+
+/// Resolves the WAL head pointer, if one has been written yet. Returns
+/// `Ok(None)` - rather than erroring - both for branches predating the
+/// index and for a head file that fails to parse, so callers always have
+/// `list_wal_paths`'s directory rescan as a safe fallback/rebuild path.
+pub fn read_wal_head(root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Option<WalHead>, EngineError> {
+    let path = wal_head_path(root, db, branch);
+    match fs::read(&path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes).ok()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(EngineError::StorageIo(format!("read({}): {e}", path.display()))),
+    }
+}
+
 pub fn list_wal_paths(root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Vec<PathBuf>, EngineError> {
     let dir = wal_dir(root, db, branch);
     let mut out = Vec::new();
@@ -46,11 +91,15 @@ pub fn list_wal_paths(root: &Path, db: &DatabaseName, branch: &BranchName) -> Re
 }
 
 pub struct WalWriter {
+    root: PathBuf,
+    db: DatabaseName,
+    branch: BranchName,
     dir: PathBuf,
     file: File,
     epoch: u64,
     seq: u64,
     bytes_written: u64,
+    record_count: u64,
     max_segment_bytes: u64,
 }
 
@@ -58,19 +107,38 @@ impl WalWriter {
     pub fn open(root: &Path, db: &DatabaseName, branch: &BranchName, max_segment_bytes: u64) -> Result<Self, EngineError> {
         let dir = wal_dir(root, db, branch);
         fs::create_dir_all(&dir).map_err(|e| EngineError::StorageIo(format!("create_dir_all({}): {e}", dir.display())))?;
-        // Determine next seq
-        let mut next_epoch = 0u64;
-        let mut next_seq = 0u64;
-        let existing = list_wal_paths(root, db, branch)?;
-        if let Some(last) = existing.last() {
-            if let Some(name) = last.file_name().and_then(|s| s.to_str()) { if let Some((ep, sq)) = parse_seq_from_name(name) {
-                next_epoch = ep;
-                next_seq = sq + 1;
-            }}
-        }
+        // The head index gives the next (epoch, seq) in one small read;
+        // fall back to a directory rescan for branches predating the index
+        // or a head file that's missing/corrupt.
+        let (next_epoch, next_seq) = match read_wal_head(root, db, branch)? {
+            Some(head) => (head.epoch, head.seq + 1),
+            None => {
+                let mut next_epoch = 0u64;
+                let mut next_seq = 0u64;
+                let existing = list_wal_paths(root, db, branch)?;
+                if let Some(last) = existing.last() {
+                    if let Some(name) = last.file_name().and_then(|s| s.to_str()) { if let Some((ep, sq)) = parse_seq_from_name(name) {
+                        next_epoch = ep;
+                        next_seq = sq + 1;
+                    }}
+                }
+                (next_epoch, next_seq)
+            }
+        };
         let path = dir.join(wal_filename(next_epoch, next_seq));
         let file = File::create(&path).map_err(|e| EngineError::StorageIo(format!("create({}): {e}", path.display())))?;
-        Ok(Self { dir, file, epoch: next_epoch, seq: next_seq, bytes_written: 0, max_segment_bytes })
+        Ok(Self {
+            root: root.to_path_buf(), db: db.clone(), branch: branch.clone(),
+            dir, file, epoch: next_epoch, seq: next_seq, bytes_written: 0, record_count: 0, max_segment_bytes,
+        })
+    }
+
+    /// The `(epoch, seq)` of the file this writer is currently appending
+    /// to, i.e. the tail `flush()` will publish - callers like
+    /// `WalSink::append_records` can read this directly instead of
+    /// re-listing the WAL directory right after writing to it.
+    pub fn tail(&self) -> (u64, u64) {
+        (self.epoch, self.seq)
     }
 
     fn rotate(&mut self) -> Result<(), EngineError> {
@@ -78,45 +146,128 @@ impl WalWriter {
         let path = self.dir.join(wal_filename(self.epoch, self.seq));
         self.file = File::create(&path).map_err(|e| EngineError::StorageIo(format!("create({}): {e}", path.display())))?;
         self.bytes_written = 0;
+        self.record_count = 0;
         Ok(())
     }
 
-    /// Write a length-prefixed record (u32 LE + payload)
+    /// Writes a length-and-checksum-framed record: `u32 LE length || u32 LE
+    /// CRC32(payload) || payload`. Uses `crc32fast`, the same checksum
+    /// `segments::Segment` already protects its body with, rather than
+    /// introducing a second checksum crate for this frame - nothing here
+    /// needs interop with an external CRC32C-producing tool, just detection
+    /// of a torn or bit-flipped record on this branch's own WAL.
     pub fn write_record(&mut self, payload: &[u8]) -> Result<(), EngineError> {
-        let need = 4u64 + payload.len() as u64;
+        let need = 8u64 + payload.len() as u64;
         if self.bytes_written + need > self.max_segment_bytes {
             self.flush()?;
             self.rotate()?;
         }
         let len = payload.len() as u32;
+        let crc = crc32fast::hash(payload);
         self.file.write_all(&len.to_le_bytes())
+            .and_then(|_| self.file.write_all(&crc.to_le_bytes()))
             .and_then(|_| self.file.write_all(payload))
             .map_err(|e| EngineError::StorageIo(format!("wal write: {e}")))?;
         self.bytes_written += need;
+        self.record_count += 1;
         Ok(())
     }
 
+    /// Fsyncs the active file, then transactionally publishes the new tail
+    /// to the head index - the write only takes effect (is visible to the
+    /// next `open`/`append_records` caller) once the file it describes is
+    /// itself durable.
     pub fn flush(&mut self) -> Result<(), EngineError> {
-        self.file.sync_all().map_err(|e| EngineError::StorageIo(format!("wal fsync: {e}")))
+        self.file.sync_all().map_err(|e| EngineError::StorageIo(format!("wal fsync: {e}")))?;
+        write_wal_head(&self.root, &self.db, &self.branch, &WalHead { epoch: self.epoch, seq: self.seq, record_count: self.record_count })
     }
 }
 
+/// Reads the records of the WAL file for `(epoch, seq)` directly by its
+/// known filename, without listing or scanning the WAL directory. Returns
+/// an empty `Vec` (matching `list_wal_paths` + linear search's prior
+/// behavior) if no such file exists.
+pub fn read_records_at(root: &Path, db: &DatabaseName, branch: &BranchName, epoch: u64, seq: u64) -> Result<Vec<Vec<u8>>, EngineError> {
+    let path = wal_dir(root, db, branch).join(wal_filename(epoch, seq));
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    read_records(&path)
+}
+
+/// Reads every record in `path`, discarding a torn or corrupt tail the same
+/// way `read_records_checked` does - see that function for the detection
+/// rule. Returns only the good records; a caller that also needs to know
+/// where the torn tail starts (to truncate it away before reopening for
+/// append) should call `read_records_checked` directly.
 pub fn read_records(path: &Path) -> Result<Vec<Vec<u8>>, EngineError> {
+    Ok(read_records_checked(path)?.0)
+}
+
+/// Like `read_records`, but tolerant of a torn tail write - a crash
+/// mid-`write_record` can leave a file ending in a truncated header, a
+/// header whose declared length runs past EOF, or a full record whose CRC
+/// doesn't match (a bit flip, or a write that landed out of order). Rather
+/// than erroring out and losing every record before the bad one, this
+/// stops cleanly at the first such record and returns what came before it,
+/// plus the byte offset that record starts at - `0` if the file is empty,
+/// or the file's full length if every record in it was good.
+pub fn read_records_checked(path: &Path) -> Result<(Vec<Vec<u8>>, u64), EngineError> {
     let mut f = File::open(path).map_err(|e| EngineError::StorageIo(format!("open({}): {e}", path.display())))?;
     let mut out = Vec::new();
+    let mut offset = 0u64;
     loop {
-        let mut len_bytes = [0u8; 4];
-        match f.read_exact(&mut len_bytes) {
-            Ok(()) => {},
-            Err(e) => {
-                if e.kind() == io::ErrorKind::UnexpectedEof { break; }
-                else { return Err(EngineError::StorageIo(format!("read len: {e}"))); }
-            }
+        let mut header = [0u8; 8];
+        match f.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(EngineError::StorageIo(format!("read header: {e}"))),
         }
-        let len = u32::from_le_bytes(len_bytes) as usize;
+        let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+        let expected_crc = u32::from_le_bytes(header[4..8].try_into().unwrap());
         let mut buf = vec![0u8; len];
-        f.read_exact(&mut buf).map_err(|e| EngineError::StorageIo(format!("read payload: {e}")))?;
+        match f.read_exact(&mut buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(EngineError::StorageIo(format!("read payload: {e}"))),
+        }
+        if crc32fast::hash(&buf) != expected_crc {
+            break;
+        }
+        offset += 8 + len as u64;
         out.push(buf);
     }
-    Ok(out)
+    Ok((out, offset))
+}
+
+/// Truncates `path` to `len` bytes - the offset `read_records_checked`
+/// reports as the start of the first torn/corrupt record - so a writer
+/// reopening after a crash starts appending right after the last good
+/// record instead of leaving a torn tail in place to confuse the next
+/// reader.
+pub fn truncate_to(path: &Path, len: u64) -> Result<(), EngineError> {
+    let file = fs::OpenOptions::new().write(true).open(path)
+        .map_err(|e| EngineError::StorageIo(format!("open({}) for truncate: {e}", path.display())))?;
+    file.set_len(len).map_err(|e| EngineError::StorageIo(format!("truncate({}): {e}", path.display())))
+}
+
+/// Removes a WAL segment file once a checkpoint has folded it into
+/// segments and advanced the manifest's `wal_tail` past it.
+pub fn delete_segment(path: &Path) -> Result<(), EngineError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(EngineError::StorageIo(format!("remove_file({}): {e}", path.display()))),
+    }
+}
+
+/// Total on-disk size of every WAL file for `branch`, so callers can decide
+/// whether accumulated WAL growth is worth folding back into segments
+/// without having to parse records out of each file first.
+pub fn total_bytes(root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<u64, EngineError> {
+    let mut total = 0u64;
+    for path in list_wal_paths(root, db, branch)? {
+        total += fs::metadata(&path).map_err(|e| EngineError::StorageIo(format!("metadata({}): {e}", path.display())))?.len();
+    }
+    Ok(total)
 }
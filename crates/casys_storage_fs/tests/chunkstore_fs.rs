@@ -0,0 +1,93 @@
+// Integration test: content-defined chunking + chunk store dedup/GC
+
+use casys_storage_fs::chunker::{chunk_boundaries, ChunkerConfig};
+use casys_storage_fs::chunkstore::{gc_unreferenced, read_chunked, write_chunked};
+use casys_core::{BranchName, DatabaseName};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::fs;
+
+fn temp_root(name: &str) -> std::path::PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("{name}_{now}"));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+#[test]
+fn chunk_boundaries_cover_all_bytes_and_respect_max_chunk() {
+    let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let config = ChunkerConfig { min_chunk: 256, max_chunk: 8 * 1024, ..ChunkerConfig::default() };
+    let ranges = chunk_boundaries(&data, &config);
+
+    assert!(!ranges.is_empty());
+    assert_eq!(ranges[0].0, 0);
+    assert_eq!(ranges.last().unwrap().1, data.len());
+    for window in ranges.windows(2) {
+        assert_eq!(window[0].1, window[1].0, "ranges must be contiguous");
+    }
+    for (start, end) in &ranges {
+        assert!(end - start <= config.max_chunk);
+    }
+}
+
+#[test]
+fn write_chunked_dedups_unchanged_chunks_across_writes() {
+    let root = temp_root("chunkstore_fs");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let config = ChunkerConfig::default();
+
+    // Pseudo-random bytes (xorshift32), so the default chunker finds content
+    // boundaries; a short repeating pattern can find none and come out as a
+    // single chunk.
+    let mut data = vec![0u8; 100_000];
+    let mut x: u32 = 0x1234_5678;
+    for b in data.iter_mut() {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        *b = (x >> 24) as u8;
+    }
+
+    let ids_v1 = write_chunked(&root, &db, &data, &config, None).unwrap();
+    assert_eq!(read_chunked(&root, &db, &ids_v1, None).unwrap(), data);
+
+    // Mutate a small region in the middle; most chunks should be unaffected.
+    let mid = data.len() / 2;
+    for b in data[mid..mid + 16].iter_mut() {
+        *b ^= 0xFF;
+    }
+    let ids_v2 = write_chunked(&root, &db, &data, &config, None).unwrap();
+    assert_eq!(read_chunked(&root, &db, &ids_v2, None).unwrap(), data);
+
+    let shared = ids_v1.iter().filter(|id| ids_v2.contains(id)).count();
+    assert!(shared > 0, "unrelated chunks should be reused across snapshots");
+}
+
+#[test]
+fn gc_unreferenced_removes_only_orphan_chunks() {
+    let root = temp_root("chunkstore_gc_fs");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+    let config = ChunkerConfig::default();
+
+    let live_ids = write_chunked(&root, &db, b"kept across gc", &config, None).unwrap();
+    let orphan_ids = write_chunked(&root, &db, b"never referenced by a manifest", &config, None).unwrap();
+    assert_ne!(live_ids, orphan_ids);
+
+    let manifest = casys_storage_fs::manifest::Manifest {
+        branch: branch.as_str().to_string(),
+        version_ts: 1,
+        segments: vec![casys_storage_fs::manifest::SegmentRef {
+            id: "sha256:placeholder".to_string(),
+            range: None,
+            chunks: live_ids.iter().map(|c| c.0.clone()).collect(),
+        }],
+        wal_tail: None,
+    };
+    casys_storage_fs::manifest::write_manifest(&root, &db, &branch, &manifest, None).unwrap();
+
+    let deleted = gc_unreferenced(&root, &db, &[branch], None).unwrap();
+    assert_eq!(deleted, orphan_ids.len());
+    assert_eq!(read_chunked(&root, &db, &live_ids, None).unwrap(), b"kept across gc");
+}
@@ -0,0 +1,91 @@
+// Integration test: binary manifest.v2 format, the per-branch docket, and
+// lazy segment parsing via ManifestView.
+
+use casys_storage_fs::docket;
+use casys_storage_fs::manifest::{self, Manifest, ManifestView, Range, SegmentRef, WalTail};
+use casys_storage_fs::manifest_bin;
+use casys_core::{BranchName, DatabaseName};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::fs;
+
+fn temp_root(name: &str) -> std::path::PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("{name}_{now}"));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn sample_manifest(branch: &BranchName, version_ts: u64) -> Manifest {
+    Manifest {
+        branch: branch.as_str().to_string(),
+        version_ts,
+        segments: vec![
+            SegmentRef { id: "sha256:a".to_string(), range: Some(Range { tx_min: 1, tx_max: 2 }), chunks: vec!["sha256:c1".to_string()] },
+            SegmentRef { id: "sha256:b".to_string(), range: None, chunks: Vec::new() },
+        ],
+        wal_tail: Some(WalTail { epoch: 3, seq: 9 }),
+    }
+}
+
+#[test]
+fn v2_manifest_round_trips_and_parses_segments_lazily() {
+    let root = temp_root("manifest_v2_round_trip");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+    let m = sample_manifest(&branch, 100);
+
+    let path = manifest_bin::write_manifest_v2(&root, &db, &branch, &m, None).unwrap();
+    assert!(path.file_name().unwrap().to_str().unwrap().ends_with(".v2"));
+
+    let handle = manifest_bin::read_manifest_v2(&path, None).unwrap();
+    assert_eq!(handle.version_ts(), 100);
+    assert_eq!(handle.wal_tail().unwrap().seq, 9);
+    assert_eq!(handle.segment_count(), 2);
+    assert_eq!(handle.segment(1).unwrap().id, "sha256:b");
+
+    let round_tripped = handle.into_manifest().unwrap();
+    assert_eq!(round_tripped.segments.len(), 2);
+    assert_eq!(round_tripped.segments[0].chunks, vec!["sha256:c1".to_string()]);
+}
+
+#[test]
+fn docket_points_latest_manifest_at_the_v2_file() {
+    let root = temp_root("manifest_v2_docket");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+    let m = sample_manifest(&branch, 200);
+
+    let path = manifest_bin::write_manifest_v2(&root, &db, &branch, &m, None).unwrap();
+    assert_eq!(docket::read_docket(&root, &db, &branch).unwrap(), Some(path));
+
+    let found = manifest::latest_manifest(&root, &db, &branch, None).unwrap().unwrap();
+    assert_eq!(found.version_ts, 200);
+    assert_eq!(found.segments.len(), 2);
+}
+
+#[test]
+fn latest_manifest_handle_defers_segment_parsing_for_v2() {
+    let root = temp_root("manifest_v2_handle");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+    manifest_bin::write_manifest_v2(&root, &db, &branch, &sample_manifest(&branch, 300), None).unwrap();
+
+    let view = manifest::latest_manifest_handle(&root, &db, &branch, None).unwrap().unwrap();
+    assert!(matches!(view, ManifestView::Binary(_)));
+    assert_eq!(view.version_ts(), 300);
+    assert_eq!(view.wal_tail().unwrap().epoch, 3);
+    assert_eq!(view.segment_count(), 2);
+}
+
+#[test]
+fn json_manifest_still_round_trips_through_latest_manifest() {
+    let root = temp_root("manifest_v2_json_compat");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+
+    manifest::write_manifest(&root, &db, &branch, &sample_manifest(&branch, 42), None).unwrap();
+    let view = manifest::latest_manifest_handle(&root, &db, &branch, None).unwrap().unwrap();
+    assert!(matches!(view, ManifestView::Json(_)));
+    assert_eq!(view.version_ts(), 42);
+}
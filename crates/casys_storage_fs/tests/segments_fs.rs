@@ -0,0 +1,59 @@
+// Integration test: content-addressed segment ids, skip-if-exists dedup, and
+// tamper detection on read.
+
+use casys_storage_fs::segments::{content_id, read_segment, segment_path, write_segment, Segment};
+use casys_core::DatabaseName;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_root(name: &str) -> std::path::PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("{name}_{now}"));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+#[test]
+fn write_segment_skips_rewrite_when_content_id_already_present() {
+    let root = temp_root("segments_dedup");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let seg = Segment::new(3, 0, b"same node bytes".to_vec());
+    let id = content_id(&seg.data);
+
+    let path = write_segment(&root, &db, &id, &seg, None).unwrap();
+    let written_at = fs::metadata(&path).unwrap().modified().unwrap();
+
+    // Writing identical bytes under the same id a second time must not touch the file.
+    let seg_again = Segment::new(3, 0, b"same node bytes".to_vec());
+    let path_again = write_segment(&root, &db, &id, &seg_again, None).unwrap();
+    assert_eq!(path, path_again);
+    assert_eq!(fs::metadata(&path_again).unwrap().modified().unwrap(), written_at);
+}
+
+#[test]
+fn read_segment_detects_tampered_bytes_under_content_id() {
+    let root = temp_root("segments_corruption");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let seg = Segment::new(1, 0, b"trustworthy bytes".to_vec());
+    let id = content_id(&seg.data);
+    write_segment(&root, &db, &id, &seg, None).unwrap();
+
+    // Swap the file for a different, but still well-formed, segment.
+    let swapped = Segment::new(1, 0, b"swapped bytes!!!!!".to_vec());
+    swapped.write_to_path(&segment_path(&root, &db, &id), None).unwrap();
+
+    let err = read_segment(&root, &db, &id, None).unwrap_err();
+    assert!(matches!(err, casys_core::EngineError::Corruption(_)));
+}
+
+#[test]
+fn read_segment_ignores_content_hash_for_non_hash_ids() {
+    let root = temp_root("segments_legacy_id");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let seg = Segment::new(0, 1, b"legacy fixed-name segment".to_vec());
+    write_segment(&root, &db, "edges", &seg, None).unwrap();
+
+    let read = read_segment(&root, &db, "edges", None).unwrap();
+    assert_eq!(read.data, b"legacy fixed-name segment");
+}
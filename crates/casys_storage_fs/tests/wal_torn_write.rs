@@ -0,0 +1,89 @@
+// Integration test: CRC-protected WAL records stop cleanly at a torn or
+// corrupt tail instead of erroring out, and `truncate_to` can drop that tail.
+
+use casys_core::{BranchName, DatabaseName};
+use casys_storage_fs::wal;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_root(name: &str) -> std::path::PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let root = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("{name}_{now}"));
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn wal_path(root: &std::path::Path, db: &DatabaseName, branch: &BranchName, epoch: u64, seq: u64) -> std::path::PathBuf {
+    root.join(db.as_str()).join("branches").join(branch.as_str()).join("wal").join(format!("wal-{epoch}-{seq}.wal"))
+}
+
+#[test]
+fn read_records_checked_reports_the_full_length_when_nothing_is_torn() {
+    let root = temp_root("wal_torn_clean");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+
+    let mut w = wal::WalWriter::open(&root, &db, &branch, 4 * 1024 * 1024).unwrap();
+    w.write_record(b"rec-1").unwrap();
+    w.write_record(b"rec-2").unwrap();
+    w.flush().unwrap();
+
+    let path = wal_path(&root, &db, &branch, 0, 0);
+    let (records, offset) = wal::read_records_checked(&path).unwrap();
+
+    assert_eq!(records, vec![b"rec-1".to_vec(), b"rec-2".to_vec()]);
+    assert_eq!(offset, fs::metadata(&path).unwrap().len());
+}
+
+#[test]
+fn a_truncated_tail_record_is_dropped_without_losing_the_records_before_it() {
+    let root = temp_root("wal_torn_tail");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+
+    let mut w = wal::WalWriter::open(&root, &db, &branch, 4 * 1024 * 1024).unwrap();
+    w.write_record(b"rec-1").unwrap();
+    w.flush().unwrap();
+    let good_len = fs::metadata(wal_path(&root, &db, &branch, 0, 0)).unwrap().len();
+    w.write_record(b"rec-2").unwrap();
+    w.flush().unwrap();
+
+    let path = wal_path(&root, &db, &branch, 0, 0);
+    // Simulate a crash mid-append by chopping off the last few bytes of the
+    // second record's payload.
+    let full_len = fs::metadata(&path).unwrap().len();
+    wal::truncate_to(&path, full_len - 2).unwrap();
+
+    let (records, offset) = wal::read_records_checked(&path).unwrap();
+    assert_eq!(records, vec![b"rec-1".to_vec()]);
+    assert_eq!(offset, good_len);
+
+    // Truncating to the reported offset drops the torn tail entirely, so a
+    // writer reopening the file sees only the good record.
+    wal::truncate_to(&path, offset).unwrap();
+    assert_eq!(wal::read_records(&path).unwrap(), vec![b"rec-1".to_vec()]);
+}
+
+#[test]
+fn a_bit_flipped_record_is_treated_as_a_torn_tail() {
+    let root = temp_root("wal_torn_crc");
+    let db = DatabaseName::try_from("testdb").unwrap();
+    let branch = BranchName::try_from("main").unwrap();
+
+    let mut w = wal::WalWriter::open(&root, &db, &branch, 4 * 1024 * 1024).unwrap();
+    w.write_record(b"rec-1").unwrap();
+    w.write_record(b"rec-2").unwrap();
+    w.flush().unwrap();
+
+    let path = wal_path(&root, &db, &branch, 0, 0);
+    // Flip the last byte (inside the second record's payload) without
+    // touching any length header, so only the CRC check can catch it.
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    fs::write(&path, &bytes).unwrap();
+
+    let (records, _) = wal::read_records_checked(&path).unwrap();
+    assert_eq!(records, vec![b"rec-1".to_vec()]);
+}
@@ -0,0 +1,46 @@
+// Integration test: cross-process writer-lock enforcement (chunk2-4)
+
+use casys_storage_fs::lock;
+use casys_core::EngineError;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::fs;
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let dir = std::env::current_dir().unwrap()
+        .join("target").join("tmp").join(format!("{name}_{now}"));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn second_try_acquire_is_locked_while_first_guard_is_held() {
+    let dir = temp_dir("writer_lock_contended");
+    let _first = lock::try_acquire(&dir).unwrap();
+
+    match lock::try_acquire(&dir) {
+        Err(EngineError::Locked(_)) => {}
+        other => panic!("expected Locked, got {other:?}"),
+    }
+}
+
+#[test]
+fn lock_is_available_again_once_guard_drops() {
+    let dir = temp_dir("writer_lock_release");
+    {
+        let _guard = lock::try_acquire(&dir).unwrap();
+        assert!(lock::try_acquire(&dir).is_err());
+    }
+    assert!(lock::try_acquire(&dir).is_ok());
+}
+
+#[test]
+fn acquire_timeout_gives_up_after_the_deadline() {
+    let dir = temp_dir("writer_lock_timeout");
+    let _held = lock::try_acquire(&dir).unwrap();
+
+    let started = std::time::Instant::now();
+    let result = lock::acquire_timeout(&dir, Duration::from_millis(80));
+    assert!(matches!(result, Err(EngineError::Locked(_))));
+    assert!(started.elapsed() >= Duration::from_millis(80));
+}
@@ -0,0 +1,165 @@
+// Integration test: RedbGraphStore against a real on-disk redb file,
+// exercising the same GraphReadStore/GraphWriteStore contract MockGraphStore
+// (casys_core/tests/graph_types.rs) covers in-memory.
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use casys_core::{GraphReadStore, GraphWriteStore, Value};
+use casys_storage_redb::RedbGraphStore;
+
+fn temp_db_path(name: &str) -> std::path::PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let dir = std::env::current_dir().unwrap().join("target").join("tmp");
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(format!("{name}_{now}.redb"))
+}
+
+#[test]
+fn add_node_then_get_node_round_trips_labels_and_properties() {
+    let mut store = RedbGraphStore::open(&temp_db_path("add_get_node")).unwrap();
+    let mut props = HashMap::new();
+    props.insert("name".to_string(), Value::String("Alice".to_string()));
+
+    let id = store.add_node(vec!["Person".to_string()], props.clone()).unwrap();
+    let node = store.get_node(id).unwrap().unwrap();
+
+    assert_eq!(node.id, id);
+    assert_eq!(node.labels, vec!["Person".to_string()]);
+    assert_eq!(node.properties, props);
+}
+
+#[test]
+fn node_ids_are_monotonic_and_gap_free_across_adds() {
+    let mut store = RedbGraphStore::open(&temp_db_path("monotonic_ids")).unwrap();
+    let a = store.add_node(vec![], HashMap::new()).unwrap();
+    let b = store.add_node(vec![], HashMap::new()).unwrap();
+    let c = store.add_node(vec![], HashMap::new()).unwrap();
+
+    assert_eq!([b, c], [a + 1, a + 2]);
+}
+
+#[test]
+fn scan_by_label_uses_the_label_index_not_every_node() {
+    let mut store = RedbGraphStore::open(&temp_db_path("scan_label")).unwrap();
+    let person = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    store.add_node(vec!["Company".to_string()], HashMap::new()).unwrap();
+
+    let people = store.scan_by_label("Person").unwrap();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id, person);
+}
+
+#[test]
+fn get_neighbors_and_incoming_follow_the_adjacency_indices() {
+    let mut store = RedbGraphStore::open(&temp_db_path("neighbors")).unwrap();
+    let alice = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    let bob = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+    store.add_edge(alice, bob, "KNOWS".to_string(), HashMap::new()).unwrap();
+
+    let outgoing = store.get_neighbors(alice, None).unwrap();
+    assert_eq!(outgoing.len(), 1);
+    assert_eq!(outgoing[0].1.id, bob);
+
+    let incoming = store.get_neighbors_incoming(bob, None).unwrap();
+    assert_eq!(incoming.len(), 1);
+    assert_eq!(incoming[0].1.id, alice);
+
+    assert!(store.get_neighbors(alice, Some("LIKES")).unwrap().is_empty());
+}
+
+#[test]
+fn delete_node_removes_it_from_scan_all_and_its_label_index_entry() {
+    let mut store = RedbGraphStore::open(&temp_db_path("delete_node")).unwrap();
+    let id = store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap();
+
+    store.delete_node(id).unwrap();
+
+    assert!(store.get_node(id).unwrap().is_none());
+    assert!(store.scan_by_label("Person").unwrap().is_empty());
+    assert!(store.scan_all().unwrap().is_empty());
+}
+
+#[test]
+fn delete_edge_removes_it_from_both_adjacency_indices() {
+    let mut store = RedbGraphStore::open(&temp_db_path("delete_edge")).unwrap();
+    let a = store.add_node(vec![], HashMap::new()).unwrap();
+    let b = store.add_node(vec![], HashMap::new()).unwrap();
+    let edge_id = store.add_edge(a, b, "KNOWS".to_string(), HashMap::new()).unwrap();
+
+    store.delete_edge(edge_id).unwrap();
+
+    assert!(store.get_neighbors(a, None).unwrap().is_empty());
+    assert!(store.get_neighbors_incoming(b, None).unwrap().is_empty());
+}
+
+#[test]
+fn reopening_the_same_file_sees_previously_committed_data() {
+    let path = temp_db_path("reopen");
+    let id = {
+        let mut store = RedbGraphStore::open(&path).unwrap();
+        store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap()
+    };
+
+    let store = RedbGraphStore::open(&path).unwrap();
+    assert!(store.get_node(id).unwrap().is_some());
+}
+
+#[test]
+fn a_bytes_property_round_trips_as_bytes_not_an_int_array() {
+    let mut store = RedbGraphStore::open(&temp_db_path("bytes_property")).unwrap();
+    let mut props = HashMap::new();
+    props.insert("blob".to_string(), Value::Bytes(vec![1, 2, 3]));
+
+    let id = store.add_node(vec!["Person".to_string()], props.clone()).unwrap();
+    let node = store.get_node(id).unwrap().unwrap();
+
+    assert_eq!(node.properties, props);
+    assert_eq!(node.properties["blob"], Value::Bytes(vec![1, 2, 3]));
+}
+
+use std::ops::Bound;
+
+#[test]
+fn scan_by_property_range_filters_by_the_total_value_order() {
+    let mut store = RedbGraphStore::open(&temp_db_path("property_range")).unwrap();
+    let mut young = HashMap::new();
+    young.insert("age".to_string(), Value::Int(20));
+    let mut old = HashMap::new();
+    old.insert("age".to_string(), Value::Int(50));
+
+    let young_id = store.add_node(vec![], young).unwrap();
+    store.add_node(vec![], old).unwrap();
+
+    let ids = store
+        .scan_by_property_range("age", Bound::Unbounded, Bound::Excluded(Value::Int(30)))
+        .unwrap();
+    assert_eq!(ids, vec![young_id]);
+}
+
+#[test]
+fn get_nodes_batches_missing_and_present_ids_in_one_transaction() {
+    let mut store = RedbGraphStore::open(&temp_db_path("get_nodes_batch")).unwrap();
+    let a = store.add_node(vec![], HashMap::new()).unwrap();
+    let b = store.add_node(vec![], HashMap::new()).unwrap();
+
+    let fetched = store.get_nodes(&[a, 999_999, b]).unwrap();
+    assert_eq!(fetched[0].as_ref().unwrap().id, a);
+    assert!(fetched[1].is_none());
+    assert_eq!(fetched[2].as_ref().unwrap().id, b);
+}
+
+#[test]
+fn scan_by_label_range_pages_the_label_index_with_a_continuation_cursor() {
+    let mut store = RedbGraphStore::open(&temp_db_path("scan_label_range")).unwrap();
+    let ids: Vec<_> = (0..3).map(|_| store.add_node(vec!["Person".to_string()], HashMap::new()).unwrap()).collect();
+
+    let (page1, cursor1) = store.scan_by_label_range("Person", None, 2).unwrap();
+    assert_eq!(page1.iter().map(|n| n.id).collect::<Vec<_>>(), ids[0..2]);
+    assert_eq!(cursor1, Some(ids[1]));
+
+    let (page2, cursor2) = store.scan_by_label_range("Person", cursor1, 2).unwrap();
+    assert_eq!(page2.iter().map(|n| n.id).collect::<Vec<_>>(), ids[2..3]);
+    assert_eq!(cursor2, None);
+}
@@ -1,68 +1,151 @@
 //! Casys Redis Storage Adapter
-//! Implements WalSink and WalSource for Redis.
-//! 
-//! Future implementation: store WAL records in Redis streams.
-//! Enables high-performance, in-memory WAL with persistence options.
-
-use casys_core::{
-    DatabaseName, BranchName, WalTailMeta, EngineError,
-    WalSink, WalSource,
-};
+//! Implements WalSink and WalSource against Redis Streams.
+//!
+//! Each `(db, branch)` gets its own stream, keyed `casys:{db}:{branch}:wal`.
+//! `append_records` does one `XADD` per record inside a single pipelined
+//! `MULTI`/`EXEC`, and a Redis Stream ID (`<ms>-<seq>`) doubles as
+//! `WalTailMeta{epoch, seq}` without any repurposing - it's already exactly
+//! that shape. `list_wal_segments`/`read_wal_segment` treat each stream
+//! entry as its own one-record segment (more round trips than grouping
+//! entries into batches, but simple and exactly reproduces append order),
+//! which is enough for `WalBackedGraphStore::recover`-style full replay.
+
 use std::path::Path;
+use std::time::Duration;
+
+use casys_core::{BranchName, DatabaseName, EngineError, WalSink, WalSource, WalTailMeta};
+use r2d2::Pool;
+use redis::Client;
+
+/// `MAXLEN ~ <n>` trimming policy applied to every `XADD`, so a stream that
+/// nobody ever checkpoints/compacts doesn't grow without bound. `~` makes it
+/// approximate, which is what lets Redis trim in O(1) amortized per add
+/// instead of walking the whole stream.
+#[derive(Debug, Clone, Copy)]
+pub struct RedisWalConfig {
+    pub maxlen_approx: Option<u64>,
+}
 
-/// Redis Storage adapter (stub for future implementation)
+impl Default for RedisWalConfig {
+    fn default() -> Self {
+        Self { maxlen_approx: Some(1_000_000) }
+    }
+}
+
+/// Redis Streams-backed `WalSink`/`WalSource`, pooled via `r2d2` so
+/// concurrent callers don't each pay a fresh `TcpStream` + `AUTH` round trip.
 pub struct RedisBackend {
-    // TODO: Add Redis client, connection pool
+    pool: Pool<Client>,
+    config: RedisWalConfig,
 }
 
 impl RedisBackend {
-    /// Create a new Redis backend
-    pub fn new() -> Self {
-        Self {}
+    /// Connects to `redis_url` (e.g. `redis://127.0.0.1:6379`) with the
+    /// default trimming policy.
+    pub fn new(redis_url: &str) -> Result<Self, EngineError> {
+        Self::with_config(redis_url, RedisWalConfig::default())
     }
-}
 
-impl Default for RedisBackend {
-    fn default() -> Self {
-        Self::new()
+    pub fn with_config(redis_url: &str, config: RedisWalConfig) -> Result<Self, EngineError> {
+        let client = Client::open(redis_url).map_err(|e| EngineError::StorageIo(format!("redis client open: {e}")))?;
+        let pool = Pool::builder()
+            .connection_timeout(Duration::from_secs(5))
+            .build(client)
+            .map_err(|e| EngineError::StorageIo(format!("redis pool init: {e}")))?;
+        Ok(Self { pool, config })
     }
+
+    fn connection(&self) -> Result<r2d2::PooledConnection<Client>, EngineError> {
+        self.pool.get().map_err(|e| EngineError::StorageIo(format!("redis pool checkout: {e}")))
+    }
+
+    fn stream_key(db: &DatabaseName, branch: &BranchName) -> String {
+        format!("casys:{}:{}:wal", db.as_str(), branch.as_str())
+    }
+
+    /// The stream's current last entry ID, or `(0, 0)` if the stream doesn't
+    /// exist yet - the same "nothing appended yet" default `ObjectStoreBackend`'s
+    /// `latest_wal_tail` uses.
+    fn latest_tail(conn: &mut redis::Connection, key: &str) -> Result<WalTailMeta, EngineError> {
+        let entries: Vec<(String, Vec<(String, Vec<u8>)>)> = redis::cmd("XREVRANGE")
+            .arg(key).arg("+").arg("-").arg("COUNT").arg(1)
+            .query(conn)
+            .map_err(|e| EngineError::StorageIo(format!("redis XREVRANGE {key}: {e}")))?;
+        match entries.into_iter().next() {
+            Some((id, _)) => parse_stream_id(&id)
+                .map(|(epoch, seq)| WalTailMeta { epoch, seq })
+                .ok_or_else(|| EngineError::StorageIo(format!("redis XREVRANGE {key}: unparseable id {id}"))),
+            None => Ok(WalTailMeta { epoch: 0, seq: 0 }),
+        }
+    }
+}
+
+/// Splits a Redis Stream ID (`<ms>-<seq>`) into the two integers it's made
+/// of - `WalTailMeta`'s own shape, so no conversion beyond parsing is needed.
+fn parse_stream_id(id: &str) -> Option<(u64, u64)> {
+    let (ms, seq) = id.split_once('-')?;
+    Some((ms.parse().ok()?, seq.parse().ok()?))
+}
+
+fn format_stream_id(epoch: u64, seq: u64) -> String {
+    format!("{epoch}-{seq}")
 }
 
 impl WalSink for RedisBackend {
-    fn append_records(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-        _records: &[Vec<u8>],
-    ) -> Result<WalTailMeta, EngineError> {
-        Err(EngineError::NotImplemented(
-            "Redis WalSink not yet implemented".into(),
-        ))
+    fn append_records(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, records: &[Vec<u8>]) -> Result<WalTailMeta, EngineError> {
+        let mut conn = self.connection()?;
+        let key = Self::stream_key(db, branch);
+
+        if records.is_empty() {
+            return Self::latest_tail(&mut conn, &key);
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for rec in records {
+            let mut cmd = redis::cmd("XADD");
+            cmd.arg(&key);
+            if let Some(maxlen) = self.config.maxlen_approx {
+                cmd.arg("MAXLEN").arg("~").arg(maxlen);
+            }
+            cmd.arg("*").arg("payload").arg(rec.as_slice());
+            pipe.add_command(cmd);
+        }
+
+        let ids: Vec<String> = pipe.query(&mut *conn).map_err(|e| EngineError::StorageIo(format!("redis XADD {key}: {e}")))?;
+        let last_id = ids.last().ok_or_else(|| EngineError::StorageIo(format!("redis XADD {key}: pipeline returned no ids")))?;
+        parse_stream_id(last_id)
+            .map(|(epoch, seq)| WalTailMeta { epoch, seq })
+            .ok_or_else(|| EngineError::StorageIo(format!("redis XADD {key}: unparseable id {last_id}")))
     }
 }
 
 impl WalSource for RedisBackend {
-    fn list_wal_segments(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-    ) -> Result<Vec<WalTailMeta>, EngineError> {
-        Err(EngineError::NotImplemented(
-            "Redis WalSource not yet implemented".into(),
-        ))
+    fn list_wal_segments(&self, _root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Vec<WalTailMeta>, EngineError> {
+        let mut conn = self.connection()?;
+        let key = Self::stream_key(db, branch);
+        let entries: Vec<(String, Vec<(String, Vec<u8>)>)> = redis::cmd("XRANGE")
+            .arg(&key).arg("-").arg("+")
+            .query(&mut *conn)
+            .map_err(|e| EngineError::StorageIo(format!("redis XRANGE {key}: {e}")))?;
+
+        entries.into_iter()
+            .map(|(id, _)| parse_stream_id(&id)
+                .map(|(epoch, seq)| WalTailMeta { epoch, seq })
+                .ok_or_else(|| EngineError::StorageIo(format!("redis XRANGE {key}: unparseable id {id}"))))
+            .collect()
     }
 
-    fn read_wal_segment(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-        _tail: &WalTailMeta,
-    ) -> Result<Vec<Vec<u8>>, EngineError> {
-        Err(EngineError::NotImplemented(
-            "Redis WalSource not yet implemented".into(),
-        ))
+    fn read_wal_segment(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, tail: &WalTailMeta) -> Result<Vec<Vec<u8>>, EngineError> {
+        let mut conn = self.connection()?;
+        let key = Self::stream_key(db, branch);
+        let id = format_stream_id(tail.epoch, tail.seq);
+        let entries: Vec<(String, Vec<(String, Vec<u8>)>)> = redis::cmd("XRANGE")
+            .arg(&key).arg(&id).arg(&id)
+            .query(&mut *conn)
+            .map_err(|e| EngineError::StorageIo(format!("redis XRANGE {key} {id}: {e}")))?;
+
+        let Some((_, fields)) = entries.into_iter().next() else { return Ok(Vec::new()) };
+        Ok(vec![fields.into_iter().find(|(field, _)| field == "payload").map(|(_, v)| v).unwrap_or_default()])
     }
 }
@@ -0,0 +1,229 @@
+//! Concrete `ObjectStore` backed by a real `aws-sdk-s3` client, gated behind
+//! the `aws-sdk` feature so the dependency-free `InMemoryObjectStore` stays
+//! the default for tests and local development.
+//!
+//! `ObjectStore` (like `SegmentStore`) is a synchronous port, but
+//! `aws-sdk-s3` is async-only, so each call drives `runtime` to completion
+//! rather than exposing an async variant of the trait - the mirror image of
+//! `casys_storage_fs`'s `#[cfg(feature = "async")]` facade, which wraps a
+//! sync implementation in an async one instead of the other way around.
+
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use aws_sdk_s3::Client;
+use tokio::runtime::Runtime;
+
+use casys_core::EngineError;
+
+use crate::object_store::ObjectStore;
+
+/// `put_object` switches from a single `PutObject` call to a real S3
+/// multipart upload once `data.len()` crosses this. Matches
+/// `segments::MULTIPART_THRESHOLD`, the size at which `ObjectStoreBackend`
+/// already splits a segment body across separate objects for any
+/// `ObjectStore` - here the split happens inside one S3 object instead, via
+/// `CreateMultipartUpload`/`UploadPart`/`CompleteMultipartUpload`, which is
+/// only possible against a real S3-compatible service.
+const MULTIPART_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// S3's multipart upload requires every part but the last to be at least
+/// 5 MiB; this is comfortably above that floor.
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Holds credentials + endpoint + bucket, the same shape a garage/S3
+/// adapter's builder takes elsewhere - `endpoint_url` is what points this
+/// at a self-hosted/garage/minio endpoint instead of real AWS.
+pub struct S3ClientBuilder {
+    bucket: String,
+    region: String,
+    endpoint_url: Option<String>,
+    credentials: Option<(String, String)>,
+}
+
+impl S3ClientBuilder {
+    pub fn new(bucket: impl Into<String>) -> Self {
+        Self {
+            bucket: bucket.into(),
+            region: "us-east-1".to_string(),
+            endpoint_url: None,
+            credentials: None,
+        }
+    }
+
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.region = region.into();
+        self
+    }
+
+    /// Points the client at an S3-compatible endpoint (garage, minio, ...)
+    /// instead of real AWS, forcing path-style addressing the way those
+    /// servers expect.
+    pub fn endpoint_url(mut self, endpoint_url: impl Into<String>) -> Self {
+        self.endpoint_url = Some(endpoint_url.into());
+        self
+    }
+
+    pub fn credentials(mut self, access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        self.credentials = Some((access_key_id.into(), secret_access_key.into()));
+        self
+    }
+
+    /// Starts a dedicated Tokio runtime and resolves the `aws-sdk-s3`
+    /// client against it, so every subsequent `ObjectStore` call can just
+    /// `block_on` that same runtime rather than paying runtime-startup cost
+    /// per call.
+    pub fn build(self) -> Result<S3Client, EngineError> {
+        let runtime = Runtime::new()
+            .map_err(|e| EngineError::StorageIo(format!("failed to start S3 client runtime: {e}")))?;
+
+        let client = runtime.block_on(async {
+            let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest())
+                .region(aws_sdk_s3::config::Region::new(self.region.clone()));
+            if let Some((access_key_id, secret_access_key)) = &self.credentials {
+                loader = loader.credentials_provider(aws_sdk_s3::config::Credentials::new(
+                    access_key_id,
+                    secret_access_key,
+                    None,
+                    None,
+                    "casys_storage_s3",
+                ));
+            }
+            let sdk_config = loader.load().await;
+            let mut s3_config = aws_sdk_s3::config::Builder::from(&sdk_config);
+            if let Some(endpoint_url) = &self.endpoint_url {
+                s3_config = s3_config.endpoint_url(endpoint_url).force_path_style(true);
+            }
+            Client::from_conf(s3_config.build())
+        });
+
+        Ok(S3Client { client, runtime, bucket: self.bucket })
+    }
+}
+
+/// `ObjectStore` against a real S3-compatible endpoint. Every call blocks
+/// the calling thread on `runtime` for the duration of the underlying async
+/// request.
+pub struct S3Client {
+    client: Client,
+    runtime: Runtime,
+    bucket: String,
+}
+
+impl S3Client {
+    pub fn builder(bucket: impl Into<String>) -> S3ClientBuilder {
+        S3ClientBuilder::new(bucket)
+    }
+}
+
+impl S3Client {
+    /// `put_object`'s body past `MULTIPART_THRESHOLD`: opens an upload,
+    /// sends each `MULTIPART_PART_SIZE` chunk as its own part, then
+    /// completes the upload with the returned part ETags in order. Aborts
+    /// the upload on any part failure so a half-uploaded object doesn't
+    /// linger as an incomplete-multipart-upload the bucket has to be
+    /// lifecycle-configured to reap.
+    async fn put_object_multipart(&self, key: &str, data: &[u8]) -> Result<(), EngineError> {
+        let create = self.client.create_multipart_upload().bucket(&self.bucket).key(key).send().await
+            .map_err(|e| EngineError::StorageIo(format!("S3 CreateMultipartUpload {key}: {e}")))?;
+        let upload_id = create.upload_id().ok_or_else(|| EngineError::StorageIo(format!("S3 CreateMultipartUpload {key}: missing upload id")))?;
+
+        let mut parts = Vec::new();
+        for (i, chunk) in data.chunks(MULTIPART_PART_SIZE).enumerate() {
+            let part_number = i as i32 + 1;
+            let upload = self.client.upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(chunk.to_vec()))
+                .send()
+                .await;
+            let upload = match upload {
+                Ok(u) => u,
+                Err(e) => {
+                    let _ = self.client.abort_multipart_upload().bucket(&self.bucket).key(key).upload_id(upload_id).send().await;
+                    return Err(EngineError::StorageIo(format!("S3 UploadPart {key} part {part_number}: {e}")));
+                }
+            };
+            let Some(e_tag) = upload.e_tag() else {
+                let _ = self.client.abort_multipart_upload().bucket(&self.bucket).key(key).upload_id(upload_id).send().await;
+                return Err(EngineError::StorageIo(format!("S3 UploadPart {key} part {part_number}: missing ETag")));
+            };
+            parts.push(CompletedPart::builder().e_tag(e_tag).part_number(part_number).build());
+        }
+
+        self.client.complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(CompletedMultipartUpload::builder().set_parts(Some(parts)).build())
+            .send()
+            .await
+            .map_err(|e| EngineError::StorageIo(format!("S3 CompleteMultipartUpload {key}: {e}")))?;
+        Ok(())
+    }
+}
+
+impl ObjectStore for S3Client {
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<(), EngineError> {
+        self.runtime.block_on(async {
+            if data.len() >= MULTIPART_THRESHOLD {
+                return self.put_object_multipart(key, data).await;
+            }
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .body(ByteStream::from(data.to_vec()))
+                .send()
+                .await
+                .map_err(|e| EngineError::StorageIo(format!("S3 PutObject {key}: {e}")))?;
+            Ok(())
+        })
+    }
+
+    /// A missing key resolves to `Ok(None)`, matching `InMemoryObjectStore`'s
+    /// contract, rather than surfacing `GetObjectError::NoSuchKey` as an
+    /// `Err` here - `ObjectStoreBackend::read_segment` already turns a `None`
+    /// into `EngineError::NotFound` one layer up, which is what keeps
+    /// `load()`'s graceful empty-graph path working.
+    fn get_object(&self, key: &str) -> Result<Option<Vec<u8>>, EngineError> {
+        self.runtime.block_on(async {
+            match self.client.get_object().bucket(&self.bucket).key(key).send().await {
+                Ok(output) => {
+                    let bytes = output
+                        .body
+                        .collect()
+                        .await
+                        .map_err(|e| EngineError::StorageIo(format!("S3 GetObject {key} body: {e}")))?;
+                    Ok(Some(bytes.into_bytes().to_vec()))
+                }
+                Err(aws_sdk_s3::error::SdkError::ServiceError(e)) if e.err().is_no_such_key() => Ok(None),
+                Err(e) => Err(EngineError::StorageIo(format!("S3 GetObject {key}: {e}"))),
+            }
+        })
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>, EngineError> {
+        self.runtime.block_on(async {
+            let mut keys = Vec::new();
+            let mut continuation_token = None;
+            loop {
+                let mut req = self.client.list_objects_v2().bucket(&self.bucket).prefix(prefix);
+                if let Some(token) = &continuation_token {
+                    req = req.continuation_token(token);
+                }
+                let output = req
+                    .send()
+                    .await
+                    .map_err(|e| EngineError::StorageIo(format!("S3 ListObjectsV2 {prefix}: {e}")))?;
+                keys.extend(output.contents().iter().filter_map(|o| o.key().map(str::to_string)));
+                continuation_token = output.next_continuation_token().map(str::to_string);
+                if continuation_token.is_none() {
+                    break;
+                }
+            }
+            Ok(keys)
+        })
+    }
+}
@@ -0,0 +1,197 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use casys_core::{
+    BranchName, DatabaseName, EngineError, ManifestMeta, ManifestStore, SegmentId, SegmentStore,
+    StorageCatalog, Timestamp, WalSink, WalSource, WalTailMeta,
+};
+
+use crate::keys;
+use crate::manifest as mf;
+use crate::object_store::{InMemoryObjectStore, ObjectStore};
+use crate::segments;
+
+/// `StorageCatalog` + `ManifestStore` + `SegmentStore` + `WalSink` +
+/// `WalSource` against an S3-compatible object store. `root` is accepted
+/// on every method (the ports are shared with `FsBackend`) but unused:
+/// `db`/`branch` map onto key prefixes instead of directories, via
+/// `crate::keys`.
+///
+/// Takes the `ObjectStore` to talk to rather than a concrete client, so a
+/// real `GetObject`/`PutObject`/`ListObjects` implementation can be dropped
+/// in later without touching this type.
+pub struct ObjectStoreBackend {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreBackend {
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Default for ObjectStoreBackend {
+    /// An in-memory object store, for tests and local development without
+    /// a real S3-compatible endpoint.
+    fn default() -> Self {
+        Self::new(Arc::new(InMemoryObjectStore::new()))
+    }
+}
+
+fn now_ms() -> Timestamp {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl StorageCatalog for ObjectStoreBackend {
+    fn list_branches(&self, _root: &Path, db: &DatabaseName) -> Result<Vec<BranchName>, EngineError> {
+        let mut seen = Vec::new();
+        for key in self.store.list_objects(&format!("{}/", db.as_str()))? {
+            if keys::parse_manifest_ts(&key).is_none() {
+                continue;
+            }
+            // key is "{db}/{branch}/manifest-{ts}.json"
+            let Some(rest) = key.strip_prefix(&format!("{}/", db.as_str())) else { continue };
+            let Some((branch_name, _)) = rest.split_once('/') else { continue };
+            if seen.iter().any(|b: &String| b == branch_name) {
+                continue;
+            }
+            seen.push(branch_name.to_string());
+        }
+        seen.sort();
+        seen.into_iter()
+            .map(|name| BranchName::try_from(name.as_str()))
+            .collect()
+    }
+
+    fn create_branch(&self, _root: &Path, db: &DatabaseName, from: &BranchName, new_branch: &BranchName, at: Option<Timestamp>) -> Result<(), EngineError> {
+        let base = match at {
+            Some(ts) => mf::pitr_manifest(self.store.as_ref(), db, from, ts)?,
+            None => mf::latest_manifest(self.store.as_ref(), db, from)?,
+        };
+        let manifest = mf::Manifest {
+            branch: new_branch.as_str().to_string(),
+            version_ts: now_ms(),
+            segments: base.as_ref().map(|m| m.segments.clone()).unwrap_or_default(),
+            wal_tail: base.as_ref().and_then(|m| m.wal_tail),
+        };
+        mf::write_manifest(self.store.as_ref(), db, new_branch, &manifest)
+    }
+}
+
+impl ManifestStore for ObjectStoreBackend {
+    fn list_snapshot_timestamps(&self, _root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Vec<Timestamp>, EngineError> {
+        Ok(mf::list_manifest_keys(self.store.as_ref(), db, branch)?.into_iter().map(|(ts, _)| ts).collect())
+    }
+
+    fn latest_manifest_meta(&self, _root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Option<ManifestMeta>, EngineError> {
+        Ok(mf::latest_manifest(self.store.as_ref(), db, branch)?.map(|m| mf::to_meta(&m)))
+    }
+
+    fn pitr_manifest_meta(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, at: Timestamp) -> Result<Option<ManifestMeta>, EngineError> {
+        Ok(mf::pitr_manifest(self.store.as_ref(), db, branch, at)?.map(|m| mf::to_meta(&m)))
+    }
+
+    fn read_manifest_meta(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, ts: Timestamp) -> Result<Option<ManifestMeta>, EngineError> {
+        let key = keys::manifest_key(db, branch, ts);
+        match self.store.get_object(&key)? {
+            Some(_) => Ok(Some(mf::to_meta(&mf::read_manifest(self.store.as_ref(), &key)?))),
+            None => Ok(None),
+        }
+    }
+
+    fn write_manifest_meta(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, meta: &ManifestMeta) -> Result<(), EngineError> {
+        mf::write_manifest(self.store.as_ref(), db, branch, &mf::from_meta(meta))
+    }
+}
+
+impl SegmentStore for ObjectStoreBackend {
+    fn write_segment(&self, _root: &Path, db: &DatabaseName, segment_id: &SegmentId, data: &[u8], node_count: u64, edge_count: u64) -> Result<(), EngineError> {
+        let encoded = segments::encode(data, node_count, edge_count);
+        if encoded.len() < segments::MULTIPART_THRESHOLD {
+            return self.store.put_object(&keys::segment_key(db, &segment_id.0), &encoded);
+        }
+        let parts = segments::split_parts(&encoded);
+        for (i, part) in parts.iter().enumerate() {
+            self.store.put_object(&keys::segment_part_key(db, &segment_id.0, i as u32), part)?;
+        }
+        self.store.put_object(&keys::segment_key(db, &segment_id.0), &segments::encode_multipart_pointer(parts.len() as u32))
+    }
+
+    fn read_segment(&self, _root: &Path, db: &DatabaseName, segment_id: &SegmentId) -> Result<(Vec<u8>, u64, u64), EngineError> {
+        let key = keys::segment_key(db, &segment_id.0);
+        let bytes = self.store.get_object(&key)?
+            .ok_or_else(|| EngineError::NotFound(segment_id.0.clone()))?;
+        if !segments::is_multipart_pointer(&bytes) {
+            return segments::decode(&bytes);
+        }
+        let part_count = segments::decode_multipart_pointer(&bytes)?;
+        let prefix = keys::segment_part_prefix(db, &segment_id.0);
+        let mut parts: Vec<(u32, String)> = self.store.list_objects(&prefix)?
+            .into_iter()
+            .filter_map(|k| keys::parse_segment_part(&k).map(|i| (i, k)))
+            .collect();
+        parts.sort_by_key(|(i, _)| *i);
+        if parts.len() as u32 != part_count {
+            return Err(EngineError::StorageIo(format!("segment {} expected {part_count} parts, found {}", segment_id.0, parts.len())));
+        }
+        let mut encoded = Vec::new();
+        for (_, part_key) in parts {
+            let chunk = self.store.get_object(&part_key)?
+                .ok_or_else(|| EngineError::NotFound(part_key.clone()))?;
+            encoded.extend_from_slice(&chunk);
+        }
+        segments::decode(&encoded)
+    }
+}
+
+fn latest_wal_tail(store: &dyn ObjectStore, db: &DatabaseName, branch: &BranchName) -> Result<WalTailMeta, EngineError> {
+    Ok(store.list_objects(&keys::branch_prefix(db, branch))?
+        .into_iter()
+        .filter_map(|key| keys::parse_wal_tail(&key))
+        .max()
+        .map(|(epoch, seq)| WalTailMeta { epoch, seq })
+        .unwrap_or(WalTailMeta { epoch: 0, seq: 0 }))
+}
+
+impl WalSink for ObjectStoreBackend {
+    fn append_records(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, records: &[Vec<u8>]) -> Result<WalTailMeta, EngineError> {
+        let mut tail = latest_wal_tail(self.store.as_ref(), db, branch)?;
+        tail.seq += 1;
+        let mut buf = Vec::new();
+        for rec in records {
+            buf.extend_from_slice(&(rec.len() as u32).to_le_bytes());
+            buf.extend_from_slice(rec);
+        }
+        self.store.put_object(&keys::wal_key(db, branch, tail.epoch, tail.seq), &buf)?;
+        Ok(tail)
+    }
+}
+
+impl WalSource for ObjectStoreBackend {
+    fn list_wal_segments(&self, _root: &Path, db: &DatabaseName, branch: &BranchName) -> Result<Vec<WalTailMeta>, EngineError> {
+        let mut out: Vec<WalTailMeta> = self.store.list_objects(&keys::branch_prefix(db, branch))?
+            .into_iter()
+            .filter_map(|key| keys::parse_wal_tail(&key).map(|(epoch, seq)| WalTailMeta { epoch, seq }))
+            .collect();
+        out.sort_by_key(|t| (t.epoch, t.seq));
+        Ok(out)
+    }
+
+    fn read_wal_segment(&self, _root: &Path, db: &DatabaseName, branch: &BranchName, tail: &WalTailMeta) -> Result<Vec<Vec<u8>>, EngineError> {
+        let key = keys::wal_key(db, branch, tail.epoch, tail.seq);
+        let Some(bytes) = self.store.get_object(&key)? else { return Ok(Vec::new()) };
+        let mut records = Vec::new();
+        let mut pos = 0usize;
+        while pos + 4 <= bytes.len() {
+            let len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+            pos += 4;
+            if pos + len > bytes.len() {
+                return Err(EngineError::StorageIo(format!("truncated wal record in {key}")));
+            }
+            records.push(bytes[pos..pos + len].to_vec());
+            pos += len;
+        }
+        Ok(records)
+    }
+}
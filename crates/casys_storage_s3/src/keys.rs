@@ -0,0 +1,58 @@
+//! Key-prefix layout for the object store. Every trait method on
+//! `ObjectStoreBackend` already takes `root`, `db`, and `branch`; `root` is
+//! ignored here (it only matters for the filesystem backend) and `db`/
+//! `branch` are mapped into flat key prefixes instead of directories.
+
+use casys_core::{BranchName, DatabaseName};
+
+pub fn branch_prefix(db: &DatabaseName, branch: &BranchName) -> String {
+    format!("{}/{}/", db.as_str(), branch.as_str())
+}
+
+pub fn manifest_key(db: &DatabaseName, branch: &BranchName, ts: u64) -> String {
+    format!("{}manifest-{}.json", branch_prefix(db, branch), ts)
+}
+
+pub fn segment_key(db: &DatabaseName, segment_id: &str) -> String {
+    format!("{}/segments/{}", db.as_str(), segment_id)
+}
+
+/// Prefix under which a multipart segment's parts are uploaded. The part
+/// objects are listed back out via `list_objects` the same way
+/// `list_wal_segments` recovers WAL files from a branch prefix, and checked
+/// against the part count stored in the pointer object at the segment's
+/// main key.
+pub fn segment_part_prefix(db: &DatabaseName, segment_id: &str) -> String {
+    format!("{}/segments/{}.parts/", db.as_str(), segment_id)
+}
+
+pub fn segment_part_key(db: &DatabaseName, segment_id: &str, part: u32) -> String {
+    format!("{}part-{:06}", segment_part_prefix(db, segment_id), part)
+}
+
+/// Recovers the part index from a key produced by `segment_part_key`.
+pub fn parse_segment_part(key: &str) -> Option<u32> {
+    let name = key.rsplit('/').next()?;
+    name.strip_prefix("part-")?.parse::<u32>().ok()
+}
+
+pub fn wal_key(db: &DatabaseName, branch: &BranchName, epoch: u64, seq: u64) -> String {
+    format!("{}wal-{}-{}.wal", branch_prefix(db, branch), epoch, seq)
+}
+
+/// Recovers `version_ts` from a key produced by `manifest_key`.
+pub fn parse_manifest_ts(key: &str) -> Option<u64> {
+    let name = key.rsplit('/').next()?;
+    let core = name.strip_prefix("manifest-")?.strip_suffix(".json")?;
+    core.parse::<u64>().ok()
+}
+
+/// Recovers `(epoch, seq)` from a key produced by `wal_key`.
+pub fn parse_wal_tail(key: &str) -> Option<(u64, u64)> {
+    let name = key.rsplit('/').next()?;
+    let core = name.strip_prefix("wal-")?.strip_suffix(".wal")?;
+    let mut it = core.split('-');
+    let epoch = it.next()?.parse::<u64>().ok()?;
+    let seq = it.next()?.parse::<u64>().ok()?;
+    Some((epoch, seq))
+}
@@ -1,116 +1,22 @@
 //! Casys S3 Storage Adapter
-//! Implements ManifestStore and SegmentStore for AWS S3.
-//! 
-//! Future implementation: store manifests and segments in S3 buckets.
-//! Enables cloud-native deployments with scalable object storage.
-
-use casys_core::{
-    DatabaseName, BranchName, SegmentId, ManifestMeta, WalTailMeta, Timestamp, EngineError,
-    ManifestStore, SegmentStore,
-};
-use std::path::Path;
-
-/// S3 Storage adapter (stub for future implementation)
-pub struct S3Backend {
-    // TODO: Add S3 client, bucket name, credentials
-}
-
-impl S3Backend {
-    /// Create a new S3 backend
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
-impl Default for S3Backend {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl ManifestStore for S3Backend {
-    fn list_snapshot_timestamps(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-    ) -> Result<Vec<Timestamp>, EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 ManifestStore not yet implemented".into(),
-        ))
-    }
-
-    fn latest_manifest_meta(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-    ) -> Result<Option<ManifestMeta>, EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 ManifestStore not yet implemented".into(),
-        ))
-    }
-
-    fn pitr_manifest_meta(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-        _at: Timestamp,
-    ) -> Result<Option<ManifestMeta>, EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 ManifestStore not yet implemented".into(),
-        ))
-    }
-
-    fn read_manifest_meta(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-        _ts: Timestamp,
-    ) -> Result<Option<ManifestMeta>, EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 ManifestStore not yet implemented".into(),
-        ))
-    }
-
-    fn write_manifest_meta(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _branch: &BranchName,
-        _meta: &ManifestMeta,
-    ) -> Result<(), EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 ManifestStore not yet implemented".into(),
-        ))
-    }
-}
-
-impl SegmentStore for S3Backend {
-    fn write_segment(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _segment_id: &SegmentId,
-        _data: &[u8],
-        _node_count: u64,
-        _edge_count: u64,
-    ) -> Result<(), EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 SegmentStore not yet implemented".into(),
-        ))
-    }
-
-    fn read_segment(
-        &self,
-        _root: &Path,
-        _db: &DatabaseName,
-        _segment_id: &SegmentId,
-    ) -> Result<(Vec<u8>, u64, u64), EngineError> {
-        Err(EngineError::NotImplemented(
-            "S3 SegmentStore not yet implemented".into(),
-        ))
-    }
-}
+//!
+//! An alternative to `casys_storage_fs::FsBackend` running the same
+//! manifest/segment/WAL model against an S3-compatible object store
+//! instead of the local filesystem. `db`/`branch` map onto key prefixes
+//! (see `keys`) rather than directories, `root` is unused, and reads/writes go
+//! through the narrow `ObjectStore` port so a real `GetObject`/
+//! `PutObject`/`ListObjects` client can be dropped in without touching
+//! `ObjectStoreBackend` itself.
+
+mod backend;
+mod keys;
+mod manifest;
+mod object_store;
+mod segments;
+#[cfg(feature = "aws-sdk")]
+mod aws_client;
+
+pub use backend::ObjectStoreBackend;
+pub use object_store::{InMemoryObjectStore, ObjectStore};
+#[cfg(feature = "aws-sdk")]
+pub use aws_client::{S3Client, S3ClientBuilder};
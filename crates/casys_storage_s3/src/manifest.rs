@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use casys_core::{BranchName, DatabaseName, EngineError, ManifestMeta, SegmentId, Timestamp, WalTailMeta};
+
+use crate::keys;
+use crate::object_store::ObjectStore;
+
+/// On-the-wire manifest body, one JSON object per key. Unlike the
+/// filesystem backend's binary `manifest.v2`, there's no docket pointer
+/// here: `latest_manifest` just lists the branch prefix, since object
+/// stores don't offer a cheaper "find the newest key" primitive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub branch: String,
+    pub version_ts: Timestamp,
+    #[serde(default)]
+    pub segments: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_tail: Option<(u64, u64)>,
+}
+
+pub fn to_meta(m: &Manifest) -> ManifestMeta {
+    ManifestMeta {
+        branch: m.branch.clone(),
+        version_ts: m.version_ts,
+        segments: m.segments.iter().map(|id| SegmentId(id.clone())).collect(),
+        wal_tail: m.wal_tail.map(|(epoch, seq)| WalTailMeta { epoch, seq }),
+    }
+}
+
+pub fn from_meta(meta: &ManifestMeta) -> Manifest {
+    Manifest {
+        branch: meta.branch.clone(),
+        version_ts: meta.version_ts,
+        segments: meta.segments.iter().map(|id| id.0.clone()).collect(),
+        wal_tail: meta.wal_tail.as_ref().map(|w| (w.epoch, w.seq)),
+    }
+}
+
+pub fn write_manifest(store: &dyn ObjectStore, db: &DatabaseName, branch: &BranchName, m: &Manifest) -> Result<(), EngineError> {
+    let bytes = serde_json::to_vec(m).map_err(|e| EngineError::StorageIo(format!("serialize manifest: {e}")))?;
+    store.put_object(&keys::manifest_key(db, branch, m.version_ts), &bytes)
+}
+
+pub fn read_manifest(store: &dyn ObjectStore, key: &str) -> Result<Manifest, EngineError> {
+    let bytes = store.get_object(key)?
+        .ok_or_else(|| EngineError::NotFound(key.to_string()))?;
+    serde_json::from_slice(&bytes).map_err(|e| EngineError::StorageIo(format!("parse manifest ({key}): {e}")))
+}
+
+/// All manifest keys for `branch`, sorted oldest-first by `version_ts`.
+pub fn list_manifest_keys(store: &dyn ObjectStore, db: &DatabaseName, branch: &BranchName) -> Result<Vec<(u64, String)>, EngineError> {
+    let mut out: Vec<(u64, String)> = store.list_objects(&keys::branch_prefix(db, branch))?
+        .into_iter()
+        .filter_map(|key| keys::parse_manifest_ts(&key).map(|ts| (ts, key)))
+        .collect();
+    out.sort_by_key(|(ts, _)| *ts);
+    Ok(out)
+}
+
+pub fn latest_manifest(store: &dyn ObjectStore, db: &DatabaseName, branch: &BranchName) -> Result<Option<Manifest>, EngineError> {
+    match list_manifest_keys(store, db, branch)?.pop() {
+        Some((_, key)) => Ok(Some(read_manifest(store, &key)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn pitr_manifest(store: &dyn ObjectStore, db: &DatabaseName, branch: &BranchName, at: Timestamp) -> Result<Option<Manifest>, EngineError> {
+    match list_manifest_keys(store, db, branch)?.into_iter().rfind(|(ts, _)| *ts <= at) {
+        Some((_, key)) => Ok(Some(read_manifest(store, &key)?)),
+        None => Ok(None),
+    }
+}
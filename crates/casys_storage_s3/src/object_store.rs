@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use casys_core::EngineError;
+
+/// The `GetObject`/`PutObject`/`ListObjects` surface `ObjectStoreBackend`
+/// needs from a remote object store. Kept deliberately narrow (no ranges,
+/// no multipart, no server-side metadata) so a real S3-compatible client
+/// can implement it with three calls and nothing else.
+pub trait ObjectStore: Send + Sync + 'static {
+    /// Uploads `data` as `key`, replacing any existing object at that key.
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<(), EngineError>;
+
+    /// Downloads `key`, or `None` if no object exists at that key.
+    fn get_object(&self, key: &str) -> Result<Option<Vec<u8>>, EngineError>;
+
+    /// Lists every key starting with `prefix`, in no particular order.
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>, EngineError>;
+}
+
+/// In-memory `ObjectStore` for tests and local development. Mirrors the
+/// mock stores the engine's own persistence tests use to exercise a port
+/// without its real backing service.
+#[derive(Default)]
+pub struct InMemoryObjectStore {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryObjectStore {
+    pub fn new() -> Self {
+        Self { objects: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl ObjectStore for InMemoryObjectStore {
+    fn put_object(&self, key: &str, data: &[u8]) -> Result<(), EngineError> {
+        self.objects.lock().expect("objects mutex poisoned").insert(key.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn get_object(&self, key: &str) -> Result<Option<Vec<u8>>, EngineError> {
+        Ok(self.objects.lock().expect("objects mutex poisoned").get(key).cloned())
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>, EngineError> {
+        let mut out: Vec<String> = self.objects.lock().expect("objects mutex poisoned")
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect();
+        out.sort();
+        Ok(out)
+    }
+}
@@ -0,0 +1,64 @@
+//! Flat `node_count`/`edge_count` header in front of the segment payload,
+//! since the object store exposes no side-channel for per-object metadata
+//! the way a filesystem's inode or a real S3 `PutObject` call's
+//! `x-amz-meta-*` headers would. Layout: two little-endian `u64`s, then the
+//! raw bytes - unlike `casys_storage_fs`'s segment header, there is no
+//! magic, version or checksum field.
+
+use casys_core::EngineError;
+
+const HEADER_LEN: usize = 16;
+
+pub fn encode(data: &[u8], node_count: u64, edge_count: u64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(HEADER_LEN + data.len());
+    buf.extend_from_slice(&node_count.to_le_bytes());
+    buf.extend_from_slice(&edge_count.to_le_bytes());
+    buf.extend_from_slice(data);
+    buf
+}
+
+pub fn decode(bytes: &[u8]) -> Result<(Vec<u8>, u64, u64), EngineError> {
+    if bytes.len() < HEADER_LEN {
+        return Err(EngineError::StorageIo("segment object too short".into()));
+    }
+    let node_count = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+    let edge_count = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    Ok((bytes[HEADER_LEN..].to_vec(), node_count, edge_count))
+}
+
+/// Segment bodies at or above this size are split across multiple
+/// part objects rather than uploaded as one `put_object` call, the way a
+/// real S3 client would switch from `PutObject` to a multipart upload past
+/// a size threshold.
+pub const MULTIPART_THRESHOLD: usize = 8 * 1024 * 1024;
+
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Marks the small pointer object left at a segment's main key once its
+/// body has been split into parts, so `read_segment` knows to list
+/// `segment_part_prefix` instead of decoding the object in place.
+const MULTIPART_MAGIC: &[u8; 8] = b"CASYSMP0";
+
+pub fn is_multipart_pointer(bytes: &[u8]) -> bool {
+    bytes.len() >= MULTIPART_MAGIC.len() && &bytes[..MULTIPART_MAGIC.len()] == MULTIPART_MAGIC
+}
+
+pub fn encode_multipart_pointer(part_count: u32) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(MULTIPART_MAGIC.len() + 4);
+    buf.extend_from_slice(MULTIPART_MAGIC);
+    buf.extend_from_slice(&part_count.to_le_bytes());
+    buf
+}
+
+pub fn decode_multipart_pointer(bytes: &[u8]) -> Result<u32, EngineError> {
+    if bytes.len() < MULTIPART_MAGIC.len() + 4 {
+        return Err(EngineError::StorageIo("multipart pointer object too short".into()));
+    }
+    Ok(u32::from_le_bytes(bytes[MULTIPART_MAGIC.len()..MULTIPART_MAGIC.len() + 4].try_into().unwrap()))
+}
+
+/// Splits an already-header-encoded segment body into `PART_SIZE` chunks
+/// for multipart upload.
+pub fn split_parts(encoded: &[u8]) -> Vec<&[u8]> {
+    encoded.chunks(PART_SIZE).collect()
+}